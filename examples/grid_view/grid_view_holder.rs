@@ -0,0 +1,62 @@
+use std::thread;
+
+use tmui::{
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    views::grid_view::GridView,
+    widget::WidgetImpl,
+};
+
+use crate::Icon;
+
+#[extends(Widget, Layout(HBox))]
+#[derive(Childrenable)]
+#[async_task(name = "BuildGridTask", value = "()")]
+pub struct GridViewHolder {
+    #[children]
+    grid_view: Tr<GridView>,
+}
+
+impl ObjectSubclass for GridViewHolder {
+    const NAME: &'static str = "GridViewHolder";
+}
+
+impl ObjectImpl for GridViewHolder {
+    fn initialize(&mut self) {
+        self.set_hexpand(true);
+        self.set_vexpand(true);
+
+        self.grid_view.set_hexpand(true);
+        self.grid_view.set_vexpand(true);
+        self.grid_view.set_tile_size(Size::new(100, 100));
+        self.grid_view.set_spacing(10);
+
+        self.grid_view.register_node_pressed(|node, _, _| {
+            println!("Tile pressed, {}", node.id());
+        });
+
+        self.grid_view.start_loading();
+        let arc = self.grid_view.concurrent_store();
+        self.build_grid_task(
+            async move {
+                println!("Build grid in thread {:?}", thread::current().id());
+                let mut store = arc.lock();
+                for idx in 0..10000 {
+                    store.add_node(&Icon { idx });
+                }
+            },
+            |w: &mut GridViewHolder, _| {
+                w.grid_view.stop_loading();
+            },
+        );
+    }
+}
+
+impl WidgetImpl for GridViewHolder {}
+
+impl GridViewHolder {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+}
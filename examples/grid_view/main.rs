@@ -0,0 +1,82 @@
+pub mod grid_view_holder;
+
+use grid_view_holder::GridViewHolder;
+use tmui::{
+    application::Application,
+    application_window::ApplicationWindow,
+    icons::svg_dom::SvgDom,
+    prelude::*,
+    views::{
+        cell::{
+            cell_render::{ImageCellRender, SvgCellRender, TextCellRender},
+            Cell,
+        },
+        list_view::list_view_object::ListViewObject,
+        node::node_render::NodeRender,
+    },
+};
+
+const SVG_ICONS: [&str; 4] = [
+    "examples/resources/search.svg",
+    "examples/resources/toggle_on.svg",
+    "examples/resources/globe_asia.svg",
+    "examples/resources/sword_rose.svg",
+];
+
+fn main() {
+    log4rs::init_file("examples/log4rs.yaml", Default::default()).unwrap();
+
+    let app = Application::builder()
+        .width(1280)
+        .height(800)
+        .title("Grid View")
+        .build();
+
+    app.connect_activate(build_ui);
+
+    app.run();
+}
+
+fn build_ui(window: &mut ApplicationWindow) {
+    window.child(GridViewHolder::new())
+}
+
+struct Icon {
+    idx: usize,
+}
+impl ListViewObject for Icon {
+    #[inline]
+    fn cells(&self) -> Vec<Cell> {
+        let thumbnail = if self.idx % 5 == 4 {
+            Cell::image()
+                .image_address("examples/resources/rust.png".to_string())
+                .cell_render(ImageCellRender::builder().build())
+                .build()
+        } else {
+            let dom = SvgDom::from_file(SVG_ICONS[self.idx % 5]);
+            Cell::svg()
+                .cell_render(SvgCellRender::builder().dom(Some(dom)).build())
+                .build()
+        };
+
+        vec![
+            thumbnail,
+            Cell::string()
+                .value(format!("Icon_{}", self.idx))
+                .cell_render(
+                    TextCellRender::builder()
+                        .color(Color::BLACK)
+                        .selection_color(Some(Color::WHITE))
+                        .halign(Align::Center)
+                        .valign(Align::Center)
+                        .build(),
+                )
+                .build(),
+        ]
+    }
+
+    #[inline]
+    fn node_render(&self) -> NodeRender {
+        NodeRender::builder().border_radius(6.).build()
+    }
+}
@@ -258,6 +258,27 @@ impl ObjectImpl for TreeViewHolder {
             .get_store_mut()
             .root_mut()
             .add_node(&Group { name: "roots" });
+        self.tree_view_2
+            .get_store_mut()
+            .root_mut()
+            .add_node(&LazyGroup {
+                name: "remote".to_string(),
+            });
+        self.tree_view_2.register_lazy_loader(|node| {
+            let name = node.get_value::<String>(0).unwrap();
+            async move {
+                tlib::tokio::time::sleep(Duration::from_secs(1)).await;
+
+                let mut children: Vec<Box<dyn TreeViewObject + Send>> = vec![];
+                for i in 0..3 {
+                    children.push(Box::new(LazyGroup {
+                        name: format!("{}-{}", name, i),
+                    }));
+                }
+                children.push(Box::new(SimpContent { name: "content" }));
+                children
+            }
+        });
         self.tree_view_2.register_node_released(|node, evt| {
             if !node.is_extensible() {
                 return;
@@ -444,6 +465,34 @@ impl TreeViewObject for Group {
     }
 }
 
+pub struct LazyGroup {
+    name: String,
+}
+impl TreeViewObject for LazyGroup {
+    #[inline]
+    fn cells(&self) -> Vec<Cell> {
+        vec![Cell::string()
+            .value(self.name.clone())
+            .cell_render(TextCellRender::builder().color(Color::BLACK).build())
+            .build()]
+    }
+
+    #[inline]
+    fn extensible(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn node_render(&self) -> NodeRender {
+        NodeRender::default()
+    }
+
+    #[inline]
+    fn lazy(&self) -> bool {
+        true
+    }
+}
+
 pub struct SimpContent {
     pub name: &'static str,
 }
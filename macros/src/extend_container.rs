@@ -5,6 +5,7 @@ use crate::{
     layout::LayoutType,
     pane::{generate_pane_inner_init, generate_pane_type_register},
     scroll_area::generate_scroll_area_pre_construct,
+    split_pane::generate_split_pane_inner_init,
     stack::{generate_stack_inner_initial, generate_stack_inner_on_property_set},
     SplitGenericsRef,
 };
@@ -94,6 +95,9 @@ pub(crate) fn expand(
                         fields.named.push(syn::Field::parse_named.parse2(quote! {
                             split_infos_vec: Vec<std::option::Option<std::ptr::NonNull<SplitInfo>>>
                         })?);
+                        fields.named.push(syn::Field::parse_named.parse2(quote! {
+                            split_handle: SplitHandle
+                        })?);
                     }
                     if layout == LayoutType::Stack {
                         fields.named.push(syn::Field::parse_named.parse2(quote! {
@@ -292,6 +296,7 @@ pub(crate) fn expand(
             let reflect_split_infos_getter = if layout.is(LayoutType::SplitPane) {
                 quote!(
                     type_registry.register::<#name, ReflectSplitInfosGetter>();
+                    type_registry.register::<#name, ReflectInnerCustomizeEventProcess>();
                 )
             } else {
                 proc_macro2::TokenStream::new()
@@ -335,6 +340,12 @@ pub(crate) fn expand(
                 proc_macro2::TokenStream::new()
             };
 
+            let split_pane_inner_init = if layout.is(LayoutType::SplitPane) {
+                generate_split_pane_inner_init()?
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+
             let layout_prepare_children_ref = if children_fields.is_some() {
                 let children_fields = children_fields.unwrap();
                 quote!(
@@ -465,6 +476,7 @@ pub(crate) fn expand(
                     fn inner_initialize(&mut self) {
                         #run_after_clause
                         #pane_inner_init
+                        #split_pane_inner_init
                         #stack_inner_initial
                         #popup_inner_initialize
                         #close_handler_register_clause
@@ -191,12 +191,12 @@ pub(crate) fn gen_shared_widget_trait_impl_clause(
     Ok(quote!(
         impl #impl_generics SharedWidgetExt for #name #ty_generics #where_clause {
             #[inline]
-            fn shared_id(&self) -> &'static str {
+            fn shared_id(&self) -> &str {
                 self.#(#shared_widget_path).*.shared_id()
             }
 
             #[inline]
-            fn set_shared_id(&mut self, id: &'static str) {
+            fn set_shared_id(&mut self, id: &str) {
                 self.#(#shared_widget_path).*.set_shared_id(id)
             }
 
@@ -235,10 +235,13 @@ pub fn async_do(input: TokenStream) -> TokenStream {
 /// for defining shortcuts involving key combinations.
 ///
 /// This macro allows users to define a shortcut by specifying a series of keys
-/// separated by `+` symbols. Each shortcut is composed of one or more modifier
-/// keys (such as `Control`, `Alt`, `Shift`, `Meta`), followed by a specific key
+/// separated by `+` symbols. Each chord is composed of zero or more modifier
+/// keys (such as `Control`, `Alt`, `Shift`, `Meta`), and exactly one specific key
 /// (e.g., the letter keys `A`, `B`, `C`, etc.).
 ///
+/// Multiple chords separated by `,` form a key sequence, which was triggered
+/// when the chords were pressed one after another.
+///
 /// # Basic Usage
 ///
 /// The following examples demonstrate how to use the `shortcut!` macro to define shortcuts:
@@ -247,16 +250,19 @@ pub fn async_do(input: TokenStream) -> TokenStream {
 /// let ctrl_a = shortcut!(Control + A);
 /// let ctrl_alt_b = shortcut!(Control + Alt + B);
 /// let alt_1 = shortcut!(Alt + 1);
+/// let escape = shortcut!(Escape);
+/// let ctrl_k_ctrl_s = shortcut!(Control + K, Control + S);
 /// ```
 ///
 /// These shortcuts can be used to define specific actions or behaviors within an application.
 ///
 /// ### Parameters
 ///
-/// - `Control`, `Alt`, `Shift`, `Meta`: Modifier key identifiers.
-/// - `A`..`Z`, `0`-`9`, `F1`-`F12`: Specific key identifiers.
+/// - `Control`(or `Ctrl`), `Alt`, `Shift`, `Meta`: Modifier key identifiers.
+/// - `A`..`Z`, `0`-`9`, `F1`-`F24`, `Enter`, `Escape`, `Tab`, `Space`, `Left`, `Comma`, `Numpad0`...:
+///   Specific key identifiers, the name of the `KeyCode` variant without the `Key` prefix.
 ///
-/// These parameters are connected by `+` symbols to form a complete shortcut definition.
+/// `KeyCode`, `KeyboardModifier` and `KeyChord` should be in scope, they were included in the prelude of `tmui`.
 ///
 /// ### Return Value
 ///
@@ -306,6 +312,14 @@ pub fn split_pane_impl(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro]
+pub fn split_pane_init(_: TokenStream) -> TokenStream {
+    match split_pane::generate_split_pane_inner_init() {
+        Ok(tkn) => tkn.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[proc_macro]
 pub fn stack_impl(input: TokenStream) -> TokenStream {
     let ident = parse_macro_input!(input as Ident);
@@ -1,9 +1,9 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{parse::Parse, Ident, LitInt, Token};
 
 pub(crate) struct Shortcut {
-    keys: Vec<Key>,
+    chords: Vec<Vec<Key>>,
 }
 
 enum Key {
@@ -13,50 +13,84 @@ enum Key {
 
 impl Parse for Shortcut {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let mut keys = Vec::new();
+        let mut chords = vec![];
+        let mut keys = vec![];
 
         while !input.is_empty() {
-            if let Ok(ident) = input.parse::<Ident>() {
-                keys.push(Key::Ident(ident));
+            if input.peek(Ident) {
+                keys.push(Key::Ident(input.parse::<Ident>()?));
+            } else if input.peek(LitInt) {
+                keys.push(Key::Int(input.parse::<LitInt>()?));
+            } else {
+                return Err(input.error("expected key name, e.g. `Control`, `A`, `F1`, `Enter`"));
             }
 
-            else if let Ok(lit_int) = input.parse::<LitInt>() {
-                keys.push(Key::Int(lit_int));
+            if input.is_empty() {
+                break;
+            }
+            if input.parse::<Token![,]>().is_ok() {
+                chords.push(std::mem::take(&mut keys));
+            } else {
+                input.parse::<Token![+]>()?;
             }
-
-            let _ = input.parse::<Token![+]>();
         }
+        chords.push(keys);
 
-        Ok(Shortcut { keys })
+        Ok(Shortcut { chords })
     }
 }
 
 impl Shortcut {
     pub(crate) fn expand(&self) -> syn::Result<TokenStream> {
-        let keys = &self.keys;
         let mut clause = TokenStream::new();
 
-        for key in keys {
-            match key {
-                Key::Ident(key) => {
-                    clause.extend(quote!(
-                        shortcut.insert(Shortcut::#key);
-                    ));
+        for keys in self.chords.iter() {
+            let mut modifier = quote!(KeyboardModifier::NoModifier);
+            let mut key_code = None;
+
+            for key in keys {
+                let (name, span) = match key {
+                    Key::Ident(key) => (key.to_string(), key.span()),
+                    Key::Int(key) => (key.to_string(), key.span()),
+                };
+
+                let modifier_name = match name.as_str() {
+                    "Control" | "Ctrl" => Some("ControlModifier"),
+                    "Alt" => Some("AltModifier"),
+                    "Shift" => Some("ShiftModifier"),
+                    "Meta" => Some("MetaModifier"),
+                    _ => None,
+                };
+                if let Some(modifier_name) = modifier_name {
+                    let m = Ident::new(modifier_name, span);
+                    modifier = quote!(#modifier.or(KeyboardModifier::#m));
+                    continue;
                 }
-                Key::Int(key) => {
-                    let mut name = "Key".to_string();
-                    name.push_str(&key.to_string());
-                    let key = Ident::new(&name, key.span());
-                    clause.extend(quote!(
-                        shortcut.insert(Shortcut::#key);
+
+                if key_code.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        "only one non-modifier key was allowed in each chord, use `,` to separate the chords",
                     ));
                 }
+                let code = Ident::new(&format!("Key{}", name), span);
+                key_code = Some(quote!(KeyCode::#code));
             }
+
+            let Some(key_code) = key_code else {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "each chord of the shortcut should contain one non-modifier key",
+                ));
+            };
+            clause.extend(quote!(
+                shortcut = shortcut.then(KeyChord::new(#key_code, #modifier));
+            ));
         }
 
         Ok(quote!(
             {
-                let mut shortcut = Shortcut::empty();
+                let mut shortcut = Shortcut::default();
                 #clause
                 shortcut
             }
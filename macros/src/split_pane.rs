@@ -36,14 +36,27 @@ pub(crate) fn generate_split_pane_remove_children() -> syn::Result<proc_macro2::
     })
 }
 
+pub(crate) fn generate_split_pane_inner_init() -> syn::Result<proc_macro2::TokenStream> {
+    Ok(quote!(
+        self.set_mouse_tracking(true);
+        self.set_propagate_mouse_tracking(true);
+
+        self.enable_bubble(EventBubble::MOUSE_MOVE);
+        self.enable_bubble(EventBubble::MOUSE_PRESSED);
+        self.enable_bubble(EventBubble::MOUSE_RELEASED);
+        self.set_propagate_event_bubble(true);
+    ))
+}
+
 pub(crate) fn generate_split_pane_impl(name: &Ident) -> syn::Result<proc_macro2::TokenStream> {
     Ok(quote! {
     impl SizeUnifiedAdjust for #name {
         fn size_unified_adjust(&mut self) {
             let parent_rect = self.contents_rect(None);
+            let min_size = self.split_handle.min_pane_size;
             let split_infos_getter = cast_mut!(self as SplitInfosGetter).unwrap();
             for split_info in split_infos_getter.split_infos_vec() {
-                nonnull_mut!(split_info).calculate_layout(parent_rect, false)
+                nonnull_mut!(split_info).calculate_layout(parent_rect, min_size, false)
             }
             for split_info in split_infos_getter.split_infos_vec() {
                 let split_info = nonnull_mut!(split_info);
@@ -61,6 +74,65 @@ pub(crate) fn generate_split_pane_impl(name: &Ident) -> syn::Result<proc_macro2:
         fn split_infos_vec(&mut self) -> &mut Vec<std::option::Option<std::ptr::NonNull<SplitInfo>>> {
             &mut self.split_infos_vec
         }
+
+        fn split_handle(&mut self) -> &mut SplitHandle {
+            &mut self.split_handle
+        }
+    }
+
+    impl InnerCustomizeEventProcess for #name {
+        fn inner_customize_mouse_move(&mut self, event: &tlib::events::MouseEvent) {
+            let pos = self.map_to_global(&event.position().into());
+
+            if self.split_handle.pressed {
+                let id = self.split_handle.hovered.unwrap();
+                if let Some(split_info) = self.split_infos.get(&id) {
+                    let ratio = split_info.ratio_at(pos, self.split_handle.min_pane_size);
+                    if ratio != split_info.ratio {
+                        self.set_split_ratio(id, ratio);
+                    }
+                }
+                return;
+            }
+
+            // The splitter handles of the panes splited later have the priority.
+            let hovered = self.split_infos_vec.iter().rev().find_map(|split_info| {
+                let split_info = nonnull_ref!(split_info);
+                split_info
+                    .handle_rect()
+                    .filter(|rect| rect.contains(&pos))
+                    .map(|_| (split_info.id, split_info.ty))
+            });
+            if hovered.map(|(id, _)| id) == self.split_handle.hovered {
+                return;
+            }
+
+            self.split_handle.hovered = hovered.map(|(id, _)| id);
+            match hovered {
+                Some((_, SplitType::SplitLeft | SplitType::SplitRight)) => {
+                    self.set_cursor_shape(SystemCursorShape::SizeHorCursor)
+                }
+                Some(_) => self.set_cursor_shape(SystemCursorShape::SizeVerCursor),
+                None => self.set_cursor_shape(SystemCursorShape::ArrowCursor),
+            }
+        }
+
+        #[inline]
+        fn inner_customize_mouse_pressed(&mut self, event: &tlib::events::MouseEvent) {
+            if self.split_handle.hovered.is_some()
+                && event.mouse_button() == tlib::namespace::MouseButton::LeftButton
+            {
+                self.split_handle.pressed = true;
+            }
+        }
+
+        #[inline]
+        fn inner_customize_mouse_released(&mut self, event: &tlib::events::MouseEvent) {
+            if self.split_handle.pressed {
+                self.split_handle.pressed = false;
+                self.inner_customize_mouse_move(event);
+            }
+        }
     }
 
     impl SplitPaneExt for #name {
@@ -99,13 +171,20 @@ pub(crate) fn generate_split_pane_impl(name: &Ident) -> syn::Result<proc_macro2:
                 split_from.split_to.retain(|s| nonnull_ref!(s).id != id);
             }
 
-            for split_to_ptr in split.split_to.iter_mut() {
-                let split_to = nonnull_mut!(split_to_ptr);
-                split_to.split_from = split.split_from;
-                split_to.ty = split.ty;
+            // The first pane splited from the closed pane takes its place,
+            // the others were splited from the first one, so the panes never overlap.
+            let mut split_to = std::mem::take(&mut split.split_to).into_iter();
+            if let Some(mut first_ptr) = split_to.next() {
+                let first = nonnull_mut!(first_ptr);
+                first.split_from = split.split_from;
+                first.ty = split.ty;
 
                 if let Some(split_from) = split_from.as_mut() {
-                    split_from.split_to.push(split_to_ptr.clone());
+                    split_from.split_to.push(first_ptr);
+                }
+                for mut other_ptr in split_to {
+                    nonnull_mut!(other_ptr).split_from = first_ptr;
+                    first.split_to.push(other_ptr);
                 }
             }
             self.split_infos_vec.retain(|s| nonnull_ref!(s).id != id);
@@ -141,6 +220,75 @@ pub(crate) fn generate_split_pane_impl(name: &Ident) -> syn::Result<proc_macro2:
             ApplicationWindow::initialize_dynamic_component(widget.as_dyn_mut(), self.is_in_tree());
             self.update()
         }
+
+        #[inline]
+        fn split_ratio(&self, id: ObjectId) -> Option<f32> {
+            self.split_infos.get(&id).map(|s| s.ratio)
+        }
+
+        fn set_split_ratio(&mut self, id: ObjectId, ratio: f32) {
+            if let Some(split_info) = self.split_infos.get_mut(&id) {
+                split_info.ratio = ratio.clamp(0., 1.);
+                self.window().layout_change(self);
+            }
+        }
+
+        #[inline]
+        fn min_pane_size(&self) -> i32 {
+            self.split_handle.min_pane_size
+        }
+
+        #[inline]
+        fn set_min_pane_size(&mut self, size: i32) {
+            self.split_handle.min_pane_size = size.max(0);
+            self.window().layout_change(self);
+        }
+
+        fn split_layout<F: Fn(&dyn WidgetImpl) -> String>(&self, key_of: F) -> Option<SplitLayout> {
+            self.split_infos_vec
+                .iter()
+                .map(|s| nonnull_ref!(s))
+                .find(|s| s.split_from.is_none())
+                .map(|s| s.layout(&key_of))
+        }
+
+        fn restore_split_layout<T, F>(&mut self, layout: &SplitLayout, mut factory: F)
+        where
+            T: WidgetImpl,
+            F: FnMut(&str) -> Option<Tr<T>>,
+        {
+            let ids: Vec<ObjectId> = self.container.children.iter().map(|c| c.id()).collect();
+            for id in ids {
+                self.remove_children(id);
+            }
+
+            let mut stack = vec![(None, layout)];
+            while let Some((base, node)) = stack.pop() {
+                let base = match factory(&node.key) {
+                    Some(widget) => {
+                        let id = widget.id();
+                        let base = base.or_else(|| self.container.children.first().map(|c| c.id()));
+                        match base {
+                            Some(base) => {
+                                let ty = match node.ty {
+                                    SplitType::SplitNone => SplitType::SplitRight,
+                                    ty => ty,
+                                };
+                                self.split(base, widget, ty);
+                            }
+                            None => self.add_child(widget),
+                        }
+                        if let Some(split_info) = self.split_infos.get_mut(&id) {
+                            split_info.ratio = node.ratio.clamp(0., 1.);
+                        }
+                        Some(id)
+                    }
+                    None => base,
+                };
+                stack.extend(node.splits.iter().rev().map(|split| (base, split)));
+            }
+            self.window().layout_change(self);
+        }
     }
     })
 }
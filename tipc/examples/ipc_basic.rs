@@ -34,7 +34,8 @@ fn main() {
 fn ipc_master() {
     let master = IpcBuilder::<UserEvent, Request>::with_customize()
         .name(NAME)
-        .master();
+        .master()
+        .unwrap();
 
     let mut cnt = 0u64;
     let mut ins = None;
@@ -85,7 +86,8 @@ fn ipc_master() {
 fn ipc_slave() {
     let slave = IpcBuilder::<UserEvent, Request>::with_customize()
         .name(NAME)
-        .slave();
+        .slave()
+        .unwrap();
 
     let mut cnt = 0u64;
     let ins = Instant::now();
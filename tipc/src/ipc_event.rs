@@ -1,7 +1,10 @@
-use std::time::Instant;
+use crate::mem::{region_table::MAX_REGION_ID_LEN, IpcError};
+use std::{path::PathBuf, time::Instant};
 use tlib::{
-    events::{DeltaType, Event, EventType, FocusEvent, KeyEvent, MouseEvent, ResizeEvent},
-    figure::Point,
+    events::{
+        DeltaType, Event, EventType, FileEvent, FocusEvent, KeyEvent, MouseEvent, ResizeEvent,
+    },
+    figure::{Point, Rect},
     global::{to_static, SemanticExt},
     namespace::{KeyCode, KeyboardModifier, MouseButton},
     prelude::SystemCursorShape, payload::PayloadWeight,
@@ -13,6 +16,9 @@ pub const IPC_TEXT_EVT_SIZE: usize = 4096;
 pub enum IpcEvent<T: 'static + Copy> {
     None,
     Exit,
+    /// The peer process has exited abnormally, or its heartbeat was timeout.
+    /// Generated locally, it will not be sent to the peer.
+    PeerLost,
     /// (width, height, timestamp)
     ResizeEvent(i32, i32, Instant),
     /// The vsync event.
@@ -39,16 +45,84 @@ pub enum IpcEvent<T: 'static + Copy> {
     SetCursorShape(SystemCursorShape),
     /// (text, timestamp)
     TextEvent(String, Instant),
+    /// The shared region was added, moved or removed by the master, sent to the slave.
+    /// (region_id, rect), `None` if removed.
+    RegionChanged(String, Option<Rect>),
+    /// The file was dropped on the master window, forwarded to the slave.
+    /// The path longer than [`IPC_TEXT_EVT_SIZE`] bytes was rejected by `try_send()`.
+    /// (path, timestamp)
+    DroppedFile(String, Instant),
+    /// (path, timestamp)
+    HoveredFile(String, Instant),
+    /// (timestamp)
+    HoveredFileCancelled(Instant),
+    /// The slave requests the text of os clipboard from the master.
+    /// (request_id)
+    ClipboardGet(u32),
+    /// The text of os clipboard responded by the master, the text was sent as the frame
+    /// encoded by [`StringCodec`](crate::mem::frame_codec::StringCodec) right before this event.
+    /// (request_id)
+    ClipboardText(u32),
+    /// The slave sets the text of os clipboard through the master, the text was sent as the frame
+    /// encoded by [`StringCodec`](crate::mem::frame_codec::StringCodec) right before this event.
+    ClipboardSet,
     /// (customize_content, timestamp)
     UserEvent(T, Instant),
 }
 
+impl<T: 'static + Copy> IpcEvent<T> {
+    /// Replace the timestamp of event, used when replaying the recorded events.
+    pub(crate) fn set_timestamp(&mut self, timestamp: Instant) {
+        match self {
+            Self::ResizeEvent(.., ts)
+            | Self::VSync(ts)
+            | Self::KeyPressedEvent(.., ts)
+            | Self::KeyReleasedEvent(.., ts)
+            | Self::MousePressedEvent(.., ts)
+            | Self::MouseReleaseEvent(.., ts)
+            | Self::MouseEnterEvent(.., ts)
+            | Self::MouseLeaveEvent(.., ts)
+            | Self::MouseMoveEvent(.., ts)
+            | Self::MouseWheelEvent(.., ts)
+            | Self::RequestFocusEvent(.., ts)
+            | Self::TextEvent(.., ts)
+            | Self::DroppedFile(.., ts)
+            | Self::HoveredFile(.., ts)
+            | Self::HoveredFileCancelled(ts)
+            | Self::UserEvent(.., ts) => *ts = timestamp,
+            Self::None
+            | Self::Exit
+            | Self::PeerLost
+            | Self::SetCursorShape(_)
+            | Self::RegionChanged(..)
+            | Self::ClipboardGet(_)
+            | Self::ClipboardText(_)
+            | Self::ClipboardSet => {}
+        }
+    }
+
+    /// Check the length of the variable-length path, which was sent in the fixed-size event.
+    pub(crate) fn check_size(&self) -> Result<(), IpcError> {
+        match self {
+            Self::DroppedFile(path, _) | Self::HoveredFile(path, _)
+                if path.len() > IPC_TEXT_EVT_SIZE =>
+            {
+                Err(IpcError::new(
+                    "The path of file event exceed limit `IPC_TEXT_EVT_SIZE`, event was aborted.",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::large_enum_variant)]
 pub(crate) enum InnerIpcEvent<T: 'static + Copy> {
     None,
     Exit,
+    PeerLost,
     /// (width, height, timestamp)
     ResizeEvent(i32, i32, Instant),
     /// (instant_of_vsync)
@@ -75,6 +149,19 @@ pub(crate) enum InnerIpcEvent<T: 'static + Copy> {
     SetCursorShape(SystemCursorShape),
     /// (text, timestamp)
     TextEvent([u8; IPC_TEXT_EVT_SIZE], Instant),
+    /// (region_id, id_len, rect)
+    RegionChanged([u8; MAX_REGION_ID_LEN], usize, Option<Rect>),
+    /// (path, path_len, timestamp)
+    DroppedFile([u8; IPC_TEXT_EVT_SIZE], usize, Instant),
+    /// (path, path_len, timestamp)
+    HoveredFile([u8; IPC_TEXT_EVT_SIZE], usize, Instant),
+    /// (timestamp)
+    HoveredFileCancelled(Instant),
+    /// (request_id)
+    ClipboardGet(u32),
+    /// (request_id)
+    ClipboardText(u32),
+    ClipboardSet,
     /// (customize_content, timestamp)
     UserEvent(T, Instant),
 }
@@ -84,6 +171,7 @@ impl<T: 'static + Copy> From<IpcEvent<T>> for InnerIpcEvent<T> {
         match val {
             IpcEvent::None => InnerIpcEvent::None,
             IpcEvent::Exit => InnerIpcEvent::Exit,
+            IpcEvent::PeerLost => InnerIpcEvent::PeerLost,
             IpcEvent::ResizeEvent(a, b, c) => InnerIpcEvent::ResizeEvent(a, b, c),
             IpcEvent::VSync(a) => InnerIpcEvent::VSync(a),
             IpcEvent::KeyPressedEvent(a, b, c, d) => {
@@ -139,6 +227,31 @@ impl<T: 'static + Copy> From<IpcEvent<T>> for InnerIpcEvent<T> {
                 data[0..bytes.len()].copy_from_slice(bytes);
                 InnerIpcEvent::TextEvent(data, b)
             }
+            IpcEvent::RegionChanged(a, b) => {
+                let bytes = a.as_bytes();
+                if bytes.len() > MAX_REGION_ID_LEN {
+                    panic!(
+                        "The region id of RegionChanged exceed limit, max: {}, get: {}",
+                        MAX_REGION_ID_LEN,
+                        bytes.len()
+                    )
+                }
+                let mut data = [0u8; MAX_REGION_ID_LEN];
+                data[0..bytes.len()].copy_from_slice(bytes);
+                InnerIpcEvent::RegionChanged(data, bytes.len(), b)
+            }
+            IpcEvent::DroppedFile(a, b) => {
+                let (data, len) = text_data(&a);
+                InnerIpcEvent::DroppedFile(data, len, b)
+            }
+            IpcEvent::HoveredFile(a, b) => {
+                let (data, len) = text_data(&a);
+                InnerIpcEvent::HoveredFile(data, len, b)
+            }
+            IpcEvent::HoveredFileCancelled(a) => InnerIpcEvent::HoveredFileCancelled(a),
+            IpcEvent::ClipboardGet(a) => InnerIpcEvent::ClipboardGet(a),
+            IpcEvent::ClipboardText(a) => InnerIpcEvent::ClipboardText(a),
+            IpcEvent::ClipboardSet => InnerIpcEvent::ClipboardSet,
             IpcEvent::UserEvent(a, b) => InnerIpcEvent::UserEvent(a, b),
         }
     }
@@ -149,6 +262,7 @@ impl<T: 'static + Copy> From<InnerIpcEvent<T>> for IpcEvent<T> {
         match val {
             InnerIpcEvent::None => IpcEvent::None,
             InnerIpcEvent::Exit => IpcEvent::Exit,
+            InnerIpcEvent::PeerLost => IpcEvent::PeerLost,
             InnerIpcEvent::ResizeEvent(a, b, c) => IpcEvent::ResizeEvent(a, b, c),
             InnerIpcEvent::VSync(a) => IpcEvent::VSync(a),
             InnerIpcEvent::KeyPressedEvent(a, b, c, d) => {
@@ -181,6 +295,16 @@ impl<T: 'static + Copy> From<InnerIpcEvent<T>> for IpcEvent<T> {
                     .to_string();
                 IpcEvent::TextEvent(str, b)
             }
+            InnerIpcEvent::RegionChanged(a, b, c) => {
+                let str = String::from_utf8_lossy(&a[..b.min(MAX_REGION_ID_LEN)]).to_string();
+                IpcEvent::RegionChanged(str, c)
+            }
+            InnerIpcEvent::DroppedFile(a, b, c) => IpcEvent::DroppedFile(text_string(&a, b), c),
+            InnerIpcEvent::HoveredFile(a, b, c) => IpcEvent::HoveredFile(text_string(&a, b), c),
+            InnerIpcEvent::HoveredFileCancelled(a) => IpcEvent::HoveredFileCancelled(a),
+            InnerIpcEvent::ClipboardGet(a) => IpcEvent::ClipboardGet(a),
+            InnerIpcEvent::ClipboardText(a) => IpcEvent::ClipboardText(a),
+            InnerIpcEvent::ClipboardSet => IpcEvent::ClipboardSet,
             InnerIpcEvent::UserEvent(a, b) => IpcEvent::UserEvent(a, b),
         }
     }
@@ -282,11 +406,29 @@ impl<T: 'static + Copy> From<IpcEvent<T>> for Event {
                 .boxed()
             }
             IpcEvent::RequestFocusEvent(is_focus, _time) => FocusEvent::new(is_focus).boxed(),
+            IpcEvent::DroppedFile(path, _time) => FileEvent::dropped(PathBuf::from(path)).boxed(),
+            IpcEvent::HoveredFile(path, _time) => FileEvent::hovered(PathBuf::from(path)).boxed(),
+            IpcEvent::HoveredFileCancelled(_time) => FileEvent::hovered_cancel().boxed(),
             _ => unreachable!(),
         }
     }
 }
 
+/// The length was checked by [`IpcEvent::check_size()`] before sent.
+#[inline]
+fn text_data(text: &str) -> ([u8; IPC_TEXT_EVT_SIZE], usize) {
+    let bytes = text.as_bytes();
+    let len = bytes.len().min(IPC_TEXT_EVT_SIZE);
+    let mut data = [0u8; IPC_TEXT_EVT_SIZE];
+    data[0..len].copy_from_slice(&bytes[..len]);
+    (data, len)
+}
+
+#[inline]
+fn text_string(data: &[u8; IPC_TEXT_EVT_SIZE], len: usize) -> String {
+    String::from_utf8_lossy(&data[..len.min(IPC_TEXT_EVT_SIZE)]).to_string()
+}
+
 impl<T: 'static + Copy> PayloadWeight for IpcEvent<T> {
     #[inline]
     fn payload_wieght(&self) -> f32 {
@@ -0,0 +1,260 @@
+use crate::{
+    channel_name,
+    ipc_event::IpcEvent,
+    ipc_master::IpcMaster,
+    mem::{handshake::HandshakeError, region_table::DEFAULT_MAX_REGIONS},
+    IpcNode,
+};
+use std::collections::BTreeMap;
+
+/// The connection state change of the slave on the channel of [`IpcHub`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelEvent {
+    /// (channel)
+    Connected(String),
+    /// (channel)
+    Disconnected(String),
+}
+
+struct Channel<T: 'static + Copy, M: 'static + Copy> {
+    master: IpcMaster<T, M>,
+    /// The connection state reported by the last polling.
+    connected: bool,
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> Channel<T, M> {
+    /// The `connected` flag was only cleared when the slave exited normally,
+    /// so the liveness of slave should be checked as well.
+    #[inline]
+    fn is_alive(&self) -> bool {
+        self.master.is_connected() && !self.master.is_peer_lost()
+    }
+}
+
+/// The master side which serves multiple slaves, each slave was connected to a named channel,
+/// and each channel has its own event queues, shared buffer and shared regions.
+///
+/// The slave connects to the channel by [`IpcBuilder::channel()`](crate::IpcBuilder::channel):
+///
+/// ```ignore
+/// // Host process:
+/// let mut hub = IpcBuilder::<UserEvent, Request>::with_customize().name("host").hub();
+/// hub.add_channel("plugin_a")?.create_buffer(800, 600);
+///
+/// // Plugin process:
+/// let slave = IpcBuilder::<UserEvent, Request>::with_customize()
+///     .name("host")
+///     .channel("plugin_a")
+///     .slave()?;
+/// ```
+pub struct IpcHub<T: 'static + Copy, M: 'static + Copy> {
+    name: String,
+    max_regions: usize,
+    channels: BTreeMap<String, Channel<T, M>>,
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcHub<T, M> {
+    #[inline]
+    pub fn new(name: &str) -> Self {
+        Self::with_max_regions(name, DEFAULT_MAX_REGIONS)
+    }
+
+    /// Each channel can hold at most `max_regions` shared regions.
+    #[inline]
+    pub fn with_max_regions(name: &str, max_regions: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            max_regions,
+            channels: BTreeMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Create the channel for a slave, return the existing one if the channel was already added.
+    ///
+    /// Return the error if the shared memories of channel could not be created.
+    pub fn add_channel(&mut self, channel: &str) -> Result<&mut IpcMaster<T, M>, HandshakeError> {
+        if !self.channels.contains_key(channel) {
+            let name = channel_name(&self.name, channel);
+            let master = IpcMaster::with_max_regions(&name, self.max_regions)?;
+            self.channels.insert(
+                channel.to_string(),
+                Channel {
+                    master,
+                    connected: false,
+                },
+            );
+        }
+        Ok(&mut self.channels.get_mut(channel).unwrap().master)
+    }
+
+    /// Remove the channel, the shared memories will be released after the returned master dropped.
+    #[inline]
+    pub fn remove_channel(&mut self, channel: &str) -> Option<IpcMaster<T, M>> {
+        self.channels.remove(channel).map(|c| c.master)
+    }
+
+    #[inline]
+    pub fn channel(&self, channel: &str) -> Option<&IpcMaster<T, M>> {
+        self.channels.get(channel).map(|c| &c.master)
+    }
+
+    #[inline]
+    pub fn channel_mut(&mut self, channel: &str) -> Option<&mut IpcMaster<T, M>> {
+        self.channels.get_mut(channel).map(|c| &mut c.master)
+    }
+
+    /// Iterate the masters of all the channels with their names.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut IpcMaster<T, M>)> {
+        self.channels
+            .iter_mut()
+            .map(|(k, c)| (k.as_str(), &mut c.master))
+    }
+
+    /// Get the names of all the channels.
+    #[inline]
+    pub fn channels(&self) -> Vec<&str> {
+        self.channels.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Get the names of channels which the slaves were connected and alive.
+    #[inline]
+    pub fn connected_channels(&self) -> Vec<&str> {
+        self.channels
+            .iter()
+            .filter(|(_, c)| c.is_alive())
+            .map(|(k, _)| k.as_str())
+            .collect()
+    }
+
+    /// Whether the slave was connected to the channel and alive,
+    /// the crashed slave was regarded as disconnected.
+    #[inline]
+    pub fn is_connected(&self, channel: &str) -> bool {
+        self.channels.get(channel).is_some_and(|c| c.is_alive())
+    }
+
+    /// Check the connection states of slaves, return the changes since the last polling.
+    ///
+    /// The slave which has exited abnormally or its heartbeat was timeout will
+    /// produce [`ChannelEvent::Disconnected`].
+    pub fn poll_connections(&mut self) -> Vec<ChannelEvent> {
+        let mut events = vec![];
+        for (name, channel) in self.channels.iter_mut() {
+            let connected = channel.is_alive();
+            if connected == channel.connected {
+                continue;
+            }
+            channel.connected = connected;
+
+            if connected {
+                events.push(ChannelEvent::Connected(name.clone()))
+            } else {
+                events.push(ChannelEvent::Disconnected(name.clone()))
+            }
+        }
+        events
+    }
+
+    /// Receive the events from all the channels.
+    pub fn try_recv_all(&self) -> Vec<(&str, IpcEvent<T>)> {
+        let mut events = vec![];
+        for (name, channel) in self.channels.iter() {
+            events.extend(
+                channel
+                    .master
+                    .try_recv_vec()
+                    .into_iter()
+                    .map(|evt| (name.as_str(), evt)),
+            );
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mem::liveness::CHECK_INTERVAL, IpcBuilder};
+    use std::thread;
+
+    #[test]
+    fn test_ipc_hub_connections() {
+        let name = format!("_ipc_hub_test_{}", std::process::id());
+        let mut hub = IpcBuilder::<(), ()>::new().name(&name).hub();
+        hub.add_channel("plugin_a").unwrap().create_buffer(10, 10);
+        hub.add_channel("plugin_b").unwrap().create_buffer(10, 10);
+        assert_eq!(hub.channels(), vec!["plugin_a", "plugin_b"]);
+        assert!(hub.poll_connections().is_empty());
+
+        let slave = IpcBuilder::<(), ()>::new()
+            .name(&name)
+            .channel("plugin_b")
+            .slave()
+            .unwrap();
+        assert_eq!(hub.connected_channels(), vec!["plugin_b"]);
+        assert_eq!(
+            hub.poll_connections(),
+            vec![ChannelEvent::Connected("plugin_b".to_string())]
+        );
+
+        slave.try_send(IpcEvent::Exit).unwrap();
+        let events = hub.try_recv_all();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], ("plugin_b", IpcEvent::Exit)));
+
+        drop(slave);
+        assert!(!hub.is_connected("plugin_b"));
+        assert_eq!(
+            hub.poll_connections(),
+            vec![ChannelEvent::Disconnected("plugin_b".to_string())]
+        );
+        assert!(hub.remove_channel("plugin_a").is_some());
+        assert_eq!(hub.channels(), vec!["plugin_b"]);
+    }
+
+    #[test]
+    fn test_ipc_hub_slave_crashed() {
+        let name = format!("_ipc_hub_crash_test_{}", std::process::id());
+        let mut hub = IpcBuilder::<(), ()>::new().name(&name).hub();
+        hub.add_channel("plugin").unwrap().create_buffer(10, 10);
+
+        let slave = IpcBuilder::<(), ()>::new()
+            .name(&name)
+            .channel("plugin")
+            .slave()
+            .unwrap();
+        assert_eq!(
+            hub.poll_connections(),
+            vec![ChannelEvent::Connected("plugin".to_string())]
+        );
+
+        // The slave crashed without clearing the `connected` flag.
+        slave.simulate_crash();
+        thread::sleep(CHECK_INTERVAL);
+        assert!(!hub.is_connected("plugin"));
+        assert_eq!(
+            hub.poll_connections(),
+            vec![ChannelEvent::Disconnected("plugin".to_string())]
+        );
+        assert!(hub.poll_connections().is_empty());
+        drop(slave);
+
+        // The restarted slave connects to the channel again.
+        let _slave = IpcBuilder::<(), ()>::new()
+            .name(&name)
+            .channel("plugin")
+            .slave()
+            .unwrap();
+        thread::sleep(CHECK_INTERVAL);
+        assert_eq!(
+            hub.poll_connections(),
+            vec![ChannelEvent::Connected("plugin".to_string())]
+        );
+    }
+}
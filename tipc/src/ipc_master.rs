@@ -1,18 +1,48 @@
 use crate::{
-    generate_u128,
     ipc_event::IpcEvent,
-    mem::{master_context::MasterContext, mem_queue::MemQueueError, MemContext, MAX_REGION_SIZE},
-    IpcNode,
+    mem::{
+        frame_channel::{FrameChannel, FrameDemux},
+        handshake::{
+            HandshakeError, FEATURE_BLOCKING_QUEUE, FEATURE_DAMAGE_RECTS, FEATURE_FRAME_QUEUE,
+            FEATURE_HEARTBEAT,
+        },
+        liveness::{poll_timeout, Beating, PEER_CHECK_WAIT, POLL_WAIT},
+        master_context::MasterContext,
+        mem_queue::WriteMode,
+        region_table::{DEFAULT_MAX_REGIONS, MAX_REGION_ID_LEN},
+        IpcError, MemContext, Segment,
+    },
+    transport::{
+        record::{IpcRecorder, RecordState},
+        IpcTransport,
+    },
+    IpcNode, IpcType,
 };
-use core::{panic, slice};
+use core::slice;
+use log::{error, warn};
 use raw_sync::Timeout;
-use shared_memory::Shmem;
-use std::{collections::VecDeque, error::Error, ffi::c_void, sync::atomic::Ordering};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    ffi::c_void,
+    io,
+    path::Path,
+    sync::atomic::Ordering,
+    thread,
+    time::{Duration, Instant},
+};
 use tlib::figure::Rect;
 
 pub struct IpcMaster<T: 'static + Copy, M: 'static + Copy> {
+    /// Declared first to stop beating before the shared memories were released.
+    _beating: Beating,
     master_context: MasterContext<T, M>,
-    retentions: VecDeque<Shmem>,
+    retentions: VecDeque<Segment>,
+    recorder: IpcRecorder<T, M>,
+    /// The received frames of other channels.
+    frames: FrameDemux,
+    /// The transport of events, frames and requests, `None` for the shared memory queues.
+    transport: Option<Box<dyn IpcTransport<T, M> + Send + Sync>>,
 }
 
 /// SAFETY: MemQueue and memory context use `Mutex` to ensure thread safety.
@@ -20,32 +50,90 @@ unsafe impl<T: 'static + Copy, M: 'static + Copy> Send for IpcMaster<T, M> {}
 unsafe impl<T: 'static + Copy, M: 'static + Copy> Sync for IpcMaster<T, M> {}
 
 impl<T: 'static + Copy, M: 'static + Copy> IpcMaster<T, M> {
-    pub fn new(name: &str) -> Self {
-        let master_context = MasterContext::create(name);
+    /// Return the error if the shared memories could not be created, e.g. the name was
+    /// occupied by another master.
+    #[inline]
+    pub fn new(name: &str) -> Result<Self, HandshakeError> {
+        Self::with_max_regions(name, DEFAULT_MAX_REGIONS)
+    }
+
+    /// Create the master which can hold at most `max_regions` shared regions.
+    pub fn with_max_regions(name: &str, max_regions: usize) -> Result<Self, HandshakeError> {
+        let master_context = MasterContext::create(name, max_regions)?;
+        // SAFETY: The heartbeat was in the shared info, which was dropped after the `Beating`.
+        let beating = unsafe { Beating::start(&master_context.shared_info().master_heartbeat) };
 
-        Self {
+        Ok(Self {
+            _beating: beating,
             master_context,
             retentions: VecDeque::new(),
+            recorder: IpcRecorder::new(IpcType::Master),
+            frames: FrameDemux::default(),
+            transport: None,
+        })
+    }
+
+    /// Take the [`IpcEvent::PeerLost`] once, only if the heartbeat was supported by both sides.
+    #[inline]
+    fn take_peer_lost_event(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.master_context.take_peer_lost_event()
+    }
+
+    #[inline]
+    fn has_peer_lost_event(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.master_context.has_peer_lost_event()
+    }
+
+    /// Add the shared region, or update it if the region with the same id exists.
+    ///
+    /// @return false if the length of `id` exceeds [`MAX_REGION_ID_LEN`], or the count of regions
+    /// reached the `max_regions` of master.
+    pub fn add_rect(&self, id: &str, rect: Rect) -> bool {
+        if id.len() > MAX_REGION_ID_LEN {
+            return false;
+        }
+
+        let added = self.master_context.region_table().upsert(id, rect);
+        if added {
+            self.notify_region_changed(id, Some(rect));
         }
+        added
     }
 
-    pub fn add_rect(&self, id: &'static str, rect: Rect) {
-        let id = generate_u128(id).unwrap_or_else(|| panic!("Invalid id: {}", id));
+    /// Move or resize the existing shared region.
+    ///
+    /// @return false if the region was not exist.
+    pub fn update_rect(&self, id: &str, rect: Rect) -> bool {
+        let updated = self.master_context.region_table().update(id, rect);
+        if updated {
+            self.notify_region_changed(id, Some(rect));
+        }
+        updated
+    }
 
-        let shared_ifo = self.master_context.shared_info();
-        let idx = shared_ifo.region_idx.load(Ordering::Acquire);
-        for uninit in shared_ifo.regions[..idx].iter_mut() {
-            let (sid, r) = unsafe { uninit.assume_init_mut() };
-            if *sid == id {
-                *r = rect;
-                return;
-            }
+    /// @return false if the region was not exist.
+    pub fn remove_rect(&self, id: &str) -> bool {
+        let removed = self.master_context.region_table().remove(id);
+        if removed {
+            self.notify_region_changed(id, None);
         }
-        if idx >= MAX_REGION_SIZE {
-            panic!("Only support {} `SharedWidget`", MAX_REGION_SIZE);
+        removed
+    }
+
+    /// The count of shared regions.
+    #[inline]
+    pub fn region_count(&self) -> usize {
+        self.master_context.region_table().len()
+    }
+
+    /// The slave connected later will read the regions from the region table directly.
+    fn notify_region_changed(&self, id: &str, rect: Option<Rect>) {
+        if !self.is_connected() {
+            return;
+        }
+        if let Err(e) = self.try_send(IpcEvent::RegionChanged(id.to_string(), rect)) {
+            error!("Notify the region changed failed, id = {}: {}", id, e);
         }
-        shared_ifo.regions[idx].write((id, rect));
-        shared_ifo.region_idx.fetch_add(1, Ordering::Release);
     }
 }
 
@@ -77,45 +165,157 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcMaster<T, M> {
     }
 
     #[inline]
-    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), MemQueueError> {
-        self.master_context.try_send(evt)
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        evt.check_size()?;
+        self.transport().try_send(self.recorder.sent_event(evt))
     }
 
     #[inline]
     fn has_event(&self) -> bool {
-        self.master_context.has_event()
+        self.transport().has_event() || self.has_peer_lost_event()
     }
 
     #[inline]
     fn try_recv(&self) -> Option<IpcEvent<T>> {
-        self.master_context.try_recv()
+        self.transport()
+            .try_recv()
+            .or_else(|| self.take_peer_lost_event().then_some(IpcEvent::PeerLost))
+            .map(|evt| self.recorder.received_event(evt))
     }
 
     #[inline]
     fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
-        self.master_context
-            .try_recv_vec()
-            .into_iter()
-            .collect()
+        let mut events = self.transport().try_recv_vec();
+        if self.take_peer_lost_event() {
+            events.push(IpcEvent::PeerLost)
+        }
+        self.recorder.received_events(events)
+    }
+
+    fn recv(&self) -> Option<IpcEvent<T>> {
+        loop {
+            if let Some(evt) = self.recv_timeout(PEER_CHECK_WAIT) {
+                return Some(evt);
+            }
+            // `IpcEvent::PeerLost` has been received, nothing will come from the lost peer.
+            if self.is_peer_lost() {
+                return None;
+            }
+        }
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        let evt = if self.has_feature(FEATURE_BLOCKING_QUEUE) {
+            self.transport().recv_timeout(timeout)
+        } else {
+            // The peer does not notify the readable, poll the events instead.
+            poll_timeout(timeout, || self.transport().try_recv())
+        };
+        evt.or_else(|| self.take_peer_lost_event().then_some(IpcEvent::PeerLost))
+            .map(|evt| self.recorder.received_event(evt))
+    }
+
+    #[inline]
+    fn set_write_mode(&self, write_mode: WriteMode) {
+        if write_mode != WriteMode::Abort && !self.has_feature(FEATURE_BLOCKING_QUEUE) {
+            warn!("The peer does not support the blocking queue, `WriteMode::Abort` was kept.");
+            return;
+        }
+        self.master_context.set_write_mode(write_mode)
+    }
+
+    #[inline]
+    fn heartbeat(&self) {
+        self.master_context.heartbeat()
+    }
+
+    #[inline]
+    fn is_peer_lost(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.master_context.is_peer_lost()
+    }
+
+    #[inline]
+    fn set_peer_timeout(&self, timeout: Duration) {
+        self.master_context.set_peer_timeout(timeout)
+    }
+
+    #[inline]
+    fn features(&self) -> u64 {
+        self.transport().features()
+    }
+
+    #[inline]
+    fn start_recording(&self, path: &Path) -> io::Result<()> {
+        let state = RecordState {
+            width: self.width(),
+            height: self.height(),
+            regions: self.master_context.region_table().entries(),
+        };
+        self.recorder.start(path, self.features(), state)
     }
 
     #[inline]
+    fn stop_recording(&self) {
+        self.recorder.stop()
+    }
+
+    #[inline]
+    fn try_send_channel_frame(
+        &self,
+        channel: FrameChannel,
+        frame: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.has_feature(FEATURE_FRAME_QUEUE) {
+            return Err(Box::new(IpcError::new(
+                "The peer does not support the frame queue, frame was aborted.",
+            )));
+        }
+        self.transport()
+            .try_send_frame(&FrameDemux::tag(channel, frame))
+    }
+
+    #[inline]
+    fn has_channel_frame(&self, channel: FrameChannel) -> bool {
+        self.has_feature(FEATURE_FRAME_QUEUE)
+            && self
+                .frames
+                .has(channel, || self.transport().try_recv_frame())
+    }
+
+    #[inline]
+    fn try_recv_channel_frame(&self, channel: FrameChannel) -> Option<Vec<u8>> {
+        if !self.has_feature(FEATURE_FRAME_QUEUE) {
+            return None;
+        }
+        self.frames
+            .recv(channel, || self.transport().try_recv_frame())
+    }
+
     fn send_request(&self, rqst: M) -> Result<Option<M>, Box<dyn Error>> {
-        self.master_context.send_request(rqst)
+        self.recorder.request(true, &rqst);
+        let resp = self.transport().send_request(rqst)?;
+        self.recorder.response(false, &resp);
+        Ok(resp)
     }
 
     #[inline]
     fn try_recv_request(&self) -> Option<M> {
-        self.master_context.try_recv_request()
+        let rqst = self.transport().try_recv_request()?;
+        self.recorder.request(false, &rqst);
+        Some(rqst)
     }
 
     #[inline]
     fn respose_request(&self, resp: Option<M>) {
-        self.master_context.response_request(resp)
+        self.recorder.response(true, &resp);
+        self.transport().response_request(resp)
     }
 
     #[inline]
-    fn terminate(&self) {}
+    fn terminate(&self) {
+        self.master_context.shared_info().master_heartbeat.detach();
+    }
 
     #[inline]
     fn wait(&self, timeout: Timeout) {
@@ -128,18 +328,8 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcMaster<T, M> {
     }
 
     #[inline]
-    fn region(&self, id: &'static str) -> Option<Rect> {
-        let id = generate_u128(id).unwrap_or_else(|| panic!("Invalid id: {}", id));
-
-        let shared_info = self.master_context.shared_info();
-        let idx = shared_info.region_idx.load(Ordering::Acquire);
-        for uninit in shared_info.regions[..idx].iter() {
-            let (sid, r) = unsafe { uninit.assume_init_ref() };
-            if *sid == id {
-                return Some(*r);
-            }
-        }
-        None
+    fn region(&self, id: &str) -> Option<Rect> {
+        self.master_context.region_table().get(id)
     }
 
     #[inline]
@@ -169,14 +359,23 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcMaster<T, M> {
 
     #[inline]
     fn create_buffer(&mut self, width: u32, height: u32) {
-        self.master_context.create_buffer(width, height)
+        self.master_context.create_buffer(width, height);
+        self.share_buffer()
     }
 
     #[inline]
     fn recreate_buffer(&mut self) {
+        let resized = self
+            .master_context
+            .shared_info()
+            .resized
+            .load(Ordering::Acquire);
         if let Some(old) = self.master_context.recreate_buffer() {
             self.retentions.push_back(old);
         }
+        if resized {
+            self.share_buffer()
+        }
     }
 
     fn release_retention(&mut self) {
@@ -201,18 +400,98 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcMaster<T, M> {
     fn set_invalidate(&self, invalidate: bool) {
         self.master_context.shared_info().invalidate.store(invalidate, Ordering::Release)
     }
+
+    #[inline]
+    fn add_dirty_rect(&self, rect: Rect) {
+        if self.has_feature(FEATURE_DAMAGE_RECTS) {
+            self.master_context.shared_info().damage.add(rect)
+        }
+    }
+
+    #[inline]
+    fn set_full_dirty(&self) {
+        if self.has_feature(FEATURE_DAMAGE_RECTS) {
+            self.master_context.shared_info().damage.set_full()
+        }
+    }
+
+    #[inline]
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>> {
+        if !self.has_feature(FEATURE_DAMAGE_RECTS) {
+            return None;
+        }
+        self.master_context.shared_info().damage.take()
+    }
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> IpcMaster<T, M> {
+    /// Whether the [`IpcSlave`](crate::ipc_slave::IpcSlave) was connected to this master.
     #[inline]
-    pub fn wait_prepared(&self) {
+    pub fn is_connected(&self) -> bool {
+        self.master_context
+            .shared_info()
+            .connected
+            .load(Ordering::Acquire)
+    }
+
+    /// Wait until the slave has prepared the shared buffer.
+    ///
+    /// @return false if the slave was lost, or it has not prepared within the peer timeout
+    /// (see [`set_peer_timeout()`](IpcNode::set_peer_timeout)), e.g. it died before attaching.
+    pub fn wait_prepared(&self) -> bool {
+        let deadline = Instant::now() + self.master_context.peer_timeout();
         while !self
             .master_context
             .shared_info()
             .prepared
             .load(Ordering::Acquire)
         {
-            std::hint::spin_loop()
+            if self.is_peer_lost() {
+                return false;
+            }
+            if Instant::now() >= deadline {
+                warn!("`IpcMaster` wait for the slave prepared timeout.");
+                return false;
+            }
+            thread::sleep(POLL_WAIT)
+        }
+        true
+    }
+
+    /// Transfer the events, frames and requests through the transport, and share the
+    /// shared memories by the file descriptors if the transport can pass them, see
+    /// [`IpcBuilder::transport()`](crate::IpcBuilder::transport).
+    pub(crate) fn set_transport(
+        &mut self,
+        transport: Option<Box<dyn IpcTransport<T, M> + Send + Sync>>,
+    ) {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Some(ref transport) = transport {
+            if let Err(e) = self.master_context.share_segments(transport.as_ref()) {
+                if e.kind() != io::ErrorKind::Unsupported {
+                    error!("`IpcMaster` share the shared memories failed. {:?}", e)
+                }
+            }
+        }
+        self.transport = transport
+    }
+
+    /// Share the created buffer with the slave connected through the transport.
+    #[inline]
+    fn share_buffer(&self) {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Some(ref transport) = self.transport {
+            if let Err(e) = self.master_context.share_buffer(transport.as_ref()) {
+                error!("`IpcMaster` share the buffer failed. {:?}", e)
+            }
+        }
+    }
+
+    #[inline]
+    fn transport(&self) -> &dyn IpcTransport<T, M> {
+        match self.transport {
+            Some(ref transport) => transport.as_ref(),
+            None => &self.master_context,
         }
     }
 }
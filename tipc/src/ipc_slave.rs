@@ -1,18 +1,41 @@
 use crate::{
-    generate_u128,
     ipc_event::IpcEvent,
-    mem::{mem_queue::MemQueueError, slave_context::SlaveContext, MemContext},
-    IpcNode,
+    mem::{
+        frame_channel::{FrameChannel, FrameDemux},
+        handshake::{
+            HandshakeError, FEATURE_BLOCKING_QUEUE, FEATURE_DAMAGE_RECTS, FEATURE_FRAME_QUEUE,
+            FEATURE_HEARTBEAT,
+        },
+        liveness::{poll_timeout, Beating, PEER_CHECK_WAIT},
+        mem_queue::WriteMode,
+        slave_context::SlaveContext,
+        IpcError, MemContext, Segment,
+    },
+    transport::{
+        record::{IpcRecorder, RecordState},
+        IpcTransport,
+    },
+    IpcNode, IpcType,
 };
 use core::slice;
+use log::warn;
 use raw_sync::Timeout;
-use shared_memory::Shmem;
-use std::{collections::VecDeque, error::Error, ffi::c_void, sync::atomic::Ordering};
+use std::{
+    collections::VecDeque, error::Error, ffi::c_void, io, path::Path, sync::atomic::Ordering,
+    time::Duration,
+};
 use tlib::figure::Rect;
 
 pub struct IpcSlave<T: 'static + Copy, M: 'static + Copy> {
+    /// Declared first to stop beating before the shared memories were released.
+    _beating: Beating,
     slave_context: SlaveContext<T, M>,
-    retentions: VecDeque<Shmem>,
+    retentions: VecDeque<Segment>,
+    recorder: IpcRecorder<T, M>,
+    /// The received frames of other channels.
+    frames: FrameDemux,
+    /// The transport of events, frames and requests, `None` for the shared memory queues.
+    transport: Option<Box<dyn IpcTransport<T, M> + Send + Sync>>,
 }
 
 /// SAFETY: MemQueue and memory context use `Mutex` to ensure thread safety.
@@ -21,13 +44,84 @@ unsafe impl<T: 'static + Copy, M: 'static + Copy> Sync for IpcSlave<T, M> {}
 
 impl<T: 'static + Copy, M: 'static + Copy> IpcSlave<T, M> {
     /// The name should be same with the [`IpcMaster`]
-    pub fn new(name: &str) -> Self {
-        let slave_context = SlaveContext::open(name);
+    ///
+    /// Return the error if the master was not created, or it was built with
+    /// the incompatible protocol version, user event or request type.
+    #[inline]
+    pub fn new(name: &str) -> Result<Self, HandshakeError> {
+        Self::attach(SlaveContext::open(name)?, None)
+    }
 
-        Self {
+    /// Connect to the master through the transport, see
+    /// [`IpcBuilder::transport()`](crate::IpcBuilder::transport).
+    ///
+    /// The shared memories of master were mapped from the file descriptors passed by the
+    /// transport, they were opened by name only if the transport can not pass them.
+    pub(crate) fn with_transport(
+        name: &str,
+        transport: Box<dyn IpcTransport<T, M> + Send + Sync>,
+    ) -> Result<Self, HandshakeError> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let slave_context = SlaveContext::connect(name, transport.as_ref())?;
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let slave_context = SlaveContext::open(name)?;
+
+        Self::attach(slave_context, Some(transport))
+    }
+
+    fn attach(
+        slave_context: SlaveContext<T, M>,
+        transport: Option<Box<dyn IpcTransport<T, M> + Send + Sync>>,
+    ) -> Result<Self, HandshakeError> {
+        let sinfo = slave_context.shared_info();
+        sinfo.slave_heartbeat.attach();
+        sinfo.connected.store(true, Ordering::Release);
+        // SAFETY: The heartbeat was in the shared info, which was dropped after the `Beating`.
+        let beating = unsafe { Beating::start(&sinfo.slave_heartbeat) };
+
+        Ok(Self {
+            _beating: beating,
             slave_context,
             retentions: VecDeque::new(),
-        }
+            recorder: IpcRecorder::new(IpcType::Slave),
+            frames: FrameDemux::default(),
+            transport,
+        })
+    }
+
+    /// Take the [`IpcEvent::PeerLost`] once, only if the heartbeat was supported by both sides.
+    #[inline]
+    fn take_peer_lost_event(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.slave_context.take_peer_lost_event()
+    }
+
+    #[inline]
+    fn has_peer_lost_event(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.slave_context.has_peer_lost_event()
+    }
+}
+
+#[cfg(test)]
+impl<T: 'static + Copy, M: 'static + Copy> IpcSlave<T, M> {
+    /// Pretend the slave process has crashed, the master will find the slave lost.
+    #[inline]
+    pub(crate) fn simulate_crash(&self) {
+        self.slave_context.shared_info().slave_heartbeat.crash()
+    }
+
+    /// Pretend the slave was built with the `features` only.
+    #[inline]
+    pub(crate) fn simulate_features(&self, features: u64) {
+        self.slave_context
+            .shared_info()
+            .handshake
+            .restrict_features(features)
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> Drop for IpcSlave<T, M> {
+    fn drop(&mut self) {
+        self.terminate()
     }
 }
 
@@ -53,45 +147,159 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcSlave<T, M> {
     }
 
     #[inline]
-    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), MemQueueError> {
-        self.slave_context.try_send(evt)
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        evt.check_size()?;
+        self.transport().try_send(self.recorder.sent_event(evt))
     }
 
     #[inline]
     fn has_event(&self) -> bool {
-        self.slave_context.has_event()
+        self.transport().has_event() || self.has_peer_lost_event()
     }
 
     #[inline]
     fn try_recv(&self) -> Option<IpcEvent<T>> {
-        self.slave_context.try_recv()
+        self.transport()
+            .try_recv()
+            .or_else(|| self.take_peer_lost_event().then_some(IpcEvent::PeerLost))
+            .map(|evt| self.recorder.received_event(evt))
     }
 
     #[inline]
     fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
-        self.slave_context
-            .try_recv_vec()
-            .into_iter()
-            .collect()
+        let mut events = self.transport().try_recv_vec();
+        if self.take_peer_lost_event() {
+            events.push(IpcEvent::PeerLost)
+        }
+        self.recorder.received_events(events)
+    }
+
+    fn recv(&self) -> Option<IpcEvent<T>> {
+        loop {
+            if let Some(evt) = self.recv_timeout(PEER_CHECK_WAIT) {
+                return Some(evt);
+            }
+            // `IpcEvent::PeerLost` has been received, nothing will come from the lost peer.
+            if self.is_peer_lost() {
+                return None;
+            }
+        }
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        let evt = if self.has_feature(FEATURE_BLOCKING_QUEUE) {
+            self.transport().recv_timeout(timeout)
+        } else {
+            // The peer does not notify the readable, poll the events instead.
+            poll_timeout(timeout, || self.transport().try_recv())
+        };
+        evt.or_else(|| self.take_peer_lost_event().then_some(IpcEvent::PeerLost))
+            .map(|evt| self.recorder.received_event(evt))
+    }
+
+    #[inline]
+    fn set_write_mode(&self, write_mode: WriteMode) {
+        if write_mode != WriteMode::Abort && !self.has_feature(FEATURE_BLOCKING_QUEUE) {
+            warn!("The peer does not support the blocking queue, `WriteMode::Abort` was kept.");
+            return;
+        }
+        self.slave_context.set_write_mode(write_mode)
+    }
+
+    #[inline]
+    fn heartbeat(&self) {
+        self.slave_context.heartbeat()
+    }
+
+    #[inline]
+    fn is_peer_lost(&self) -> bool {
+        self.has_feature(FEATURE_HEARTBEAT) && self.slave_context.is_peer_lost()
     }
 
     #[inline]
+    fn set_peer_timeout(&self, timeout: Duration) {
+        self.slave_context.set_peer_timeout(timeout)
+    }
+
+    #[inline]
+    fn features(&self) -> u64 {
+        self.transport().features()
+    }
+
+    #[inline]
+    fn start_recording(&self, path: &Path) -> io::Result<()> {
+        let state = RecordState {
+            width: self.width(),
+            height: self.height(),
+            regions: self.slave_context.region_table().entries(),
+        };
+        self.recorder.start(path, self.features(), state)
+    }
+
+    #[inline]
+    fn stop_recording(&self) {
+        self.recorder.stop()
+    }
+
+    #[inline]
+    fn try_send_channel_frame(
+        &self,
+        channel: FrameChannel,
+        frame: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.has_feature(FEATURE_FRAME_QUEUE) {
+            return Err(Box::new(IpcError::new(
+                "The peer does not support the frame queue, frame was aborted.",
+            )));
+        }
+        self.transport()
+            .try_send_frame(&FrameDemux::tag(channel, frame))
+    }
+
+    #[inline]
+    fn has_channel_frame(&self, channel: FrameChannel) -> bool {
+        self.has_feature(FEATURE_FRAME_QUEUE)
+            && self
+                .frames
+                .has(channel, || self.transport().try_recv_frame())
+    }
+
+    #[inline]
+    fn try_recv_channel_frame(&self, channel: FrameChannel) -> Option<Vec<u8>> {
+        if !self.has_feature(FEATURE_FRAME_QUEUE) {
+            return None;
+        }
+        self.frames
+            .recv(channel, || self.transport().try_recv_frame())
+    }
+
     fn send_request(&self, rqst: M) -> Result<Option<M>, Box<dyn Error>> {
-        self.slave_context.send_request(rqst)
+        self.recorder.request(true, &rqst);
+        let resp = self.transport().send_request(rqst)?;
+        self.recorder.response(false, &resp);
+        Ok(resp)
     }
 
     #[inline]
     fn try_recv_request(&self) -> Option<M> {
-        self.slave_context.try_recv_request()
+        let rqst = self.transport().try_recv_request()?;
+        self.recorder.request(false, &rqst);
+        Some(rqst)
     }
 
     #[inline]
     fn respose_request(&self, resp: Option<M>) {
-        self.slave_context.response_request(resp)
+        self.recorder.response(true, &resp);
+        self.transport().response_request(resp)
     }
 
     #[inline]
-    fn terminate(&self) {}
+    fn terminate(&self) {
+        let sinfo = self.slave_context.shared_info();
+        sinfo.connected.store(false, Ordering::Release);
+        sinfo.slave_heartbeat.detach();
+    }
 
     #[inline]
     fn wait(&self, timeout: Timeout) {
@@ -104,18 +312,8 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcSlave<T, M> {
     }
 
     #[inline]
-    fn region(&self, id: &'static str) -> Option<Rect> {
-        let id = generate_u128(id).unwrap_or_else(|| panic!("Invalid id: {}", id));
-
-        let shared_info = self.slave_context.shared_info();
-        let idx = shared_info.region_idx.load(Ordering::Acquire);
-        for uninit in shared_info.regions[..idx].iter() {
-            let (sid, r) = unsafe { uninit.assume_init_ref() };
-            if *sid == id {
-                return Some(*r);
-            }
-        }
-        None
+    fn region(&self, id: &str) -> Option<Rect> {
+        self.slave_context.region_table().get(id)
     }
 
     #[inline]
@@ -150,9 +348,20 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcSlave<T, M> {
 
     #[inline]
     fn recreate_buffer(&mut self) {
-        if let Some(old) = self.slave_context.recreate_buffer() {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let old = match self.transport {
+            Some(ref transport) if self.slave_context.is_mapped() => {
+                self.slave_context.remap_buffer(transport.as_ref())
+            }
+            _ => self.slave_context.recreate_buffer(),
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let old = self.slave_context.recreate_buffer();
+
+        if let Some(old) = old {
             self.retentions.push_back(old);
         }
+        self.set_full_dirty();
     }
 
     fn release_retention(&mut self) {
@@ -178,6 +387,28 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcNode<T, M> for IpcSlave<T, M> {
             .invalidate
             .store(invalidate, Ordering::Release)
     }
+
+    #[inline]
+    fn add_dirty_rect(&self, rect: Rect) {
+        if self.has_feature(FEATURE_DAMAGE_RECTS) {
+            self.slave_context.shared_info().damage.add(rect)
+        }
+    }
+
+    #[inline]
+    fn set_full_dirty(&self) {
+        if self.has_feature(FEATURE_DAMAGE_RECTS) {
+            self.slave_context.shared_info().damage.set_full()
+        }
+    }
+
+    #[inline]
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>> {
+        if !self.has_feature(FEATURE_DAMAGE_RECTS) {
+            return None;
+        }
+        self.slave_context.shared_info().damage.take()
+    }
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> IpcSlave<T, M> {
@@ -188,4 +419,12 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcSlave<T, M> {
             .prepared
             .store(true, Ordering::Release);
     }
+
+    #[inline]
+    fn transport(&self) -> &dyn IpcTransport<T, M> {
+        match self.transport {
+            Some(ref transport) => transport.as_ref(),
+            None => &self.slave_context,
+        }
+    }
 }
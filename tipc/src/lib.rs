@@ -1,17 +1,34 @@
 use ipc_event::IpcEvent;
+use ipc_hub::IpcHub;
 use ipc_master::IpcMaster;
 use ipc_slave::IpcSlave;
 use lazy_static::lazy_static;
-use mem::{mem_queue::MemQueueError, mem_rw_lock::MemRwLock};
+use mem::{
+    frame_channel::FrameChannel,
+    frame_codec::{FrameCodec, FrameCodecError},
+    handshake::HandshakeError,
+    mem_queue::WriteMode,
+    mem_rw_lock::MemRwLock,
+    region_table::DEFAULT_MAX_REGIONS,
+    IPC_CHANNEL_SEP,
+};
 use nohash_hasher::IntMap;
 use raw_sync::Timeout;
-use std::{error::Error, ffi::c_void, marker::PhantomData, sync::Arc};
+use std::{
+    error::Error, ffi::c_void, io, marker::PhantomData, path::Path, sync::Arc, time::Duration,
+};
 use tlib::figure::Rect;
+use transport::{
+    record::{IpcReplayer, ReplayMode},
+    IpcTransport,
+};
 
 pub mod ipc_event;
+pub mod ipc_hub;
 pub mod ipc_master;
 pub mod ipc_slave;
 pub mod mem;
+pub mod transport;
 
 pub mod parking_lot {
     pub use parking_lot::*;
@@ -33,6 +50,9 @@ pub enum IpcType {
 
 pub struct IpcBuilder<T: 'static + Copy, M: 'static + Copy> {
     name: Option<String>,
+    channel: Option<String>,
+    max_regions: usize,
+    transport: Option<Box<dyn IpcTransport<T, M> + Send + Sync>>,
     _user_event: PhantomData<T>,
     _request_response: PhantomData<M>,
 }
@@ -56,6 +76,9 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcBuilder<T, M> {
     pub fn with_customize() -> Self {
         Self {
             name: None,
+            channel: None,
+            max_regions: DEFAULT_MAX_REGIONS,
+            transport: None,
             _user_event: Default::default(),
             _request_response: Default::default(),
         }
@@ -67,28 +90,83 @@ impl<T: 'static + Copy, M: 'static + Copy> IpcBuilder<T, M> {
         self
     }
 
+    /// Connect to the named channel of [`IpcHub`], which has the same `name`.
     #[inline]
-    pub fn master(self) -> IpcMaster<T, M> {
-        IpcMaster::<T, M>::new(
-            self.name
-                .as_ref()
-                .expect("Build IpcMaster require `name` not none."),
-        )
+    pub fn channel(mut self, channel: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self
     }
 
+    /// The maximum count of shared regions the master can hold, the default was
+    /// [`DEFAULT_MAX_REGIONS`](mem::region_table::DEFAULT_MAX_REGIONS).
+    ///
+    /// It only works on the master side, the slave reads it from the master.
     #[inline]
-    pub fn slave(self) -> IpcSlave<T, M> {
-        IpcSlave::<T, M>::new(
+    pub fn max_regions(mut self, max_regions: usize) -> Self {
+        self.max_regions = max_regions;
+        self
+    }
+
+    /// Transfer the events, frames and requests through the transport instead of the
+    /// shared memory queues, e.g. [`UdsTransport`](transport::uds::UdsTransport).
+    ///
+    /// The shared memories of master were passed to the slave by the file descriptors
+    /// if the transport can pass them, e.g. [`UdsTransport`](transport::uds::UdsTransport)
+    /// on linux, so the slave never opens them by name, otherwise they were opened by name.
+    /// Both master and slave should be built with the connected transports.
+    #[inline]
+    pub fn transport(mut self, transport: impl IpcTransport<T, M> + Send + Sync + 'static) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    #[inline]
+    pub fn master(self) -> Result<IpcMaster<T, M>, HandshakeError> {
+        let mut master = IpcMaster::<T, M>::with_max_regions(
+            &self.full_name("Build IpcMaster require `name` not none."),
+            self.max_regions,
+        )?;
+        master.set_transport(self.transport);
+        Ok(master)
+    }
+
+    /// Connect to the master, return the error if the handshake failed.
+    #[inline]
+    pub fn slave(self) -> Result<IpcSlave<T, M>, HandshakeError> {
+        let name = self.full_name("Build IpcSlave require `name` not none.");
+        match self.transport {
+            Some(transport) => IpcSlave::<T, M>::with_transport(&name, transport),
+            None => IpcSlave::<T, M>::new(&name),
+        }
+    }
+
+    /// Build the master side which serves multiple slaves, the `channel` and `transport` were ignored.
+    #[inline]
+    pub fn hub(self) -> IpcHub<T, M> {
+        IpcHub::<T, M>::with_max_regions(
             self.name
                 .as_ref()
-                .expect("Build IpcSlave require `name` not none."),
+                .expect("Build IpcHub require `name` not none."),
+            self.max_regions,
         )
     }
+
+    #[inline]
+    fn full_name(&self, expect: &str) -> String {
+        let name = self.name.as_ref().expect(expect);
+        match self.channel {
+            Some(ref channel) => channel_name(name, channel),
+            None => name.to_string(),
+        }
+    }
 }
 
 pub trait WithIpcMaster<T: 'static + Copy, M: 'static + Copy> {
     fn create_ipc_master(name: &'static str) -> IpcMaster<T, M> {
-        IpcBuilder::<T, M>::with_customize().name(name).master()
+        IpcBuilder::<T, M>::with_customize()
+            .name(name)
+            .master()
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
     fn with_ipc_master(&mut self, name: &'static str) {
@@ -100,13 +178,37 @@ pub trait WithIpcMaster<T: 'static + Copy, M: 'static + Copy> {
 
 pub trait WithIpcSlave<T: 'static + Copy, M: 'static + Copy> {
     fn create_ipc_slave(name: &'static str) -> IpcSlave<T, M> {
-        IpcBuilder::<T, M>::with_customize().name(name).slave()
+        IpcBuilder::<T, M>::with_customize()
+            .name(name)
+            .slave()
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
     fn with_ipc_slave(&mut self, name: &'static str) {
         self.proc_ipc_slave(Self::create_ipc_slave(name))
     }
 
+    /// Connect to the named channel of [`IpcHub`] served by the master.
+    fn with_ipc_slave_channel(&mut self, name: &'static str, channel: &'static str) {
+        let slave = IpcBuilder::<T, M>::with_customize()
+            .name(name)
+            .channel(channel)
+            .slave()
+            .unwrap_or_else(|e| panic!("{}", e));
+        self.proc_ipc_slave(slave)
+    }
+
+    /// Replay the file recorded by [`IpcNode::start_recording`] without the master, see [`IpcReplayer`].
+    fn with_ipc_replay(&mut self, name: &'static str, path: &Path, mode: ReplayMode) {
+        let replayer = IpcReplayer::<T, M>::open(path, mode).unwrap_or_else(|e| panic!("{}", e));
+        let slave = IpcBuilder::<T, M>::with_customize()
+            .name(name)
+            .transport(replayer)
+            .slave()
+            .unwrap_or_else(|e| panic!("{}", e));
+        self.proc_ipc_slave(slave)
+    }
+
     fn proc_ipc_slave(&mut self, slave: IpcSlave<T, M>);
 }
 
@@ -117,7 +219,7 @@ pub trait IpcNode<T: 'static + Copy, M: 'static + Copy> {
 
     fn buffer_raw_pointer(&self) -> *mut c_void;
 
-    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), MemQueueError>;
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>>;
 
     fn has_event(&self) -> bool;
 
@@ -125,6 +227,101 @@ pub trait IpcNode<T: 'static + Copy, M: 'static + Copy> {
 
     fn try_recv_vec(&self) -> Vec<IpcEvent<T>>;
 
+    /// Block until an event was received, instead of polling with [`try_recv`](Self::try_recv).
+    ///
+    /// @return `None` once the peer was lost, after [`IpcEvent::PeerLost`] was received.
+    fn recv(&self) -> Option<IpcEvent<T>>;
+
+    /// Block until an event was received or timeout.
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>>;
+
+    /// Set the behavior of [`try_send`](Self::try_send) when the event queue was full.
+    fn set_write_mode(&self, write_mode: WriteMode);
+
+    /// Beat the heartbeat of this side.
+    ///
+    /// The heartbeat was beaten by the background thread periodically, so the idle process
+    /// will not be regarded as lost, it was not necessary to call it manually.
+    fn heartbeat(&self);
+
+    /// Whether the peer process has exited abnormally, or its heartbeat was timeout.
+    ///
+    /// [`IpcEvent::PeerLost`] will be received once when the peer was lost.
+    fn is_peer_lost(&self) -> bool;
+
+    /// Set the timeout of the peer's heartbeat, the default was
+    /// [`DEFAULT_PEER_TIMEOUT`](mem::liveness::DEFAULT_PEER_TIMEOUT).
+    fn set_peer_timeout(&self, timeout: Duration);
+
+    /// The feature flags supported by both master and slave, see [`mem::handshake`].
+    fn features(&self) -> u64;
+
+    /// Whether the `feature` was supported by both master and slave.
+    #[inline]
+    fn has_feature(&self, feature: u64) -> bool {
+        self.features() & feature == feature
+    }
+
+    /// Record the events, requests and responses passing through this node into the file
+    /// with their timestamps, the recording was stopped when the node dropped.
+    ///
+    /// The file can be replayed into the slave without the master by
+    /// [`IpcReplayer`](transport::record::IpcReplayer).
+    fn start_recording(&self, path: &Path) -> io::Result<()>;
+
+    fn stop_recording(&self);
+
+    /// Send the variable-length frame on the `channel`, the frame will be aborted if the queue
+    /// has no enough space.
+    fn try_send_channel_frame(
+        &self,
+        channel: FrameChannel,
+        frame: &[u8],
+    ) -> Result<(), Box<dyn Error>>;
+
+    fn has_channel_frame(&self, channel: FrameChannel) -> bool;
+
+    fn try_recv_channel_frame(&self, channel: FrameChannel) -> Option<Vec<u8>>;
+
+    /// Send the variable-length frame of application, the frame will be aborted if the queue
+    /// has no enough space.
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.try_send_channel_frame(FrameChannel::User, frame)
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        self.has_channel_frame(FrameChannel::User)
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        self.try_recv_channel_frame(FrameChannel::User)
+    }
+
+    /// Encode the message with the codec, and send it as a frame.
+    fn try_send_message<X, C: FrameCodec<X>>(
+        &self,
+        codec: &C,
+        msg: &X,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        Self: Sized,
+    {
+        let frame = codec.encode(msg)?;
+        self.try_send_frame(&frame)?;
+        Ok(())
+    }
+
+    /// Receive a frame and decode it to the message with the codec.
+    fn try_recv_message<X, C: FrameCodec<X>>(&self, codec: &C) -> Option<Result<X, FrameCodecError>>
+    where
+        Self: Sized,
+    {
+        self.try_recv_frame().map(|frame| codec.decode(&frame))
+    }
+
     fn send_request(&self, rqst: M) -> Result<Option<M>, Box<dyn Error>>;
 
     fn try_recv_request(&self) -> Option<M>;
@@ -137,7 +334,8 @@ pub trait IpcNode<T: 'static + Copy, M: 'static + Copy> {
 
     fn signal(&self);
 
-    fn region(&self, id: &'static str) -> Option<Rect>;
+    /// Get the shared region added by [`IpcMaster::add_rect()`].
+    fn region(&self, id: &str) -> Option<Rect>;
 
     fn width(&self) -> u32;
 
@@ -158,6 +356,17 @@ pub trait IpcNode<T: 'static + Copy, M: 'static + Copy> {
     fn is_invalidate(&self) -> bool;
 
     fn set_invalidate(&self, invalidate: bool);
+
+    /// Add the dirty rect of shared buffer, called by the slave after rendering.
+    fn add_dirty_rect(&self, rect: Rect);
+
+    /// Mark the whole shared buffer was dirty.
+    fn set_full_dirty(&self);
+
+    /// Take the dirty rects since the last taking, called by the master before copying the shared buffer.
+    ///
+    /// @return `None` if the whole shared buffer was dirty.
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>>;
 }
 
 lazy_static! {
@@ -189,6 +398,12 @@ lazy_static! {
     };
 }
 
+/// Get the shared memory name of the channel of [`IpcHub`].
+#[inline]
+pub fn channel_name(name: &str, channel: &str) -> String {
+    format!("{}{}{}", name, IPC_CHANNEL_SEP, channel)
+}
+
 #[inline]
 pub fn generate_u128(input: &str) -> Option<u128> {
     let bytes = input.as_bytes();
@@ -204,7 +419,20 @@ pub fn generate_u128(input: &str) -> Option<u128> {
 
 #[cfg(test)]
 mod tests {
-    use crate::generate_u128;
+    use crate::{
+        generate_u128,
+        ipc_event::{IpcEvent, IPC_TEXT_EVT_SIZE},
+        mem::{
+            frame_channel::FrameChannel, frame_codec::StringCodec, handshake::FEATURE_HEARTBEAT,
+            region_table::MAX_REGION_ID_LEN,
+        },
+        IpcBuilder, IpcNode,
+    };
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+    use tlib::figure::Rect;
 
     #[test]
     fn test_generate_u128() {
@@ -216,4 +444,178 @@ mod tests {
 
         assert!(res1.unwrap() != res2.unwrap());
     }
+
+    #[test]
+    fn test_wait_prepared_timeout() {
+        let name = format!("_tipc_wait_prepared_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        master.set_peer_timeout(Duration::from_millis(100));
+        master.pretreat_resize(20, 20);
+        master.recreate_buffer();
+
+        // The slave was never attached to prepare the resized buffer.
+        let start = Instant::now();
+        assert!(!master.wait_prepared());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_add_rect_limits() {
+        let name = format!("_tipc_add_rect_test_{}", std::process::id());
+        let master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .max_regions(1)
+            .master()
+            .unwrap();
+
+        let rect = Rect::new(0, 0, 5, 5);
+        assert!(master.add_rect("a", rect));
+        // Update the existing region when the table was full.
+        assert!(master.add_rect("a", Rect::new(1, 1, 5, 5)));
+        // The errors were reported instead of panicked.
+        assert!(!master.add_rect("b", rect));
+        assert!(!master.add_rect(&"c".repeat(MAX_REGION_ID_LEN + 1), rect));
+        assert_eq!(master.region_count(), 1);
+    }
+
+    #[test]
+    fn test_idle_peer_alive() {
+        let name = format!("_tipc_idle_peer_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        let slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .slave()
+            .unwrap();
+        master.set_peer_timeout(Duration::from_millis(300));
+        slave.set_peer_timeout(Duration::from_millis(300));
+
+        // Neither side polls the events, the heartbeats were beaten in background.
+        thread::sleep(Duration::from_millis(900));
+        assert!(!master.is_peer_lost());
+        assert!(!slave.is_peer_lost());
+    }
+
+    #[test]
+    fn test_recv_peer_lost() {
+        let name = format!("_tipc_recv_peer_lost_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        let slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .slave()
+            .unwrap();
+
+        slave.try_send(IpcEvent::UserEvent(1, Instant::now())).unwrap();
+        assert!(matches!(master.recv(), Some(IpcEvent::UserEvent(1, _))));
+
+        slave.simulate_crash();
+        assert!(matches!(master.recv(), Some(IpcEvent::PeerLost)));
+        assert!(master.recv().is_none());
+    }
+
+    #[test]
+    fn test_file_and_clipboard_events() {
+        let name = format!("_tipc_file_clipboard_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        let slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .slave()
+            .unwrap();
+
+        // The trailing NULs were kept.
+        let path = "/tmp/file\0\0".to_string();
+        master
+            .try_send(IpcEvent::DroppedFile(path.clone(), Instant::now()))
+            .unwrap();
+        assert!(matches!(slave.try_recv(), Some(IpcEvent::DroppedFile(p, _)) if p == path));
+
+        // The path exceeds the limit was rejected instead of panicked.
+        let long_path = "p".repeat(IPC_TEXT_EVT_SIZE + 1);
+        assert!(master
+            .try_send(IpcEvent::HoveredFile(long_path, Instant::now()))
+            .is_err());
+        assert!(slave.try_recv().is_none());
+
+        // The clipboard text exceeds the limit of event was sent as one frame on its own channel,
+        // never interleaved with the frames of application.
+        let text = format!("{}\0", "clipboard text ".repeat(1000));
+        master.try_send_frame(b"user frame").unwrap();
+        master
+            .try_send_channel_frame(FrameChannel::Clipboard, text.as_bytes())
+            .unwrap();
+        master.try_send(IpcEvent::ClipboardText(1)).unwrap();
+        assert!(matches!(slave.try_recv(), Some(IpcEvent::ClipboardText(1))));
+        assert!(slave.has_channel_frame(FrameChannel::Clipboard));
+        assert_eq!(
+            slave
+                .try_recv_channel_frame(FrameChannel::Clipboard)
+                .unwrap(),
+            text.as_bytes()
+        );
+        assert_eq!(slave.try_recv_frame().unwrap(), b"user frame");
+        assert!(!slave.has_frame());
+
+        slave.try_send_message(&StringCodec, &text).unwrap();
+        slave
+            .try_send_channel_frame(FrameChannel::Clipboard, text.as_bytes())
+            .unwrap();
+        slave.try_send(IpcEvent::ClipboardSet).unwrap();
+        assert!(matches!(master.try_recv(), Some(IpcEvent::ClipboardSet)));
+        assert_eq!(
+            master
+                .try_recv_channel_frame(FrameChannel::Clipboard)
+                .unwrap(),
+            text.as_bytes()
+        );
+        assert_eq!(
+            master.try_recv_message(&StringCodec).unwrap().unwrap(),
+            text
+        );
+    }
+
+    #[test]
+    fn test_unsupported_features() {
+        let name = format!("_tipc_unsupported_features_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        let slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .slave()
+            .unwrap();
+        slave.simulate_features(FEATURE_HEARTBEAT);
+        assert_eq!(master.features(), FEATURE_HEARTBEAT);
+
+        assert!(slave.try_send_frame(&[1, 2, 3]).is_err());
+        assert!(!master.has_frame());
+        assert!(master.try_recv_frame().is_none());
+
+        slave.add_dirty_rect(Rect::new(0, 0, 5, 5));
+        slave.set_full_dirty();
+        assert!(master.take_dirty_rects().is_none());
+
+        // The events were polled without the blocking queue.
+        slave.try_send(IpcEvent::UserEvent(1, Instant::now())).unwrap();
+        assert!(matches!(
+            master.recv_timeout(Duration::from_millis(100)),
+            Some(IpcEvent::UserEvent(1, _))
+        ));
+    }
 }
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tlib::figure::Rect;
+
+/// The maximum count of dirty rects recorded in one frame,
+/// the rects will be merged to their bounding rect when exceeded.
+pub(crate) const MAX_DIRTY_RECTS: usize = 16;
+
+/// The dirty rects of shared buffer in shared memory.
+///
+/// The rects were added by the slave under the write lock of shared buffer,
+/// and taken by the master under the read lock of shared buffer,
+/// so they will never be accessed by both sides at the same time.
+#[repr(C)]
+pub(crate) struct Damage {
+    rects: [Rect; MAX_DIRTY_RECTS],
+    len: AtomicUsize,
+    /// The whole shared buffer was dirty.
+    full: AtomicBool,
+}
+
+impl Damage {
+    pub(crate) fn add(&mut self, rect: Rect) {
+        if !rect.is_valid() || self.full.load(Ordering::Acquire) {
+            return;
+        }
+
+        let len = self.len.load(Ordering::Acquire);
+        if let Some(r) = self.rects[..len]
+            .iter_mut()
+            .find(|r| r.is_intersects(&rect))
+        {
+            r.or(&rect);
+            return;
+        }
+
+        if len < MAX_DIRTY_RECTS {
+            self.rects[len] = rect;
+            self.len.store(len + 1, Ordering::Release);
+        } else {
+            let bounding = self.rects.iter().fold(rect, |acc, r| acc.union(r));
+            self.rects[0] = bounding;
+            self.len.store(1, Ordering::Release);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_full(&self) {
+        self.full.store(true, Ordering::Release)
+    }
+
+    /// Take the dirty rects since the last taking.
+    ///
+    /// @return `None` if the whole shared buffer was dirty.
+    pub(crate) fn take(&mut self) -> Option<Vec<Rect>> {
+        let len = self.len.swap(0, Ordering::AcqRel);
+        if self.full.swap(false, Ordering::AcqRel) {
+            return None;
+        }
+        Some(self.rects[..len].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_damage() {
+        let mut damage = Damage {
+            rects: [Rect::default(); MAX_DIRTY_RECTS],
+            len: AtomicUsize::new(0),
+            full: AtomicBool::new(false),
+        };
+
+        damage.add(Rect::new(0, 0, 10, 10));
+        damage.add(Rect::new(5, 5, 10, 10));
+        damage.add(Rect::new(100, 100, 2, 2));
+        damage.add(Rect::new(100, 100, 0, 0));
+        assert_eq!(
+            damage.take(),
+            Some(vec![Rect::new(0, 0, 15, 15), Rect::new(100, 100, 2, 2)])
+        );
+        assert_eq!(damage.take(), Some(vec![]));
+
+        for i in 0..=MAX_DIRTY_RECTS as i32 {
+            damage.add(Rect::new(i * 10, 0, 5, 5));
+        }
+        assert_eq!(
+            damage.take(),
+            Some(vec![Rect::new(0, 0, MAX_DIRTY_RECTS as i32 * 10 + 5, 5)])
+        );
+
+        damage.add(Rect::new(0, 0, 10, 10));
+        damage.set_full();
+        damage.add(Rect::new(20, 20, 10, 10));
+        assert_eq!(damage.take(), None);
+        assert_eq!(damage.take(), Some(vec![]));
+    }
+}
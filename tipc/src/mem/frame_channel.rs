@@ -0,0 +1,125 @@
+use log::warn;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+const CHANNEL_COUNT: usize = 2;
+
+/// The channel of frames, the frames of framework and application were sent through the same
+/// frame queue, but never received from each other's channel.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameChannel {
+    /// The frames of application, sent by [`IpcNode::try_send_frame()`](crate::IpcNode::try_send_frame).
+    User = 0,
+    /// Reserved for the os clipboard brokered by the master.
+    Clipboard,
+}
+
+impl FrameChannel {
+    #[inline]
+    fn from_u8(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::User),
+            1 => Some(Self::Clipboard),
+            _ => None,
+        }
+    }
+}
+
+/// Dispatch the received frames by the channel tag prefixed, the frames of other channels
+/// were kept until they were received from their own channel.
+#[derive(Default)]
+pub(crate) struct FrameDemux {
+    pending: Mutex<[VecDeque<Vec<u8>>; CHANNEL_COUNT]>,
+}
+
+impl FrameDemux {
+    /// Prefix the frame with the tag of `channel`.
+    #[inline]
+    pub(crate) fn tag(channel: FrameChannel, frame: &[u8]) -> Vec<u8> {
+        let mut tagged = Vec::with_capacity(frame.len() + 1);
+        tagged.push(channel as u8);
+        tagged.extend_from_slice(frame);
+        tagged
+    }
+
+    /// Receive the frames by `recv` until a frame of `channel` was received.
+    pub(crate) fn recv(
+        &self,
+        channel: FrameChannel,
+        mut recv: impl FnMut() -> Option<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        let mut pending = self.pending.lock();
+        if let Some(frame) = pending[channel as usize].pop_front() {
+            return Some(frame);
+        }
+
+        while let Some(mut frame) = recv() {
+            match frame.first().copied().and_then(FrameChannel::from_u8) {
+                Some(tag) => {
+                    frame.remove(0);
+                    if tag == channel {
+                        return Some(frame);
+                    }
+                    pending[tag as usize].push_back(frame)
+                }
+                None => warn!("Ipc => the frame without valid channel tag was dropped."),
+            }
+        }
+        None
+    }
+
+    /// Whether there is a frame of `channel`, the frames of other channels were kept.
+    pub(crate) fn has(&self, channel: FrameChannel, recv: impl FnMut() -> Option<Vec<u8>>) -> bool {
+        match self.recv(channel, recv) {
+            Some(frame) => {
+                self.pending.lock()[channel as usize].push_front(frame);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_demux() {
+        let mut queue: VecDeque<Vec<u8>> = [
+            FrameDemux::tag(FrameChannel::User, b"user 1"),
+            FrameDemux::tag(FrameChannel::Clipboard, b"clipboard"),
+            vec![u8::MAX, 1, 2],
+            FrameDemux::tag(FrameChannel::User, b"user 2"),
+        ]
+        .into();
+
+        let demux = FrameDemux::default();
+        assert!(demux.has(FrameChannel::Clipboard, || queue.pop_front()));
+        assert_eq!(
+            demux
+                .recv(FrameChannel::Clipboard, || queue.pop_front())
+                .unwrap(),
+            b"clipboard"
+        );
+        assert!(demux
+            .recv(FrameChannel::Clipboard, || queue.pop_front())
+            .is_none());
+
+        // The frames of user were kept in order, the invalid frame was dropped.
+        assert_eq!(
+            demux
+                .recv(FrameChannel::User, || queue.pop_front())
+                .unwrap(),
+            b"user 1"
+        );
+        assert_eq!(
+            demux
+                .recv(FrameChannel::User, || queue.pop_front())
+                .unwrap(),
+            b"user 2"
+        );
+        assert!(!demux.has(FrameChannel::User, || queue.pop_front()));
+    }
+}
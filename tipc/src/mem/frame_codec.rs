@@ -0,0 +1,112 @@
+use std::{error::Error, fmt::Display};
+
+/// The codec to convert the messages from/to the frames of
+/// [`MemFrameQueue`](super::mem_frame_queue::MemFrameQueue).
+///
+/// Implement this trait to send customized messages, for example:
+///
+/// ```ignore
+/// struct BincodeCodec;
+///
+/// impl<T: Serialize + DeserializeOwned> FrameCodec<T> for BincodeCodec {
+///     fn encode(&self, msg: &T) -> Result<Vec<u8>, FrameCodecError> {
+///         bincode::serialize(msg).map_err(FrameCodecError::new)
+///     }
+///
+///     fn decode(&self, frame: &[u8]) -> Result<T, FrameCodecError> {
+///         bincode::deserialize(frame).map_err(FrameCodecError::new)
+///     }
+/// }
+/// ```
+pub trait FrameCodec<T> {
+    fn encode(&self, msg: &T) -> Result<Vec<u8>, FrameCodecError>;
+
+    fn decode(&self, frame: &[u8]) -> Result<T, FrameCodecError>;
+}
+
+/// The codec transfer the raw bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BytesCodec;
+
+impl FrameCodec<Vec<u8>> for BytesCodec {
+    #[inline]
+    fn encode(&self, msg: &Vec<u8>) -> Result<Vec<u8>, FrameCodecError> {
+        Ok(msg.clone())
+    }
+
+    #[inline]
+    fn decode(&self, frame: &[u8]) -> Result<Vec<u8>, FrameCodecError> {
+        Ok(frame.to_vec())
+    }
+}
+
+/// The codec transfer the utf-8 text.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StringCodec;
+
+impl FrameCodec<String> for StringCodec {
+    #[inline]
+    fn encode(&self, msg: &String) -> Result<Vec<u8>, FrameCodecError> {
+        Ok(msg.as_bytes().to_vec())
+    }
+
+    #[inline]
+    fn decode(&self, frame: &[u8]) -> Result<String, FrameCodecError> {
+        String::from_utf8(frame.to_vec()).map_err(FrameCodecError::new)
+    }
+}
+
+/// The codec transfer the serde-encoded structs in json.
+#[cfg(feature = "serde")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FrameCodec<T> for JsonCodec {
+    #[inline]
+    fn encode(&self, msg: &T) -> Result<Vec<u8>, FrameCodecError> {
+        serde_json::to_vec(msg).map_err(FrameCodecError::new)
+    }
+
+    #[inline]
+    fn decode(&self, frame: &[u8]) -> Result<T, FrameCodecError> {
+        serde_json::from_slice(frame).map_err(FrameCodecError::new)
+    }
+}
+
+#[derive(Debug)]
+pub struct FrameCodecError {
+    msg: String,
+}
+impl FrameCodecError {
+    pub fn new(msg: impl ToString) -> Self {
+        Self {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl Error for FrameCodecError {}
+
+impl Display for FrameCodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_codec() {
+        let text = "long pasted text ".repeat(1000);
+        let frame = StringCodec.encode(&text).unwrap();
+        assert_eq!(StringCodec.decode(&frame).unwrap(), text);
+        assert!(StringCodec.decode(&[0xff, 0xfe]).is_err());
+
+        let bytes = vec![0u8, 1, 2, 255];
+        let frame = BytesCodec.encode(&bytes).unwrap();
+        assert_eq!(BytesCodec.decode(&frame).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,260 @@
+use super::SharedInfo;
+use crate::{
+    ipc_event::InnerIpcEvent,
+    transport::wire::{Decoder, Encoder},
+};
+use std::{
+    error::Error,
+    fmt::Display,
+    mem::{align_of, size_of},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The version of the shared memory protocol, increase it when the layout of
+/// the shared memories or the semantics of the protocol were changed.
+pub const IPC_PROTOCOL_VERSION: u32 = 5;
+
+/// The master and slave support the variable-length frame queue.
+pub const FEATURE_FRAME_QUEUE: u64 = 1;
+/// The master and slave support the blocking receive and write backpressure of event queue.
+pub const FEATURE_BLOCKING_QUEUE: u64 = 1 << 1;
+/// The master and slave support the heartbeat and peer lost detection.
+pub const FEATURE_HEARTBEAT: u64 = 1 << 2;
+/// The slave reports the dirty rects of shared buffer, the master only copies them.
+pub const FEATURE_DAMAGE_RECTS: u64 = 1 << 3;
+
+/// The features supported by this build.
+pub const IPC_FEATURES: u64 =
+    FEATURE_FRAME_QUEUE | FEATURE_BLOCKING_QUEUE | FEATURE_HEARTBEAT | FEATURE_DAMAGE_RECTS;
+
+/// Identify the shared info segment was created by tipc master.
+const HANDSHAKE_MAGIC: u32 = 0x7469_7063;
+
+/// The size of the handshake record encoded by [`Handshake::encode()`].
+pub(crate) const HANDSHAKE_SIZE: usize = 7 * size_of::<u32>() + size_of::<u64>();
+
+/// The layout of the types shared between the master and slave,
+/// the master and slave must be built with the same user event and request types.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub event_size: u32,
+    pub event_align: u32,
+    pub request_size: u32,
+    pub request_align: u32,
+    pub shared_info_size: u32,
+}
+
+impl Fingerprint {
+    #[inline]
+    pub(crate) fn of<T: 'static + Copy, M: 'static + Copy>() -> Self {
+        Self {
+            event_size: size_of::<InnerIpcEvent<T>>() as u32,
+            event_align: align_of::<InnerIpcEvent<T>>() as u32,
+            request_size: size_of::<M>() as u32,
+            request_align: align_of::<M>() as u32,
+            shared_info_size: size_of::<SharedInfo<M>>() as u32,
+        }
+    }
+}
+
+/// The handshake record at the beginning of the shared info segment,
+/// it was written by the master and verified by the slave.
+#[repr(C)]
+pub(crate) struct Handshake {
+    magic: u32,
+    version: u32,
+    fingerprint: Fingerprint,
+    /// The features supported by master, it was replaced by the features supported
+    /// by both sides when the slave was connected.
+    features: AtomicU64,
+}
+
+impl Handshake {
+    #[inline]
+    pub(crate) fn new<T: 'static + Copy, M: 'static + Copy>() -> Self {
+        Self {
+            magic: HANDSHAKE_MAGIC,
+            version: IPC_PROTOCOL_VERSION,
+            fingerprint: Fingerprint::of::<T, M>(),
+            features: AtomicU64::new(IPC_FEATURES),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn init<T: 'static + Copy, M: 'static + Copy>(&mut self) {
+        *self = Self::new::<T, M>();
+    }
+
+    /// Verify the record written by master, and negotiate the features.
+    pub(crate) fn verify<T: 'static + Copy, M: 'static + Copy>(
+        &self,
+    ) -> Result<(), HandshakeError> {
+        if self.magic != HANDSHAKE_MAGIC {
+            return Err(HandshakeError::Magic);
+        }
+        if self.version != IPC_PROTOCOL_VERSION {
+            return Err(HandshakeError::Version {
+                master: self.version,
+                slave: IPC_PROTOCOL_VERSION,
+            });
+        }
+        let fingerprint = Fingerprint::of::<T, M>();
+        if self.fingerprint != fingerprint {
+            return Err(HandshakeError::Fingerprint {
+                master: self.fingerprint,
+                slave: fingerprint,
+            });
+        }
+
+        self.features.fetch_and(IPC_FEATURES, Ordering::AcqRel);
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn features(&self) -> u64 {
+        self.features.load(Ordering::Acquire)
+    }
+
+    /// Encode the record field by field for the transports and recording files.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let fingerprint = &self.fingerprint;
+        let mut enc = Encoder::default();
+        for val in [
+            self.magic,
+            self.version,
+            fingerprint.event_size,
+            fingerprint.event_align,
+            fingerprint.request_size,
+            fingerprint.request_align,
+            fingerprint.shared_info_size,
+        ] {
+            enc.u32(val)
+        }
+        enc.u64(self.features());
+        enc.0
+    }
+
+    /// Decode the record encoded by [`encode()`](Self::encode), it should be verified then.
+    ///
+    /// @return `None` if the length of bytes mismatched.
+    pub(crate) fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != HANDSHAKE_SIZE {
+            return None;
+        }
+        let mut dec = Decoder(bytes);
+        Some(Self {
+            magic: dec.u32()?,
+            version: dec.u32()?,
+            fingerprint: Fingerprint {
+                event_size: dec.u32()?,
+                event_align: dec.u32()?,
+                request_size: dec.u32()?,
+                request_align: dec.u32()?,
+                shared_info_size: dec.u32()?,
+            },
+            features: AtomicU64::new(dec.u64()?),
+        })
+    }
+
+    /// Pretend the features were negotiated with the peer supported `features` only.
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn restrict_features(&self, features: u64) {
+        self.features.fetch_and(features, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandshakeError {
+    /// The shared memory of master could not be created, (error message).
+    Create(String),
+    /// The shared memory of master could not be opened, (error message).
+    Open(String),
+    /// The connection of transport failed, (error message).
+    Io(String),
+    /// The shared memory was not created by a compatible tipc master.
+    Magic,
+    Version {
+        master: u32,
+        slave: u32,
+    },
+    Fingerprint {
+        master: Fingerprint,
+        slave: Fingerprint,
+    },
+}
+
+impl Error for HandshakeError {}
+
+impl Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create(e) => write!(f, "Ipc handshake failed, create the shared memory failed: {}", e),
+            Self::Open(e) => write!(f, "Ipc handshake failed, open the shared memory failed: {}", e),
+            Self::Io(e) => write!(f, "Ipc handshake failed, the connection failed: {}", e),
+            Self::Magic => f.write_str(
+                "Ipc handshake failed, the shared memory was not created by a compatible `IpcMaster`",
+            ),
+            Self::Version { master, slave } => write!(
+                f,
+                "Ipc handshake failed, protocol version mismatch: master = {}, slave = {}",
+                master, slave
+            ),
+            Self::Fingerprint { master, slave } => write!(
+                f,
+                "Ipc handshake failed, the layout of user event or request type mismatch: master = {:?}, slave = {:?}",
+                master, slave
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handshake() -> Handshake {
+        Handshake {
+            magic: 0,
+            version: 0,
+            fingerprint: Fingerprint::of::<(), ()>(),
+            features: AtomicU64::new(0),
+        }
+    }
+
+    #[test]
+    fn test_handshake() {
+        let mut record = handshake();
+        assert_eq!(record.verify::<(), ()>(), Err(HandshakeError::Magic));
+
+        record.init::<u64, [u8; 16]>();
+        assert!(record.verify::<u64, [u8; 16]>().is_ok());
+        assert_eq!(record.features(), IPC_FEATURES);
+        assert!(matches!(
+            record.verify::<u64, [u8; 32]>(),
+            Err(HandshakeError::Fingerprint { .. })
+        ));
+
+        // The master does not support the heartbeat.
+        record
+            .features
+            .store(FEATURE_FRAME_QUEUE | (1 << 63), Ordering::Release);
+        assert!(record.verify::<u64, [u8; 16]>().is_ok());
+        assert_eq!(record.features(), FEATURE_FRAME_QUEUE);
+
+        let decoded = Handshake::decode(&record.encode()).unwrap();
+        assert!(decoded.verify::<u64, [u8; 16]>().is_ok());
+        assert_eq!(decoded.features(), FEATURE_FRAME_QUEUE);
+        assert!(Handshake::decode(&record.encode()[1..]).is_none());
+
+        record.version = IPC_PROTOCOL_VERSION + 1;
+        assert_eq!(
+            record.verify::<u64, [u8; 16]>(),
+            Err(HandshakeError::Version {
+                master: IPC_PROTOCOL_VERSION + 1,
+                slave: IPC_PROTOCOL_VERSION
+            })
+        );
+    }
+}
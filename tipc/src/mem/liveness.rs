@@ -0,0 +1,381 @@
+use super::{
+    SharedInfo, IPC_MEM_BUFFER_NAME, IPC_MEM_LOCK_NAME, IPC_MEM_MASTER_FRAME_QUEUE,
+    IPC_MEM_MASTER_QUEUE, IPC_MEM_REGION_NAME, IPC_MEM_SHARED_INFO_NAME, IPC_MEM_SIGNAL_EVT,
+    IPC_MEM_SLAVE_FRAME_QUEUE, IPC_MEM_SLAVE_QUEUE,
+};
+use log::warn;
+use parking_lot::Mutex;
+use shared_memory::ShmemConf;
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The default timeout of the peer's heartbeat.
+pub const DEFAULT_PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The time slice of blocking waiting, the peer will be checked after each slice.
+pub(crate) const PEER_CHECK_WAIT: Duration = Duration::from_secs(1);
+
+/// The minimum interval to beat and check the peer, checking the process was a system call.
+///
+/// The heartbeats of this process were also beaten by the pacemaker thread in this interval.
+pub(crate) const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The time slice of polling a shared flag while waiting for the peer.
+pub(crate) const POLL_WAIT: Duration = Duration::from_millis(1);
+
+/// Poll until `f` returns the value or timeout.
+pub(crate) fn poll_timeout<R>(timeout: Duration, mut f: impl FnMut() -> Option<R>) -> Option<R> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(r) = f() {
+            return Some(r);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(POLL_WAIT);
+    }
+}
+
+/// The heartbeat of one side in shared memory.
+#[repr(C)]
+pub(crate) struct Heartbeat {
+    /// The process id, 0 if the side was not attached or has exited normally.
+    pid: AtomicU32,
+    /// The milliseconds since `UNIX_EPOCH` of the last beat.
+    beat: AtomicU64,
+}
+
+impl Heartbeat {
+    #[inline]
+    pub(crate) fn attach(&self) {
+        self.pid.store(std::process::id(), Ordering::Release);
+        self.beat();
+    }
+
+    #[inline]
+    pub(crate) fn detach(&self) {
+        self.pid.store(0, Ordering::Release);
+        self.beat.store(0, Ordering::Release);
+    }
+
+    /// Pretend the process of this side has crashed, the pid was never alive.
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn crash(&self) {
+        self.pid.store(i32::MAX as u32, Ordering::Release);
+    }
+
+    #[inline]
+    pub(crate) fn beat(&self) {
+        self.beat.store(now_millis(), Ordering::Release);
+    }
+
+    /// The side was alive if it was not attached, or the process was running
+    /// and the last beat was within the timeout.
+    pub(crate) fn is_alive(&self, timeout: Duration) -> bool {
+        let pid = self.pid.load(Ordering::Acquire);
+        if pid == 0 {
+            return true;
+        }
+        if !process_alive(pid) {
+            return false;
+        }
+
+        let beat = self.beat.load(Ordering::Acquire);
+        now_millis().saturating_sub(beat) <= timeout.as_millis() as u64
+    }
+}
+
+/// The heartbeats of this process which were beaten by the pacemaker thread.
+struct Pacemaker {
+    heartbeats: Vec<(u64, HeartbeatPtr)>,
+    next_id: u64,
+    running: bool,
+}
+
+struct HeartbeatPtr(*const Heartbeat);
+
+/// SAFETY: The heartbeat was in the shared memory, and it was only accessed by atomics.
+unsafe impl Send for HeartbeatPtr {}
+
+static PACEMAKER: Mutex<Pacemaker> = Mutex::new(Pacemaker {
+    heartbeats: Vec::new(),
+    next_id: 0,
+    running: false,
+});
+
+/// Keep beating the heartbeat of own side in the pacemaker thread, so that the peer will not
+/// regard this side as lost when it was idle (not polling the events) but healthy.
+///
+/// The heartbeat stops beating when the `Beating` dropped, the pacemaker thread exits
+/// when there were no heartbeats to beat.
+pub(crate) struct Beating {
+    id: u64,
+}
+
+impl Beating {
+    /// # Safety
+    /// The heartbeat should outlive the returned `Beating`.
+    pub(crate) unsafe fn start(heartbeat: &Heartbeat) -> Self {
+        let mut pacemaker = PACEMAKER.lock();
+        let id = pacemaker.next_id;
+        pacemaker.next_id += 1;
+        pacemaker.heartbeats.push((id, HeartbeatPtr(heartbeat)));
+
+        if !pacemaker.running {
+            pacemaker.running = true;
+            thread::Builder::new()
+                .name("tipc-pacemaker".to_string())
+                .spawn(pace)
+                .expect("Spawn the pacemaker thread of ipc failed.");
+        }
+        Self { id }
+    }
+}
+
+impl Drop for Beating {
+    #[inline]
+    fn drop(&mut self) {
+        PACEMAKER.lock().heartbeats.retain(|(id, _)| *id != self.id);
+    }
+}
+
+fn pace() {
+    loop {
+        thread::sleep(CHECK_INTERVAL);
+
+        let mut pacemaker = PACEMAKER.lock();
+        if pacemaker.heartbeats.is_empty() {
+            pacemaker.running = false;
+            return;
+        }
+        for (_, heartbeat) in pacemaker.heartbeats.iter() {
+            // SAFETY: The heartbeat was removed under the lock before it was released.
+            unsafe { (*heartbeat.0).beat() }
+        }
+    }
+}
+
+/// The local state to detect the peer lost, the result of checking was cached in [`CHECK_INTERVAL`].
+pub(crate) struct Liveness {
+    timeout: Mutex<Duration>,
+    last_check: Mutex<Option<Instant>>,
+    lost: AtomicBool,
+    reported: AtomicBool,
+}
+
+impl Default for Liveness {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            timeout: Mutex::new(DEFAULT_PEER_TIMEOUT),
+            last_check: Mutex::new(None),
+            lost: AtomicBool::new(false),
+            reported: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Liveness {
+    #[inline]
+    pub(crate) fn set_timeout(&self, timeout: Duration) {
+        *self.timeout.lock() = timeout
+    }
+
+    #[inline]
+    pub(crate) fn timeout(&self) -> Duration {
+        *self.timeout.lock()
+    }
+
+    /// Beat the heartbeat of own side, and check the peer side.
+    ///
+    /// @return true if the peer was lost.
+    pub(crate) fn check(&self, own: &Heartbeat, peer: &Heartbeat) -> bool {
+        let mut last_check = self.last_check.lock();
+        if last_check.is_some_and(|last| last.elapsed() < CHECK_INTERVAL) {
+            return self.lost.load(Ordering::Acquire);
+        }
+        *last_check = Some(Instant::now());
+
+        own.beat();
+        let lost = !peer.is_alive(*self.timeout.lock());
+        if !lost {
+            // The peer was reconnected.
+            self.reported.store(false, Ordering::Release);
+        }
+        self.lost.store(lost, Ordering::Release);
+        lost
+    }
+
+    /// Whether the peer was lost and it has not been reported.
+    #[inline]
+    pub(crate) fn has_lost_event(&self, own: &Heartbeat, peer: &Heartbeat) -> bool {
+        self.check(own, peer) && !self.reported.load(Ordering::Acquire)
+    }
+
+    /// @return true only once for each peer lost.
+    #[inline]
+    pub(crate) fn take_lost_event(&self, own: &Heartbeat, peer: &Heartbeat) -> bool {
+        self.check(own, peer) && !self.reported.swap(true, Ordering::AcqRel)
+    }
+}
+
+/// Remove the shared memories left behind by the crashed master with the same name,
+/// the shared memories will not be touched if the previous master was still running.
+pub(crate) fn reclaim_orphaned<M: 'static + Copy>(name: &str) {
+    let info_name = format!("{}{}", name, IPC_MEM_SHARED_INFO_NAME);
+    let Ok(shared_info) = ShmemConf::new().os_id(&info_name).open() else {
+        return;
+    };
+    if shared_info.len() < std::mem::size_of::<SharedInfo<M>>() {
+        return;
+    }
+
+    let sinfo = unsafe {
+        (shared_info.as_ptr() as *const SharedInfo<M>)
+            .as_ref()
+            .unwrap()
+    };
+    let pid = sinfo.master_heartbeat.pid.load(Ordering::Acquire);
+    if pid != 0 && process_alive(pid) {
+        return;
+    }
+    let name_helper = sinfo.name_helper.load(Ordering::Acquire);
+    drop(shared_info);
+
+    warn!(
+        "Ipc => reclaim the shared memories left behind by the crashed master `{}`.",
+        name
+    );
+    let suffixes = [
+        IPC_MEM_SIGNAL_EVT,
+        IPC_MEM_LOCK_NAME,
+        IPC_MEM_MASTER_QUEUE,
+        IPC_MEM_SLAVE_QUEUE,
+        IPC_MEM_MASTER_FRAME_QUEUE,
+        IPC_MEM_SLAVE_FRAME_QUEUE,
+        IPC_MEM_REGION_NAME,
+        IPC_MEM_SHARED_INFO_NAME,
+    ];
+    let buffers = (0..=name_helper).map(|i| format!("{}{}_{}", name, IPC_MEM_BUFFER_NAME, i));
+    for os_id in suffixes
+        .iter()
+        .map(|suffix| format!("{}{}", name, suffix))
+        .chain(buffers)
+    {
+        // The shared memory was removed when the owner dropped.
+        if let Ok(mut shmem) = ShmemConf::new().os_id(os_id).open() {
+            shmem.set_owner(true);
+        }
+    }
+}
+
+#[inline]
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    use nix::{
+        errno::Errno,
+        sys::signal::{kill, Signal},
+        unistd::Pid,
+    };
+
+    // Send no signal, only check the existence of process.
+    !matches!(
+        kill(Pid::from_raw(pid as i32), None::<Signal>),
+        Err(Errno::ESRCH)
+    )
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    use winapi::um::{
+        handleapi::CloseHandle,
+        minwinbase::STILL_ACTIVE,
+        processthreadsapi::{GetExitCodeProcess, OpenProcess},
+        winnt::PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut code = 0;
+        let ok = GetExitCodeProcess(handle, &mut code);
+        CloseHandle(handle);
+        ok != 0 && code == STILL_ACTIVE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat() {
+        let heartbeat = Heartbeat {
+            pid: AtomicU32::new(0),
+            beat: AtomicU64::new(0),
+        };
+        assert!(heartbeat.is_alive(DEFAULT_PEER_TIMEOUT));
+
+        heartbeat.attach();
+        assert!(heartbeat.is_alive(DEFAULT_PEER_TIMEOUT));
+
+        // Heartbeat timeout.
+        heartbeat
+            .beat
+            .store(now_millis() - 10_000, Ordering::Release);
+        assert!(!heartbeat.is_alive(DEFAULT_PEER_TIMEOUT));
+
+        heartbeat.detach();
+        assert!(heartbeat.is_alive(DEFAULT_PEER_TIMEOUT));
+    }
+
+    #[test]
+    fn test_beating() {
+        let heartbeat = Heartbeat {
+            pid: AtomicU32::new(0),
+            beat: AtomicU64::new(0),
+        };
+
+        let beating = unsafe { Beating::start(&heartbeat) };
+        thread::sleep(CHECK_INTERVAL * 3);
+        assert_ne!(heartbeat.beat.load(Ordering::Acquire), 0);
+
+        drop(beating);
+        heartbeat.beat.store(0, Ordering::Release);
+        thread::sleep(CHECK_INTERVAL * 3);
+        assert_eq!(heartbeat.beat.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn test_liveness() {
+        let own = Heartbeat {
+            pid: AtomicU32::new(0),
+            beat: AtomicU64::new(0),
+        };
+        let peer = Heartbeat {
+            pid: AtomicU32::new(std::process::id()),
+            beat: AtomicU64::new(now_millis() - 10_000),
+        };
+        let liveness = Liveness::default();
+
+        assert!(liveness.has_lost_event(&own, &peer));
+        assert_ne!(own.beat.load(Ordering::Acquire), 0);
+        assert!(liveness.take_lost_event(&own, &peer));
+        assert!(!liveness.take_lost_event(&own, &peer));
+        assert!(liveness.check(&own, &peer));
+    }
+}
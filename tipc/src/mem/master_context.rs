@@ -1,8 +1,16 @@
 use super::{
-    mem_queue::{MemQueue, MemQueueError},
+    handshake::HandshakeError,
+    liveness::{reclaim_orphaned, Heartbeat, Liveness, PEER_CHECK_WAIT},
+    mem_frame_queue::{MemFrameQueue, MemFrameQueueBuilder},
+    mem_queue::{MemQueue, MemQueueError, WriteMode},
     mem_rw_lock::MemRwLock,
-    BuildType, MemContext, IPC_MEM_LOCK_NAME, IPC_MEM_SIGNAL_EVT, IPC_QUEUE_SIZE,
+    region_table::RegionTable,
+    BuildType, MemContext, Segment, IPC_FRAME_BUFFER_SIZE, IPC_MEM_LOCK_NAME,
+    IPC_MEM_MASTER_FRAME_QUEUE, IPC_MEM_REGION_NAME, IPC_MEM_SIGNAL_EVT, IPC_MEM_SLAVE_FRAME_QUEUE,
+    IPC_QUEUE_SIZE,
 };
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::transport::{uds::SharedBuffer, IpcTransport, SegmentKind};
 use crate::{
     ipc_event::{InnerIpcEvent, IpcEvent},
     mem::{
@@ -16,45 +24,63 @@ use raw_sync::{
     events::{Event, EventInit, EventState},
     Timeout,
 };
-use shared_memory::{Shmem, ShmemConf};
+use shared_memory::{ShmemConf, ShmemError};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::io;
 use std::{
     error::Error,
     marker::PhantomData,
     mem::size_of,
     sync::{atomic::Ordering, Arc},
+    time::Duration,
 };
 use tlib::global::SemanticExt;
 
 pub(crate) struct MasterContext<T: 'static + Copy, M: 'static + Copy> {
     name: String,
-    buffer: Option<Shmem>,
-    shared_info: Shmem,
-    wait_signal_mem: Shmem,
+    buffer: Option<Segment>,
+    shared_info: Segment,
+    wait_signal_mem: Segment,
     buffer_lock: Arc<MemRwLock>,
     master_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
     slave_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
+    master_frame_queue: MemFrameQueue<IPC_FRAME_BUFFER_SIZE>,
+    slave_frame_queue: MemFrameQueue<IPC_FRAME_BUFFER_SIZE>,
+    region_table: RegionTable,
     _request_type: PhantomData<M>,
     mutex: Mutex<()>,
+    liveness: Liveness,
     pretreat_size: (u32, u32),
+    /// Whether the segments were shared through the transport,
+    /// the buffers were created in memfd and shared through it too.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    segments_shared: bool,
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
-    pub(crate) fn create<P: ToString>(name: P) -> Self {
+    /// Create the shared memories of master.
+    ///
+    /// @return the error if the shared memories could not be created,
+    /// e.g. the master with the same name was still running.
+    pub(crate) fn create<P: ToString>(name: P, max_regions: usize) -> Result<Self, HandshakeError> {
+        reclaim_orphaned::<M>(&name.to_string());
+
         let mut shared_info_name = name.to_string();
         shared_info_name.push_str(IPC_MEM_SHARED_INFO_NAME);
         let shared_info = ShmemConf::new()
             .size(size_of::<SharedInfo<M>>())
             .os_id(shared_info_name)
             .create()
-            .unwrap();
+            .map_err(create_error)?;
 
-        unsafe {
+        let sinfo = unsafe {
             (shared_info.as_ptr() as *mut SharedInfo<M>)
                 .as_mut()
                 .unwrap()
-                .prepared
-                .store(true, Ordering::Release)
         };
+        sinfo.handshake.init::<T, M>();
+        sinfo.prepared.store(true, Ordering::Release);
+        sinfo.master_heartbeat.attach();
 
         let mut event_signal_name = name.to_string();
         event_signal_name.push_str(IPC_MEM_SIGNAL_EVT);
@@ -62,7 +88,7 @@ impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
             .size(size_of::<Event>())
             .os_id(event_signal_name)
             .create()
-            .unwrap();
+            .map_err(create_error)?;
 
         let mut lock_name = name.to_string();
         lock_name.push_str(IPC_MEM_LOCK_NAME);
@@ -70,7 +96,7 @@ impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
             .os_id(lock_name)
             .build_type(BuildType::Create)
             .build()
-            .unwrap()
+            .map_err(create_error)?
             .arc();
 
         let mut master_queue_name = name.to_string();
@@ -79,7 +105,7 @@ impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
             .build_type(BuildType::Create)
             .os_id(master_queue_name)
             .build()
-            .unwrap();
+            .map_err(create_error)?;
 
         let mut slave_queue_name = name.to_string();
         slave_queue_name.push_str(IPC_MEM_SLAVE_QUEUE);
@@ -87,20 +113,47 @@ impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
             .build_type(BuildType::Create)
             .os_id(slave_queue_name)
             .build()
-            .unwrap();
+            .map_err(create_error)?;
+
+        let mut master_frame_queue_name = name.to_string();
+        master_frame_queue_name.push_str(IPC_MEM_MASTER_FRAME_QUEUE);
+        let master_frame_queue = MemFrameQueueBuilder::new()
+            .build_type(BuildType::Create)
+            .os_id(master_frame_queue_name)
+            .build()
+            .map_err(create_error)?;
+
+        let mut slave_frame_queue_name = name.to_string();
+        slave_frame_queue_name.push_str(IPC_MEM_SLAVE_FRAME_QUEUE);
+        let slave_frame_queue = MemFrameQueueBuilder::new()
+            .build_type(BuildType::Create)
+            .os_id(slave_frame_queue_name)
+            .build()
+            .map_err(create_error)?;
+
+        let mut region_table_name = name.to_string();
+        region_table_name.push_str(IPC_MEM_REGION_NAME);
+        let region_table =
+            RegionTable::create(&region_table_name, max_regions).map_err(create_error)?;
 
-        Self {
+        Ok(Self {
             name: name.to_string(),
             buffer: None,
-            shared_info,
+            shared_info: Segment::Shm(shared_info),
             buffer_lock,
-            wait_signal_mem: event_signal_mem,
+            wait_signal_mem: Segment::Shm(event_signal_mem),
             master_queue,
             slave_queue,
+            master_frame_queue,
+            slave_frame_queue,
+            region_table,
             _request_type: Default::default(),
             mutex: Mutex::new(()),
+            liveness: Liveness::default(),
             pretreat_size: (0, 0),
-        }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            segments_shared: false,
+        })
     }
 
     pub(crate) fn shared_info(&self) -> &'static mut SharedInfo<M> {
@@ -110,6 +163,53 @@ impl<T: 'static + Copy, M: 'static + Copy> MasterContext<T, M> {
                 .unwrap()
         }
     }
+
+    #[inline]
+    pub(crate) fn region_table(&self) -> &RegionTable {
+        &self.region_table
+    }
+
+    /// Share the segments with the slave through the transport, the buffers were created in memfd
+    /// and shared through the transport since then.
+    ///
+    /// @return the error with kind [`io::ErrorKind::Unsupported`] if the transport
+    /// can not pass the file descriptors.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn share_segments(&mut self, transport: &dyn IpcTransport<T, M>) -> io::Result<()> {
+        let (lock, lock_mutex) = self.buffer_lock.segments();
+        self.shared_info.share(SegmentKind::SharedInfo, transport)?;
+        self.wait_signal_mem.share(SegmentKind::Signal, transport)?;
+        lock.share(SegmentKind::BufferLock, transport)?;
+        lock_mutex.share(SegmentKind::BufferLockMutex, transport)?;
+        self.region_table
+            .segment()
+            .share(SegmentKind::RegionTable, transport)?;
+
+        self.segments_shared = true;
+        self.share_buffer(transport)
+    }
+
+    /// Share the current buffer with the slave, if the segments were shared through the transport.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn share_buffer(&self, transport: &dyn IpcTransport<T, M>) -> io::Result<()> {
+        match self.buffer {
+            Some(ref buffer) if self.segments_shared => {
+                buffer.share(SegmentKind::Buffer, transport)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Create the buffer in memfd if the segments were shared through the transport,
+    /// otherwise in the shared memory named `os_id`.
+    fn create_segment(&self, os_id: String, size: usize) -> Segment {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if self.segments_shared {
+            return Segment::Mapped(SharedBuffer::create(size).unwrap());
+        }
+
+        Segment::Shm(ShmemConf::new().size(size).os_id(os_id).create().unwrap())
+    }
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T, M> {
@@ -159,6 +259,38 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T,
         vec
     }
 
+    #[inline]
+    fn recv(&self) -> Option<IpcEvent<T>> {
+        self.slave_queue.recv().map(|ipc_evt| ipc_evt.into())
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        self.slave_queue
+            .recv_timeout(timeout)
+            .map(|ipc_evt| ipc_evt.into())
+    }
+
+    #[inline]
+    fn set_write_mode(&self, write_mode: WriteMode) {
+        self.master_queue.set_write_mode(write_mode)
+    }
+
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), MemQueueError> {
+        self.master_frame_queue.try_write(frame)
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        self.slave_frame_queue.has_frame()
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        self.slave_frame_queue.try_read()
+    }
+
     #[inline]
     fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>> {
         let _guard = self.mutex.lock();
@@ -166,6 +298,11 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T,
         if info.occupied.load(Ordering::Acquire) {
             return Err(Box::new(IpcError::new("`send_request()` failed")));
         }
+        if self.is_peer_lost() {
+            return Err(Box::new(IpcError::new(
+                "`send_request()` failed, the peer was lost",
+            )));
+        }
         let (evt, _) = unsafe { Event::new(self.wait_signal_mem.as_ptr(), true)? };
 
         // Set the request.
@@ -173,8 +310,16 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T,
         info.request = request;
         info.request_side = RequestSide::Master;
 
-        // Wait the response.
-        evt.wait(Timeout::Infinite)?;
+        // Wait the response, stop waiting if the peer was lost.
+        while evt.wait(Timeout::Val(PEER_CHECK_WAIT)).is_err() {
+            if self.is_peer_lost() {
+                info.occupied.store(false, Ordering::Release);
+                info.request_side = RequestSide::None;
+                return Err(Box::new(IpcError::new(
+                    "`send_request()` failed, the peer was lost",
+                )));
+            }
+        }
 
         // Get response.
         let response = info.response.take();
@@ -247,20 +392,14 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T,
     fn create_buffer(&mut self, width: u32, height: u32) {
         let buffer_name = format!("{}{}_{}", self.name, IPC_MEM_BUFFER_NAME, 0);
 
-        self.buffer = Some(
-            ShmemConf::new()
-                .size((width * height * 4) as usize)
-                .os_id(buffer_name)
-                .create()
-                .unwrap(),
-        );
+        self.buffer = Some(self.create_segment(buffer_name, (width * height * 4) as usize));
 
         let info_data = self.shared_info();
         info_data.width.store(width, Ordering::Release);
         info_data.height.store(height, Ordering::Release);
     }
 
-    fn recreate_buffer(&mut self) -> Option<Shmem> {
+    fn recreate_buffer(&mut self) -> Option<Segment> {
         let info_data = unsafe {
             (self.shared_info.as_ptr() as *mut SharedInfo<M>)
                 .as_mut()
@@ -283,12 +422,24 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for MasterContext<T,
         let buffer_name = format!("{}{}_{}", self.name, IPC_MEM_BUFFER_NAME, name_helper + 1);
 
         let (width, height) = (self.pretreat_size.0, self.pretreat_size.1);
-        let buffer = ShmemConf::new()
-            .size((width * height * 4) as usize)
-            .os_id(buffer_name)
-            .create()
-            .unwrap();
+        let buffer = self.create_segment(buffer_name, (width * height * 4) as usize);
 
         self.buffer.replace(buffer)
     }
+
+    #[inline]
+    fn liveness(&self) -> &Liveness {
+        &self.liveness
+    }
+
+    #[inline]
+    fn heartbeats(&self) -> (&Heartbeat, &Heartbeat) {
+        let info = self.shared_info();
+        (&info.master_heartbeat, &info.slave_heartbeat)
+    }
+}
+
+#[inline]
+fn create_error(e: ShmemError) -> HandshakeError {
+    HandshakeError::Create(e.to_string())
 }
@@ -0,0 +1,323 @@
+use super::{mem_queue::MemQueueError, BuildType};
+use log::warn;
+use parking_lot::Mutex;
+use shared_memory::{Shmem, ShmemConf, ShmemError};
+use std::{
+    mem::size_of,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The frame was prefixed by the length of payload, in little-endian `u32`.
+pub const FRAME_HEADER_SIZE: usize = size_of::<u32>();
+
+/// The byte ring buffer, the read/write indicates were the total bytes has been read/written.
+#[repr(C)]
+struct _MemFrameQueue<const BUFFER_SIZE: usize> {
+    read_indicate: AtomicUsize,
+    write_indicate: AtomicUsize,
+    buffer: [u8; BUFFER_SIZE],
+}
+
+impl<const BUFFER_SIZE: usize> _MemFrameQueue<BUFFER_SIZE> {
+    #[inline]
+    fn clear(&self) {
+        self.read_indicate.store(0, Ordering::Release);
+        self.write_indicate.store(0, Ordering::Release);
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        self.read_indicate.load(Ordering::Relaxed) != self.write_indicate.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn try_read(&self) -> Option<Vec<u8>> {
+        let read = self.read_indicate.load(Ordering::Acquire);
+        let write = self.write_indicate.load(Ordering::Acquire);
+        let readable = write.wrapping_sub(read);
+        if readable < FRAME_HEADER_SIZE {
+            return None;
+        }
+
+        let mut header = [0u8; FRAME_HEADER_SIZE];
+        self.copy_out(read, &mut header);
+        let len = u32::from_le_bytes(header) as usize;
+
+        // The writer publishes the header and payload together, so the header was corrupted
+        // if the frame exceeds the readable bytes or the capacity, discard the unreadable bytes.
+        if readable > BUFFER_SIZE
+            || len > BUFFER_SIZE - FRAME_HEADER_SIZE
+            || readable < FRAME_HEADER_SIZE + len
+        {
+            warn!(
+                "`MemFrameQueue` got the invalid frame header, len = {}, readable = {}, discard the remaining bytes.",
+                len, readable
+            );
+            self.read_indicate.store(write, Ordering::Release);
+            return None;
+        }
+
+        let mut frame = vec![0u8; len];
+        self.copy_out(read.wrapping_add(FRAME_HEADER_SIZE), &mut frame);
+        self.read_indicate.store(
+            read.wrapping_add(FRAME_HEADER_SIZE + len),
+            Ordering::Release,
+        );
+
+        Some(frame)
+    }
+
+    #[inline]
+    fn try_write(&mut self, frame: &[u8]) -> Result<(), MemQueueError> {
+        let required = FRAME_HEADER_SIZE + frame.len();
+        if required > BUFFER_SIZE || frame.len() > u32::MAX as usize {
+            return Err(MemQueueError::new(
+                "Frame was larger than the capacity of `MemFrameQueue`, frame was aborted.",
+            ));
+        }
+
+        let read = self.read_indicate.load(Ordering::Acquire);
+        let write = self.write_indicate.load(Ordering::Acquire);
+        if BUFFER_SIZE - write.wrapping_sub(read) < required {
+            return Err(MemQueueError::new(
+                "`MemFrameQueue` was full, frame was aborted.",
+            ));
+        }
+
+        self.copy_in(write, &(frame.len() as u32).to_le_bytes());
+        self.copy_in(write.wrapping_add(FRAME_HEADER_SIZE), frame);
+        self.write_indicate
+            .store(write.wrapping_add(required), Ordering::Release);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn copy_in(&mut self, at: usize, data: &[u8]) {
+        let start = at % BUFFER_SIZE;
+        let first = data.len().min(BUFFER_SIZE - start);
+        self.buffer[start..start + first].copy_from_slice(&data[..first]);
+        self.buffer[..data.len() - first].copy_from_slice(&data[first..]);
+    }
+
+    #[inline]
+    fn copy_out(&self, at: usize, out: &mut [u8]) {
+        let start = at % BUFFER_SIZE;
+        let first = out.len().min(BUFFER_SIZE - start);
+        let len = out.len();
+        out[..first].copy_from_slice(&self.buffer[start..start + first]);
+        out[first..].copy_from_slice(&self.buffer[..len - first]);
+    }
+}
+
+/// The shared memory queue of variable-length frames, each frame was a length-prefixed bytes,
+/// use [`FrameCodec`](super::frame_codec::FrameCodec) to convert the frames from/to messages.
+///
+/// Same as [`MemQueue`](super::mem_queue::MemQueue), there should be only one writer process
+/// and one reader process.
+pub struct MemFrameQueue<const BUFFER_SIZE: usize> {
+    shmem: Shmem,
+    mutex: Mutex<()>,
+}
+
+impl<const BUFFER_SIZE: usize> MemFrameQueue<BUFFER_SIZE> {
+    pub fn create() -> Result<Self, ShmemError> {
+        let shmem = ShmemConf::new()
+            .size(size_of::<_MemFrameQueue<BUFFER_SIZE>>())
+            .create()?;
+
+        Ok(Self {
+            shmem,
+            mutex: Mutex::new(()),
+        })
+    }
+
+    pub fn create_with_os_id(os_id: &str) -> Result<Self, ShmemError> {
+        let shmem = ShmemConf::new()
+            .size(size_of::<_MemFrameQueue<BUFFER_SIZE>>())
+            .os_id(os_id)
+            .create()?;
+
+        Ok(Self {
+            shmem,
+            mutex: Mutex::new(()),
+        })
+    }
+
+    pub fn open(os_id: &str) -> Result<Self, ShmemError> {
+        let shmem = ShmemConf::new().os_id(os_id).open()?;
+
+        Ok(Self {
+            shmem,
+            mutex: Mutex::new(()),
+        })
+    }
+
+    #[inline]
+    pub fn os_id(&self) -> &str {
+        self.shmem.get_os_id()
+    }
+
+    #[inline]
+    pub fn clear(&self) {
+        self.queue_mut().clear();
+    }
+
+    #[inline]
+    pub fn has_frame(&self) -> bool {
+        self.queue_mut().has_frame()
+    }
+
+    #[inline]
+    pub fn try_read(&self) -> Option<Vec<u8>> {
+        let _guard = self.mutex.lock();
+        self.queue_mut().try_read()
+    }
+
+    /// If the queue has no enough space, the frame will be aborted.
+    #[inline]
+    pub fn try_write(&self, frame: &[u8]) -> Result<(), MemQueueError> {
+        let _guard = self.mutex.lock();
+        self.queue_mut().try_write(frame)
+    }
+
+    #[inline]
+    fn queue_mut(&self) -> &'static mut _MemFrameQueue<BUFFER_SIZE> {
+        unsafe {
+            (self.shmem.as_ptr() as *mut _MemFrameQueue<BUFFER_SIZE>)
+                .as_mut()
+                .unwrap()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MemFrameQueueBuilder {
+    build_type: BuildType,
+    os_id: Option<String>,
+}
+
+impl MemFrameQueueBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn build_type(mut self, build_type: BuildType) -> Self {
+        self.build_type = build_type;
+        self
+    }
+
+    #[inline]
+    pub fn os_id<P: ToString>(mut self, os_id: P) -> Self {
+        self.os_id = Some(os_id.to_string());
+        self
+    }
+
+    pub fn build<const BUFFER_SIZE: usize>(self) -> Result<MemFrameQueue<BUFFER_SIZE>, ShmemError> {
+        match self.build_type {
+            BuildType::Create => {
+                if let Some(ref os_id) = self.os_id {
+                    MemFrameQueue::create_with_os_id(os_id)
+                } else {
+                    MemFrameQueue::create()
+                }
+            }
+            BuildType::Open => {
+                if let Some(ref os_id) = self.os_id {
+                    MemFrameQueue::open(os_id)
+                } else {
+                    panic!("`Open` MemFrameQueue must assign the os_id")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue<const BUFFER_SIZE: usize>() -> Box<_MemFrameQueue<BUFFER_SIZE>> {
+        Box::new(_MemFrameQueue {
+            read_indicate: AtomicUsize::new(0),
+            write_indicate: AtomicUsize::new(0),
+            buffer: [0; BUFFER_SIZE],
+        })
+    }
+
+    #[test]
+    fn test_frame_queue() {
+        let mut queue = queue::<32>();
+        assert!(!queue.has_frame());
+        assert!(queue.try_read().is_none());
+
+        queue.try_write(b"hello").unwrap();
+        queue.try_write(b"").unwrap();
+        queue.try_write(b"world!").unwrap();
+        assert!(queue.has_frame());
+
+        assert_eq!(queue.try_read().unwrap(), b"hello");
+        assert_eq!(queue.try_read().unwrap(), b"");
+        assert_eq!(queue.try_read().unwrap(), b"world!");
+        assert!(!queue.has_frame());
+    }
+
+    #[test]
+    fn test_frame_queue_wrap_around() {
+        let mut queue = queue::<32>();
+
+        for i in 0..100u8 {
+            let frame: Vec<u8> = (0..(i % 20)).map(|b| b.wrapping_add(i)).collect();
+            queue.try_write(&frame).unwrap();
+            assert_eq!(queue.try_read().unwrap(), frame);
+        }
+        assert!(!queue.has_frame());
+    }
+
+    #[test]
+    fn test_frame_queue_full() {
+        let mut queue = queue::<32>();
+
+        assert!(queue.try_write(&[0; 29]).is_err());
+        queue.try_write(&[1; 20]).unwrap();
+        assert!(queue.try_write(&[2; 5]).is_err());
+        queue.try_write(&[3; 4]).unwrap();
+
+        assert_eq!(queue.try_read().unwrap(), [1; 20]);
+        queue.try_write(&[4; 20]).unwrap();
+        assert_eq!(queue.try_read().unwrap(), [3; 4]);
+        assert_eq!(queue.try_read().unwrap(), [4; 20]);
+    }
+
+    #[test]
+    fn test_frame_queue_invalid_header() {
+        let mut queue = queue::<32>();
+
+        // Length exceeds the capacity.
+        queue.buffer[..FRAME_HEADER_SIZE].copy_from_slice(&u32::MAX.to_le_bytes());
+        queue.write_indicate.store(FRAME_HEADER_SIZE, Ordering::Release);
+        assert!(queue.try_read().is_none());
+        assert!(!queue.has_frame());
+
+        // Length exceeds the readable bytes.
+        let read = queue.read_indicate.load(Ordering::Acquire);
+        queue.buffer[read..read + FRAME_HEADER_SIZE].copy_from_slice(&10u32.to_le_bytes());
+        queue
+            .write_indicate
+            .store(read + FRAME_HEADER_SIZE + 2, Ordering::Release);
+        assert!(queue.try_read().is_none());
+        assert!(!queue.has_frame());
+
+        // Readable bytes exceed the capacity.
+        queue.read_indicate.store(0, Ordering::Release);
+        queue.write_indicate.store(64, Ordering::Release);
+        assert!(queue.try_read().is_none());
+        assert!(!queue.has_frame());
+
+        // The queue keeps working after the bytes were discarded.
+        queue.try_write(b"hello").unwrap();
+        assert_eq!(queue.try_read().unwrap(), b"hello");
+    }
+}
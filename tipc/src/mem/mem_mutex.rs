@@ -1,4 +1,5 @@
-use shared_memory::{Shmem, ShmemConf, ShmemError};
+use super::Segment;
+use shared_memory::{ShmemConf, ShmemError};
 use std::{
     mem::size_of,
     sync::atomic::{AtomicBool, Ordering},
@@ -12,10 +13,14 @@ struct _MemMutex {
 /// Cross process mutex based on shared memory. <br>
 /// `Non reentrant lock`
 pub struct MemMutex {
-    inner: Shmem,
+    inner: Segment,
 }
 
 impl MemMutex {
+    /// The bytes size of the segment.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) const SEGMENT_SIZE: usize = size_of::<_MemMutex>();
+
     pub fn new(key: &str, op: MemMutexOp) -> Result<Self, ShmemError> {
         let key = Self::gen_key(key);
         let inner = match op {
@@ -25,7 +30,21 @@ impl MemMutex {
                 .create()?,
             MemMutexOp::Open => ShmemConf::new().os_id(&key).open()?,
         };
-        Ok(Self { inner })
+        Ok(Self {
+            inner: Segment::Shm(inner),
+        })
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn from_segment(inner: Segment) -> Self {
+        Self { inner }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn segment(&self) -> &Segment {
+        &self.inner
     }
 
     #[inline]
@@ -1,5 +1,10 @@
 use super::BuildType;
+use log::error;
 use parking_lot::Mutex;
+use raw_sync::{
+    events::{Event, EventImpl, EventInit, EventState},
+    Timeout,
+};
 use shared_memory::{Shmem, ShmemConf, ShmemError};
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
@@ -8,8 +13,12 @@ use std::{
     fmt::Display,
     mem::size_of,
     sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
 };
 
+/// The alignment of the cross process events in shared memory.
+const EVENT_ALIGN: usize = 16;
+
 #[repr(C)]
 struct _MemQueue<const QUEUE_SIZE: usize, T: 'static + Copy> {
     read_indicate: AtomicUsize,
@@ -63,46 +72,56 @@ impl<const QUEUE_SIZE: usize, T: 'static + Copy> _MemQueue<QUEUE_SIZE, T> {
     }
 }
 
+/// The behavior of [`MemQueue::try_write`] when the queue was full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Abort the event and return [`MemQueueError`].
+    #[default]
+    Abort,
+    /// Wait until the reader has read events and the queue has space.
+    Block,
+    /// Wait for the space of queue, abort the event if timeout.
+    BlockTimeout(Duration),
+}
+
+/// The shared memory queue of fixed size events.
+///
+/// The shared memory contains the queue and two cross process events, `readable` was signaled
+/// when an event was written, `writable` was signaled when an event was read,
+/// so the reader and writer can block on them instead of polling.
 pub struct MemQueue<const QUEUE_SIZE: usize, T: 'static + Copy> {
     shmem: Shmem,
+    readable: Box<dyn EventImpl>,
+    writable: Box<dyn EventImpl>,
+    write_mode: Mutex<WriteMode>,
     _type_holder: PhantomData<T>,
     mutex: Mutex<()>,
 }
 
+/// SAFETY: The cross process events were thread safe, and the queue was guarded by `Mutex`.
+unsafe impl<const QUEUE_SIZE: usize, T: 'static + Copy> Send for MemQueue<QUEUE_SIZE, T> {}
+unsafe impl<const QUEUE_SIZE: usize, T: 'static + Copy> Sync for MemQueue<QUEUE_SIZE, T> {}
+
 impl<const QUEUE_SIZE: usize, T: 'static + Copy> MemQueue<QUEUE_SIZE, T> {
     pub fn create() -> Result<Self, ShmemError> {
-        let shmem = ShmemConf::new()
-            .size(size_of::<_MemQueue<QUEUE_SIZE, T>>())
-            .create()?;
+        let shmem = ShmemConf::new().size(Self::shmem_size()).create()?;
 
-        Ok(Self {
-            shmem,
-            _type_holder: PhantomData,
-            mutex: Mutex::new(()),
-        })
+        Self::new(shmem, BuildType::Create)
     }
 
     pub fn create_with_os_id(os_id: &str) -> Result<Self, ShmemError> {
         let shmem = ShmemConf::new()
-            .size(size_of::<_MemQueue<QUEUE_SIZE, T>>())
+            .size(Self::shmem_size())
             .os_id(os_id)
             .create()?;
 
-        Ok(Self {
-            shmem,
-            _type_holder: PhantomData,
-            mutex: Mutex::new(()),
-        })
+        Self::new(shmem, BuildType::Create)
     }
 
     pub fn open(os_id: &str) -> Result<Self, ShmemError> {
         let shmem = ShmemConf::new().os_id(os_id).open()?;
 
-        Ok(Self {
-            shmem,
-            _type_holder: PhantomData,
-            mutex: Mutex::new(()),
-        })
+        Self::new(shmem, BuildType::Open)
     }
 
     #[inline]
@@ -123,17 +142,149 @@ impl<const QUEUE_SIZE: usize, T: 'static + Copy> MemQueue<QUEUE_SIZE, T> {
     #[inline]
     pub fn try_read(&self) -> Option<T> {
         let _guard = self.mutex.lock();
-        self.queue_mut().try_read()
+        let evt = self.queue_mut().try_read();
+        if evt.is_some() {
+            Self::notify(self.writable.as_ref());
+        }
+        evt
+    }
+
+    /// Block until an event was received.
+    ///
+    /// @return [`None`] if waiting on the cross process event failed.
+    #[inline]
+    pub fn recv(&self) -> Option<T> {
+        self.recv_until(None)
+    }
+
+    /// Block until an event was received or timeout.
+    #[inline]
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        self.recv_until(Some(Instant::now() + timeout))
+    }
+
+    #[inline]
+    pub fn write_mode(&self) -> WriteMode {
+        *self.write_mode.lock()
+    }
+
+    /// Set the behavior of [`try_write`](Self::try_write) when the queue was full,
+    /// the default was [`WriteMode::Abort`].
+    #[inline]
+    pub fn set_write_mode(&self, write_mode: WriteMode) {
+        *self.write_mode.lock() = write_mode
     }
 
-    /// If the queue was full, the event will be aborted.
+    /// If the queue was full, the event will be aborted,
+    /// or wait for the space of queue under the blocking [`WriteMode`].
     #[inline]
     pub fn try_write(&self, evt: T) -> Result<(), MemQueueError> {
+        let deadline = match self.write_mode() {
+            WriteMode::Abort => return self.write_once(evt),
+            WriteMode::Block => None,
+            WriteMode::BlockTimeout(timeout) => Some(Instant::now() + timeout),
+        };
+
+        loop {
+            let err = match self.write_once(evt) {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            if !Self::wait(self.writable.as_ref(), deadline) {
+                return self.write_once(evt).map_err(|_| err);
+            }
+        }
+    }
+
+    #[inline]
+    fn write_once(&self, evt: T) -> Result<(), MemQueueError> {
         let _guard = self.mutex.lock();
         self.queue_mut().try_write(evt)?;
+        Self::notify(self.readable.as_ref());
         Ok(())
     }
 
+    fn recv_until(&self, deadline: Option<Instant>) -> Option<T> {
+        loop {
+            if let Some(evt) = self.try_read() {
+                return Some(evt);
+            }
+            // The `readable` event stays signaled if an event was written after `try_read()`,
+            // so the event would not be missed.
+            if !Self::wait(self.readable.as_ref(), deadline) {
+                return self.try_read();
+            }
+        }
+    }
+
+    /// @return false if timeout or waiting failed.
+    ///
+    /// The timed waiting may return before the event was signaled,
+    /// the caller should check the queue and wait again.
+    #[inline]
+    fn wait(event: &dyn EventImpl, deadline: Option<Instant>) -> bool {
+        let Some(deadline) = deadline else {
+            return event.wait(Timeout::Infinite).is_ok();
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        let _ = event.wait(Timeout::Val(clamp_timeout(deadline - now)));
+        true
+    }
+
+    #[inline]
+    fn notify(event: &dyn EventImpl) {
+        if let Err(e) = event.set(EventState::Signaled) {
+            error!("`MemQueue` signal event failed. {:?}", e)
+        }
+    }
+
+    fn new(shmem: Shmem, build_type: BuildType) -> Result<Self, ShmemError> {
+        let init_event = |offset: usize| {
+            let ptr = unsafe { shmem.as_ptr().add(offset) };
+            let res = match build_type {
+                BuildType::Create => unsafe { Event::new(ptr, true) },
+                BuildType::Open => unsafe { Event::from_existing(ptr) },
+            };
+            res.map(|(event, _)| event).map_err(|e| {
+                error!(
+                    "`MemQueue` initialize the cross process event failed. {:?}",
+                    e
+                );
+                ShmemError::UnknownOsError(0)
+            })
+        };
+        let readable = init_event(Self::queue_size())?;
+        let writable = init_event(Self::queue_size() + Self::event_size())?;
+
+        Ok(Self {
+            shmem,
+            readable,
+            writable,
+            write_mode: Mutex::new(WriteMode::default()),
+            _type_holder: PhantomData,
+            mutex: Mutex::new(()),
+        })
+    }
+
+    #[inline]
+    fn queue_size() -> usize {
+        size_of::<_MemQueue<QUEUE_SIZE, T>>().next_multiple_of(EVENT_ALIGN)
+    }
+
+    #[inline]
+    fn event_size() -> usize {
+        Event::size_of(None).next_multiple_of(EVENT_ALIGN)
+    }
+
+    #[inline]
+    fn shmem_size() -> usize {
+        Self::queue_size() + 2 * Self::event_size()
+    }
+
     #[inline]
     fn queue_mut(&self) -> &'static mut _MemQueue<QUEUE_SIZE, T> {
         unsafe {
@@ -144,6 +295,31 @@ impl<const QUEUE_SIZE: usize, T: 'static + Copy> MemQueue<QUEUE_SIZE, T> {
     }
 }
 
+/// `raw_sync` does not normalize the absolute time of timed waiting on unix, the waiting fails
+/// immediately if the nanoseconds overflowed, so clamp the timeout within the current second.
+#[cfg(unix)]
+#[inline]
+fn clamp_timeout(timeout: Duration) -> Duration {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    const NANOS_PER_SEC: u32 = 1_000_000_000;
+    const MARGIN_NANOS: u32 = 1_000_000;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let budget = NANOS_PER_SEC
+        .saturating_sub(now)
+        .saturating_sub(MARGIN_NANOS);
+    Duration::new(timeout.as_secs(), timeout.subsec_nanos().min(budget))
+}
+
+#[cfg(not(unix))]
+#[inline]
+fn clamp_timeout(timeout: Duration) -> Duration {
+    timeout
+}
+
 #[derive(Default)]
 pub struct MemQueueBuilder {
     build_type: BuildType,
@@ -207,3 +383,42 @@ impl Display for MemQueueError {
         f.write_str(self.msg)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_recv_timeout() {
+        let writer: MemQueue<4, u32> = MemQueue::create().unwrap();
+        let reader: MemQueue<4, u32> = MemQueue::open(writer.os_id()).unwrap();
+
+        assert_eq!(reader.recv_timeout(Duration::from_millis(10)), None);
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            writer.try_write(1).unwrap();
+            writer
+        });
+        assert_eq!(reader.recv_timeout(Duration::from_secs(5)), Some(1));
+        let writer = handle.join().unwrap();
+
+        // The queue holds `QUEUE_SIZE - 1` events.
+        for i in 0..3 {
+            writer.try_write(i).unwrap();
+        }
+        assert!(writer.try_write(3).is_err());
+
+        writer.set_write_mode(WriteMode::BlockTimeout(Duration::from_millis(10)));
+        assert!(writer.try_write(3).is_err());
+
+        writer.set_write_mode(WriteMode::Block);
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            (0..4).map(|_| reader.recv().unwrap()).collect::<Vec<_>>()
+        });
+        writer.try_write(3).unwrap();
+        assert_eq!(handle.join().unwrap(), vec![0, 1, 2, 3]);
+    }
+}
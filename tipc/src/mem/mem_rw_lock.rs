@@ -1,8 +1,8 @@
 use super::{
     mem_mutex::{MemMutex, MemMutexOp},
-    BuildType,
+    BuildType, Segment,
 };
-use shared_memory::{Shmem, ShmemConf, ShmemError};
+use shared_memory::{ShmemConf, ShmemError};
 use std::{
     mem::size_of,
     sync::atomic::{AtomicU8, Ordering},
@@ -18,7 +18,7 @@ struct _MemRwLock {
 /// It's a cross process lock. <br>
 /// `Non reentrant lock`
 pub struct MemRwLock {
-    inner: Shmem,
+    inner: Segment,
     mutex: MemMutex,
 }
 
@@ -26,6 +26,11 @@ unsafe impl Send for MemRwLock {}
 unsafe impl Sync for MemRwLock {}
 
 impl MemRwLock {
+    /// The bytes size of the segments of (lock, mutex).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) const SEGMENT_SIZES: (usize, usize) =
+        (size_of::<_MemRwLock>(), MemMutex::SEGMENT_SIZE);
+
     #[inline]
     pub fn create_with_os_id(os_id: &str) -> Result<Self, ShmemError> {
         let shmem = ShmemConf::new()
@@ -34,7 +39,7 @@ impl MemRwLock {
             .create()?;
 
         Ok(Self {
-            inner: shmem,
+            inner: Segment::Shm(shmem),
             mutex: MemMutex::new(os_id, MemMutexOp::Create)?,
         })
     }
@@ -44,11 +49,28 @@ impl MemRwLock {
         let shmem = ShmemConf::new().os_id(os_id).open()?;
 
         Ok(Self {
-            inner: shmem,
+            inner: Segment::Shm(shmem),
             mutex: MemMutex::new(os_id, MemMutexOp::Open)?,
         })
     }
 
+    /// Build the lock from the segments of lock and its mutex received by the transport.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn from_segments(inner: Segment, mutex: Segment) -> Self {
+        Self {
+            inner,
+            mutex: MemMutex::from_segment(mutex),
+        }
+    }
+
+    /// @return the segments of (lock, mutex).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn segments(&self) -> (&Segment, &Segment) {
+        (&self.inner, self.mutex.segment())
+    }
+
     #[inline]
     pub fn builder() -> MemRwLockBuilder {
         MemRwLockBuilder::new()
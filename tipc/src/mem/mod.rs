@@ -1,33 +1,57 @@
-use self::{mem_queue::MemQueueError, mem_rw_lock::MemRwLock};
+use self::{
+    damage::Damage,
+    handshake::Handshake,
+    liveness::{Heartbeat, Liveness},
+    mem_queue::{MemQueueError, WriteMode},
+    mem_rw_lock::MemRwLock,
+};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::transport::{uds::SharedBuffer, IpcTransport, SegmentKind};
 use crate::ipc_event::IpcEvent;
 use raw_sync::Timeout;
 use shared_memory::Shmem;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::{
+    ffi::CString,
+    io,
+    os::fd::{AsFd, FromRawFd, OwnedFd},
+};
 use std::{
     error::Error,
     fmt::Display,
-    mem::MaybeUninit,
     sync::{
         atomic::{AtomicBool, AtomicU32, AtomicUsize},
         Arc,
     },
+    time::Duration,
 };
-use tlib::figure::Rect;
 
+pub mod damage;
+pub mod frame_channel;
+pub mod frame_codec;
+pub mod handshake;
+pub mod liveness;
 pub mod master_context;
+pub mod mem_frame_queue;
 pub mod mem_mutex;
 pub mod mem_queue;
 pub mod mem_rw_lock;
+pub mod region_table;
 pub mod slave_context;
 
 pub(crate) const IPC_QUEUE_SIZE: usize = 10000;
-pub(crate) const MAX_REGION_SIZE: usize = 10;
+pub(crate) const IPC_FRAME_BUFFER_SIZE: usize = 4 * 1024 * 1024;
 
 pub(crate) const IPC_MEM_BUFFER_NAME: &str = "_mem_bf";
 pub(crate) const IPC_MEM_SHARED_INFO_NAME: &str = "_mem_sh_info";
 pub(crate) const IPC_MEM_LOCK_NAME: &str = "_mem_rwl";
 pub(crate) const IPC_MEM_MASTER_QUEUE: &str = "_mem_m_q";
 pub(crate) const IPC_MEM_SLAVE_QUEUE: &str = "_mem_s_q";
+pub(crate) const IPC_MEM_MASTER_FRAME_QUEUE: &str = "_mem_m_fq";
+pub(crate) const IPC_MEM_SLAVE_FRAME_QUEUE: &str = "_mem_s_fq";
 pub(crate) const IPC_MEM_SIGNAL_EVT: &str = "_mem_e_s";
+pub(crate) const IPC_MEM_REGION_NAME: &str = "_mem_rg";
+pub(crate) const IPC_CHANNEL_SEP: &str = "_ch_";
 
 pub(crate) trait MemContext<T: 'static + Copy, M: 'static + Copy> {
     fn name(&self) -> &str;
@@ -46,6 +70,18 @@ pub(crate) trait MemContext<T: 'static + Copy, M: 'static + Copy> {
 
     fn try_recv_vec(&self) -> Vec<IpcEvent<T>>;
 
+    fn recv(&self) -> Option<IpcEvent<T>>;
+
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>>;
+
+    fn set_write_mode(&self, write_mode: WriteMode);
+
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), MemQueueError>;
+
+    fn has_frame(&self) -> bool;
+
+    fn try_recv_frame(&self) -> Option<Vec<u8>>;
+
     fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>>;
 
     fn try_recv_request(&self) -> Option<M>;
@@ -62,7 +98,95 @@ pub(crate) trait MemContext<T: 'static + Copy, M: 'static + Copy> {
 
     fn create_buffer(&mut self, width: u32, height: u32);
 
-    fn recreate_buffer(&mut self) -> Option<Shmem>;
+    fn recreate_buffer(&mut self) -> Option<Segment>;
+
+    fn liveness(&self) -> &Liveness;
+
+    /// The heartbeats of (own side, peer side).
+    fn heartbeats(&self) -> (&Heartbeat, &Heartbeat);
+
+    #[inline]
+    fn heartbeat(&self) {
+        self.heartbeats().0.beat()
+    }
+
+    #[inline]
+    fn is_peer_lost(&self) -> bool {
+        let (own, peer) = self.heartbeats();
+        self.liveness().check(own, peer)
+    }
+
+    #[inline]
+    fn has_peer_lost_event(&self) -> bool {
+        let (own, peer) = self.heartbeats();
+        self.liveness().has_lost_event(own, peer)
+    }
+
+    #[inline]
+    fn take_peer_lost_event(&self) -> bool {
+        let (own, peer) = self.heartbeats();
+        self.liveness().take_lost_event(own, peer)
+    }
+
+    #[inline]
+    fn set_peer_timeout(&self, timeout: Duration) {
+        self.liveness().set_timeout(timeout)
+    }
+
+    #[inline]
+    fn peer_timeout(&self) -> Duration {
+        self.liveness().timeout()
+    }
+}
+
+/// The shared memory segment, opened by name, or mapped from the file descriptor
+/// received by the transport.
+pub(crate) enum Segment {
+    Shm(Shmem),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Mapped(SharedBuffer),
+}
+
+impl Segment {
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> *mut u8 {
+        match self {
+            Self::Shm(shmem) => shmem.as_ptr(),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Self::Mapped(buffer) => buffer.as_ptr(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Shm(shmem) => shmem.len(),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Self::Mapped(buffer) => buffer.len(),
+        }
+    }
+
+    /// Pass the file descriptor of segment to the peer through the transport.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn share<T: 'static + Copy, M: 'static + Copy>(
+        &self,
+        kind: SegmentKind,
+        transport: &dyn IpcTransport<T, M>,
+    ) -> io::Result<()> {
+        match self {
+            Self::Shm(shmem) => {
+                // `Shmem` does not expose the file descriptor, open it again by the os id.
+                let os_id = CString::new(shmem.get_os_id())?;
+                let fd = unsafe { libc::shm_open(os_id.as_ptr(), libc::O_RDWR, 0) };
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+                transport.send_segment(kind, fd.as_fd(), shmem.len())
+            }
+            Self::Mapped(buffer) => transport.send_segment(kind, buffer.as_fd(), buffer.len()),
+        }
+    }
 }
 
 #[repr(C)]
@@ -82,16 +206,15 @@ pub enum BuildType {
 
 #[repr(C)]
 pub(crate) struct SharedInfo<M: 'static + Copy> {
+    /// Should be the first field, so it can be verified before accessing the other fields.
+    pub(crate) handshake: Handshake,
+
     pub(crate) name_helper: AtomicU32,
 
     /// The size of application.
     pub(crate) width: AtomicU32,
     pub(crate) height: AtomicU32,
 
-    /// The clip region to renderer in slave.
-    pub(crate) regions: [MaybeUninit<(u128, Rect)>; MAX_REGION_SIZE],
-    pub(crate) region_idx: AtomicUsize,
-
     pub(crate) occupied: AtomicBool,
     pub(crate) request_side: RequestSide,
     pub(crate) request: M,
@@ -101,6 +224,14 @@ pub(crate) struct SharedInfo<M: 'static + Copy> {
     pub(crate) release_idx: AtomicUsize,
     pub(crate) prepared: AtomicBool,
     pub(crate) invalidate: AtomicBool,
+    /// The dirty rects of shared buffer since the master copied it last time.
+    pub(crate) damage: Damage,
+
+    /// Whether the slave was connected, maintained by the slave side.
+    pub(crate) connected: AtomicBool,
+
+    pub(crate) master_heartbeat: Heartbeat,
+    pub(crate) slave_heartbeat: Heartbeat,
 }
 
 #[derive(Debug)]
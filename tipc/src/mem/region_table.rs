@@ -0,0 +1,297 @@
+use super::Segment;
+use parking_lot::Mutex;
+use shared_memory::{ShmemConf, ShmemError};
+use std::{
+    hint::spin_loop,
+    mem::size_of,
+    sync::atomic::{fence, AtomicU32, Ordering},
+};
+use tlib::figure::Rect;
+
+/// The maximum bytes length of the region id.
+pub const MAX_REGION_ID_LEN: usize = 64;
+/// The default capacity of the region table,
+/// see [`IpcBuilder::max_regions()`](crate::IpcBuilder::max_regions).
+pub const DEFAULT_MAX_REGIONS: usize = 64;
+
+/// The read will be retried until the master finished writing,
+/// give up waiting when the master was crashed during the writing.
+const MAX_READ_RETRY: usize = 1 << 16;
+
+#[repr(C)]
+struct TableHeader {
+    capacity: u32,
+    len: AtomicU32,
+    /// The sequence lock of the entries, odd when the master was writing.
+    seq: AtomicU32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RegionEntry {
+    id_len: u32,
+    id: [u8; MAX_REGION_ID_LEN],
+    rect: Rect,
+}
+
+impl RegionEntry {
+    #[inline]
+    fn id(&self) -> &[u8] {
+        &self.id[..(self.id_len as usize).min(MAX_REGION_ID_LEN)]
+    }
+}
+
+/// The shared regions of `SharedWidget` in the separate shared memory,
+/// the capacity was decided by the master when created.
+///
+/// The table was only written by master, and read by both sides.
+pub(crate) struct RegionTable {
+    shmem: Segment,
+    /// Serialize the writing of master threads.
+    write_lock: Mutex<()>,
+}
+
+impl RegionTable {
+    pub(crate) fn create(os_id: &str, capacity: usize) -> Result<Self, ShmemError> {
+        let capacity = capacity.max(1);
+        let shmem = ShmemConf::new()
+            .size(Self::segment_size(capacity))
+            .os_id(os_id)
+            .create()?;
+
+        Ok(Self::init(Segment::Shm(shmem), capacity))
+    }
+
+    /// Initialize the empty table in the segment of [`segment_size(capacity)`](Self::segment_size).
+    pub(crate) fn init(shmem: Segment, capacity: usize) -> Self {
+        debug_assert!(shmem.len() >= Self::segment_size(capacity));
+        let table = Self {
+            shmem,
+            write_lock: Mutex::new(()),
+        };
+        unsafe {
+            table.header_ptr().write(TableHeader {
+                capacity: capacity as u32,
+                len: AtomicU32::new(0),
+                seq: AtomicU32::new(0),
+            })
+        };
+        table
+    }
+
+    /// The bytes size of the table which can hold `capacity` regions.
+    #[inline]
+    pub(crate) fn segment_size(capacity: usize) -> usize {
+        size_of::<TableHeader>() + capacity * size_of::<RegionEntry>()
+    }
+
+    #[inline]
+    pub(crate) fn open(os_id: &str) -> Result<Self, ShmemError> {
+        Self::from_segment(Segment::Shm(ShmemConf::new().os_id(os_id).open()?))
+    }
+
+    /// Build the table from the segment opened by name or received by the transport.
+    pub(crate) fn from_segment(shmem: Segment) -> Result<Self, ShmemError> {
+        if shmem.len() < size_of::<TableHeader>() {
+            return Err(ShmemError::MapOpenFailed(0));
+        }
+        let table = Self {
+            shmem,
+            write_lock: Mutex::new(()),
+        };
+
+        if table.shmem.len() < Self::segment_size(table.capacity()) {
+            return Err(ShmemError::MapOpenFailed(0));
+        }
+        Ok(table)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn segment(&self) -> &Segment {
+        &self.shmem
+    }
+
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.header().capacity as usize
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.read(|entries| entries.len())
+    }
+
+    /// All the regions of (id, rect).
+    pub(crate) fn entries(&self) -> Vec<(String, Rect)> {
+        self.read(|entries| {
+            entries
+                .iter()
+                .map(|e| (String::from_utf8_lossy(e.id()).into_owned(), e.rect))
+                .collect()
+        })
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, id: &str) -> Option<Rect> {
+        self.read(|entries| {
+            entries
+                .iter()
+                .find(|e| e.id() == id.as_bytes())
+                .map(|e| e.rect)
+        })
+    }
+
+    /// Insert or update the region.
+    ///
+    /// @return false if the region was new and the table was full.
+    pub(crate) fn upsert(&self, id: &str, rect: Rect) -> bool {
+        debug_assert!(id.len() <= MAX_REGION_ID_LEN);
+        let capacity = self.capacity();
+
+        self.write(|entries, len| {
+            if let Some(e) = entries[..*len].iter_mut().find(|e| e.id() == id.as_bytes()) {
+                e.rect = rect;
+                return true;
+            }
+            if *len >= capacity {
+                return false;
+            }
+
+            let mut entry = RegionEntry {
+                id_len: id.len() as u32,
+                id: [0; MAX_REGION_ID_LEN],
+                rect,
+            };
+            entry.id[..id.len()].copy_from_slice(id.as_bytes());
+            entries[*len] = entry;
+            *len += 1;
+            true
+        })
+    }
+
+    /// Update the existing region.
+    ///
+    /// @return false if the region was not exist.
+    pub(crate) fn update(&self, id: &str, rect: Rect) -> bool {
+        self.write(|entries, len| {
+            entries[..*len]
+                .iter_mut()
+                .find(|e| e.id() == id.as_bytes())
+                .map(|e| e.rect = rect)
+                .is_some()
+        })
+    }
+
+    /// @return false if the region was not exist.
+    pub(crate) fn remove(&self, id: &str) -> bool {
+        self.write(|entries, len| {
+            match entries[..*len].iter().position(|e| e.id() == id.as_bytes()) {
+                Some(idx) => {
+                    entries[idx] = entries[*len - 1];
+                    *len -= 1;
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    #[inline]
+    fn header_ptr(&self) -> *mut TableHeader {
+        self.shmem.as_ptr() as *mut TableHeader
+    }
+
+    #[inline]
+    fn header(&self) -> &TableHeader {
+        unsafe { self.header_ptr().as_ref().unwrap() }
+    }
+
+    #[inline]
+    fn entries_ptr(&self) -> *mut RegionEntry {
+        unsafe { self.shmem.as_ptr().add(size_of::<TableHeader>()) as *mut RegionEntry }
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut [RegionEntry], &mut usize) -> R) -> R {
+        let _guard = self.write_lock.lock();
+        let header = self.header();
+
+        header.seq.fetch_add(1, Ordering::AcqRel);
+        fence(Ordering::Release);
+
+        let entries =
+            unsafe { std::slice::from_raw_parts_mut(self.entries_ptr(), self.capacity()) };
+        let mut len = header.len.load(Ordering::Acquire) as usize;
+        let res = f(entries, &mut len);
+        header.len.store(len as u32, Ordering::Release);
+
+        header.seq.fetch_add(1, Ordering::Release);
+        res
+    }
+
+    /// Read the entries under the sequence lock, `f` may be called multiple times.
+    fn read<R>(&self, f: impl Fn(&[RegionEntry]) -> R) -> R {
+        let header = self.header();
+        let capacity = self.capacity();
+
+        for _ in 0..MAX_READ_RETRY {
+            let seq = header.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                spin_loop();
+                continue;
+            }
+
+            let len = (header.len.load(Ordering::Acquire) as usize).min(capacity);
+            let entries: Vec<RegionEntry> =
+                unsafe { std::slice::from_raw_parts(self.entries_ptr(), len) }.to_vec();
+
+            fence(Ordering::Acquire);
+            if header.seq.load(Ordering::Relaxed) == seq {
+                return f(&entries);
+            }
+        }
+
+        let len = (header.len.load(Ordering::Acquire) as usize).min(capacity);
+        f(unsafe { std::slice::from_raw_parts(self.entries_ptr(), len) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_table() {
+        let os_id = format!("_tipc_region_table_test_{}", std::process::id());
+        let master = RegionTable::create(&os_id, 2).unwrap();
+        let slave = RegionTable::open(&os_id).unwrap();
+        assert_eq!(slave.capacity(), 2);
+
+        let id = "a".repeat(MAX_REGION_ID_LEN);
+        assert!(master.upsert(&id, Rect::new(0, 0, 10, 10)));
+        assert!(master.upsert("widget_b", Rect::new(10, 10, 10, 10)));
+        assert!(!master.upsert("widget_c", Rect::new(20, 20, 10, 10)));
+        assert!(master.upsert("widget_b", Rect::new(30, 30, 10, 10)));
+        assert_eq!(slave.get(&id), Some(Rect::new(0, 0, 10, 10)));
+        assert_eq!(slave.get("widget_b"), Some(Rect::new(30, 30, 10, 10)));
+        assert_eq!(slave.len(), 2);
+        assert_eq!(
+            slave.entries(),
+            vec![
+                (id.clone(), Rect::new(0, 0, 10, 10)),
+                ("widget_b".to_string(), Rect::new(30, 30, 10, 10))
+            ]
+        );
+
+        assert!(master.update(&id, Rect::new(5, 5, 10, 10)));
+        assert!(!master.update("widget_c", Rect::new(5, 5, 10, 10)));
+        assert_eq!(slave.get(&id), Some(Rect::new(5, 5, 10, 10)));
+
+        assert!(master.remove(&id));
+        assert!(!master.remove(&id));
+        assert_eq!(slave.get(&id), None);
+        assert_eq!(slave.get("widget_b"), Some(Rect::new(30, 30, 10, 10)));
+        assert!(master.upsert("widget_c", Rect::new(20, 20, 10, 10)));
+        assert_eq!(slave.len(), 2);
+    }
+}
@@ -1,42 +1,73 @@
 use super::{
-    mem_queue::{MemQueue, MemQueueBuilder, MemQueueError},
+    handshake::{Handshake, HandshakeError},
+    liveness::{Heartbeat, Liveness, PEER_CHECK_WAIT},
+    mem_frame_queue::{MemFrameQueue, MemFrameQueueBuilder},
+    mem_queue::{MemQueue, MemQueueBuilder, MemQueueError, WriteMode},
     mem_rw_lock::MemRwLock,
-    BuildType, IpcError, MemContext, RequestSide, SharedInfo, IPC_MEM_BUFFER_NAME,
-    IPC_MEM_LOCK_NAME, IPC_MEM_MASTER_QUEUE, IPC_MEM_SHARED_INFO_NAME, IPC_MEM_SIGNAL_EVT,
+    region_table::RegionTable,
+    BuildType, IpcError, MemContext, RequestSide, Segment, SharedInfo, IPC_FRAME_BUFFER_SIZE,
+    IPC_MEM_BUFFER_NAME, IPC_MEM_LOCK_NAME, IPC_MEM_MASTER_FRAME_QUEUE, IPC_MEM_MASTER_QUEUE,
+    IPC_MEM_REGION_NAME, IPC_MEM_SHARED_INFO_NAME, IPC_MEM_SIGNAL_EVT, IPC_MEM_SLAVE_FRAME_QUEUE,
     IPC_MEM_SLAVE_QUEUE, IPC_QUEUE_SIZE,
 };
 use crate::ipc_event::{InnerIpcEvent, IpcEvent};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::{
+    mem::liveness::DEFAULT_PEER_TIMEOUT,
+    transport::{IpcTransport, SegmentKind},
+};
 use log::error;
 use parking_lot::Mutex;
 use raw_sync::{
     events::{Event, EventInit, EventState},
     Timeout,
 };
-use shared_memory::{Shmem, ShmemConf};
+use shared_memory::ShmemConf;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::io;
 use std::{
     error::Error,
+    fmt::Display,
     marker::PhantomData,
+    mem::size_of,
     sync::{atomic::Ordering, Arc},
+    time::Duration,
 };
 use tlib::global::SemanticExt;
 
+/// The shared memory queues of events and frames.
+struct SlaveQueues<T: 'static + Copy> {
+    master_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
+    slave_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
+    master_frame_queue: MemFrameQueue<IPC_FRAME_BUFFER_SIZE>,
+    slave_frame_queue: MemFrameQueue<IPC_FRAME_BUFFER_SIZE>,
+}
+
 pub(crate) struct SlaveContext<T: 'static + Copy, M: 'static + Copy> {
     name: String,
-    buffer: Option<Shmem>,
-    shared_info: Shmem,
-    wait_signal_mem: Shmem,
+    buffer: Option<Segment>,
+    shared_info: Segment,
+    wait_signal_mem: Segment,
     buffer_lock: Arc<MemRwLock>,
-    master_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
-    slave_queue: MemQueue<IPC_QUEUE_SIZE, InnerIpcEvent<T>>,
+    /// `None` if the slave was connected through the transport, see [`connect()`](Self::connect).
+    queues: Option<SlaveQueues<T>>,
+    region_table: RegionTable,
     _request_type: PhantomData<M>,
     mutex: Mutex<()>,
+    liveness: Liveness,
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> SlaveContext<T, M> {
-    pub(crate) fn open<P: ToString>(name: P) -> Self {
+    /// Open the shared memories of master, the handshake record will be verified first.
+    pub(crate) fn open<P: ToString>(name: P) -> Result<Self, HandshakeError> {
         let mut shared_info_name = name.to_string();
         shared_info_name.push_str(IPC_MEM_SHARED_INFO_NAME);
-        let shared_info = ShmemConf::new().os_id(shared_info_name).open().unwrap();
+        let shared_info = ShmemConf::new()
+            .os_id(shared_info_name)
+            .open()
+            .map_err(open_error)?;
+        let shared_info = Segment::Shm(shared_info);
+        Self::verify(&shared_info)?;
 
         let sinfo = unsafe {
             (shared_info.as_ptr() as *const SharedInfo<M>)
@@ -50,11 +81,12 @@ impl<T: 'static + Copy, M: 'static + Copy> SlaveContext<T, M> {
             IPC_MEM_BUFFER_NAME,
             name_helper
         );
-        let buffer = ShmemConf::new().os_id(buffer_name).open().unwrap();
+        let buffer = Segment::Shm(ShmemConf::new().os_id(buffer_name).open().unwrap());
 
         let mut event_signal_name = name.to_string();
         event_signal_name.push_str(IPC_MEM_SIGNAL_EVT);
-        let event_signal_mem = ShmemConf::new().os_id(event_signal_name).open().unwrap();
+        let event_signal_mem =
+            Segment::Shm(ShmemConf::new().os_id(event_signal_name).open().unwrap());
 
         let mut lock_name = name.to_string();
         lock_name.push_str(IPC_MEM_LOCK_NAME);
@@ -81,19 +113,137 @@ impl<T: 'static + Copy, M: 'static + Copy> SlaveContext<T, M> {
             .build()
             .unwrap();
 
-        Self {
+        let mut master_frame_queue_name = name.to_string();
+        master_frame_queue_name.push_str(IPC_MEM_MASTER_FRAME_QUEUE);
+        let master_frame_queue = MemFrameQueueBuilder::new()
+            .build_type(BuildType::Open)
+            .os_id(master_frame_queue_name)
+            .build()
+            .unwrap();
+
+        let mut slave_frame_queue_name = name.to_string();
+        slave_frame_queue_name.push_str(IPC_MEM_SLAVE_FRAME_QUEUE);
+        let slave_frame_queue = MemFrameQueueBuilder::new()
+            .build_type(BuildType::Open)
+            .os_id(slave_frame_queue_name)
+            .build()
+            .unwrap();
+
+        let mut region_table_name = name.to_string();
+        region_table_name.push_str(IPC_MEM_REGION_NAME);
+        let region_table = RegionTable::open(&region_table_name).map_err(open_error)?;
+
+        Ok(Self {
             name: name.to_string(),
             buffer: Some(buffer),
             shared_info,
             wait_signal_mem: event_signal_mem,
             buffer_lock,
-            master_queue,
-            slave_queue,
+            queues: Some(SlaveQueues {
+                master_queue,
+                slave_queue,
+                master_frame_queue,
+                slave_frame_queue,
+            }),
+            region_table,
             _request_type: Default::default(),
             mutex: Mutex::new(()),
+            liveness: Liveness::default(),
+        })
+    }
+
+    /// Map the shared memories of master received by the transport, none of them was opened
+    /// by name. The events, frames and requests were transferred through the transport,
+    /// so the shared memory queues were not mapped.
+    ///
+    /// Fall back to [`open()`](Self::open) if the transport can not pass the file descriptors.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn connect<P: ToString>(
+        name: P,
+        transport: &dyn IpcTransport<T, M>,
+    ) -> Result<Self, HandshakeError> {
+        let recv = |kind| {
+            transport
+                .recv_segment(kind, DEFAULT_PEER_TIMEOUT)
+                .map(Segment::Mapped)
+        };
+
+        let shared_info = match recv(SegmentKind::SharedInfo) {
+            Ok(shared_info) => shared_info,
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => return Self::open(name),
+            Err(e) => return Err(open_error(e)),
+        };
+        Self::verify(&shared_info)?;
+
+        let wait_signal_mem = recv(SegmentKind::Signal).map_err(open_error)?;
+        let buffer_lock = MemRwLock::from_segments(
+            recv(SegmentKind::BufferLock).map_err(open_error)?,
+            recv(SegmentKind::BufferLockMutex).map_err(open_error)?,
+        )
+        .arc();
+        let region_table =
+            RegionTable::from_segment(recv(SegmentKind::RegionTable).map_err(open_error)?)
+                .map_err(open_error)?;
+        let buffer = recv(SegmentKind::Buffer).map_err(open_error)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            buffer: Some(buffer),
+            shared_info,
+            wait_signal_mem,
+            buffer_lock,
+            queues: None,
+            region_table,
+            _request_type: Default::default(),
+            mutex: Mutex::new(()),
+            liveness: Liveness::default(),
+        })
+    }
+
+    /// Verify the handshake record at the head of shared info.
+    fn verify(shared_info: &Segment) -> Result<(), HandshakeError> {
+        if shared_info.len() < size_of::<Handshake>() {
+            return Err(HandshakeError::Magic);
+        }
+        unsafe {
+            (shared_info.as_ptr() as *const Handshake)
+                .as_ref()
+                .unwrap()
+                .verify::<T, M>()
+        }
+    }
+
+    /// Whether the shared memories were mapped from the segments received by the transport.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub(crate) fn is_mapped(&self) -> bool {
+        matches!(self.shared_info, Segment::Mapped(_))
+    }
+
+    /// Map the buffer recreated by the master from the segment received by the transport,
+    /// in place of [`recreate_buffer()`](MemContext::recreate_buffer) if [`is_mapped()`](Self::is_mapped).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn remap_buffer(&mut self, transport: &dyn IpcTransport<T, M>) -> Option<Segment> {
+        if !self.shared_info().resized.load(Ordering::Acquire) {
+            return None;
+        }
+
+        match transport.recv_segment(SegmentKind::Buffer, self.peer_timeout()) {
+            Ok(buffer) => self.buffer.replace(Segment::Mapped(buffer)),
+            Err(e) => {
+                error!("Ipc => slave context receive the buffer failed. {:?}", e);
+                None
+            }
         }
     }
 
+    #[inline]
+    fn queues(&self) -> &SlaveQueues<T> {
+        self.queues
+            .as_ref()
+            .expect("The shared memory queues were replaced by the transport.")
+    }
+
     pub(crate) fn shared_info(&self) -> &'static mut SharedInfo<M> {
         unsafe {
             (self.shared_info.as_ptr() as *mut SharedInfo<M>)
@@ -101,6 +251,11 @@ impl<T: 'static + Copy, M: 'static + Copy> SlaveContext<T, M> {
                 .unwrap()
         }
     }
+
+    #[inline]
+    pub(crate) fn region_table(&self) -> &RegionTable {
+        &self.region_table
+    }
 }
 
 impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T, M> {
@@ -126,30 +281,72 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T,
 
     #[inline]
     fn try_send(&self, evt: IpcEvent<T>) -> Result<(), MemQueueError> {
-        self.slave_queue.try_write(evt.into())
+        self.queues().slave_queue.try_write(evt.into())
     }
 
     #[inline]
     fn has_event(&self) -> bool {
-        self.master_queue.has_event()
+        self.queues().master_queue.has_event()
     }
 
     #[inline]
     fn try_recv(&self) -> Option<IpcEvent<T>> {
-        self.master_queue.try_read().map(|ipc_evt| ipc_evt.into())
+        self.queues()
+            .master_queue
+            .try_read()
+            .map(|ipc_evt| ipc_evt.into())
     }
 
     #[inline]
     fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
+        let master_queue = &self.queues().master_queue;
         let mut vec = vec![];
-        while self.master_queue.has_event() {
-            if let Some(evt) = self.master_queue.try_read() {
+        while master_queue.has_event() {
+            if let Some(evt) = master_queue.try_read() {
                 vec.push(evt.into())
             }
         }
         vec
     }
 
+    #[inline]
+    fn recv(&self) -> Option<IpcEvent<T>> {
+        self.queues()
+            .master_queue
+            .recv()
+            .map(|ipc_evt| ipc_evt.into())
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        self.queues()
+            .master_queue
+            .recv_timeout(timeout)
+            .map(|ipc_evt| ipc_evt.into())
+    }
+
+    #[inline]
+    fn set_write_mode(&self, write_mode: WriteMode) {
+        if let Some(ref queues) = self.queues {
+            queues.slave_queue.set_write_mode(write_mode)
+        }
+    }
+
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), MemQueueError> {
+        self.queues().slave_frame_queue.try_write(frame)
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        self.queues().master_frame_queue.has_frame()
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        self.queues().master_frame_queue.try_read()
+    }
+
     #[inline]
     fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>> {
         let _guard = self.mutex.lock();
@@ -157,6 +354,11 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T,
         if info.occupied.load(Ordering::Acquire) {
             return Err(Box::new(IpcError::new("`send_request()` failed")));
         }
+        if self.is_peer_lost() {
+            return Err(Box::new(IpcError::new(
+                "`send_request()` failed, the peer was lost",
+            )));
+        }
         let (evt, _) = unsafe { Event::new(self.wait_signal_mem.as_ptr(), true)? };
 
         // Set the request.
@@ -164,8 +366,16 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T,
         info.request = request;
         info.request_side = RequestSide::Slave;
 
-        // Wait the response.
-        evt.wait(Timeout::Infinite)?;
+        // Wait the response, stop waiting if the peer was lost.
+        while evt.wait(Timeout::Val(PEER_CHECK_WAIT)).is_err() {
+            if self.is_peer_lost() {
+                info.occupied.store(false, Ordering::Release);
+                info.request_side = RequestSide::None;
+                return Err(Box::new(IpcError::new(
+                    "`send_request()` failed, the peer was lost",
+                )));
+            }
+        }
 
         // Get response.
         let response = info.response.take();
@@ -229,7 +439,7 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T,
         unreachable!()
     }
 
-    fn recreate_buffer(&mut self) -> Option<Shmem> {
+    fn recreate_buffer(&mut self) -> Option<Segment> {
         let sinfo = unsafe {
             (self.shared_info.as_ptr() as *const SharedInfo<M>)
                 .as_ref()
@@ -241,15 +451,26 @@ impl<T: 'static + Copy, M: 'static + Copy> MemContext<T, M> for SlaveContext<T,
 
         let name_helper = sinfo.name_helper.load(Ordering::Acquire);
 
-        let buffer_name = format!(
-            "{}{}_{}",
-            self.name,
-            IPC_MEM_BUFFER_NAME,
-            name_helper
-        );
+        let buffer_name = format!("{}{}_{}", self.name, IPC_MEM_BUFFER_NAME, name_helper);
 
         let buffer = ShmemConf::new().os_id(buffer_name).open().unwrap();
 
-        self.buffer.replace(buffer)
+        self.buffer.replace(Segment::Shm(buffer))
+    }
+
+    #[inline]
+    fn liveness(&self) -> &Liveness {
+        &self.liveness
+    }
+
+    #[inline]
+    fn heartbeats(&self) -> (&Heartbeat, &Heartbeat) {
+        let info = self.shared_info();
+        (&info.slave_heartbeat, &info.master_heartbeat)
     }
 }
+
+#[inline]
+fn open_error<E: Display>(e: E) -> HandshakeError {
+    HandshakeError::Open(e.to_string())
+}
@@ -0,0 +1,212 @@
+use crate::{
+    ipc_event::IpcEvent,
+    mem::{master_context::MasterContext, slave_context::SlaveContext, MemContext},
+};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::{io, os::fd::BorrowedFd};
+use std::{error::Error, iter, time::Duration};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use uds::SharedBuffer;
+
+pub mod record;
+#[cfg(unix)]
+pub mod uds;
+pub(crate) mod wire;
+
+/// The memory segments of master shared with the slave through the transport,
+/// see [`IpcTransport::send_segment()`].
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    SharedInfo = 0,
+    Signal,
+    BufferLock,
+    BufferLockMutex,
+    RegionTable,
+    /// The pixel buffer, it was shared again when the buffer was recreated.
+    Buffer,
+}
+
+/// The channel to transfer the events, frames and requests between master and slave.
+///
+/// [`IpcMaster`](crate::ipc_master::IpcMaster) and [`IpcSlave`](crate::ipc_slave::IpcSlave)
+/// transfer them through the shared memory queues by default, or through the transport chosen by
+/// [`IpcBuilder::transport()`](crate::IpcBuilder::transport), e.g.
+/// [`UdsTransport`](uds::UdsTransport) for the sandboxed or containerized slaves,
+/// [`IpcReplayer`](record::IpcReplayer) replays the recorded master side to the slave.
+pub trait IpcTransport<T: 'static + Copy, M: 'static + Copy> {
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>>;
+
+    fn has_event(&self) -> bool;
+
+    fn try_recv(&self) -> Option<IpcEvent<T>>;
+
+    #[inline]
+    fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
+        iter::from_fn(|| self.try_recv()).collect()
+    }
+
+    /// Block until an event was received or timeout.
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>>;
+
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), Box<dyn Error>>;
+
+    fn has_frame(&self) -> bool;
+
+    fn try_recv_frame(&self) -> Option<Vec<u8>>;
+
+    /// Send the request and block until the response was received.
+    fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>>;
+
+    fn try_recv_request(&self) -> Option<M>;
+
+    fn response_request(&self, response: Option<M>);
+
+    /// The feature flags supported by both sides, see [`handshake`](crate::mem::handshake).
+    fn features(&self) -> u64;
+
+    /// Share the memory segment of master with the peer by its file descriptor, so the slave
+    /// built with the transport maps it instead of opening the shared memory by name.
+    ///
+    /// @return the error with kind [`io::ErrorKind::Unsupported`] if the transport
+    /// can not pass the file descriptors.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn send_segment(&self, _kind: SegmentKind, _fd: BorrowedFd, _len: usize) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Block until the segment of `kind` was received or timeout, the outdated segments
+    /// of the same kind received before were dropped.
+    ///
+    /// @return the error with kind [`io::ErrorKind::Unsupported`] if the transport
+    /// can not pass the file descriptors, or [`io::ErrorKind::TimedOut`].
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn recv_segment(&self, _kind: SegmentKind, _timeout: Duration) -> io::Result<SharedBuffer> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcTransport<T, M> for MasterContext<T, M> {
+    #[inline]
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        Ok(MemContext::try_send(self, evt)?)
+    }
+
+    #[inline]
+    fn has_event(&self) -> bool {
+        MemContext::has_event(self)
+    }
+
+    #[inline]
+    fn try_recv(&self) -> Option<IpcEvent<T>> {
+        MemContext::try_recv(self)
+    }
+
+    #[inline]
+    fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
+        MemContext::try_recv_vec(self)
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        MemContext::recv_timeout(self, timeout)
+    }
+
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(MemContext::try_send_frame(self, frame)?)
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        MemContext::has_frame(self)
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        MemContext::try_recv_frame(self)
+    }
+
+    #[inline]
+    fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>> {
+        MemContext::send_request(self, request)
+    }
+
+    #[inline]
+    fn try_recv_request(&self) -> Option<M> {
+        MemContext::try_recv_request(self)
+    }
+
+    #[inline]
+    fn response_request(&self, response: Option<M>) {
+        MemContext::response_request(self, response)
+    }
+
+    #[inline]
+    fn features(&self) -> u64 {
+        self.shared_info().handshake.features()
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcTransport<T, M> for SlaveContext<T, M> {
+    #[inline]
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        Ok(MemContext::try_send(self, evt)?)
+    }
+
+    #[inline]
+    fn has_event(&self) -> bool {
+        MemContext::has_event(self)
+    }
+
+    #[inline]
+    fn try_recv(&self) -> Option<IpcEvent<T>> {
+        MemContext::try_recv(self)
+    }
+
+    #[inline]
+    fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
+        MemContext::try_recv_vec(self)
+    }
+
+    #[inline]
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        MemContext::recv_timeout(self, timeout)
+    }
+
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(MemContext::try_send_frame(self, frame)?)
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        MemContext::has_frame(self)
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        MemContext::try_recv_frame(self)
+    }
+
+    #[inline]
+    fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>> {
+        MemContext::send_request(self, request)
+    }
+
+    #[inline]
+    fn try_recv_request(&self) -> Option<M> {
+        MemContext::try_recv_request(self)
+    }
+
+    #[inline]
+    fn response_request(&self, response: Option<M>) {
+        MemContext::response_request(self, response)
+    }
+
+    #[inline]
+    fn features(&self) -> u64 {
+        self.shared_info().handshake.features()
+    }
+}
@@ -0,0 +1,745 @@
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::{uds::SharedBuffer, SegmentKind};
+use super::{
+    wire::{decode_event, encode_event, from_bytes, to_bytes, Decoder, Encoder},
+    IpcTransport,
+};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::mem::{
+    mem_mutex::MemMutex, mem_rw_lock::MemRwLock, region_table::RegionTable, Segment, SharedInfo,
+};
+use crate::{
+    ipc_event::IpcEvent,
+    mem::{
+        handshake::{Handshake, FEATURE_HEARTBEAT, HANDSHAKE_SIZE},
+        liveness::POLL_WAIT,
+        region_table::DEFAULT_MAX_REGIONS,
+        IpcError,
+    },
+    IpcType,
+};
+use log::error;
+use parking_lot::Mutex;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use raw_sync::events::Event;
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs::File,
+    io::{self, Write},
+    marker::PhantomData,
+    mem::size_of,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tlib::figure::Rect;
+
+/// Identify the file was recorded by tipc.
+const RECORD_MAGIC: u32 = 0x7472_6563;
+/// The file header: (magic: `u32`, handshake record, features: `u64`, [`RecordState`]).
+/// The entry was prefixed by the header:
+/// (kind: `u8`, origin: `u8`, offset nanos since recording started: `u64`, length of payload: `u32`).
+const ENTRY_HEADER_SIZE: usize = 2 + size_of::<u64>() + size_of::<u32>();
+
+const KIND_EVENT: u8 = 0;
+const KIND_REQUEST: u8 = 1;
+const KIND_RESPONSE: u8 = 2;
+
+const ORIGIN_MASTER: u8 = 0;
+const ORIGIN_SLAVE: u8 = 1;
+/// Generated by the recording side itself, e.g. [`IpcEvent::PeerLost`].
+const ORIGIN_LOCAL: u8 = 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Deliver the entries with the same intervals as they were recorded.
+    #[default]
+    RealTime,
+    /// Deliver the entries as fast as possible.
+    Fast,
+}
+
+/// The state of master when the recording started, the replayer plays the master with it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct RecordState {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// The shared regions of (id, rect).
+    pub(crate) regions: Vec<(String, Rect)>,
+}
+
+impl RecordState {
+    fn encode(&self, enc: &mut Encoder) {
+        enc.u32(self.width);
+        enc.u32(self.height);
+        enc.u32(self.regions.len() as u32);
+        for (id, rect) in self.regions.iter() {
+            enc.str(id);
+            enc.rect(*rect);
+        }
+    }
+
+    fn decode(dec: &mut Decoder) -> Option<Self> {
+        let (width, height) = (dec.u32()?, dec.u32()?);
+        let regions = (0..dec.u32()?)
+            .map(|_| Some((dec.string()?, dec.rect()?)))
+            .collect::<Option<_>>()?;
+        Some(Self {
+            width,
+            height,
+            regions,
+        })
+    }
+}
+
+struct RecordFile {
+    file: File,
+    start: Instant,
+}
+
+/// Record the events and requests passing through the [`IpcMaster`](crate::ipc_master::IpcMaster)
+/// or [`IpcSlave`](crate::ipc_slave::IpcSlave), see [`IpcNode::start_recording()`](crate::IpcNode::start_recording).
+pub(crate) struct IpcRecorder<T: 'static + Copy, M: 'static + Copy> {
+    ty: IpcType,
+    recording: AtomicBool,
+    file: Mutex<Option<RecordFile>>,
+    _type_holder: PhantomData<(T, M)>,
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcRecorder<T, M> {
+    #[inline]
+    pub(crate) fn new(ty: IpcType) -> Self {
+        Self {
+            ty,
+            recording: AtomicBool::new(false),
+            file: Mutex::new(None),
+            _type_holder: PhantomData,
+        }
+    }
+
+    /// Create the record file, the previous recording will be stopped.
+    pub(crate) fn start(&self, path: &Path, features: u64, state: RecordState) -> io::Result<()> {
+        let mut file = File::create(path)?;
+
+        let mut header = Encoder(Vec::new());
+        header.u32(RECORD_MAGIC);
+        header.bytes(&Handshake::new::<T, M>().encode());
+        header.u64(features);
+        state.encode(&mut header);
+        file.write_all(&header.0)?;
+
+        *self.file.lock() = Some(RecordFile {
+            file,
+            start: Instant::now(),
+        });
+        self.recording.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn stop(&self) {
+        self.recording.store(false, Ordering::Release);
+        self.file.lock().take();
+    }
+
+    /// Record the event sent by this side, and return it back.
+    #[inline]
+    pub(crate) fn sent_event(&self, evt: IpcEvent<T>) -> IpcEvent<T> {
+        self.event(self.origin(true), evt)
+    }
+
+    /// Record the event received from the peer, and return it back.
+    #[inline]
+    pub(crate) fn received_event(&self, evt: IpcEvent<T>) -> IpcEvent<T> {
+        // `IpcEvent::PeerLost` was generated by this side, it was never sent by the peer.
+        let origin = match evt {
+            IpcEvent::PeerLost => ORIGIN_LOCAL,
+            _ => self.origin(false),
+        };
+        self.event(origin, evt)
+    }
+
+    #[inline]
+    pub(crate) fn received_events(&self, events: Vec<IpcEvent<T>>) -> Vec<IpcEvent<T>> {
+        if !self.recording.load(Ordering::Acquire) {
+            return events;
+        }
+        events
+            .into_iter()
+            .map(|evt| self.received_event(evt))
+            .collect()
+    }
+
+    /// @param `sent`: the request was sent by this side or received from the peer.
+    #[inline]
+    pub(crate) fn request(&self, sent: bool, request: &M) {
+        if self.recording.load(Ordering::Acquire) {
+            self.write(KIND_REQUEST, self.origin(sent), &to_bytes(request))
+        }
+    }
+
+    /// @param `sent`: the response was sent by this side or received from the peer.
+    pub(crate) fn response(&self, sent: bool, response: &Option<M>) {
+        if !self.recording.load(Ordering::Acquire) {
+            return;
+        }
+        let mut payload = vec![response.is_some() as u8];
+        match response {
+            Some(ref response) => payload.extend_from_slice(&to_bytes(response)),
+            None => payload.resize(1 + size_of::<M>(), 0),
+        }
+        self.write(KIND_RESPONSE, self.origin(sent), &payload)
+    }
+
+    fn event(&self, origin: u8, evt: IpcEvent<T>) -> IpcEvent<T> {
+        if !self.recording.load(Ordering::Acquire) {
+            return evt;
+        }
+        self.write(KIND_EVENT, origin, &encode_event(&evt));
+        evt
+    }
+
+    #[inline]
+    fn origin(&self, sent: bool) -> u8 {
+        match (self.ty, sent) {
+            (IpcType::Master, true) | (IpcType::Slave, false) => ORIGIN_MASTER,
+            (IpcType::Master, false) | (IpcType::Slave, true) => ORIGIN_SLAVE,
+        }
+    }
+
+    /// Write the entry in one system call, so the recorded entries were kept if the process crashed.
+    fn write(&self, kind: u8, origin: u8, payload: &[u8]) {
+        let mut guard = self.file.lock();
+        let Some(record) = guard.as_mut() else {
+            return;
+        };
+
+        let offset = record.start.elapsed().as_nanos() as u64;
+        let mut entry = Vec::with_capacity(ENTRY_HEADER_SIZE + payload.len());
+        entry.push(kind);
+        entry.push(origin);
+        entry.extend_from_slice(&offset.to_le_bytes());
+        entry.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        entry.extend_from_slice(payload);
+
+        if let Err(e) = record.file.write_all(&entry) {
+            error!("`IpcRecorder` write failed, recording stopped. {:?}", e);
+            guard.take();
+            self.recording.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// The recorded entry sent by the master.
+enum Entry<T: 'static + Copy, M: 'static + Copy> {
+    Event(IpcEvent<T>),
+    Request(M),
+}
+
+/// Replay the recorded file into the slave without the master, as the transport of
+/// [`IpcBuilder::transport()`](crate::IpcBuilder::transport).
+///
+/// The events and requests sent by the master were delivered in the recorded order, so the event
+/// behind a request was delivered after the request was received. The responses of master were
+/// returned to the requests of slave in the recorded order. The events, frames and responses
+/// sent by the slave were discarded, and the timestamps of the events were replaced with
+/// the replaying time.
+///
+/// On linux and android the replayer plays the master who shares the segments, the shared buffer
+/// and regions were restored to the state when the recording started, and the buffer was recreated
+/// when the recorded [`IpcEvent::ResizeEvent`] was delivered. So the slave was built without
+/// any shared memory of master, and there is no heartbeat of the master to check.
+///
+/// ```ignore
+/// // Plugin process recorded the trace:
+/// slave.start_recording(Path::new("plugin.trace"))?;
+///
+/// // Reproduce the issue without the host process:
+/// let replayer = IpcReplayer::<UserEvent, Request>::open("plugin.trace", ReplayMode::RealTime)?;
+/// let slave = IpcBuilder::<UserEvent, Request>::with_customize()
+///     .name("plugin")
+///     .transport(replayer)
+///     .slave()?;
+/// while let Some(evt) = slave.recv() {
+///     // Process the event as it was received from the master.
+/// }
+/// ```
+pub struct IpcReplayer<T: 'static + Copy, M: 'static + Copy> {
+    mode: ReplayMode,
+    features: u64,
+    /// Set when the first entry was dispatched, so the time the slave took to connect was
+    /// not counted in the intervals.
+    start: OnceLock<Instant>,
+    #[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+    state: RecordState,
+    /// The events and requests of master in the recorded order.
+    entries: Mutex<VecDeque<(Duration, Entry<T, M>)>>,
+    responses: Mutex<VecDeque<Option<M>>>,
+    /// Created when the slave received the first segment.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    segments: Mutex<Option<ReplaySegments>>,
+}
+
+/// SAFETY: The entries were guarded by `Mutex`.
+unsafe impl<T: 'static + Copy, M: 'static + Copy> Send for IpcReplayer<T, M> {}
+unsafe impl<T: 'static + Copy, M: 'static + Copy> Sync for IpcReplayer<T, M> {}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcReplayer<T, M> {
+    /// Open the recorded file, return the error with kind [`io::ErrorKind::InvalidData`]
+    /// if the file was not recorded by the master and slave with the same types.
+    pub fn open<P: AsRef<Path>>(path: P, mode: ReplayMode) -> io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let mut dec = Decoder(&bytes);
+        if dec.u32() != Some(RECORD_MAGIC) {
+            return Err(invalid_data("the file was not recorded by tipc"));
+        }
+
+        let handshake = dec
+            .take(HANDSHAKE_SIZE)
+            .and_then(Handshake::decode)
+            .ok_or_else(|| invalid_data("the file contains invalid handshake"))?;
+        handshake
+            .verify::<T, M>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let (features, state) = dec
+            .u64()
+            .zip(RecordState::decode(&mut dec))
+            .ok_or_else(|| invalid_data("the file contains invalid header"))?;
+
+        let mut entries = VecDeque::new();
+        let mut responses = VecDeque::new();
+        let mut remain = dec.0;
+        // The tail of file may be truncated if the recording process crashed.
+        while let Some((kind, origin, offset, payload)) = parse_entry(remain) {
+            remain = &remain[ENTRY_HEADER_SIZE + payload.len()..];
+            if origin != ORIGIN_MASTER {
+                continue;
+            }
+
+            match kind {
+                KIND_EVENT => match decode_event(payload) {
+                    Some(evt) => entries.push_back((offset, Entry::Event(evt))),
+                    None => return Err(invalid_data("the file contains invalid event")),
+                },
+                KIND_REQUEST if payload.len() == size_of::<M>() => {
+                    let request = from_bytes::<M>(payload).unwrap();
+                    entries.push_back((offset, Entry::Request(request)))
+                }
+                KIND_RESPONSE if payload.len() == 1 + size_of::<M>() => responses
+                    .push_back((payload[0] != 0).then(|| from_bytes::<M>(&payload[1..]).unwrap())),
+                _ => return Err(invalid_data("the file contains invalid entry")),
+            }
+        }
+        // The intervals were counted from the first entry.
+        if let Some(&(first, _)) = entries.front() {
+            entries.iter_mut().for_each(|(offset, _)| *offset -= first);
+        }
+
+        Ok(Self {
+            mode,
+            features,
+            start: OnceLock::new(),
+            state,
+            entries: Mutex::new(entries),
+            responses: Mutex::new(responses),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            segments: Mutex::new(None),
+        })
+    }
+
+    /// Whether all the recorded events and requests were delivered.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+
+    #[inline]
+    fn start(&self) -> Instant {
+        *self.start.get_or_init(Instant::now)
+    }
+
+    #[inline]
+    fn is_due(&self, offset: Duration) -> bool {
+        match self.mode {
+            ReplayMode::RealTime => self.start().elapsed() >= offset,
+            ReplayMode::Fast => true,
+        }
+    }
+
+    /// Pop the front entry if it was due and `matched`.
+    fn pop_due(&self, matched: impl Fn(&Entry<T, M>) -> bool) -> Option<(Duration, Entry<T, M>)> {
+        let mut entries = self.entries.lock();
+        let (offset, entry) = entries.front()?;
+        if !matched(entry) || !self.is_due(*offset) {
+            return None;
+        }
+        entries.pop_front()
+    }
+
+    /// Recreate the buffer of new size as the master did before it sent the resize event.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn resize(&self, width: i32, height: i32) -> io::Result<()> {
+        let mut segments = self.segments.lock();
+        // The slave was not built with the replayer.
+        let Some(segments) = segments.as_mut() else {
+            return Ok(());
+        };
+
+        let (width, height) = (width.max(0) as u32, height.max(0) as u32);
+        let buffer = SharedBuffer::create(buffer_size(width, height)?)?;
+        let sinfo = segments.shared_info::<M>();
+        sinfo.width.store(width, Ordering::Release);
+        sinfo.height.store(height, Ordering::Release);
+        sinfo.resized.store(true, Ordering::Release);
+
+        segments
+            .pending
+            .retain(|(kind, _)| *kind != SegmentKind::Buffer);
+        segments.pending.push((SegmentKind::Buffer, buffer));
+        Ok(())
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcTransport<T, M> for IpcReplayer<T, M> {
+    /// The events sent by slave were discarded.
+    #[inline]
+    fn try_send(&self, _: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    #[inline]
+    fn has_event(&self) -> bool {
+        self.entries
+            .lock()
+            .front()
+            .is_some_and(|(offset, entry)| matches!(entry, Entry::Event(_)) && self.is_due(*offset))
+    }
+
+    fn try_recv(&self) -> Option<IpcEvent<T>> {
+        let Some((offset, Entry::Event(mut evt))) =
+            self.pop_due(|entry| matches!(entry, Entry::Event(_)))
+        else {
+            return None;
+        };
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let IpcEvent::ResizeEvent(width, height, _) = evt {
+            if let Err(e) = self.resize(width, height) {
+                error!("`IpcReplayer` recreate the buffer failed. {:?}", e)
+            }
+        }
+
+        let timestamp = match self.mode {
+            ReplayMode::RealTime => self.start() + offset,
+            ReplayMode::Fast => Instant::now(),
+        };
+        evt.set_timestamp(timestamp);
+        Some(evt)
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(evt) = self.try_recv() {
+                return Some(evt);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            // Sleep until the next recorded event was due, or the request in front was received.
+            let wake = match self.entries.lock().front() {
+                Some((offset, Entry::Event(_))) => self.start() + *offset,
+                Some((_, Entry::Request(_))) => now + POLL_WAIT,
+                None => deadline,
+            };
+            thread::sleep(wake.min(deadline).saturating_duration_since(now));
+        }
+    }
+
+    /// The frames were not recorded, the frames sent by slave were discarded.
+    #[inline]
+    fn try_send_frame(&self, _: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Return the next recorded response of master.
+    #[inline]
+    fn send_request(&self, _: M) -> Result<Option<M>, Box<dyn Error>> {
+        self.responses.lock().pop_front().ok_or_else(|| {
+            Box::new(IpcError::new(
+                "`send_request()` failed, no more recorded responses",
+            )) as Box<dyn Error>
+        })
+    }
+
+    #[inline]
+    fn try_recv_request(&self) -> Option<M> {
+        match self.pop_due(|entry| matches!(entry, Entry::Request(_)))? {
+            (_, Entry::Request(request)) => Some(request),
+            _ => None,
+        }
+    }
+
+    /// The responses of slave were discarded.
+    #[inline]
+    fn response_request(&self, _: Option<M>) {}
+
+    /// The recorded features without the heartbeat, there is no master beating.
+    #[inline]
+    fn features(&self) -> u64 {
+        self.features & !FEATURE_HEARTBEAT
+    }
+
+    /// Play the master who shares the segments.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn recv_segment(&self, kind: SegmentKind, _: Duration) -> io::Result<SharedBuffer> {
+        let mut segments = self.segments.lock();
+        if segments.is_none() {
+            *segments = Some(ReplaySegments::create::<T, M>(&self.state)?);
+        }
+
+        let pending = &mut segments.as_mut().unwrap().pending;
+        match pending.iter().position(|(k, _)| *k == kind) {
+            Some(idx) => Ok(pending.remove(idx).1),
+            None => Err(io::ErrorKind::TimedOut.into()),
+        }
+    }
+}
+
+/// The segments created by the replayer in place of the master.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+struct ReplaySegments {
+    /// Mapped by the replayer too, to resize the buffer as the master did.
+    shared_info: SharedBuffer,
+    /// The segments not received by the slave yet.
+    pending: Vec<(SegmentKind, SharedBuffer)>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl ReplaySegments {
+    fn create<T: 'static + Copy, M: 'static + Copy>(state: &RecordState) -> io::Result<Self> {
+        let shared_info = SharedBuffer::create(size_of::<SharedInfo<M>>())?;
+        let mut segments = Self {
+            shared_info: shared_info.try_clone()?,
+            pending: vec![],
+        };
+        let sinfo = segments.shared_info::<M>();
+        sinfo.handshake.init::<T, M>();
+        sinfo.width.store(state.width, Ordering::Release);
+        sinfo.height.store(state.height, Ordering::Release);
+        sinfo.prepared.store(true, Ordering::Release);
+
+        let capacity = state.regions.len().max(DEFAULT_MAX_REGIONS);
+        let region_table = SharedBuffer::create(RegionTable::segment_size(capacity))?;
+        let table = RegionTable::init(Segment::Mapped(region_table.try_clone()?), capacity);
+        for (id, rect) in state.regions.iter() {
+            table.upsert(id, *rect);
+        }
+
+        let (lock_size, lock_mutex_size) = MemRwLock::SEGMENT_SIZES;
+        debug_assert_eq!(lock_mutex_size, MemMutex::SEGMENT_SIZE);
+        let buffer_size = buffer_size(state.width, state.height)?;
+        Ok(Self {
+            pending: vec![
+                (SegmentKind::SharedInfo, shared_info),
+                (
+                    SegmentKind::Signal,
+                    SharedBuffer::create(size_of::<Event>())?,
+                ),
+                (SegmentKind::BufferLock, SharedBuffer::create(lock_size)?),
+                (
+                    SegmentKind::BufferLockMutex,
+                    SharedBuffer::create(lock_mutex_size)?,
+                ),
+                (SegmentKind::RegionTable, region_table),
+                (SegmentKind::Buffer, SharedBuffer::create(buffer_size)?),
+            ],
+            ..segments
+        })
+    }
+
+    #[inline]
+    fn shared_info<M: 'static + Copy>(&mut self) -> &mut SharedInfo<M> {
+        unsafe {
+            (self.shared_info.as_ptr() as *mut SharedInfo<M>)
+                .as_mut()
+                .unwrap()
+        }
+    }
+}
+
+/// The size of the rgba buffer, return the error if the recorded size overflows.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[inline]
+fn buffer_size(width: u32, height: u32) -> io::Result<usize> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|size| size.checked_mul(4))
+        .ok_or_else(|| invalid_data("the recorded buffer size overflows"))
+}
+
+#[inline]
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// @return (kind, origin, offset, payload) if the bytes contain a complete entry.
+#[inline]
+fn parse_entry(bytes: &[u8]) -> Option<(u8, u8, Duration, &[u8])> {
+    if bytes.len() < ENTRY_HEADER_SIZE {
+        return None;
+    }
+    let offset = u64::from_le_bytes(bytes[2..10].try_into().unwrap());
+    let len = u32::from_le_bytes(bytes[10..ENTRY_HEADER_SIZE].try_into().unwrap()) as usize;
+    let payload = bytes.get(ENTRY_HEADER_SIZE..ENTRY_HEADER_SIZE + len)?;
+    Some((bytes[0], bytes[1], Duration::from_nanos(offset), payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::handshake::IPC_FEATURES;
+
+    #[test]
+    fn test_record_replay() {
+        let path =
+            std::env::temp_dir().join(format!("_ipc_record_test_{}.trace", std::process::id()));
+
+        let recorder = IpcRecorder::<u32, u64>::new(IpcType::Slave);
+        recorder
+            .start(&path, IPC_FEATURES, RecordState::default())
+            .unwrap();
+        recorder.received_event(IpcEvent::UserEvent(1, Instant::now()));
+        recorder.sent_event(IpcEvent::UserEvent(100, Instant::now()));
+        recorder.request(false, &7);
+        recorder.response(true, &Some(8));
+        recorder.received_event(IpcEvent::TextEvent("text".to_string(), Instant::now()));
+        recorder.request(true, &9);
+        recorder.response(false, &Some(10));
+        // Detected by the slave itself, not replayed.
+        recorder.received_event(IpcEvent::PeerLost);
+        recorder.stop();
+        // Not recorded after stopped.
+        recorder.received_event(IpcEvent::Exit);
+
+        let replayer = IpcReplayer::<u32, u64>::open(&path, ReplayMode::Fast).unwrap();
+        assert_eq!(replayer.features(), IPC_FEATURES & !FEATURE_HEARTBEAT);
+        assert!(matches!(
+            replayer.try_recv(),
+            Some(IpcEvent::UserEvent(1, _))
+        ));
+        // The event behind the request was delivered after the request was received.
+        assert!(!replayer.has_event());
+        assert!(replayer.try_recv().is_none());
+        assert_eq!(replayer.try_recv_request(), Some(7));
+        assert!(replayer.try_recv_request().is_none());
+        assert!(matches!(replayer.try_recv(), Some(IpcEvent::TextEvent(t, _)) if t == "text"));
+        assert!(replayer.try_recv().is_none());
+        assert_eq!(replayer.send_request(9).unwrap(), Some(10));
+        assert!(replayer.send_request(9).is_err());
+        assert!(replayer.is_finished());
+
+        let err = IpcReplayer::<u32, [u8; 16]>::open(&path, ReplayMode::Fast)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_real_time() {
+        let path = std::env::temp_dir().join(format!(
+            "_ipc_replay_real_time_test_{}.trace",
+            std::process::id()
+        ));
+
+        let recorder = IpcRecorder::<u32, u64>::new(IpcType::Slave);
+        recorder
+            .start(&path, IPC_FEATURES, RecordState::default())
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+        recorder.received_event(IpcEvent::UserEvent(1, Instant::now()));
+        thread::sleep(Duration::from_millis(200));
+        recorder.received_event(IpcEvent::UserEvent(2, Instant::now()));
+        recorder.stop();
+
+        let replayer = IpcReplayer::<u32, u64>::open(&path, ReplayMode::RealTime).unwrap();
+        // The slave took long to connect, the first event was still dispatched at once.
+        thread::sleep(Duration::from_millis(400));
+        assert!(matches!(
+            replayer.try_recv(),
+            Some(IpcEvent::UserEvent(1, _))
+        ));
+        // The interval between the events was kept.
+        assert!(replayer.try_recv().is_none());
+        assert!(matches!(
+            replayer.recv_timeout(Duration::from_secs(1)),
+            Some(IpcEvent::UserEvent(2, _))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_replay_slave() {
+        use crate::{IpcBuilder, IpcNode};
+
+        let pid = std::process::id();
+        let path = std::env::temp_dir().join(format!("_ipc_replay_slave_test_{}.trace", pid));
+
+        let rect = Rect::new(10, 10, 50, 50);
+        let state = RecordState {
+            width: 100,
+            height: 80,
+            regions: vec![("widget".to_string(), rect)],
+        };
+        let recorder = IpcRecorder::<u32, u64>::new(IpcType::Slave);
+        recorder.start(&path, IPC_FEATURES, state).unwrap();
+        recorder.received_event(IpcEvent::ResizeEvent(200, 160, Instant::now()));
+        recorder.stop();
+
+        // There is no master of the name.
+        let replayer = IpcReplayer::<u32, u64>::open(&path, ReplayMode::Fast).unwrap();
+        let mut slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&format!("_ipc_replay_slave_{}", pid))
+            .transport(replayer)
+            .slave()
+            .unwrap();
+        assert_eq!((slave.width(), slave.height()), (100, 80));
+        assert_eq!(slave.region("widget"), Some(rect));
+        assert_eq!(slave.buffer().len(), 100 * 80 * 4);
+
+        assert!(matches!(
+            slave.try_recv(),
+            Some(IpcEvent::ResizeEvent(200, 160, _))
+        ));
+        slave.recreate_buffer();
+        assert_eq!((slave.width(), slave.height()), (200, 160));
+        assert_eq!(slave.buffer().len(), 200 * 160 * 4);
+        assert!(!slave.is_peer_lost());
+
+        // The corrupted size was rejected instead of overflowed.
+        assert!(buffer_size(u32::MAX, u32::MAX).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
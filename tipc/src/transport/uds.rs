@@ -0,0 +1,806 @@
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::SegmentKind;
+use super::{
+    wire::{decode_event, encode_event, from_bytes, to_bytes},
+    IpcTransport,
+};
+use crate::{
+    ipc_event::IpcEvent,
+    mem::{
+        handshake::{Handshake, HandshakeError, HANDSHAKE_SIZE},
+        IpcError,
+    },
+};
+use log::error;
+use parking_lot::{Condvar, Mutex};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    ffi::c_void,
+    io::{self, Read, Write},
+    mem::{size_of, MaybeUninit},
+    net::Shutdown,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+        unix::net::{UnixListener, UnixStream},
+    },
+    path::Path,
+    ptr,
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// The frame was prefixed by the header: (kind: `u8`, length of payload: little-endian `u32`).
+const FRAME_HEADER_SIZE: usize = 1 + size_of::<u32>();
+/// The size of bytes received from the socket at once.
+const RECV_CHUNK_SIZE: usize = 64 * 1024;
+
+const KIND_HANDSHAKE: u8 = 0;
+const KIND_ACK: u8 = 1;
+const KIND_EVENT: u8 = 2;
+const KIND_REQUEST: u8 = 3;
+const KIND_RESPONSE: u8 = 4;
+const KIND_SEGMENT: u8 = 5;
+const KIND_FRAME: u8 = 6;
+
+/// The received but not consumed data, filled by the reader thread.
+struct Inbox<T: 'static + Copy, M: 'static + Copy> {
+    events: VecDeque<IpcEvent<T>>,
+    frames: VecDeque<Vec<u8>>,
+    requests: VecDeque<M>,
+    responses: VecDeque<Option<M>>,
+    /// (kind of segment, file descriptor, length)
+    segments: VecDeque<(u8, OwnedFd, usize)>,
+    closed: bool,
+}
+
+struct Shared<T: 'static + Copy, M: 'static + Copy> {
+    inbox: Mutex<Inbox<T, M>>,
+    /// Notified when the data was received or the connection was closed.
+    received: Condvar,
+}
+
+/// SAFETY: The inbox was guarded by `Mutex`.
+unsafe impl<T: 'static + Copy, M: 'static + Copy> Send for Shared<T, M> {}
+unsafe impl<T: 'static + Copy, M: 'static + Copy> Sync for Shared<T, M> {}
+
+/// The transport of events, frames and requests over the unix domain socket.
+///
+/// The events were encoded field by field into the length-prefixed frames, the user events
+/// and requests were transferred as their raw bytes, so both sides must be built with the same
+/// types, which was verified by the handshake when connecting, and the types must not contain
+/// the padding bytes.
+///
+/// The shared memories of master were passed by the file descriptors, so the slave maps them
+/// without opening any shared memory by name, and the pixel buffers were shared by the
+/// file descriptors of memfd, see [`SegmentKind`](super::SegmentKind) and [`SharedBuffer`].
+///
+/// The socket was read by a dedicated reader thread, so the blocking
+/// [`send_request`](IpcTransport::send_request) will not stall the receiving of other threads.
+///
+/// ```ignore
+/// // Master:
+/// let listener = UnixListener::bind("/tmp/host.sock")?;
+/// let master = UdsTransport::<UserEvent, Request>::accept(&listener)?;
+///
+/// // Slave:
+/// let slave = UdsTransport::<UserEvent, Request>::connect("/tmp/host.sock")?;
+///
+/// // Transfer the traffic of `IpcMaster` or `IpcSlave` through it:
+/// let master = IpcBuilder::<UserEvent, Request>::with_customize()
+///     .name("host")
+///     .transport(master)
+///     .master()?;
+/// ```
+pub struct UdsTransport<T: 'static + Copy, M: 'static + Copy> {
+    stream: UnixStream,
+    features: u64,
+    shared: Arc<Shared<T, M>>,
+    reader: Option<JoinHandle<()>>,
+    write_lock: Mutex<()>,
+    /// Only one request can be waiting for the response at the same time.
+    request_lock: Mutex<()>,
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> UdsTransport<T, M> {
+    /// Accept a slave from the listener as the master side, block until the handshake finished.
+    pub fn accept(listener: &UnixListener) -> Result<Self, HandshakeError> {
+        let (stream, _) = listener.accept().map_err(io_error)?;
+        Self::send_handshake(&stream)?;
+        Self::finish_handshake(stream)
+    }
+
+    /// Connect to the master as the slave side, the handshake record of master will be verified.
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, HandshakeError> {
+        let stream = UnixStream::connect(path).map_err(io_error)?;
+        Self::verify_handshake(stream)
+    }
+
+    /// Create the connected (master, slave) pair in the process.
+    pub fn pair() -> Result<(Self, Self), HandshakeError> {
+        let (master, slave) = UnixStream::pair().map_err(io_error)?;
+        Self::send_handshake(&master)?;
+        let slave = Self::verify_handshake(slave)?;
+        let master = Self::finish_handshake(master)?;
+        Ok((master, slave))
+    }
+
+    /// Share the buffer with the peer, the peer receives it by [`try_recv_buffer`](Self::try_recv_buffer).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn send_buffer(&self, buffer: &SharedBuffer) -> io::Result<()> {
+        self.send_segment(SegmentKind::Buffer, buffer.as_fd(), buffer.len())
+    }
+
+    /// Receive the latest buffer shared by the peer, the outdated buffers were dropped.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn try_recv_buffer(&self) -> Option<SharedBuffer> {
+        self.recv_segment(SegmentKind::Buffer, Duration::ZERO)
+            .map_err(|e| {
+                if e.kind() != io::ErrorKind::TimedOut {
+                    error!("`UdsTransport` map the shared buffer failed. {:?}", e)
+                }
+            })
+            .ok()
+    }
+
+    /// Whether the peer has closed the connection.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.shared.inbox.lock().closed
+    }
+
+    fn send_handshake(stream: &UnixStream) -> Result<(), HandshakeError> {
+        let handshake = Handshake::new::<T, M>();
+        let frame = frame(KIND_HANDSHAKE, &handshake.encode());
+        (&*stream).write_all(&frame).map_err(io_error)
+    }
+
+    fn finish_handshake(stream: UnixStream) -> Result<Self, HandshakeError> {
+        let payload = read_frame(&stream, KIND_ACK)?;
+        let features = payload
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| HandshakeError::Io("invalid handshake acknowledge".to_string()))?;
+        Self::new(stream, features)
+    }
+
+    fn verify_handshake(stream: UnixStream) -> Result<Self, HandshakeError> {
+        let payload = read_frame(&stream, KIND_HANDSHAKE)?;
+        let handshake = Handshake::decode(&payload).ok_or(HandshakeError::Magic)?;
+        handshake.verify::<T, M>()?;
+
+        let features = handshake.features();
+        (&stream)
+            .write_all(&frame(KIND_ACK, &features.to_le_bytes()))
+            .map_err(io_error)?;
+        Self::new(stream, features)
+    }
+
+    /// Start the reader thread after the handshake finished.
+    fn new(stream: UnixStream, features: u64) -> Result<Self, HandshakeError> {
+        let shared = Arc::new(Shared {
+            inbox: Mutex::new(Inbox {
+                events: VecDeque::new(),
+                frames: VecDeque::new(),
+                requests: VecDeque::new(),
+                responses: VecDeque::new(),
+                segments: VecDeque::new(),
+                closed: false,
+            }),
+            received: Condvar::new(),
+        });
+
+        let reader_stream = stream.try_clone().map_err(io_error)?;
+        let reader_shared = shared.clone();
+        let reader = thread::Builder::new()
+            .name("uds-reader".to_string())
+            .spawn(move || read_loop(reader_stream, reader_shared))
+            .map_err(io_error)?;
+
+        Ok(Self {
+            stream,
+            features,
+            shared,
+            reader: Some(reader),
+            write_lock: Mutex::new(()),
+            request_lock: Mutex::new(()),
+        })
+    }
+
+    fn write_frame(&self, kind: u8, payload: &[u8]) -> io::Result<()> {
+        let frame = frame(kind, payload);
+        let _guard = self.write_lock.lock();
+        (&self.stream).write_all(&frame)
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> Drop for UdsTransport<T, M> {
+    fn drop(&mut self) {
+        // Wake up the blocking receiving of reader thread.
+        let _ = self.stream.shutdown(Shutdown::Both);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+impl<T: 'static + Copy, M: 'static + Copy> IpcTransport<T, M> for UdsTransport<T, M> {
+    #[inline]
+    fn try_send(&self, evt: IpcEvent<T>) -> Result<(), Box<dyn Error>> {
+        self.write_frame(KIND_EVENT, &encode_event(&evt))?;
+        Ok(())
+    }
+
+    #[inline]
+    fn has_event(&self) -> bool {
+        !self.shared.inbox.lock().events.is_empty()
+    }
+
+    #[inline]
+    fn try_recv(&self) -> Option<IpcEvent<T>> {
+        self.shared.inbox.lock().events.pop_front()
+    }
+
+    #[inline]
+    fn try_recv_vec(&self) -> Vec<IpcEvent<T>> {
+        self.shared.inbox.lock().events.drain(..).collect()
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Option<IpcEvent<T>> {
+        let deadline = Instant::now() + timeout;
+        let mut inbox = self.shared.inbox.lock();
+        while inbox.events.is_empty() && !inbox.closed {
+            if self
+                .shared
+                .received
+                .wait_until(&mut inbox, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+        inbox.events.pop_front()
+    }
+
+    #[inline]
+    fn try_send_frame(&self, frame: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.write_frame(KIND_FRAME, frame)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn has_frame(&self) -> bool {
+        !self.shared.inbox.lock().frames.is_empty()
+    }
+
+    #[inline]
+    fn try_recv_frame(&self) -> Option<Vec<u8>> {
+        self.shared.inbox.lock().frames.pop_front()
+    }
+
+    fn send_request(&self, request: M) -> Result<Option<M>, Box<dyn Error>> {
+        let _guard = self.request_lock.lock();
+        self.write_frame(KIND_REQUEST, &to_bytes(&request))?;
+
+        // The inbox was unlocked while waiting, so the other threads can still receive.
+        let mut inbox = self.shared.inbox.lock();
+        loop {
+            if let Some(response) = inbox.responses.pop_front() {
+                return Ok(response);
+            }
+            if inbox.closed {
+                return Err(Box::new(IpcError::new(
+                    "`send_request()` failed, the peer was lost",
+                )));
+            }
+            self.shared.received.wait(&mut inbox);
+        }
+    }
+
+    #[inline]
+    fn try_recv_request(&self) -> Option<M> {
+        self.shared.inbox.lock().requests.pop_front()
+    }
+
+    fn response_request(&self, response: Option<M>) {
+        let mut payload = vec![response.is_some() as u8];
+        match response {
+            Some(ref response) => payload.extend_from_slice(&to_bytes(response)),
+            None => payload.resize(1 + size_of::<M>(), 0),
+        }
+        if let Err(e) = self.write_frame(KIND_RESPONSE, &payload) {
+            error!("`UdsTransport` response request failed. {:?}", e)
+        }
+    }
+
+    #[inline]
+    fn features(&self) -> u64 {
+        self.features
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn send_segment(&self, kind: SegmentKind, fd: BorrowedFd, len: usize) -> io::Result<()> {
+        let mut payload = vec![kind as u8];
+        payload.extend_from_slice(&(len as u64).to_le_bytes());
+        let frame = frame(KIND_SEGMENT, &payload);
+        let _guard = self.write_lock.lock();
+        send_with_fd(&self.stream, &frame, fd.as_raw_fd())
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn recv_segment(&self, kind: SegmentKind, timeout: Duration) -> io::Result<SharedBuffer> {
+        let deadline = Instant::now() + timeout;
+        let mut inbox = self.shared.inbox.lock();
+        loop {
+            if let Some(idx) = inbox.segments.iter().rposition(|(k, ..)| *k == kind as u8) {
+                let (_, fd, len) = inbox.segments.remove(idx).unwrap();
+                inbox.segments.retain(|(k, ..)| *k != kind as u8);
+                drop(inbox);
+                return SharedBuffer::map(fd, len);
+            }
+            if inbox.closed {
+                return Err(io::ErrorKind::NotConnected.into());
+            }
+            if Instant::now() >= deadline {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            self.shared.received.wait_until(&mut inbox, deadline);
+        }
+    }
+}
+
+/// Receive the data from socket and dispatch the complete frames, until the connection was closed.
+fn read_loop<T: 'static + Copy, M: 'static + Copy>(stream: UnixStream, shared: Arc<Shared<T, M>>) {
+    let mut bytes = vec![];
+    let mut fds = VecDeque::new();
+    let mut chunk = vec![0u8; RECV_CHUNK_SIZE];
+
+    loop {
+        let received = match recv_with_fds(&stream, &mut chunk, &mut fds) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                error!("`UdsTransport` receive failed. {:?}", e);
+                break;
+            }
+        };
+        bytes.extend_from_slice(&chunk[..received]);
+
+        let mut consumed = 0;
+        let mut inbox = shared.inbox.lock();
+        while let Some((kind, payload)) = parse_frame(&bytes[consumed..]) {
+            consumed += FRAME_HEADER_SIZE + payload.len();
+            dispatch(&mut inbox, &mut fds, kind, payload);
+        }
+        drop(inbox);
+        bytes.drain(..consumed);
+        shared.received.notify_all();
+    }
+
+    shared.inbox.lock().closed = true;
+    shared.received.notify_all();
+}
+
+fn dispatch<T: 'static + Copy, M: 'static + Copy>(
+    inbox: &mut Inbox<T, M>,
+    fds: &mut VecDeque<OwnedFd>,
+    kind: u8,
+    payload: &[u8],
+) {
+    match kind {
+        KIND_EVENT => match decode_event(payload) {
+            Some(evt) => inbox.events.push_back(evt),
+            None => error!("`UdsTransport` received invalid event."),
+        },
+        KIND_FRAME => inbox.frames.push_back(payload.to_vec()),
+        KIND_REQUEST if payload.len() == size_of::<M>() => {
+            inbox.requests.extend(from_bytes::<M>(payload))
+        }
+        KIND_RESPONSE if payload.len() == 1 + size_of::<M>() => {
+            let response = (payload[0] != 0).then(|| from_bytes::<M>(&payload[1..]).unwrap());
+            inbox.responses.push_back(response)
+        }
+        KIND_SEGMENT if payload.len() == 1 + size_of::<u64>() => {
+            let len = u64::from_le_bytes(payload[1..].try_into().unwrap()) as usize;
+            match fds.pop_front() {
+                Some(fd) => inbox.segments.push_back((payload[0], fd, len)),
+                None => error!("`UdsTransport` received the segment without file descriptor."),
+            }
+        }
+        _ => error!("`UdsTransport` received invalid frame, kind = {}", kind),
+    }
+}
+
+/// The pixel buffer shared by the file descriptor of memfd.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub struct SharedBuffer {
+    fd: OwnedFd,
+    ptr: *mut u8,
+    len: usize,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe impl Send for SharedBuffer {}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe impl Sync for SharedBuffer {}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl SharedBuffer {
+    pub fn create(len: usize) -> io::Result<Self> {
+        let fd = unsafe { libc::memfd_create(c"tipc_buffer".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        if unsafe { libc::ftruncate(fd.as_raw_fd(), len as libc::off_t) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Self::map(fd, len)
+    }
+
+    /// Map the same memory again, both of the mappings were valid until dropped.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Self::map(self.fd.try_clone()?, self.len)
+    }
+
+    fn map(fd: OwnedFd, len: usize) -> io::Result<Self> {
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len.max(1),
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            fd,
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl AsFd for SharedBuffer {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl Drop for SharedBuffer {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr as *mut c_void, self.len.max(1)) };
+    }
+}
+
+#[inline]
+fn io_error(e: io::Error) -> HandshakeError {
+    HandshakeError::Io(e.to_string())
+}
+
+#[inline]
+fn frame(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+    frame.push(kind);
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// @return (kind, payload) if the bytes contain a complete frame.
+#[inline]
+fn parse_frame(bytes: &[u8]) -> Option<(u8, &[u8])> {
+    if bytes.len() < FRAME_HEADER_SIZE {
+        return None;
+    }
+    let len = u32::from_le_bytes(bytes[1..FRAME_HEADER_SIZE].try_into().unwrap()) as usize;
+    let payload = bytes.get(FRAME_HEADER_SIZE..FRAME_HEADER_SIZE + len)?;
+    Some((bytes[0], payload))
+}
+
+/// Read a frame in blocking mode, only used in the handshake.
+fn read_frame(mut stream: &UnixStream, kind: u8) -> Result<Vec<u8>, HandshakeError> {
+    let mut header = [0u8; FRAME_HEADER_SIZE];
+    stream.read_exact(&mut header).map_err(io_error)?;
+    if header[0] != kind {
+        return Err(HandshakeError::Magic);
+    }
+
+    let len = u32::from_le_bytes(header[1..].try_into().unwrap()) as usize;
+    if len > HANDSHAKE_SIZE {
+        return Err(HandshakeError::Magic);
+    }
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).map_err(io_error)?;
+    Ok(payload)
+}
+
+fn recv_with_fds(
+    stream: &UnixStream,
+    buf: &mut [u8],
+    fds: &mut VecDeque<OwnedFd>,
+) -> io::Result<usize> {
+    // Aligned buffer of the control messages.
+    let mut control = [0u64; 32];
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { MaybeUninit::zeroed().assume_init() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut c_void;
+    msg.msg_controllen = size_of::<[u64; 32]>() as _;
+
+    // The received fds were not inherited by the processes spawned later.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let flags = 0;
+    let received = unsafe { libc::recvmsg(stream.as_raw_fd(), &mut msg, flags) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    while !cmsg.is_null() {
+        let header = unsafe { &*cmsg };
+        if header.cmsg_level == libc::SOL_SOCKET && header.cmsg_type == libc::SCM_RIGHTS {
+            let data = unsafe { libc::CMSG_DATA(cmsg) } as *const RawFd;
+            let cnt = (header.cmsg_len as usize - unsafe { libc::CMSG_LEN(0) } as usize)
+                / size_of::<RawFd>();
+            for i in 0..cnt {
+                let fd = unsafe { ptr::read_unaligned(data.add(i)) };
+                fds.push_back(unsafe { OwnedFd::from_raw_fd(fd) });
+            }
+        }
+        cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
+    }
+
+    Ok(received as usize)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn send_with_fd(mut stream: &UnixStream, data: &[u8], fd: RawFd) -> io::Result<()> {
+    let mut control = [0u64; 4];
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut c_void,
+        iov_len: data.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { MaybeUninit::zeroed().assume_init() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut c_void;
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(size_of::<RawFd>() as u32) } as _;
+
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<RawFd>() as u32) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut RawFd, fd);
+    }
+
+    let sent = unsafe { libc::sendmsg(stream.as_raw_fd(), &msg, 0) };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // The file descriptor was sent with the first byte, send the rest bytes.
+    stream.write_all(&data[sent as usize..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mem::{
+            IPC_MEM_BUFFER_NAME, IPC_MEM_LOCK_NAME, IPC_MEM_MASTER_FRAME_QUEUE,
+            IPC_MEM_MASTER_QUEUE, IPC_MEM_REGION_NAME, IPC_MEM_SHARED_INFO_NAME,
+            IPC_MEM_SIGNAL_EVT, IPC_MEM_SLAVE_FRAME_QUEUE, IPC_MEM_SLAVE_QUEUE,
+        },
+        IpcBuilder, IpcNode,
+    };
+    use std::ffi::CString;
+    use tlib::figure::Rect;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Request(u64);
+
+    #[test]
+    fn test_uds_transport() {
+        let (master, slave) = UdsTransport::<u32, Request>::pair().unwrap();
+        assert_eq!(master.features(), slave.features());
+        assert!(!master.has_event());
+
+        for i in 0..100 {
+            slave
+                .try_send(IpcEvent::UserEvent(i, Instant::now()))
+                .unwrap();
+        }
+        for i in 0..100 {
+            let evt = master.recv_timeout(Duration::from_secs(1));
+            assert!(matches!(evt, Some(IpcEvent::UserEvent(e, _)) if e == i));
+        }
+        assert!(master.try_recv().is_none());
+
+        slave.try_send_frame(b"frame").unwrap();
+        while !master.has_frame() {
+            thread::yield_now();
+        }
+        assert_eq!(master.try_recv_frame().unwrap(), b"frame");
+
+        // The requests were queued until received.
+        master
+            .write_frame(KIND_REQUEST, &to_bytes(&Request(1)))
+            .unwrap();
+        master
+            .write_frame(KIND_REQUEST, &to_bytes(&Request(2)))
+            .unwrap();
+        let mut requests = vec![];
+        while requests.len() < 2 {
+            requests.extend(slave.try_recv_request());
+        }
+        assert_eq!(requests, vec![Request(1), Request(2)]);
+
+        let master = Arc::new(master);
+        let requester = master.clone();
+        let handle = thread::spawn(move || requester.send_request(Request(1)).unwrap());
+
+        // The events can still be received while the request was waiting for the response.
+        let request = loop {
+            if let Some(request) = slave.try_recv_request() {
+                break request;
+            }
+            thread::yield_now();
+        };
+        slave
+            .try_send(IpcEvent::UserEvent(7, Instant::now()))
+            .unwrap();
+        assert!(matches!(
+            master.recv_timeout(Duration::from_secs(1)),
+            Some(IpcEvent::UserEvent(7, _))
+        ));
+        assert!(!handle.is_finished());
+
+        slave.response_request(Some(Request(request.0 + 1)));
+        assert_eq!(handle.join().unwrap(), Some(Request(2)));
+
+        drop(slave);
+        assert!(master.recv_timeout(Duration::from_secs(1)).is_none());
+        assert!(master.is_closed());
+        assert!(master.send_request(Request(1)).is_err());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_uds_ipc_node() {
+        let (master_transport, slave_transport) = UdsTransport::<u32, u64>::pair().unwrap();
+        let name = format!("_tipc_uds_node_test_{}", std::process::id());
+        let mut master = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .transport(master_transport)
+            .master()
+            .unwrap();
+        master.create_buffer(10, 10);
+        master.add_rect("widget", Rect::new(0, 0, 5, 5));
+
+        // Remove the names of shared memories, the slave should map them from the transport.
+        let shm_unlink = |os_id: &str| {
+            let os_id = CString::new(os_id).unwrap();
+            unsafe { libc::shm_unlink(os_id.as_ptr()) }
+        };
+        let lock_name = format!("{}{}", name, IPC_MEM_LOCK_NAME);
+        for suffix in [
+            IPC_MEM_SHARED_INFO_NAME,
+            IPC_MEM_SIGNAL_EVT,
+            IPC_MEM_REGION_NAME,
+            IPC_MEM_MASTER_QUEUE,
+            IPC_MEM_SLAVE_QUEUE,
+            IPC_MEM_MASTER_FRAME_QUEUE,
+            IPC_MEM_SLAVE_FRAME_QUEUE,
+        ] {
+            assert_eq!(shm_unlink(&format!("{}{}", name, suffix)), 0);
+        }
+        assert_eq!(shm_unlink(&lock_name), 0);
+        assert_eq!(shm_unlink(&format!("mmtx_{}", lock_name)), 0);
+        // The buffer was created in memfd.
+        assert_ne!(shm_unlink(&format!("{}{}_0", name, IPC_MEM_BUFFER_NAME)), 0);
+
+        let mut slave = IpcBuilder::<u32, u64>::with_customize()
+            .name(&name)
+            .transport(slave_transport)
+            .slave()
+            .unwrap();
+        assert_eq!(slave.region("widget"), Some(Rect::new(0, 0, 5, 5)));
+        master.buffer()[0] = 7;
+        assert_eq!(slave.buffer()[0], 7);
+
+        master.pretreat_resize(20, 20);
+        master.recreate_buffer();
+        slave.recreate_buffer();
+        assert_eq!(slave.width(), 20);
+        master.buffer()[399] = 9;
+        assert_eq!(slave.buffer()[399], 9);
+
+        slave
+            .try_send(IpcEvent::UserEvent(1, Instant::now()))
+            .unwrap();
+        assert!(matches!(
+            master.recv_timeout(Duration::from_secs(1)),
+            Some(IpcEvent::UserEvent(1, _))
+        ));
+
+        let handle = thread::spawn(move || {
+            loop {
+                if let Some(request) = slave.try_recv_request() {
+                    slave.respose_request(Some(request + 1));
+                    break;
+                }
+                thread::yield_now();
+            }
+            slave
+        });
+        assert_eq!(master.send_request(1).unwrap(), Some(2));
+        drop(handle.join().unwrap());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_uds_shared_buffer() {
+        let (master, slave) = UdsTransport::<(), ()>::pair().unwrap();
+
+        let mut buffer = SharedBuffer::create(16).unwrap();
+        buffer.as_mut_slice().copy_from_slice(&[7; 16]);
+        master.send_buffer(&buffer).unwrap();
+
+        let mut received = loop {
+            if let Some(buffer) = slave.try_recv_buffer() {
+                break buffer;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(received.as_slice(), &[7; 16]);
+        let fd_flags = unsafe { libc::fcntl(received.as_fd().as_raw_fd(), libc::F_GETFD) };
+        assert_ne!(fd_flags & libc::FD_CLOEXEC, 0);
+
+        received.as_mut_slice()[0] = 1;
+        assert_eq!(buffer.as_slice()[0], 1);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::ipc_event::IpcEvent;
+use lazy_static::lazy_static;
+use std::{
+    mem::size_of,
+    ptr, slice,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tlib::{
+    events::DeltaType,
+    figure::{Point, Rect},
+    prelude::SystemCursorShape,
+};
+
+lazy_static! {
+    /// The `Instant` was opaque, it was transferred as the wall clock converted by this pair.
+    static ref CLOCK_ANCHOR: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+}
+
+const TAG_NONE: u8 = 0;
+const TAG_EXIT: u8 = 1;
+const TAG_PEER_LOST: u8 = 2;
+const TAG_RESIZE: u8 = 3;
+const TAG_VSYNC: u8 = 4;
+const TAG_KEY_PRESSED: u8 = 5;
+const TAG_KEY_RELEASED: u8 = 6;
+const TAG_MOUSE_PRESSED: u8 = 7;
+const TAG_MOUSE_RELEASE: u8 = 8;
+const TAG_MOUSE_ENTER: u8 = 9;
+const TAG_MOUSE_LEAVE: u8 = 10;
+const TAG_MOUSE_MOVE: u8 = 11;
+const TAG_MOUSE_WHEEL: u8 = 12;
+const TAG_REQUEST_FOCUS: u8 = 13;
+const TAG_SET_CURSOR_SHAPE: u8 = 14;
+const TAG_TEXT: u8 = 15;
+const TAG_REGION_CHANGED: u8 = 16;
+const TAG_DROPPED_FILE: u8 = 17;
+const TAG_HOVERED_FILE: u8 = 18;
+const TAG_HOVERED_FILE_CANCELLED: u8 = 19;
+const TAG_CLIPBOARD_GET: u8 = 20;
+const TAG_CLIPBOARD_TEXT: u8 = 21;
+const TAG_CLIPBOARD_SET: u8 = 22;
+const TAG_USER_EVENT: u8 = 23;
+
+/// The raw bytes of the value, the types transferred were verified by the handshake.
+///
+/// Only used for the user event `T` and the request `M`, which must not contain
+/// the padding bytes, e.g. the primitives, or the `#[repr(C)]` structs whose fields
+/// fill the whole size. Reading the uninitialized padding bytes was undefined behavior.
+#[inline]
+pub(crate) fn to_bytes<X: Copy>(val: &X) -> Vec<u8> {
+    // SAFETY: `X` was required to be padding-free, so all the bytes were initialized.
+    unsafe { slice::from_raw_parts(val as *const X as *const u8, size_of::<X>()) }.to_vec()
+}
+
+/// Read the value from the bytes produced by [`to_bytes()`].
+///
+/// @return `None` if the length of bytes mismatched.
+#[inline]
+pub(crate) fn from_bytes<X: Copy>(bytes: &[u8]) -> Option<X> {
+    (bytes.len() == size_of::<X>())
+        .then(|| unsafe { ptr::read_unaligned(bytes.as_ptr() as *const X) })
+}
+
+/// Encode the event field by field, the strings were prefixed by their lengths,
+/// so they were not limited by the fixed size of shared memory queue.
+pub(crate) fn encode_event<T: 'static + Copy>(evt: &IpcEvent<T>) -> Vec<u8> {
+    let mut enc = Encoder::default();
+    match evt {
+        IpcEvent::None => enc.u8(TAG_NONE),
+        IpcEvent::Exit => enc.u8(TAG_EXIT),
+        IpcEvent::PeerLost => enc.u8(TAG_PEER_LOST),
+        IpcEvent::ResizeEvent(w, h, ts) => {
+            enc.u8(TAG_RESIZE);
+            enc.i32(*w);
+            enc.i32(*h);
+            enc.instant(*ts);
+        }
+        IpcEvent::VSync(ts) => {
+            enc.u8(TAG_VSYNC);
+            enc.instant(*ts);
+        }
+        IpcEvent::KeyPressedEvent(s, key_code, modifier, ts)
+        | IpcEvent::KeyReleasedEvent(s, key_code, modifier, ts) => {
+            enc.u8(match evt {
+                IpcEvent::KeyPressedEvent(..) => TAG_KEY_PRESSED,
+                _ => TAG_KEY_RELEASED,
+            });
+            enc.str(s);
+            enc.u32(*key_code);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::MousePressedEvent(n_press, x, y, button, modifier, ts) => {
+            enc.u8(TAG_MOUSE_PRESSED);
+            enc.i32(*n_press);
+            enc.i32(*x);
+            enc.i32(*y);
+            enc.u32(*button);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::MouseReleaseEvent(x, y, button, modifier, ts)
+        | IpcEvent::MouseMoveEvent(x, y, button, modifier, ts) => {
+            enc.u8(match evt {
+                IpcEvent::MouseReleaseEvent(..) => TAG_MOUSE_RELEASE,
+                _ => TAG_MOUSE_MOVE,
+            });
+            enc.i32(*x);
+            enc.i32(*y);
+            enc.u32(*button);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::MouseEnterEvent(x, y, modifier, ts) => {
+            enc.u8(TAG_MOUSE_ENTER);
+            enc.i32(*x);
+            enc.i32(*y);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::MouseLeaveEvent(modifier, ts) => {
+            enc.u8(TAG_MOUSE_LEAVE);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::MouseWheelEvent(x, y, delta, delta_type, modifier, ts) => {
+            enc.u8(TAG_MOUSE_WHEEL);
+            enc.i32(*x);
+            enc.i32(*y);
+            enc.i32(delta.x());
+            enc.i32(delta.y());
+            enc.u8(*delta_type as u8);
+            enc.u32(*modifier);
+            enc.instant(*ts);
+        }
+        IpcEvent::RequestFocusEvent(focus, ts) => {
+            enc.u8(TAG_REQUEST_FOCUS);
+            enc.bool(*focus);
+            enc.instant(*ts);
+        }
+        IpcEvent::SetCursorShape(shape) => {
+            enc.u8(TAG_SET_CURSOR_SHAPE);
+            enc.u8(*shape as u8);
+        }
+        IpcEvent::TextEvent(s, ts)
+        | IpcEvent::DroppedFile(s, ts)
+        | IpcEvent::HoveredFile(s, ts) => {
+            enc.u8(match evt {
+                IpcEvent::TextEvent(..) => TAG_TEXT,
+                IpcEvent::DroppedFile(..) => TAG_DROPPED_FILE,
+                _ => TAG_HOVERED_FILE,
+            });
+            enc.str(s);
+            enc.instant(*ts);
+        }
+        IpcEvent::RegionChanged(id, rect) => {
+            enc.u8(TAG_REGION_CHANGED);
+            enc.str(id);
+            enc.bool(rect.is_some());
+            enc.rect(rect.unwrap_or_default());
+        }
+        IpcEvent::HoveredFileCancelled(ts) => {
+            enc.u8(TAG_HOVERED_FILE_CANCELLED);
+            enc.instant(*ts);
+        }
+        IpcEvent::ClipboardGet(id) => {
+            enc.u8(TAG_CLIPBOARD_GET);
+            enc.u32(*id);
+        }
+        IpcEvent::ClipboardText(id) => {
+            enc.u8(TAG_CLIPBOARD_TEXT);
+            enc.u32(*id);
+        }
+        IpcEvent::ClipboardSet => enc.u8(TAG_CLIPBOARD_SET),
+        IpcEvent::UserEvent(evt, ts) => {
+            enc.u8(TAG_USER_EVENT);
+            enc.bytes(&to_bytes(evt));
+            enc.instant(*ts);
+        }
+    }
+    enc.0
+}
+
+/// Decode the event encoded by [`encode_event()`].
+///
+/// @return `None` if the bytes were not a valid event.
+pub(crate) fn decode_event<T: 'static + Copy>(bytes: &[u8]) -> Option<IpcEvent<T>> {
+    let mut dec = Decoder(bytes);
+    let evt = match dec.u8()? {
+        TAG_NONE => IpcEvent::None,
+        TAG_EXIT => IpcEvent::Exit,
+        TAG_PEER_LOST => IpcEvent::PeerLost,
+        TAG_RESIZE => IpcEvent::ResizeEvent(dec.i32()?, dec.i32()?, dec.instant()?),
+        TAG_VSYNC => IpcEvent::VSync(dec.instant()?),
+        TAG_KEY_PRESSED => {
+            IpcEvent::KeyPressedEvent(dec.string()?, dec.u32()?, dec.u32()?, dec.instant()?)
+        }
+        TAG_KEY_RELEASED => {
+            IpcEvent::KeyReleasedEvent(dec.string()?, dec.u32()?, dec.u32()?, dec.instant()?)
+        }
+        TAG_MOUSE_PRESSED => IpcEvent::MousePressedEvent(
+            dec.i32()?,
+            dec.i32()?,
+            dec.i32()?,
+            dec.u32()?,
+            dec.u32()?,
+            dec.instant()?,
+        ),
+        TAG_MOUSE_RELEASE => IpcEvent::MouseReleaseEvent(
+            dec.i32()?,
+            dec.i32()?,
+            dec.u32()?,
+            dec.u32()?,
+            dec.instant()?,
+        ),
+        TAG_MOUSE_ENTER => {
+            IpcEvent::MouseEnterEvent(dec.i32()?, dec.i32()?, dec.u32()?, dec.instant()?)
+        }
+        TAG_MOUSE_LEAVE => IpcEvent::MouseLeaveEvent(dec.u32()?, dec.instant()?),
+        TAG_MOUSE_MOVE => IpcEvent::MouseMoveEvent(
+            dec.i32()?,
+            dec.i32()?,
+            dec.u32()?,
+            dec.u32()?,
+            dec.instant()?,
+        ),
+        TAG_MOUSE_WHEEL => IpcEvent::MouseWheelEvent(
+            dec.i32()?,
+            dec.i32()?,
+            Point::new(dec.i32()?, dec.i32()?),
+            dec.u8()
+                .filter(|t| *t <= DeltaType::Line as u8)
+                .map(DeltaType::from)?,
+            dec.u32()?,
+            dec.instant()?,
+        ),
+        TAG_REQUEST_FOCUS => IpcEvent::RequestFocusEvent(dec.bool()?, dec.instant()?),
+        TAG_SET_CURSOR_SHAPE => IpcEvent::SetCursorShape(
+            dec.u8()
+                .filter(|s| *s <= SystemCursorShape::CellCursor as u8)
+                .map(SystemCursorShape::from)?,
+        ),
+        TAG_TEXT => IpcEvent::TextEvent(dec.string()?, dec.instant()?),
+        TAG_REGION_CHANGED => {
+            let id = dec.string()?;
+            let some = dec.bool()?;
+            let rect = dec.rect()?;
+            IpcEvent::RegionChanged(id, some.then_some(rect))
+        }
+        TAG_DROPPED_FILE => IpcEvent::DroppedFile(dec.string()?, dec.instant()?),
+        TAG_HOVERED_FILE => IpcEvent::HoveredFile(dec.string()?, dec.instant()?),
+        TAG_HOVERED_FILE_CANCELLED => IpcEvent::HoveredFileCancelled(dec.instant()?),
+        TAG_CLIPBOARD_GET => IpcEvent::ClipboardGet(dec.u32()?),
+        TAG_CLIPBOARD_TEXT => IpcEvent::ClipboardText(dec.u32()?),
+        TAG_CLIPBOARD_SET => IpcEvent::ClipboardSet,
+        TAG_USER_EVENT => {
+            IpcEvent::UserEvent(from_bytes(dec.take(size_of::<T>())?)?, dec.instant()?)
+        }
+        _ => return None,
+    };
+    dec.0.is_empty().then_some(evt)
+}
+
+/// Append the fields in little-endian.
+#[derive(Default)]
+pub(crate) struct Encoder(pub(crate) Vec<u8>);
+
+impl Encoder {
+    #[inline]
+    pub(crate) fn u8(&mut self, val: u8) {
+        self.0.push(val)
+    }
+
+    #[inline]
+    pub(crate) fn bool(&mut self, val: bool) {
+        self.0.push(val as u8)
+    }
+
+    #[inline]
+    pub(crate) fn u32(&mut self, val: u32) {
+        self.0.extend_from_slice(&val.to_le_bytes())
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self, val: i32) {
+        self.0.extend_from_slice(&val.to_le_bytes())
+    }
+
+    #[inline]
+    pub(crate) fn u64(&mut self, val: u64) {
+        self.0.extend_from_slice(&val.to_le_bytes())
+    }
+
+    #[inline]
+    pub(crate) fn bytes(&mut self, val: &[u8]) {
+        self.0.extend_from_slice(val)
+    }
+
+    /// The string was prefixed by its length.
+    #[inline]
+    pub(crate) fn str(&mut self, val: &str) {
+        self.u32(val.len() as u32);
+        self.bytes(val.as_bytes())
+    }
+
+    pub(crate) fn rect(&mut self, val: Rect) {
+        self.i32(val.x());
+        self.i32(val.y());
+        self.i32(val.width());
+        self.i32(val.height());
+    }
+
+    /// The nanoseconds of wall clock since `UNIX_EPOCH`.
+    pub(crate) fn instant(&mut self, val: Instant) {
+        let (instant, system) = *CLOCK_ANCHOR;
+        let wall = if val >= instant {
+            system.checked_add(val - instant)
+        } else {
+            system.checked_sub(instant - val)
+        };
+        let nanos = wall
+            .unwrap_or(system)
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        self.u64(nanos as u64)
+    }
+}
+
+/// Take the fields written by [`Encoder`], return `None` if the bytes were exhausted.
+pub(crate) struct Decoder<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Decoder<'a> {
+    #[inline]
+    pub(crate) fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (bytes, remain) = self.0.split_at(len);
+        self.0 = remain;
+        Some(bytes)
+    }
+
+    #[inline]
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    #[inline]
+    pub(crate) fn bool(&mut self) -> Option<bool> {
+        self.u8().map(|b| b != 0)
+    }
+
+    #[inline]
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self) -> Option<i32> {
+        self.take(4)
+            .map(|b| i32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    #[inline]
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// @return `None` if the string was not valid UTF-8.
+    #[inline]
+    pub(crate) fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+
+    #[inline]
+    pub(crate) fn rect(&mut self) -> Option<Rect> {
+        Some(Rect::new(
+            self.i32()?,
+            self.i32()?,
+            self.i32()?,
+            self.i32()?,
+        ))
+    }
+
+    pub(crate) fn instant(&mut self) -> Option<Instant> {
+        let wall = UNIX_EPOCH + Duration::from_nanos(self.u64()?);
+        let (instant, system) = *CLOCK_ANCHOR;
+        let val = match wall.duration_since(system) {
+            Ok(after) => instant.checked_add(after),
+            Err(e) => instant.checked_sub(e.duration()),
+        };
+        Some(val.unwrap_or(instant))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_event() {
+        let now = Instant::now();
+        let events: Vec<IpcEvent<u64>> = vec![
+            IpcEvent::Exit,
+            IpcEvent::KeyPressedEvent("a".to_string(), 65, 1, now),
+            IpcEvent::MouseWheelEvent(1, 2, Point::new(3, -4), DeltaType::Line, 0, now),
+            IpcEvent::TextEvent("t".repeat(10000), now),
+            IpcEvent::RegionChanged("region".to_string(), Some(Rect::new(1, 2, 3, 4))),
+            IpcEvent::RegionChanged("region".to_string(), None),
+            IpcEvent::UserEvent(u64::MAX, now),
+            IpcEvent::DroppedFile("/tmp/file\0".to_string(), now),
+            IpcEvent::ClipboardText(7),
+            IpcEvent::ClipboardSet,
+        ];
+
+        for evt in events.iter() {
+            let bytes = encode_event(evt);
+            let decoded = decode_event::<u64>(&bytes).unwrap();
+            assert_eq!(encode_event(&decoded), bytes);
+            // The truncated or extended bytes were invalid.
+            assert!(decode_event::<u64>(&bytes[..bytes.len() - 1]).is_none());
+            assert!(decode_event::<u64>(&[bytes.as_slice(), &[0]].concat()).is_none());
+        }
+
+        let Some(IpcEvent::UserEvent(evt, ts)) = decode_event::<u64>(&encode_event(&events[6]))
+        else {
+            panic!()
+        };
+        assert_eq!(evt, u64::MAX);
+        // The timestamp was kept within the precision of the clock conversion.
+        assert!(ts.max(now) - ts.min(now) < Duration::from_millis(1));
+
+        assert!(decode_event::<u64>(&[TAG_USER_EVENT + 1]).is_none());
+        assert!(decode_event::<u64>(&[TAG_SET_CURSOR_SHAPE, u8::MAX]).is_none());
+    }
+}
@@ -93,7 +93,7 @@ pub trait AsNumeric<T: ToBytes> {
 ////////////////////////////////////////////////////////////////////////////////////////////////
 /// The enum to represent the key code on keyboard.
 #[repr(u32)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumIter, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
 pub enum KeyCode {
     #[default]
     Unknown = 0x00,
@@ -613,7 +613,7 @@ implements_enum_value!(KeyCode, u32);
 ////////////////////////////////////////////////////////////////////////////////////////////////
 /// The enum to represent the keyboard modifier.
 #[repr(u32)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, EnumIter)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, EnumIter)]
 pub enum KeyboardModifier {
     #[default]
     NoModifier,
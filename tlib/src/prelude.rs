@@ -9,7 +9,7 @@ pub use crate::figure::color::Color;
 pub use crate::figure::region::CoordRegion;
 pub use crate::figure::rectangle::{CoordRect, FRect};
 pub use crate::global::AsAny;
-pub use crate::namespace::{Align, BorderStyle, Coordinate, SystemCursorShape, Orientation, KeyboardModifier};
+pub use crate::namespace::{Align, BorderStyle, Coordinate, SystemCursorShape, Orientation, KeyCode, KeyboardModifier};
 pub use crate::object::{
     InnerInitializer, Object, ObjectAcquire, ObjectChildrenConstruct, ObjectExt, ObjectImpl,
     ObjectImplExt, ObjectOperation, ReflectObjectChildrenConstruct, ReflectObjectImpl,
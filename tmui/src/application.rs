@@ -11,24 +11,28 @@ use crate::{
     backend::BackendType,
     event_hints::event_hints,
     graphics::icon::Icon,
-    platform::{PlatformContext, PlatformIpc, PlatformType},
+    platform::{ipc_clipboard, PlatformContext, PlatformIpc, PlatformType},
     prelude::CloseHandlerMgr,
-    primitive::{cpu_balance::CpuBalance, shared_channel::SharedChannel},
+    primitive::{convert_event, cpu_balance::CpuBalance, shared_channel::SharedChannel},
     runtime::{start_ui_runtime, windows_process::WindowsProcess},
     window::win_config::{WindowConfig, WindowConfigBuilder},
 };
-use log::error;
+use log::{error, warn};
 use std::{
     any::Any,
     cell::RefCell,
     marker::PhantomData,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU8, Ordering},
         Once,
     },
 };
-use tipc::{WithIpcMaster, WithIpcSlave};
-use tlib::{events::Event, figure::Size, winit::window::WindowButtons};
+use tipc::{
+    ipc_event::IpcEvent, ipc_hub::IpcHub, parking_lot::RwLock, transport::record::ReplayMode,
+    IpcNode, WithIpcMaster, WithIpcSlave,
+};
+use tlib::{events::Event, figure::Size, payload::PayloadWeight, winit::window::WindowButtons};
 
 thread_local! {
     pub(crate) static IS_UI_MAIN_THREAD: RefCell<bool> = const { RefCell::new(false) };
@@ -74,6 +78,8 @@ pub struct Application<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync
 
     shared_mem_name: Option<&'static str>,
     shared_widget_id: Option<&'static str>,
+    shared_channel: Option<&'static str>,
+    replay: Option<(PathBuf, ReplayMode)>,
 }
 
 impl Application<(), ()> {
@@ -169,7 +175,15 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
                     .shared_widget_id
                     .expect("`PlatformType::Ipc` require non-None.`");
                 platform_context.set_shared_widget_id(shared_widget_id);
-                platform_context.with_ipc_slave(shared_mem_name);
+                match (self.replay.take(), self.shared_channel) {
+                    (Some((path, mode)), _) => {
+                        platform_context.with_ipc_replay(shared_mem_name, &path, mode)
+                    }
+                    (None, Some(channel)) => {
+                        platform_context.with_ipc_slave_channel(shared_mem_name, channel)
+                    }
+                    (None, None) => platform_context.with_ipc_slave(shared_mem_name),
+                }
 
                 platform_context.wrap()
             }
@@ -320,6 +334,72 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
         });
     }
 
+    /// Process the connection changes and the events of slaves on the channels of [`IpcHub`].
+    pub(crate) fn process_ipc_hub(
+        window: &mut ApplicationWindow,
+        cpu_balance: &mut CpuBalance,
+        hub: &RwLock<IpcHub<T, M>>,
+        on_user_event_receive: Option<&FnUserEventReceive<T>>,
+    ) {
+        let (changes, events) = {
+            let mut hub = hub.write();
+            hub.iter_mut()
+                .for_each(|(_, master)| master.release_retention());
+            let changes = hub.poll_connections();
+            let events: Vec<(String, IpcEvent<T>)> = hub
+                .try_recv_all()
+                .into_iter()
+                .map(|(channel, evt)| (channel.to_string(), evt))
+                .collect();
+            (changes, events)
+        };
+
+        for change in changes {
+            window.handle_channel_event(change);
+        }
+
+        for (channel, evt) in events {
+            cpu_balance.add_payload(evt.payload_wieght());
+
+            match evt {
+                IpcEvent::UserEvent(evt, _timestamp) => {
+                    if let Some(on_user_event_receive) = on_user_event_receive {
+                        on_user_event_receive(window, evt)
+                    }
+                }
+                IpcEvent::PeerLost => {
+                    warn!("The ipc slave on channel `{}` was lost.", channel)
+                }
+                // The master brokers the os clipboard for the slaves on channels as well.
+                IpcEvent::ClipboardGet(id) => {
+                    if let Some(master) = hub.read().channel(&channel) {
+                        ipc_clipboard::respond_text(master, id)
+                    }
+                }
+                IpcEvent::ClipboardSet => {
+                    if let Some(master) = hub.read().channel(&channel) {
+                        ipc_clipboard::receive_set_text(master)
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Forward the event of window to the connected slaves on the channels of [`IpcHub`].
+    pub(crate) fn send_event_ipc_hub(hub: &RwLock<IpcHub<T, M>>, evt: &Event) {
+        let hub = hub.read();
+        for channel in hub.connected_channels() {
+            let master = hub.channel(channel).unwrap();
+            if let Err(e) = master.try_send(convert_event::<T>(evt)) {
+                error!(
+                    "Forward the event to ipc channel `{}` failed: {}",
+                    channel, e
+                );
+            }
+        }
+    }
+
     #[inline]
     pub(crate) fn set_app_started() {
         APP_STARTED.store(true, Ordering::Release);
@@ -365,6 +445,8 @@ pub struct ApplicationBuilder<T: 'static + Copy + Sync + Send, M: 'static + Copy
     ui_stack_size: usize,
     shared_mem_name: Option<&'static str>,
     shared_widget_id: Option<&'static str>,
+    shared_channel: Option<&'static str>,
+    replay: Option<(PathBuf, ReplayMode)>,
     opti_track: bool,
     _user_event: PhantomData<T>,
     _request: PhantomData<M>,
@@ -383,6 +465,8 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
             win_cfg_bld: WindowConfigBuilder::default(),
             shared_mem_name,
             shared_widget_id: None,
+            shared_channel: None,
+            replay: None,
             opti_track: false,
             _user_event: PhantomData,
             _request: PhantomData,
@@ -400,6 +484,8 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
             on_activate: Default::default(),
             shared_mem_name: Default::default(),
             shared_widget_id: Default::default(),
+            shared_channel: Default::default(),
+            replay: Default::default(),
             on_user_event_receive: Default::default(),
             on_request_receive: Default::default(),
         };
@@ -430,6 +516,10 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
         if let Some(shared_widget_id) = self.shared_widget_id {
             app.shared_widget_id = Some(shared_widget_id)
         }
+        if let Some(shared_channel) = self.shared_channel {
+            app.shared_channel = Some(shared_channel)
+        }
+        app.replay = self.replay;
 
         if let Some(backend) = self.backend {
             app.backend_type = backend
@@ -464,6 +554,13 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
         self
     }
 
+    /// Set the name of application main window, see [`WindowConfigBuilder::name()`].
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.win_cfg_bld = self.win_cfg_bld.name(name);
+        self
+    }
+
     /// Set the title of application main window.
     ///
     /// The default value was "Tmui Window".
@@ -598,6 +695,26 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> Applicati
         self
     }
 
+    /// Connect to the channel of master which serves the `SharedWidget` with the same
+    /// [`shared_channel`](crate::shared_widget::SharedWidgetExt::set_shared_channel).
+    ///
+    /// Only works on the [`Ipc`](PlatformType::Ipc) platform.
+    #[inline]
+    pub fn shared_channel(mut self, channel: &'static str) -> Self {
+        self.shared_channel = Some(channel);
+        self
+    }
+
+    /// Replay the file recorded by the slave or master instead of connecting to the master,
+    /// to reproduce the issue of shared widget without the host process.
+    ///
+    /// Only works on the [`Ipc`](PlatformType::Ipc) platform.
+    #[inline]
+    pub fn replay(mut self, path: impl Into<PathBuf>, mode: ReplayMode) -> Self {
+        self.replay = Some((path.into(), mode));
+        self
+    }
+
     /// Set the cpu payload threshold (`payloads/per sec`).<br>
     /// When the program payload exceeds the threshold,
     /// the program will increase CPU usage to generate frame data more accurately in time. <br>
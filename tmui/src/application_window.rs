@@ -9,7 +9,11 @@ use crate::{
     input::{dialog::TyInputDialog, focus_mgr::FocusMgr, ReflectInputEle},
     layout::LayoutMgr,
     loading::LoadingMgr,
-    platform::{ipc_bridge::IpcBridge, PlatformType},
+    menu::context_menu::{discard_menu, ContextMenuMgr},
+    platform::{
+        ipc_bridge::{IpcBridge, IpcHubBridge},
+        PlatformType,
+    },
     prelude::*,
     primitive::{global_watch::GlobalWatchEvent, Message},
     runtime::{wed, window_context::OutputSender},
@@ -34,9 +38,10 @@ use std::{
     sync::Once,
     thread::{self, ThreadId},
 };
+use tipc::ipc_hub::ChannelEvent;
 use tlib::{
     connect,
-    events::{DeltaType, Event, EventType, MouseEvent},
+    events::{downcast_event_ref, DeltaType, Event, EventType, MouseEvent, ResizeEvent},
     figure::Size,
     namespace::{KeyboardModifier, MouseButton},
     nonnull_mut, nonnull_ref,
@@ -63,6 +68,7 @@ pub struct ApplicationWindow {
     winit_id: Option<WindowId>,
     platform_type: PlatformType,
     ipc_bridge: Option<Box<dyn IpcBridge>>,
+    ipc_hub: Option<Box<dyn IpcHubBridge>>,
     shared_widget_size_changed: bool,
     high_load_request: bool,
     defer_display: bool,
@@ -72,6 +78,10 @@ pub struct ApplicationWindow {
     client_position: Point,
     params: Option<AHashMap<String, Value>>,
     min_size: Option<Size>,
+    /// The scale factor of the monitor which the window was on,
+    /// the layout of window was in logical pixels.
+    #[derivative(Default(value = "1."))]
+    scale_factor: f32,
 
     board: Option<NonNull<Board>>,
     output_sender: Option<OutputSender>,
@@ -96,6 +106,7 @@ pub struct ApplicationWindow {
     win_widgets: Vec<WinWidgetHnd>,
     removed: Vec<DynTr>,
     radius_widgets: Vec<ObjectId>,
+    edge_widgets: Vec<(WindowEdge, DynTr)>,
 
     #[cfg(not(win_dialog))]
     input_dialog: Option<Tr<crate::input::dialog::InputDialog>>,
@@ -119,6 +130,9 @@ impl ObjectImpl for ApplicationWindow {
     }
 
     fn initialize(&mut self) {
+        // Should be done before the initialize-phase, which forbids adding ui components.
+        self.arrange_edge_widgets();
+
         INTIALIZE_PHASE.with(|p| *p.borrow_mut() = true);
         debug!("Initialize-phase start.");
 
@@ -316,6 +330,23 @@ impl ApplicationWindow {
         self.platform_type
     }
 
+    /// Place the app-chrome widget(e.g. [`ToolBar`](crate::tool_bar::ToolBar),
+    /// [`StatusBar`](crate::status_bar::StatusBar)) on the edge of window,
+    /// the child of window fills the remaining area.
+    ///
+    /// Widgets on the same edge were arranged from the outside in, by the adding order.
+    /// The top and bottom edges span the whole width of window.
+    ///
+    /// Notice: this function should be called before the window was initialized,
+    /// e.g. in the callback of `Application::connect_activate()`.
+    pub fn add_edge_widget<T: WidgetImpl>(&mut self, edge: WindowEdge, widget: Tr<T>) {
+        if self.initialized() {
+            error!("`add_edge_widget()` should be called before the window was initialized.");
+            return;
+        }
+        self.edge_widgets.push((edge, widget.into()));
+    }
+
     #[inline]
     pub fn create_window(&self, window_bld: WindowBuilder) {
         if self.platform_type == PlatformType::Ipc {
@@ -424,19 +455,17 @@ impl ApplicationWindow {
         self.outer_position
     }
 
+    /// Map the logical position in window to the outer position on the screen coordinate.
     #[inline]
     pub fn map_to_outer(&self, pos: &Point) -> Point {
-        Point::new(
-            pos.x() + self.outer_position.x(),
-            pos.y() + self.outer_position.y(),
-        )
+        map_to_screen(pos, self.scale_factor, self.outer_position)
     }
 
     #[inline]
     pub fn map_to_outer_f(&self, pos: &FPoint) -> FPoint {
         FPoint::new(
-            pos.x() + self.outer_position.x() as f32,
-            pos.y() + self.outer_position.y() as f32,
+            pos.x() * self.scale_factor + self.outer_position.x() as f32,
+            pos.y() * self.scale_factor + self.outer_position.y() as f32,
         )
     }
 
@@ -446,19 +475,64 @@ impl ApplicationWindow {
         self.client_position
     }
 
+    /// Map the logical position in window to the client position on the screen coordinate.
     #[inline]
     pub fn map_to_client(&self, pos: &Point) -> Point {
-        Point::new(
-            pos.x() + self.client_position.x(),
-            pos.y() + self.client_position.y(),
-        )
+        map_to_screen(pos, self.scale_factor, self.client_position)
     }
 
     #[inline]
     pub fn map_to_client_f(&self, pos: &FPoint) -> FPoint {
         FPoint::new(
-            pos.x() + self.client_position.x() as f32,
-            pos.y() + self.client_position.y() as f32,
+            pos.x() * self.scale_factor + self.client_position.x() as f32,
+            pos.y() * self.scale_factor + self.client_position.y() as f32,
+        )
+    }
+
+    /// Map the position on the screen coordinate to the logical position in window.
+    #[inline]
+    pub fn map_from_client(&self, pos: &Point) -> Point {
+        map_from_screen(pos, self.scale_factor, self.client_position)
+    }
+
+    /// Get the scale factor of the monitor which the window was on.
+    ///
+    /// The widgets were layouted and painted in logical pixels,
+    /// `physical pixels = logical pixels * scale factor`.
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    #[inline]
+    pub fn to_logical_size(&self, size: Size) -> Size {
+        Size::new(
+            to_logical(size.width(), self.scale_factor),
+            to_logical(size.height(), self.scale_factor),
+        )
+    }
+
+    #[inline]
+    pub fn to_physical_size(&self, size: Size) -> Size {
+        Size::new(
+            to_physical(size.width(), self.scale_factor),
+            to_physical(size.height(), self.scale_factor),
+        )
+    }
+
+    #[inline]
+    pub fn to_logical_point(&self, pos: &Point) -> Point {
+        Point::new(
+            to_logical(pos.x(), self.scale_factor),
+            to_logical(pos.y(), self.scale_factor),
+        )
+    }
+
+    #[inline]
+    pub fn to_physical_point(&self, pos: &Point) -> Point {
+        Point::new(
+            to_physical(pos.x(), self.scale_factor),
+            to_physical(pos.y(), self.scale_factor),
         )
     }
 
@@ -564,6 +638,33 @@ impl ApplicationWindow {
         self.ipc_bridge.as_deref()
     }
 
+    #[inline]
+    pub(crate) fn set_ipc_hub(&mut self, ipc_hub: Option<Box<dyn IpcHubBridge>>) {
+        self.ipc_hub = ipc_hub
+    }
+
+    #[inline]
+    pub(crate) fn ipc_hub(&self) -> Option<&dyn IpcHubBridge> {
+        self.ipc_hub.as_deref()
+    }
+
+    /// Notify the `SharedWidget` served by the channel that its slave was connected or disconnected.
+    pub(crate) fn handle_channel_event(&mut self, evt: ChannelEvent) {
+        let (channel, connected) = match evt {
+            ChannelEvent::Connected(channel) => (channel, true),
+            ChannelEvent::Disconnected(channel) => (channel, false),
+        };
+
+        for hnd in self.widgets.values_mut() {
+            let widget = nonnull_mut!(hnd);
+            if let Some(shared_widget) = cast_mut!(widget as SharedWidgetImpl) {
+                if shared_widget.shared_channel() == Some(channel.as_str()) {
+                    shared_widget.on_channel_changed(connected);
+                }
+            }
+        }
+    }
+
     #[inline]
     pub(crate) fn set_focused_widget(&mut self, id: ObjectId) {
         if self.focused_widget != 0 && self.focused_widget != id {
@@ -727,9 +828,15 @@ impl ApplicationWindow {
 
     #[inline]
     pub(crate) fn dispatch_event(&mut self, evt: Event) -> Option<Event> {
+        let evt = logical_event(evt, self.scale_factor);
         wed::win_evt_dispatch(self, evt)
     }
 
+    #[inline]
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor
+    }
+
     #[inline]
     pub(crate) fn iter_execute(&mut self) {
         self.handle_removed_widget();
@@ -754,12 +861,26 @@ impl ApplicationWindow {
     }
 
     pub(crate) fn handle_removed_widget(&mut self) {
+        let mut context_menus = vec![];
+
         for removed in self.removed.iter_mut() {
             let mut ids = vec![removed.id()];
             ids.extend(removed.children_index().iter().copied());
 
             for id in ids.into_iter() {
-                self.widgets.remove(&id);
+                if let Some(hnd) = self.widgets.remove(&id) {
+                    // Release the shared region of removed `SharedWidget`, the slave will be notified.
+                    let widget = nonnull_ref!(hnd);
+                    if let Some(shared_widget) = cast!(widget as SharedWidgetImpl) {
+                        if let Some(channel) = shared_widget.shared_channel() {
+                            if let Some(ref hub) = self.ipc_hub {
+                                hub.remove_channel(channel);
+                            }
+                        } else if let Some(ref bridge) = self.ipc_bridge {
+                            bridge.remove_shared_region(shared_widget.shared_id());
+                        }
+                    }
+                }
                 self.iter_executors.retain(|r| nonnull_ref!(r).id() != id);
                 self.shadow_mouse_watch
                     .retain(|r| nonnull_ref!(r).id() != id);
@@ -799,10 +920,18 @@ impl ApplicationWindow {
                 FocusMgr::with(|mgr| mgr.borrow_mut().remove(id));
                 ShortcutMgr::with(|mgr| mgr.borrow_mut().remove_shortcut_all(id));
                 CloseHandlerMgr::remove(id);
+                if let Some(menu) = ContextMenuMgr::with(|mgr| mgr.borrow_mut().remove(id)) {
+                    context_menus.push(menu);
+                }
             }
         }
 
         self.removed.clear();
+
+        // The context menus will be removed in the next round.
+        for menu in context_menus {
+            discard_menu(menu, &mut self.removed);
+        }
     }
 
     #[inline]
@@ -1171,6 +1300,59 @@ impl ApplicationWindow {
         let mut order_counter = 0;
         assign_render_order(self, &mut order_counter);
     }
+
+    /// Wrap the child of window with the edge widgets:
+    /// `VBox[top.., HBox[left.., child, right..], bottom..]`.
+    fn arrange_edge_widgets(&mut self) {
+        if self.edge_widgets.is_empty() {
+            return;
+        }
+        let edge_widgets = std::mem::take(&mut self.edge_widgets);
+        let child = self.widget._take_child_internal();
+
+        let mut vbox = VBox::new();
+        vbox.set_hexpand(true);
+        vbox.set_vexpand(true);
+        let mut hbox = HBox::new();
+        hbox.set_hexpand(true);
+        hbox.set_vexpand(true);
+
+        let mut top = vec![];
+        let mut bottom = vec![];
+        let mut left = vec![];
+        let mut right = vec![];
+        for (edge, widget) in edge_widgets {
+            match edge {
+                WindowEdge::Top => top.push(widget),
+                WindowEdge::Bottom => bottom.insert(0, widget),
+                WindowEdge::Left => left.push(widget),
+                WindowEdge::Right => right.insert(0, widget),
+            }
+        }
+
+        for mut widget in left.into_iter().chain(child).chain(right) {
+            widget.set_parent(hbox.bind_mut());
+            hbox.container.children.push(widget);
+        }
+
+        top.push(hbox.into());
+        for mut widget in top.into_iter().chain(bottom) {
+            widget.set_parent(vbox.bind_mut());
+            vbox.container.children.push(widget);
+        }
+
+        self.child(vbox);
+    }
+}
+
+/// The edge of window to place the app-chrome widgets,
+/// see [`ApplicationWindow::add_edge_widget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
 }
 
 /// Get window id in current ui thread.
@@ -1336,3 +1518,166 @@ fn assign_render_order(node: &mut dyn WidgetImpl, order_counter: &mut usize) {
         assign_render_order(child, order_counter);
     }
 }
+
+/// Convert the physical pixels to logical pixels, rounded to the nearest.
+#[inline]
+fn to_logical(value: i32, scale_factor: f32) -> i32 {
+    (value as f32 / scale_factor).round() as i32
+}
+
+/// Convert the logical pixels to physical pixels, rounded to the nearest.
+#[inline]
+fn to_physical(value: i32, scale_factor: f32) -> i32 {
+    (value as f32 * scale_factor).round() as i32
+}
+
+/// Map the logical position in window to the screen coordinate, `origin` was the physical
+/// position of window on the screen.
+#[inline]
+fn map_to_screen(pos: &Point, scale_factor: f32, origin: Point) -> Point {
+    Point::new(
+        to_physical(pos.x(), scale_factor) + origin.x(),
+        to_physical(pos.y(), scale_factor) + origin.y(),
+    )
+}
+
+/// The reverse of [`map_to_screen()`].
+#[inline]
+fn map_from_screen(pos: &Point, scale_factor: f32, origin: Point) -> Point {
+    Point::new(
+        to_logical(pos.x() - origin.x(), scale_factor),
+        to_logical(pos.y() - origin.y(), scale_factor),
+    )
+}
+
+/// Convert the size and position of the platform event from physical pixels to logical pixels.
+fn logical_event(mut evt: Event, scale_factor: f32) -> Event {
+    if scale_factor == 1. {
+        return evt;
+    }
+
+    match evt.event_type() {
+        EventType::Resize => {
+            let size = downcast_event_ref::<ResizeEvent>(&evt).unwrap().size();
+            Box::new(ResizeEvent::new(
+                to_logical(size.width(), scale_factor),
+                to_logical(size.height(), scale_factor),
+            ))
+        }
+        EventType::MouseButtonPress
+        | EventType::MouseButtonRelease
+        | EventType::MouseMove
+        | EventType::MouseWhell => {
+            if let Some(mouse) = evt.as_mut().as_any_mut().downcast_mut::<MouseEvent>() {
+                let (x, y) = mouse.position();
+                mouse.set_position((to_logical(x, scale_factor), to_logical(y, scale_factor)));
+            }
+            evt
+        }
+        _ => evt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{logical_event, map_from_screen, map_to_screen, to_logical, to_physical};
+    use tlib::{
+        events::{
+            downcast_event_ref, DeltaType, Event, EventTrait, EventType, KeyEvent, MouseEvent,
+            ResizeEvent,
+        },
+        figure::Point,
+        namespace::{KeyCode, KeyboardModifier, MouseButton},
+    };
+
+    fn mouse_event(type_: EventType, x: i32, y: i32) -> Event {
+        Box::new(MouseEvent::new(
+            type_,
+            (x, y),
+            MouseButton::LeftButton,
+            KeyboardModifier::NoModifier,
+            1,
+            Point::default(),
+            DeltaType::default(),
+        ))
+    }
+
+    #[test]
+    fn test_scale_rounding() {
+        assert_eq!(to_logical(300, 1.5), 200);
+        assert_eq!(to_logical(301, 1.5), 201);
+        assert_eq!(to_logical(302, 1.5), 201);
+        assert_eq!(to_logical(1, 2.), 1);
+        assert_eq!(to_logical(-3, 2.), -2);
+        assert_eq!(to_logical(7, 1.25), 6);
+
+        assert_eq!(to_physical(200, 1.5), 300);
+        assert_eq!(to_physical(201, 1.5), 302);
+        assert_eq!(to_physical(5, 1.25), 6);
+        assert_eq!(to_physical(-5, 1.25), -6);
+
+        // Round trip of logical pixels was stable.
+        for scale_factor in [1., 1.25, 1.5, 1.75, 2., 2.5, 3.] {
+            for v in -50..50 {
+                assert_eq!(to_logical(to_physical(v, scale_factor), scale_factor), v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_screen() {
+        let origin = Point::new(100, -20);
+        assert_eq!(
+            map_to_screen(&Point::new(10, 10), 1.5, origin),
+            Point::new(115, -5)
+        );
+        assert_eq!(
+            map_from_screen(&Point::new(115, -5), 1.5, origin),
+            Point::new(10, 10)
+        );
+        assert_eq!(
+            map_to_screen(&Point::new(10, 10), 1., origin),
+            Point::new(110, -10)
+        );
+        assert_eq!(
+            map_from_screen(&Point::new(99, -21), 2., origin),
+            Point::new(-1, -1)
+        );
+    }
+
+    #[test]
+    fn test_logical_event() {
+        let evt = logical_event(Box::new(ResizeEvent::new(1921, 1081)), 1.5);
+        let resize = downcast_event_ref::<ResizeEvent>(&evt).unwrap();
+        assert_eq!((resize.width(), resize.height()), (1281, 721));
+
+        for type_ in [
+            EventType::MouseButtonPress,
+            EventType::MouseButtonRelease,
+            EventType::MouseMove,
+            EventType::MouseWhell,
+        ] {
+            let evt = logical_event(mouse_event(type_, 301, 151), 1.5);
+            let mouse = downcast_event_ref::<MouseEvent>(&evt).unwrap();
+            assert_eq!(mouse.position(), (201, 101));
+            assert_eq!(mouse.event_type(), type_);
+        }
+
+        // The events were kept as is when the scale factor was 1.
+        let evt = logical_event(mouse_event(EventType::MouseMove, 301, 151), 1.);
+        let mouse = downcast_event_ref::<MouseEvent>(&evt).unwrap();
+        assert_eq!(mouse.position(), (301, 151));
+
+        // The events without geometry were not touched.
+        let evt = logical_event(
+            Box::new(KeyEvent::new(
+                EventType::KeyPress,
+                KeyCode::KeyA,
+                KeyboardModifier::NoModifier,
+                "a",
+            )),
+            2.,
+        );
+        assert_eq!(evt.event_type(), EventType::KeyPress);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::platform::ipc_clipboard;
 use clipboard::{ClipboardContext, ClipboardProvider};
 use log::error;
 use std::sync::Mutex;
@@ -11,21 +12,42 @@ pub enum ClipboardLevel {
 
 pub struct Clipboard {
     text: Mutex<Option<String>>,
-    os_clipboard_ctx: ClipboardContext,
+    /// Accessed by the ipc thread of master too, when brokering the clipboard of slave.
+    os_clipboard_ctx: Mutex<ClipboardContext>,
 }
 
 impl Clipboard {
     pub(crate) fn new() -> Self {
         Self {
             text: Mutex::new(None),
-            os_clipboard_ctx: ClipboardContext::new().expect("Get `ClipboardContext` failed."),
+            os_clipboard_ctx: Mutex::new(
+                ClipboardContext::new().expect("Get `ClipboardContext` failed."),
+            ),
         }
     }
 
+    /// The os clipboard of shared memory slave was brokered by the master, getting the os text
+    /// blocks the ui thread until the master responded, at most 500 ms.
+    ///
+    /// Use [`text_async()`](Self::text_async) to avoid freezing the ui of slave.
     pub fn text(&mut self, level: ClipboardLevel) -> Option<String> {
         match level {
             ClipboardLevel::Application => (*self.text.lock().unwrap()).clone(),
-            ClipboardLevel::Os => self.os_clipboard_ctx.get_contents().ok(),
+            ClipboardLevel::Os if ipc_clipboard::is_forwarded() => ipc_clipboard::request_text(),
+            ClipboardLevel::Os => self.os_clipboard_ctx.lock().unwrap().get_contents().ok(),
+        }
+    }
+
+    /// Get the text of clipboard without blocking the ui thread.
+    ///
+    /// `f` was invoked in the ui thread later when the text of os clipboard was responded
+    /// by the master on shared memory slave, otherwise it was invoked immediately.
+    pub fn text_async<F: FnOnce(Option<String>) + 'static>(&mut self, level: ClipboardLevel, f: F) {
+        match level {
+            ClipboardLevel::Os if ipc_clipboard::is_forwarded() => {
+                ipc_clipboard::request_text_async(f)
+            }
+            _ => f(self.text(level)),
         }
     }
 
@@ -34,8 +56,15 @@ impl Clipboard {
             ClipboardLevel::Application => {
                 let _ = self.text.lock().unwrap().insert(text.to_string());
             }
+            ClipboardLevel::Os if ipc_clipboard::is_forwarded() => {
+                ipc_clipboard::set_text(text.to_string())
+            }
             ClipboardLevel::Os => {
-                let r = self.os_clipboard_ctx.set_contents(text.to_string());
+                let r = self
+                    .os_clipboard_ctx
+                    .lock()
+                    .unwrap()
+                    .set_contents(text.to_string());
                 if r.is_err() {
                     error!("`Clipboard` set text failed, level=Os.")
                 }
@@ -19,6 +19,9 @@ pub enum ContainerLayoutEnum {
     ScrollArea,
     Pane,
     Overlay,
+    TabWidget,
+    ToolBar,
+    StatusBar,
 }
 
 #[extends(Widget)]
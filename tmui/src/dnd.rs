@@ -0,0 +1,680 @@
+use crate::{
+    application_window::ApplicationWindow, event_hints::event_hints, platform::PlatformType,
+    prelude::*, primitive::Message, widget::WidgetImpl,
+};
+use lazy_static::lazy_static;
+use log::warn;
+use std::{
+    cell::{Cell, RefCell},
+    sync::Arc,
+};
+use tipc::parking_lot::Mutex;
+use tlib::{
+    events::KeyEvent, namespace::KeyCode, nonnull_ref, object::ObjectId, winit::window::WindowId,
+};
+
+/// The offset of drag preview from the cursor, so the preview will not cover the drop target.
+const PREVIEW_OFFSET: i32 = 12;
+
+lazy_static! {
+    /// Only one drag session can exist in the process, it was shared by all the ui threads.
+    static ref SESSION: Mutex<Option<DragSession>> = Mutex::new(None);
+}
+
+thread_local! {
+    static PREVIEW: RefCell<Option<DynPopupTr>> = RefCell::new(None);
+    /// The drop target and its accepted action in current window,
+    /// `None` if the dragging cursor was not in the window.
+    static TARGET: Cell<Option<(ObjectId, DropAction)>> = Cell::new(None);
+}
+
+/// The data carried by the drag operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DragPayload {
+    Value(Value),
+    Bytes(Vec<u8>),
+}
+
+impl DragPayload {
+    #[inline]
+    pub fn value(&self) -> Option<&Value> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+impl From<Value> for DragPayload {
+    #[inline]
+    fn from(value: Value) -> Self {
+        Self::Value(value)
+    }
+}
+
+impl From<Vec<u8>> for DragPayload {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+/// The action of drop, [`DropAction::Ignore`] means the drop was rejected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DropAction {
+    #[default]
+    Ignore,
+    Copy,
+    Move,
+    Link,
+}
+
+impl DropAction {
+    #[inline]
+    pub fn cursor_shape(&self) -> SystemCursorShape {
+        match self {
+            Self::Ignore => SystemCursorShape::ForbiddenCursor,
+            Self::Copy => SystemCursorShape::DragCopyCursor,
+            Self::Move => SystemCursorShape::DragMoveCursor,
+            Self::Link => SystemCursorShape::DragLinkCursor,
+        }
+    }
+}
+
+/// The event passed to [`DropTarget`] during the drag operation.
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    mime: String,
+    payload: Arc<DragPayload>,
+    position: Point,
+    proposed_action: DropAction,
+    source: ObjectId,
+    same_window: bool,
+}
+
+impl DragEvent {
+    /// Get the mime type of payload, e.g. `"application/x-tab"`, `"text/plain"`.
+    #[inline]
+    pub fn mime(&self) -> &str {
+        &self.mime
+    }
+
+    #[inline]
+    pub fn payload(&self) -> &DragPayload {
+        &self.payload
+    }
+
+    /// Get the position of cursor in the `widget coordinate` of drop target.
+    #[inline]
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// The action proposed by the drag source.
+    #[inline]
+    pub fn proposed_action(&self) -> DropAction {
+        self.proposed_action
+    }
+
+    /// Get the id of drag source widget,
+    /// the widget was in other window if [`is_same_window()`](DragEvent::is_same_window) was false.
+    #[inline]
+    pub fn source(&self) -> ObjectId {
+        self.source
+    }
+
+    /// Whether the drag source and the drop target were in the same window.
+    #[inline]
+    pub fn is_same_window(&self) -> bool {
+        self.same_window
+    }
+}
+
+/// The widget which can accept the dropping.
+///
+/// Register the reflect trait in [`ObjectImpl::type_register`] to make it work:
+/// ```ignore
+/// fn type_register(&self, type_registry: &mut TypeRegistry) {
+///     type_registry.register::<Self, ReflectDropTarget>();
+/// }
+/// ```
+#[reflect_trait]
+pub trait DropTarget: WidgetImpl {
+    /// The dragging cursor entered the widget.
+    ///
+    /// @return the accepted action, [`DropAction::Ignore`] to reject the dragging.
+    #[inline]
+    fn on_drag_enter(&mut self, _: &DragEvent) -> DropAction {
+        DropAction::Ignore
+    }
+
+    /// The dragging cursor moved in the widget.
+    ///
+    /// @param `accepted`: the action accepted last time.<br>
+    /// @return the accepted action, [`DropAction::Ignore`] to reject the dragging.
+    #[inline]
+    fn on_drag_move(&mut self, _: &DragEvent, accepted: DropAction) -> DropAction {
+        accepted
+    }
+
+    /// The dragging cursor left the widget, or the drag was cancelled.
+    #[inline]
+    fn on_drag_leave(&mut self) {}
+
+    /// The dragging was dropped on the widget with accepted action.
+    ///
+    /// @return false if the drop was failed.
+    fn on_drop(&mut self, event: &DragEvent) -> bool;
+}
+
+/// The widget which starts the drag operation and wants to know the result.
+///
+/// Register the reflect trait in [`ObjectImpl::type_register`] like [`DropTarget`].
+#[reflect_trait]
+pub trait DragSource: WidgetImpl {
+    /// The drag operation has finished,
+    /// `action` is [`DropAction::Ignore`] if the drop was rejected or cancelled.
+    fn on_drag_finished(&mut self, action: DropAction);
+}
+
+/// The drag operation.
+///
+/// Start the drag when the mouse was pressed and moved for a distance
+/// (see [`Drag::reached_start_distance`]), the dragging works across
+/// the windows in the same process, and can be cancelled by `Escape`.
+///
+/// ```ignore
+/// fn on_mouse_move(&mut self, evt: &MouseEvent) {
+///     if let Some(pressed) = self.pressed_pos {
+///         if Drag::reached_start_distance(pressed, evt.position().into()) {
+///             self.pressed_pos = None;
+///             Drag::new(self, "application/x-pane", self.id().to_value())
+///                 .action(DropAction::Move)
+///                 .start();
+///         }
+///     }
+/// }
+/// ```
+pub struct Drag {
+    source: ObjectId,
+    window: ObjectId,
+    mime: String,
+    payload: DragPayload,
+    action: DropAction,
+    preview: Option<DynPopupTr>,
+}
+
+impl Drag {
+    #[inline]
+    pub fn new<P: Into<DragPayload>>(source: &dyn WidgetImpl, mime: &str, payload: P) -> Self {
+        Self {
+            source: source.id(),
+            window: source.window_id(),
+            mime: mime.to_string(),
+            payload: payload.into(),
+            action: DropAction::Move,
+            preview: None,
+        }
+    }
+
+    /// The action proposed to the drop target, default was [`DropAction::Move`].
+    #[inline]
+    pub fn action(mut self, action: DropAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Set the preview popup which follows the cursor in the source window.
+    ///
+    /// The preview will be removed after the drag finished,
+    /// if the preview was animatable(implemented [`Snapshot`]), the animation will play when shown and hidden.
+    #[inline]
+    pub fn preview<T: Into<DynPopupTr>>(mut self, preview: T) -> Self {
+        self.preview = Some(preview.into());
+        self
+    }
+
+    /// Start the drag operation, do nothing if there was a drag in progress.
+    pub fn start(self) {
+        let window = ApplicationWindow::window_of(self.window);
+        if window.platform_type() == PlatformType::Ipc {
+            warn!("Drag and drop was not supported in the shared window.");
+            return;
+        }
+        let Some(source_window) = window.winit_id() else {
+            return;
+        };
+
+        {
+            let mut session = SESSION.lock();
+            if session.is_some() {
+                warn!("Start the drag when another drag was in progress, ignored.");
+                return;
+            }
+            *session = Some(DragSession {
+                mime: self.mime,
+                payload: Arc::new(self.payload),
+                action: self.action,
+                source: self.source,
+                source_window,
+                target_window: None,
+                dropped: false,
+            });
+        }
+
+        if let Some(mut preview) = self.preview {
+            if !preview.initialized() {
+                ApplicationWindow::initialize_dynamic_component(preview.as_widget_impl_mut(), true);
+            }
+            PREVIEW.with(|p| *p.borrow_mut() = Some(preview));
+        }
+        window.send_message(Message::SetCursorShape(
+            DropAction::Ignore.cursor_shape(),
+            source_window,
+        ));
+    }
+
+    /// Whether there was a drag operation in progress.
+    #[inline]
+    pub fn is_dragging() -> bool {
+        SESSION.lock().is_some()
+    }
+
+    /// Cancel the drag operation, only works in the source window.
+    #[inline]
+    pub fn cancel() {
+        cancel(ApplicationWindow::window());
+    }
+
+    /// Check whether the mouse has moved far enough from the pressed position to start the drag,
+    /// see [`start_drag_distance`](crate::application::start_drag_distance).
+    #[inline]
+    pub fn reached_start_distance(pressed: Point, pos: Point) -> bool {
+        let distance = (pos.x() - pressed.x()).abs() + (pos.y() - pressed.y()).abs();
+        distance >= event_hints().start_drag_distance()
+    }
+}
+
+struct DragSession {
+    mime: String,
+    payload: Arc<DragPayload>,
+    action: DropAction,
+    source: ObjectId,
+    source_window: WindowId,
+    /// The window under the cursor except the source window.
+    target_window: Option<WindowId>,
+    dropped: bool,
+}
+
+impl DragEvent {
+    fn current(window: WindowId, position: Point) -> Option<Self> {
+        SESSION.lock().as_ref().map(|s| DragEvent {
+            mime: s.mime.clone(),
+            payload: s.payload.clone(),
+            position,
+            proposed_action: s.action,
+            source: s.source,
+            same_window: s.source_window == window,
+        })
+    }
+}
+
+/// Handle the mouse move of the source window while dragging.
+///
+/// @return true if the event was consumed by the drag operation.
+pub(crate) fn handle_mouse_move(window: &mut ApplicationWindow, pos: Point) -> bool {
+    handle_source_cursor(window, pos, false)
+}
+
+/// Handle the mouse release of the source window while dragging,
+/// the drag will be dropped at the position.
+///
+/// @return true if the event was consumed by the drag operation.
+pub(crate) fn handle_mouse_release(window: &mut ApplicationWindow, pos: Point) -> bool {
+    handle_source_cursor(window, pos, true)
+}
+
+/// Cancel the drag when `Escape` was pressed in the source window.
+///
+/// @return true if the event was consumed by the drag operation.
+pub(crate) fn handle_key_pressed(window: &mut ApplicationWindow, evt: &KeyEvent) -> bool {
+    evt.key_code() == KeyCode::KeyEscape && cancel(window)
+}
+
+/// Cancel the drag started from the window.
+///
+/// @return false if there was no drag started from the window.
+pub(crate) fn cancel(window: &mut ApplicationWindow) -> bool {
+    let Some(winit_id) = window.winit_id() else {
+        return false;
+    };
+    let target_window = {
+        let mut session = SESSION.lock();
+        let Some(session) = session
+            .as_mut()
+            .filter(|s| s.source_window == winit_id && !s.dropped)
+        else {
+            return false;
+        };
+        session.dropped = true;
+        session.target_window.take()
+    };
+
+    if let Some(target_window) = target_window {
+        window.send_message(Message::WindowResponse(target_window, Box::new(drag_leave)));
+    }
+    drag_leave(window);
+    finish(window, DropAction::Ignore);
+    true
+}
+
+/// Route the dragging cursor which was outside the source window, called in the main thread.
+///
+/// @param `target`: the window under the cursor.<br>
+/// @param `pos`: the position of cursor in `screen coordinate`.<br>
+/// @return the messages should be sent to the windows.
+pub(crate) fn route_outside(
+    source: WindowId,
+    target: Option<WindowId>,
+    pos: Point,
+    dropped: bool,
+) -> Vec<(WindowId, Message)> {
+    let mut session = SESSION.lock();
+    let Some(session) = session.as_mut() else {
+        return vec![];
+    };
+    let mut messages = vec![];
+
+    let last = session.target_window;
+    if let Some(last) = last.filter(|&last| Some(last) != target) {
+        messages.push((last, Message::WindowResponse(last, Box::new(drag_leave))));
+    }
+    session.target_window = target;
+
+    match target {
+        Some(target) => {
+            let closure = move |w: &mut ApplicationWindow| {
+                let pos = w.map_from_client(&pos);
+                drag_over(w, pos, dropped)
+            };
+            messages.push((target, Message::WindowResponse(target, Box::new(closure))));
+        }
+        None if dropped => {
+            let closure = |w: &mut ApplicationWindow| finish(w, DropAction::Ignore);
+            messages.push((source, Message::WindowResponse(source, Box::new(closure))));
+        }
+        None if last.is_some() => {
+            let closure = move |w: &mut ApplicationWindow| {
+                w.send_message(Message::SetCursorShape(
+                    DropAction::Ignore.cursor_shape(),
+                    source,
+                ))
+            };
+            messages.push((source, Message::WindowResponse(source, Box::new(closure))));
+        }
+        None => {}
+    }
+
+    messages
+}
+
+fn handle_source_cursor(window: &mut ApplicationWindow, pos: Point, dropped: bool) -> bool {
+    let Some(winit_id) = window.winit_id() else {
+        return false;
+    };
+    let inside = pos.x() >= 0
+        && pos.y() >= 0
+        && pos.x() < window.size().width()
+        && pos.y() < window.size().height();
+
+    let target_window = {
+        let mut session = SESSION.lock();
+        let Some(session) = session.as_mut().filter(|s| s.source_window == winit_id) else {
+            return false;
+        };
+        if session.dropped {
+            return true;
+        }
+        session.dropped = dropped;
+
+        if inside {
+            session.target_window.take()
+        } else {
+            None
+        }
+    };
+
+    move_preview(window, pos, inside && !dropped);
+
+    if inside {
+        if let Some(target_window) = target_window {
+            window.send_message(Message::WindowResponse(target_window, Box::new(drag_leave)));
+        }
+        drag_over(window, pos, dropped);
+    } else {
+        drag_leave(window);
+        window.send_message(Message::DragOutside(
+            winit_id,
+            window.map_to_client(&pos),
+            dropped,
+        ));
+    }
+
+    if dropped {
+        window.set_pressed_widget(0);
+    }
+    true
+}
+
+fn move_preview(window: &mut ApplicationWindow, pos: Point, visible: bool) {
+    PREVIEW.with(|preview| {
+        let mut preview = preview.borrow_mut();
+        let Some(preview) = preview.as_mut() else {
+            return;
+        };
+
+        if !visible {
+            if preview.visible() {
+                preview.hide();
+            }
+            return;
+        }
+
+        preview.set_fixed_x(pos.x() + PREVIEW_OFFSET);
+        preview.set_fixed_y(pos.y() + PREVIEW_OFFSET);
+        preview.calc_relative_position();
+        if !preview.visible() {
+            preview.show();
+        }
+        window.layout_change(preview.as_widget_impl_mut());
+    })
+}
+
+/// Find the drop target under the position, or the nearest ancestor which was drop target.
+///
+/// The popup was not in the widget tree of its supervisor,
+/// the supervisor will be treated as the parent of popup.
+fn drop_target_at(window: &ApplicationWindow, pos: &Point) -> Option<ObjectId> {
+    let widgets = ApplicationWindow::widgets_of(window.id());
+    let mut current = widgets
+        .values()
+        .map(|w| nonnull_ref!(w))
+        .find(|w| w.visible() && w.point_effective(pos));
+
+    while let Some(w) = current {
+        if cast!(w as DropTarget).is_some() {
+            return Some(w.id());
+        }
+        current = match cast!(w as PopupImpl) {
+            Some(popup) if popup.supervisor().id() != window.id() => Some(popup.supervisor()),
+            _ => w.get_parent_ref(),
+        };
+    }
+    None
+}
+
+/// The dragging cursor was over the window.
+///
+/// @param `pos`: the position of cursor in `client coordinate`.
+fn drag_over(window: &mut ApplicationWindow, pos: Point, dropped: bool) {
+    let Some(winit_id) = window.winit_id() else {
+        return;
+    };
+    let Some(mut event) = DragEvent::current(winit_id, pos) else {
+        return;
+    };
+
+    let last = TARGET.with(|t| t.get());
+    let target = drop_target_at(window, &pos);
+
+    if let Some((last_id, _)) = last {
+        if last_id != 0 && Some(last_id) != target {
+            if let Some(w) = window.find_id_mut(last_id) {
+                if let Some(drop_target) = cast_mut!(w as DropTarget) {
+                    drop_target.on_drag_leave();
+                }
+            }
+        }
+    }
+
+    let mut action = DropAction::Ignore;
+    if let Some(w) = target.and_then(|id| window.find_id_mut(id)) {
+        event.position = w.map_to_widget(&pos);
+        let drop_target = cast_mut!(w as DropTarget).unwrap();
+
+        action = match last {
+            Some((last_id, accepted)) if Some(last_id) == target => {
+                drop_target.on_drag_move(&event, accepted)
+            }
+            _ => drop_target.on_drag_enter(&event),
+        };
+        if dropped && (action == DropAction::Ignore || !drop_target.on_drop(&event)) {
+            action = DropAction::Ignore;
+        }
+    }
+
+    if dropped {
+        TARGET.with(|t| t.set(None));
+        if !event.same_window {
+            window.send_message(Message::SetCursorShape(
+                SystemCursorShape::ArrowCursor,
+                winit_id,
+            ));
+        }
+        notify_finished(window, action);
+        return;
+    }
+
+    TARGET.with(|t| t.set(Some((target.unwrap_or(0), action))));
+    if last.map(|(_, accepted)| accepted) != Some(action) {
+        let shape = action.cursor_shape();
+        window.send_message(Message::SetCursorShape(shape, winit_id));
+
+        if let Some(source_window) = SESSION.lock().as_ref().map(|s| s.source_window) {
+            if source_window != winit_id {
+                window.send_message(Message::SetCursorShape(shape, source_window));
+            }
+        }
+    }
+}
+
+/// The dragging cursor left the window.
+fn drag_leave(window: &mut ApplicationWindow) {
+    let Some((last_id, _)) = TARGET.with(|t| t.take()) else {
+        return;
+    };
+
+    if let Some(w) = window.find_id_mut(last_id) {
+        if let Some(drop_target) = cast_mut!(w as DropTarget) {
+            drop_target.on_drag_leave();
+        }
+    }
+
+    let Some(winit_id) = window.winit_id() else {
+        return;
+    };
+    let is_source = SESSION
+        .lock()
+        .as_ref()
+        .map_or(false, |s| s.source_window == winit_id);
+    let shape = if is_source {
+        DropAction::Ignore.cursor_shape()
+    } else {
+        SystemCursorShape::ArrowCursor
+    };
+    window.send_message(Message::SetCursorShape(shape, winit_id));
+}
+
+fn notify_finished(window: &mut ApplicationWindow, action: DropAction) {
+    let Some(source_window) = SESSION.lock().as_ref().map(|s| s.source_window) else {
+        return;
+    };
+
+    if window.winit_id() == Some(source_window) {
+        finish(window, action)
+    } else {
+        window.send_message(Message::WindowResponse(
+            source_window,
+            Box::new(move |w| finish(w, action)),
+        ))
+    }
+}
+
+/// Finish the drag session, called in the source window.
+fn finish(window: &mut ApplicationWindow, action: DropAction) {
+    let Some(session) = SESSION.lock().take() else {
+        return;
+    };
+
+    PREVIEW.with(|preview| {
+        if let Some(mut preview) = preview.borrow_mut().take() {
+            if preview.visible() {
+                preview.hide();
+            }
+            window._add_removed_widget(preview.into());
+        }
+    });
+    window.send_message(Message::SetCursorShape(
+        SystemCursorShape::ArrowCursor,
+        session.source_window,
+    ));
+
+    if let Some(w) = window.find_id_mut(session.source) {
+        if let Some(source) = cast_mut!(w as DragSource) {
+            source.on_drag_finished(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Drag, DragPayload};
+    use tlib::{figure::Point, values::ToValue};
+
+    #[test]
+    fn test_drag_payload() {
+        let payload: DragPayload = 12.to_value().into();
+        assert_eq!(payload.value().unwrap().get::<i32>(), 12);
+        assert!(payload.bytes().is_none());
+
+        let payload: DragPayload = vec![1u8, 2, 3].into();
+        assert_eq!(payload.bytes(), Some(&[1u8, 2, 3][..]));
+        assert!(payload.value().is_none());
+    }
+
+    #[test]
+    fn test_reached_start_distance() {
+        let pressed = Point::new(10, 10);
+        assert!(!Drag::reached_start_distance(pressed, Point::new(13, 14)));
+        assert!(Drag::reached_start_distance(pressed, Point::new(15, 15)));
+        assert!(Drag::reached_start_distance(pressed, Point::new(10, 0)));
+    }
+}
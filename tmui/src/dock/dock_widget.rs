@@ -0,0 +1,422 @@
+use super::DOCK_MIME;
+use crate::{
+    dnd::{Drag, DropAction},
+    font::FontCalculation,
+    graphics::painter::Painter,
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{RegionClear, WidgetImpl},
+};
+use tlib::{connect, emit, events::MouseEvent, namespace::MouseButton, signals};
+
+const TITLE_PADDING: i32 = 4;
+const TITLE_SPACING: i32 = 4;
+const BUTTON_SIZE: i32 = 16;
+const BUTTON_RADIUS: f32 = 3.;
+
+const TITLE_BACKGROUND: Color = Color::rgb(238, 238, 238);
+const HOVER_BACKGROUND: Color = Color::rgb(215, 215, 215);
+const SEPARATOR_COLOR: Color = Color::rgb(210, 210, 210);
+
+/// The panel can be docked in [`DockArea`](super::DockArea).
+///
+/// Drag the title bar to move the dock onto the drop zones of dock areas, including the dock
+/// areas in other windows. The float button detaches the dock into a floating window,
+/// click it again in the floating window docks the panel back.
+///
+/// Docks were identified by their keys, see [`DockArea::new`](super::DockArea::new).
+#[extends(Widget)]
+pub struct DockWidget {
+    key: String,
+    #[derivative(Default(value = "DockTitleBar::new_alloc()"))]
+    title_bar: Tr<DockTitleBar>,
+}
+
+pub trait DockWidgetSignals: ActionExt {
+    signals!(
+        DockWidgetSignals:
+
+        /// Emitted when the close button of title bar was clicked.
+        ///
+        /// @param [`ObjectId`]: the id of dock widget.
+        close_requested(ObjectId);
+
+        /// Emitted when the float button of title bar was clicked.
+        ///
+        /// @param [`ObjectId`]: the id of dock widget.
+        float_requested(ObjectId);
+
+        /// Emitted when the dock was dragged and dropped into a dock area,
+        /// the dock area has created the new dock for the key.
+        ///
+        /// @param [`ObjectId`]: the id of dock widget.
+        dragged_away(ObjectId);
+    );
+}
+impl DockWidgetSignals for DockWidget {}
+
+impl ObjectSubclass for DockWidget {
+    const NAME: &'static str = "DockWidget";
+}
+
+impl ObjectImpl for DockWidget {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_hexpand(true);
+        self.set_vexpand(true);
+
+        connect!(self.title_bar, close_clicked(), self, on_close_clicked());
+        connect!(self.title_bar, float_clicked(), self, on_float_clicked());
+        connect!(self.title_bar, dragged_away(), self, on_dragged_away());
+    }
+}
+
+impl WidgetImpl for DockWidget {}
+
+impl DockWidget {
+    /// Create the dock with the unique `key`, which was used to recreate the dock.
+    pub fn new<T: WidgetImpl>(key: &str, title: &str, mut content: Tr<T>) -> Tr<Self> {
+        let mut dock = Self::new_alloc();
+        dock.key = key.to_string();
+        dock.title_bar.key = key.to_string();
+        dock.title_bar.title = title.to_string();
+
+        content.set_hexpand(true);
+        content.set_vexpand(true);
+        let mut vbox = VBox::new();
+        vbox.set_hexpand(true);
+        vbox.set_vexpand(true);
+        vbox.add_child(dock.title_bar.clone());
+        vbox.add_child(content);
+        dock.child(vbox);
+        dock
+    }
+
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    #[inline]
+    pub fn title(&self) -> &str {
+        &self.title_bar.title
+    }
+
+    #[inline]
+    pub fn is_closable(&self) -> bool {
+        self.title_bar.closable
+    }
+
+    /// Show the close button on title bar or not.
+    ///
+    /// The default value was [`true`].
+    #[inline]
+    pub fn set_closable(&mut self, closable: bool) {
+        self.title_bar.closable = closable;
+        self.title_bar.update();
+    }
+
+    #[inline]
+    pub fn is_floatable(&self) -> bool {
+        self.title_bar.floatable
+    }
+
+    /// Show the float button on title bar or not.
+    ///
+    /// The default value was [`true`].
+    #[inline]
+    pub fn set_floatable(&mut self, floatable: bool) {
+        self.title_bar.floatable = floatable;
+        self.title_bar.update();
+    }
+
+    /// Whether the dock was in the floating window.
+    #[inline]
+    pub fn is_floating(&self) -> bool {
+        self.title_bar.floating
+    }
+
+    #[inline]
+    pub(crate) fn set_floating(&mut self, floating: bool) {
+        self.title_bar.floating = floating;
+        self.title_bar.update();
+    }
+
+    /// The id of title bar, which was the source of dragging.
+    #[inline]
+    pub(crate) fn title_bar_id(&self) -> ObjectId {
+        self.title_bar.id()
+    }
+}
+
+impl DockWidget {
+    #[inline]
+    fn on_close_clicked(&mut self) {
+        let id = self.id();
+        emit!(self, close_requested(id));
+    }
+
+    #[inline]
+    fn on_float_clicked(&mut self) {
+        let id = self.id();
+        emit!(self, float_requested(id));
+    }
+
+    #[inline]
+    fn on_dragged_away(&mut self) {
+        let id = self.id();
+        emit!(self, dragged_away(id));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TitleButton {
+    Float,
+    Close,
+}
+
+/// The title bar of [`DockWidget`], shows the title and the float, close buttons.
+#[extends(Widget)]
+pub struct DockTitleBar {
+    key: String,
+    title: String,
+    #[derivative(Default(value = "true"))]
+    closable: bool,
+    #[derivative(Default(value = "true"))]
+    floatable: bool,
+    floating: bool,
+
+    hovered: Option<TitleButton>,
+    pressed_button: Option<TitleButton>,
+    /// The position pressed on the title, for starting the drag.
+    pressed: Option<Point>,
+}
+
+pub trait DockTitleBarSignals: ActionExt {
+    signals!(
+        DockTitleBarSignals:
+
+        /// Emitted when the close button was clicked.
+        close_clicked();
+
+        /// Emitted when the float button was clicked.
+        float_clicked();
+
+        /// Emitted when the dragging dock was dropped into a dock area.
+        dragged_away();
+    );
+}
+impl DockTitleBarSignals for DockTitleBar {}
+
+impl ObjectSubclass for DockTitleBar {
+    const NAME: &'static str = "DockTitleBar";
+}
+
+impl ObjectImpl for DockTitleBar {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_mouse_tracking(true);
+        self.set_hexpand(true);
+        self.set_paddings(
+            TITLE_PADDING,
+            TITLE_PADDING,
+            TITLE_PADDING,
+            TITLE_PADDING * 2,
+        );
+        self.calc_height();
+    }
+
+    #[inline]
+    fn type_register(&self, type_registry: &mut TypeRegistry) {
+        type_registry.register::<DockTitleBar, ReflectDragSource>();
+    }
+}
+
+impl WidgetImpl for DockTitleBar {
+    #[inline]
+    fn font_changed(&mut self) {
+        self.calc_height();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let rect = self.origin_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        painter.save_pen();
+        painter.set_antialiasing(true);
+        painter.fill_rect(rect, TITLE_BACKGROUND);
+        painter.set_color(SEPARATOR_COLOR);
+        painter.set_line_width(1.);
+        painter.draw_line_f(
+            rect.x(),
+            rect.bottom() - 0.5,
+            rect.right(),
+            rect.bottom() - 0.5,
+        );
+
+        let contents = self.contents_rect_f(Some(Coordinate::Widget));
+        let font_height = self.font().calc_font_dimension().1;
+        let mut text_width = contents.width();
+        for button in self.buttons() {
+            text_width -= (BUTTON_SIZE + TITLE_SPACING) as f32;
+
+            let button_rect: FRect = self.button_rect(button).into();
+            if self.hovered == Some(button) {
+                let r = BUTTON_RADIUS;
+                painter.fill_round_rect(button_rect, (r, r, r, r), HOVER_BACKGROUND);
+            }
+            painter.set_color(Color::BLACK);
+            painter.set_line_width(1.2);
+            match button {
+                TitleButton::Float => render_float(painter, button_rect, self.floating),
+                TitleButton::Close => render_close(painter, button_rect),
+            }
+        }
+
+        painter.set_color(Color::BLACK);
+        painter.draw_paragraph(
+            &self.title,
+            (
+                contents.x(),
+                contents.y() + (contents.height() - font_height) / 2.,
+            ),
+            0.,
+            text_width.max(0.),
+            Some(1),
+            true,
+        );
+        painter.restore_pen();
+    }
+
+    fn on_mouse_pressed(&mut self, event: &MouseEvent) {
+        if event.mouse_button() != MouseButton::LeftButton {
+            return;
+        }
+        let pos: Point = event.position().into();
+        match self.button_at(pos) {
+            Some(button) => self.pressed_button = Some(button),
+            None => self.pressed = Some(pos),
+        }
+    }
+
+    fn on_mouse_released(&mut self, event: &MouseEvent) {
+        if event.mouse_button() != MouseButton::LeftButton {
+            return;
+        }
+        self.pressed = None;
+
+        let Some(button) = self.pressed_button.take() else {
+            return;
+        };
+        if self.button_at(event.position().into()) != Some(button) {
+            return;
+        }
+        match button {
+            TitleButton::Float => emit!(self, float_clicked()),
+            TitleButton::Close => emit!(self, close_clicked()),
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseEvent) {
+        let pos: Point = event.position().into();
+
+        if let Some(pressed) = self.pressed {
+            if Drag::reached_start_distance(pressed, pos) {
+                self.pressed = None;
+                Drag::new(self, DOCK_MIME, self.key.as_bytes().to_vec())
+                    .action(DropAction::Move)
+                    .start();
+            }
+        }
+
+        let hovered = self.button_at(pos);
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            self.update();
+        }
+    }
+
+    #[inline]
+    fn on_mouse_leave(&mut self, _: &MouseEvent) {
+        if self.hovered.take().is_some() {
+            self.update();
+        }
+    }
+}
+
+impl DragSource for DockTitleBar {
+    /// The dock area which accepted the dropping has created the new dock,
+    /// this dock should be removed.
+    #[inline]
+    fn on_drag_finished(&mut self, action: DropAction) {
+        if action == DropAction::Move {
+            emit!(self, dragged_away());
+        }
+    }
+}
+
+impl DockTitleBar {
+    fn calc_height(&mut self) {
+        let font_height = self.font().calc_font_dimension().1.ceil() as i32;
+        let (top, _, bottom, _) = self.paddings();
+
+        self.height_request(font_height.max(BUTTON_SIZE) + top + bottom);
+    }
+
+    /// The visible buttons, from right to left.
+    fn buttons(&self) -> Vec<TitleButton> {
+        let mut buttons = vec![];
+        if self.closable {
+            buttons.push(TitleButton::Close);
+        }
+        if self.floatable {
+            buttons.push(TitleButton::Float);
+        }
+        buttons
+    }
+
+    fn button_rect(&self, button: TitleButton) -> Rect {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        let idx = self
+            .buttons()
+            .iter()
+            .position(|b| *b == button)
+            .unwrap_or_default() as i32;
+
+        Rect::new(
+            contents.right() - BUTTON_SIZE * (idx + 1) - TITLE_SPACING * idx,
+            contents.y() + (contents.height() - BUTTON_SIZE) / 2,
+            BUTTON_SIZE,
+            BUTTON_SIZE,
+        )
+    }
+
+    fn button_at(&self, pos: Point) -> Option<TitleButton> {
+        self.buttons()
+            .into_iter()
+            .find(|button| self.button_rect(*button).contains(&pos))
+    }
+}
+
+/// Render the window glyph, or the glyph of docking back if the dock was floating.
+fn render_float(painter: &mut Painter, rect: FRect, floating: bool) {
+    let (cx, cy) = (rect.x() + rect.width() / 2., rect.y() + rect.height() / 2.);
+    let d = 4.;
+    if floating {
+        painter.draw_rect(FRect::new(cx - d, cy - d + 2., d * 2. - 2., d * 2. - 2.));
+        painter.draw_line_f(cx - d + 2., cy - d, cx + d, cy - d);
+        painter.draw_line_f(cx + d, cy - d, cx + d, cy + d - 2.);
+    } else {
+        painter.draw_rect(FRect::new(cx - d, cy - d, d * 2., d * 2.));
+        painter.draw_line_f(cx - d, cy - d + 1.5, cx + d, cy - d + 1.5);
+    }
+}
+
+fn render_close(painter: &mut Painter, rect: FRect) {
+    let (cx, cy) = (rect.x() + rect.width() / 2., rect.y() + rect.height() / 2.);
+    let cross = 3.5;
+    painter.draw_line_f(cx - cross, cy - cross, cx + cross, cy + cross);
+    painter.draw_line_f(cx + cross, cy - cross, cx - cross, cy + cross);
+}
@@ -0,0 +1,191 @@
+use super::DockPosition;
+use crate::split_pane::DEFAULT_SPLIT_RATIO;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display};
+
+/// The serializable layout of [`DockArea`](super::DockArea), docks were recorded by their keys,
+/// and will be recreated by the factory of dock area when the layout was restored.
+///
+/// ```ignore
+/// let json = dock_area.layout().to_json()?;
+/// // On the next startup:
+/// dock_area.restore_layout(&DockLayout::from_json(&json)?);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    /// The root group of dock area, `None` if the dock area was empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<DockGroupLayout>,
+
+    /// The floating windows detached from the dock area.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub floating: Vec<FloatingLayout>,
+}
+
+/// The group of docks shown as tabs, and the groups splited from it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockGroupLayout {
+    /// The keys of docks, in the order of tabs.
+    pub docks: Vec<String>,
+
+    /// The index of current tab.
+    #[serde(default)]
+    pub current: usize,
+
+    /// The groups splited from this group, in the splitting order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<DockSplit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockSplit {
+    /// The side of splited group relative to the group it was splited from,
+    /// never be [`DockPosition::Center`].
+    pub position: DockPosition,
+
+    /// The ratio of splited group in the area shared with the group it was splited from.
+    #[serde(default = "default_split_ratio")]
+    pub ratio: f32,
+
+    pub group: DockGroupLayout,
+}
+
+#[inline]
+fn default_split_ratio() -> f32 {
+    DEFAULT_SPLIT_RATIO
+}
+
+/// The floating window, the geometry was in `screen coordinate`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatingLayout {
+    #[serde(default)]
+    pub title: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub root: DockGroupLayout,
+}
+
+#[derive(Debug)]
+pub struct DockLayoutError {
+    msg: String,
+}
+impl DockLayoutError {
+    pub fn new(msg: impl ToString) -> Self {
+        Self {
+            msg: msg.to_string(),
+        }
+    }
+}
+impl Error for DockLayoutError {}
+impl Display for DockLayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl DockLayout {
+    #[inline]
+    pub fn from_json(content: &str) -> Result<Self, DockLayoutError> {
+        serde_json::from_str(content).map_err(DockLayoutError::new)
+    }
+
+    #[inline]
+    pub fn to_json(&self) -> Result<String, DockLayoutError> {
+        serde_json::to_string_pretty(self).map_err(DockLayoutError::new)
+    }
+
+    /// Get the keys of all the docks, including the docks in floating windows.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys = vec![];
+        if let Some(root) = self.root.as_ref() {
+            root.collect_keys(&mut keys);
+        }
+        for floating in self.floating.iter() {
+            floating.root.collect_keys(&mut keys);
+        }
+        keys
+    }
+}
+
+impl DockGroupLayout {
+    /// The group contains the docks, without splits.
+    #[inline]
+    pub fn new(docks: Vec<String>) -> Self {
+        Self {
+            docks,
+            ..Default::default()
+        }
+    }
+
+    fn collect_keys<'a>(&'a self, keys: &mut Vec<&'a str>) {
+        keys.extend(self.docks.iter().map(|key| key.as_str()));
+        for split in self.splits.iter() {
+            split.group.collect_keys(keys);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_json() {
+        let layout = DockLayout {
+            root: Some(DockGroupLayout {
+                docks: vec!["editor".to_string()],
+                current: 0,
+                splits: vec![
+                    DockSplit {
+                        position: DockPosition::Left,
+                        ratio: 0.25,
+                        group: DockGroupLayout::new(vec![
+                            "files".to_string(),
+                            "outline".to_string(),
+                        ]),
+                    },
+                    DockSplit {
+                        position: DockPosition::Bottom,
+                        ratio: 0.3,
+                        group: DockGroupLayout::new(vec!["terminal".to_string()]),
+                    },
+                ],
+            }),
+            floating: vec![FloatingLayout {
+                title: "Problems".to_string(),
+                x: 100,
+                y: 80,
+                width: 400,
+                height: 300,
+                root: DockGroupLayout::new(vec!["problems".to_string()]),
+            }],
+        };
+
+        let json = layout.to_json().unwrap();
+        let restored = DockLayout::from_json(&json).unwrap();
+        assert_eq!(restored, layout);
+        let splits = &restored.root.as_ref().unwrap().splits;
+        assert_eq!(splits[0].ratio, 0.25);
+        assert_eq!(splits[1].ratio, 0.3);
+        assert_eq!(
+            layout.keys(),
+            vec!["editor", "files", "outline", "terminal", "problems"]
+        );
+
+        let layout = DockLayout::from_json(r#"{ "root": { "docks": ["editor"] } }"#).unwrap();
+        assert_eq!(layout.root.unwrap().current, 0);
+        assert!(layout.floating.is_empty());
+        assert!(DockLayout::from_json(r#"{ "root": { "current": 1 } }"#).is_err());
+
+        // The layout saved without ratio was restored by the default ratio.
+        let layout = DockLayout::from_json(
+            r#"{ "root": { "docks": ["editor"], "splits": [
+                { "position": "left", "group": { "docks": ["files"] } }
+            ] } }"#,
+        )
+        .unwrap();
+        assert_eq!(layout.root.unwrap().splits[0].ratio, DEFAULT_SPLIT_RATIO);
+    }
+}
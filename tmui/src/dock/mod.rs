@@ -0,0 +1,894 @@
+pub mod dock_widget;
+pub mod layout;
+
+use crate::{
+    dnd::{DragEvent, DragPayload, DropAction},
+    prelude::*,
+    split_pane::DEFAULT_SPLIT_RATIO,
+    tab_bar::TabPosition,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::WidgetImpl,
+    window::{win_builder::WindowBuilder, win_config::WindowConfig},
+};
+use dock_widget::{DockWidget, DockWidgetSignals};
+use layout::{DockGroupLayout, DockLayout, DockSplit, FloatingLayout};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tlib::{connect, emit, object::ObjectId, signals};
+
+/// The mime of dragging dock, the payload was the utf-8 bytes of dock key.
+pub const DOCK_MIME: &str = "application/x-tmui-dock";
+
+/// The ratio of each edge zone in the group, the rest center area was the tab zone.
+const EDGE_ZONE_RATIO: f32 = 0.25;
+const FLOATING_MIN_SIZE: i32 = 200;
+
+const INDICATOR_BACKGROUND: Color = Color::rgba(0, 120, 215, 60);
+const INDICATOR_BORDER: Color = Color::rgb(0, 120, 215);
+
+/// Create the dock by the key, return `None` if the key was unknown.
+///
+/// The factory was shared by the floating windows, and was called in the ui thread
+/// of the window which the dock will be placed in.
+pub type DockFactory = Arc<dyn Fn(&str) -> Option<Tr<DockWidget>> + Send + Sync>;
+
+/// The drop position relative to the group of docks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// As the new tab of the group.
+    #[default]
+    Center,
+}
+
+impl DockPosition {
+    #[inline]
+    fn from_split_type(ty: SplitType) -> Self {
+        match ty {
+            SplitType::SplitLeft => Self::Left,
+            SplitType::SplitUp => Self::Top,
+            SplitType::SplitRight => Self::Right,
+            SplitType::SplitDown => Self::Bottom,
+            SplitType::SplitNone => Self::Center,
+        }
+    }
+}
+
+/// The area to place the [`DockWidget`]s, built on [`SplitPane`] whose panes were
+/// the groups of docks, each group was a [`TabWidget`] holds the docks as tabs.
+///
+/// Dragging the title bar of dock over the area shows the drop zone under the cursor:
+/// the edges of group split a new group beside it, the center adds the dock as a new tab.
+/// Docks can be detached into floating windows, which hold their own dock areas.
+///
+/// Docks were moved by recreating them through the [`DockFactory`] with their keys,
+/// even in the same window, so the state of panel should be kept in the model of application.
+/// This also makes the layout can be serialized, see [`layout()`](DockArea::layout)
+/// and [`restore_layout()`](DockArea::restore_layout).
+#[extends(Widget)]
+#[run_after]
+#[close_handler]
+pub struct DockArea {
+    #[derivative(Default(value = "SplitPane::new_alloc()"))]
+    split_pane: Tr<SplitPane>,
+    groups: Vec<Tr<TabWidget>>,
+    factory: Option<DockFactory>,
+
+    /// The hovered group(`None` if the area was empty) and the drop position while dragging.
+    drop_zone: Option<(Option<ObjectId>, DockPosition)>,
+    #[derivative(Default(value = "DockIndicator::new_alloc()"))]
+    indicator: Tr<DockIndicator>,
+
+    floatings: Vec<(usize, FloatingLayout)>,
+    float_seq: usize,
+    /// The floating windows to open after the window was initialized.
+    pending_floatings: Vec<FloatingLayout>,
+    /// The origin dock area and the floating index, if this area was in the floating window.
+    origin: Option<(ObjectId, usize)>,
+}
+
+pub trait DockAreaSignals: ActionExt {
+    signals!(
+        DockAreaSignals:
+
+        /// Emitted when the docks were added, removed, moved, or floated.
+        layout_changed();
+
+        /// Emitted when the dock was closed by the close button of its title bar.
+        ///
+        /// @param [`String`]: the key of closed dock.
+        dock_closed(String);
+    );
+}
+impl DockAreaSignals for DockArea {}
+
+impl ObjectSubclass for DockArea {
+    const NAME: &'static str = "DockArea";
+}
+
+impl ObjectImpl for DockArea {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_hexpand(true);
+        self.set_vexpand(true);
+
+        let mut split_pane = self.split_pane.clone();
+        split_pane.set_hexpand(true);
+        split_pane.set_vexpand(true);
+        self.child(split_pane);
+
+        connect!(self, size_changed(), self, on_size_changed(Size));
+    }
+
+    #[inline]
+    fn type_register(&self, type_registry: &mut TypeRegistry) {
+        type_registry.register::<DockArea, ReflectDropTarget>();
+    }
+}
+
+impl WidgetImpl for DockArea {
+    #[inline]
+    fn run_after(&mut self) {
+        for floating in std::mem::take(&mut self.pending_floatings) {
+            self.open_floating(floating)
+        }
+    }
+}
+
+impl DockArea {
+    /// Create the dock area with the factory, which recreates the docks by their keys
+    /// when the docks were moved, floated or restored.
+    #[inline]
+    pub fn new<F>(factory: F) -> Tr<Self>
+    where
+        F: Fn(&str) -> Option<Tr<DockWidget>> + Send + Sync + 'static,
+    {
+        Self::with_factory(Arc::new(factory))
+    }
+
+    #[inline]
+    pub fn with_factory(factory: DockFactory) -> Tr<Self> {
+        let mut area = Self::new_alloc();
+        area.factory = Some(factory);
+        area
+    }
+
+    /// Add the dock as a new tab of the first group.
+    #[inline]
+    pub fn add_dock(&mut self, dock: Tr<DockWidget>) {
+        let group = self.groups.first().map(|g| g.id());
+        self.insert_dock(dock, group, DockPosition::Center)
+    }
+
+    /// Add the dock beside the group which holds the dock of `target` key,
+    /// the dock will be added to the first group if the `target` was not found.
+    #[inline]
+    pub fn add_dock_at(&mut self, dock: Tr<DockWidget>, target: &str, position: DockPosition) {
+        let group = self
+            .group_of_key(target)
+            .or_else(|| self.groups.first().map(|g| g.id()));
+        self.insert_dock(dock, group, position)
+    }
+
+    /// Remove and destroy the dock.
+    pub fn remove_dock(&mut self, key: &str) {
+        if let Some(id) = self.dock_id(key) {
+            self.remove_dock_by_id(id);
+        }
+    }
+
+    /// Detach the dock into a floating window.
+    pub fn float_dock(&mut self, key: &str) {
+        if let Some(id) = self.dock_id(key) {
+            self.float_dock_by_id(id);
+        }
+    }
+
+    /// Switch the group to the tab of dock.
+    pub fn raise_dock(&mut self, key: &str) {
+        let Some(id) = self.dock_id(key) else {
+            return;
+        };
+        for group in self.groups.iter_mut() {
+            if let Some(idx) = group.index_of(id) {
+                group.set_current_index(idx);
+                return;
+            }
+        }
+    }
+
+    /// Whether the dock was in this area, the docks in floating windows were excluded.
+    #[inline]
+    pub fn contains(&self, key: &str) -> bool {
+        self.dock_id(key).is_some()
+    }
+
+    /// Get the keys of docks in this area, the docks in floating windows were excluded.
+    pub fn dock_keys(&self) -> Vec<String> {
+        self.groups
+            .iter()
+            .flat_map(|group| docks_of(group))
+            .map(|dock| dock.key().to_string())
+            .collect()
+    }
+
+    /// Get the serializable layout of the area and its floating windows.
+    ///
+    /// The geometries of floating windows were recorded when their docks or sizes were changed.
+    pub fn layout(&self) -> DockLayout {
+        DockLayout {
+            root: self.root_layout(),
+            floating: self.floatings.iter().map(|(_, f)| f.clone()).collect(),
+        }
+    }
+
+    /// Replace the docks by the layout, the docks were created by the factory,
+    /// the unknown keys were skipped.
+    ///
+    /// The floating windows will be opened after the window was initialized,
+    /// the floating windows opened before were not affected.
+    pub fn restore_layout(&mut self, layout: &DockLayout) {
+        for group in std::mem::take(&mut self.groups) {
+            self.split_pane.remove_children(group.id());
+        }
+        if let Some(root) = layout.root.as_ref() {
+            self.apply_group(None, root);
+        }
+
+        if self.origin.is_none() {
+            if self.window().initialized() {
+                for floating in layout.floating.iter() {
+                    self.open_floating(floating.clone())
+                }
+            } else {
+                self.pending_floatings
+                    .extend(layout.floating.iter().cloned());
+            }
+        }
+        self.layout_changed();
+    }
+}
+
+impl DockArea {
+    fn create_dock(&self, key: &str) -> Option<Tr<DockWidget>> {
+        let dock = self.factory.as_ref().and_then(|factory| factory(key));
+        if dock.is_none() {
+            warn!(
+                "`DockArea` can not create the dock with unknown key `{}`.",
+                key
+            );
+        }
+        dock
+    }
+
+    /// Insert the dock to the group, or split a new group beside the group.
+    ///
+    /// `group` should be `None` only if the area was empty.
+    fn insert_dock(
+        &mut self,
+        dock: Tr<DockWidget>,
+        group: Option<ObjectId>,
+        position: DockPosition,
+    ) {
+        let mut target = match (group, position) {
+            (Some(id), DockPosition::Center) => match self.group(id) {
+                Some(group) => group,
+                None => return,
+            },
+            (Some(id), position) => self.new_group(Some((id, position))),
+            (None, _) => self.new_group(None),
+        };
+        self.add_to_group(&mut target, dock);
+        self.layout_changed();
+    }
+
+    fn new_group(&mut self, base: Option<(ObjectId, DockPosition)>) -> Tr<TabWidget> {
+        let mut group = TabWidget::new();
+        group.set_tab_position(TabPosition::Bottom);
+
+        match base {
+            Some((id, DockPosition::Left)) => self.split_pane.split_left(id, group.clone()),
+            Some((id, DockPosition::Right)) => self.split_pane.split_right(id, group.clone()),
+            Some((id, DockPosition::Top)) => self.split_pane.split_up(id, group.clone()),
+            Some((id, DockPosition::Bottom)) => self.split_pane.split_down(id, group.clone()),
+            Some((_, DockPosition::Center)) | None => self.split_pane.add_child(group.clone()),
+        }
+        self.groups.push(group.clone());
+        group
+    }
+
+    fn add_to_group(&mut self, group: &mut Tr<TabWidget>, mut dock: Tr<DockWidget>) {
+        dock.set_floating(self.origin.is_some());
+        connect!(dock, close_requested(), self, on_close_requested(ObjectId));
+        connect!(dock, float_requested(), self, on_float_requested(ObjectId));
+        connect!(dock, dragged_away(), self, on_dragged_away(ObjectId));
+
+        let title = dock.title().to_string();
+        let idx = group.add_tab(dock, &title);
+        group.set_current_index(idx);
+    }
+
+    /// Apply the layout of group, the group without any available dock was skipped,
+    /// the first available group splited from it takes its place.
+    ///
+    /// @return the id of applied group.
+    fn apply_group(
+        &mut self,
+        base: Option<(ObjectId, DockPosition)>,
+        layout: &DockGroupLayout,
+    ) -> Option<ObjectId> {
+        let docks: Vec<Tr<DockWidget>> = layout
+            .docks
+            .iter()
+            .filter_map(|key| self.create_dock(key))
+            .collect();
+
+        let mut applied = None;
+        if !docks.is_empty() {
+            let mut group = self.new_group(base);
+            for dock in docks {
+                self.add_to_group(&mut group, dock);
+            }
+            group.set_current_index(layout.current.min(group.len() - 1));
+            applied = Some(group.id());
+        }
+
+        for split in layout.splits.iter() {
+            let base = match applied {
+                Some(id) => Some((id, split.position)),
+                None => base,
+            };
+            let id = self.apply_group(base, &split.group);
+            if let (Some(_), Some(id)) = (applied, id) {
+                self.split_pane.set_split_ratio(id, split.ratio);
+            }
+            applied = applied.or(id);
+        }
+        applied
+    }
+
+    fn remove_dock_by_id(&mut self, id: ObjectId) -> bool {
+        let Some(mut group) = self
+            .groups
+            .iter()
+            .find(|g| g.index_of(id).is_some())
+            .cloned()
+        else {
+            return false;
+        };
+
+        group.remove_tab(group.index_of(id).unwrap());
+        if group.is_empty() {
+            self.groups.retain(|g| g.id() != group.id());
+            self.split_pane.remove_children(group.id());
+        }
+        self.layout_changed();
+        true
+    }
+
+    fn float_dock_by_id(&mut self, id: ObjectId) {
+        let Some(dock) = self.dock(id) else {
+            return;
+        };
+        let key = dock.key().to_string();
+        let title = dock.title().to_string();
+        let rect = dock.rect();
+        let client = self.window().map_to_client(&rect.top_left());
+
+        self.remove_dock_by_id(id);
+        self.open_floating(FloatingLayout {
+            title,
+            x: client.x(),
+            y: client.y(),
+            width: rect.width().max(FLOATING_MIN_SIZE),
+            height: rect.height().max(FLOATING_MIN_SIZE),
+            root: DockGroupLayout::new(vec![key]),
+        });
+    }
+
+    fn open_floating(&mut self, floating: FloatingLayout) {
+        let Some(factory) = self.factory.clone() else {
+            return;
+        };
+        self.float_seq += 1;
+        let float_id = self.float_seq;
+        let origin = self.id();
+
+        let title = if floating.title.is_empty() {
+            floating.root.docks.first().cloned().unwrap_or_default()
+        } else {
+            floating.title.clone()
+        };
+        let config = WindowConfig::builder()
+            .title(title)
+            .width(floating.width.max(FLOATING_MIN_SIZE) as u32)
+            .height(floating.height.max(FLOATING_MIN_SIZE) as u32)
+            .position(Point::new(floating.x, floating.y))
+            .build();
+        let root = floating.root.clone();
+        self.floatings.push((float_id, floating));
+
+        self.window()
+            .create_window(
+                WindowBuilder::new()
+                    .config(config)
+                    .on_activate(move |window| {
+                        let mut area = DockArea::with_factory(factory);
+                        area.origin = Some((origin, float_id));
+                        area.apply_group(None, &root);
+                        window.child(area);
+                    }),
+            );
+        emit!(self, layout_changed());
+    }
+
+    /// Dock the panel of floating window back to the origin area.
+    fn dock_back(&mut self, id: ObjectId) {
+        let Some((origin, _)) = self.origin else {
+            return;
+        };
+        let Some(key) = self.dock(id).map(|dock| dock.key().to_string()) else {
+            return;
+        };
+
+        self.window().call_response(move |window| {
+            if let Some(area) = area_of(window, origin) {
+                if let Some(dock) = area.create_dock(&key) {
+                    let group = area.groups.first().map(|g| g.id());
+                    area.insert_dock(dock, group, DockPosition::Center);
+                }
+            }
+        });
+        self.remove_dock_by_id(id);
+    }
+
+    /// Record the layout of floating window in the origin area,
+    /// `root` was `None` if the floating window was closed.
+    fn update_floating(&mut self, float_id: usize, root: Option<DockGroupLayout>, geometry: Rect) {
+        match root {
+            Some(root) => {
+                if let Some((_, floating)) =
+                    self.floatings.iter_mut().find(|(id, _)| *id == float_id)
+                {
+                    floating.root = root;
+                    floating.x = geometry.x();
+                    floating.y = geometry.y();
+                    floating.width = geometry.width();
+                    floating.height = geometry.height();
+                }
+            }
+            None => self.floatings.retain(|(id, _)| *id != float_id),
+        }
+        emit!(self, layout_changed());
+    }
+
+    /// Notify the origin area the changes of floating window,
+    /// the floating window will be closed if there was no dock.
+    fn sync_origin(&mut self) {
+        let Some((origin, float_id)) = self.origin else {
+            return;
+        };
+        let window = self.window();
+        if !window.initialized() {
+            return;
+        }
+
+        let root = self.root_layout();
+        let closing = root.is_none();
+        let position = window.outer_position();
+        let size = window.size();
+        let geometry = Rect::new(position.x(), position.y(), size.width(), size.height());
+        window.call_response(move |window| {
+            if let Some(area) = area_of(window, origin) {
+                area.update_floating(float_id, root, geometry);
+            }
+        });
+
+        if closing {
+            window.close();
+        }
+    }
+
+    #[inline]
+    fn layout_changed(&mut self) {
+        emit!(self, layout_changed());
+        self.sync_origin();
+    }
+
+    fn root_layout(&self) -> Option<DockGroupLayout> {
+        let mut split_pane = self.split_pane.clone();
+        let nodes: Vec<(ObjectId, Option<ObjectId>, SplitType)> = split_pane
+            .split_infos_vec()
+            .iter()
+            .map(|info| {
+                let info = nonnull_ref!(info);
+                let from = if info.split_from.is_some() {
+                    Some(nonnull_ref!(info.split_from).id)
+                } else {
+                    None
+                };
+                (info.id, from, info.ty)
+            })
+            .collect();
+
+        let (root, _, _) = nodes.iter().find(|(_, from, _)| from.is_none())?;
+        Some(self.group_layout(*root, &nodes))
+    }
+
+    fn group_layout(
+        &self,
+        id: ObjectId,
+        nodes: &[(ObjectId, Option<ObjectId>, SplitType)],
+    ) -> DockGroupLayout {
+        let (docks, current) = match self.group(id) {
+            Some(group) => (
+                docks_of(&group)
+                    .map(|dock| dock.key().to_string())
+                    .collect(),
+                group.current_index().unwrap_or_default(),
+            ),
+            None => (vec![], 0),
+        };
+        let splits = nodes
+            .iter()
+            .filter(|(_, from, _)| *from == Some(id))
+            .map(|(child, _, ty)| DockSplit {
+                position: DockPosition::from_split_type(*ty),
+                ratio: self
+                    .split_pane
+                    .split_ratio(*child)
+                    .unwrap_or(DEFAULT_SPLIT_RATIO),
+                group: self.group_layout(*child, nodes),
+            })
+            .collect();
+
+        DockGroupLayout {
+            docks,
+            current,
+            splits,
+        }
+    }
+
+    #[inline]
+    fn group(&self, id: ObjectId) -> Option<Tr<TabWidget>> {
+        self.groups.iter().find(|g| g.id() == id).cloned()
+    }
+
+    fn group_of_key(&self, key: &str) -> Option<ObjectId> {
+        let id = self.dock_id(key)?;
+        self.groups
+            .iter()
+            .find(|g| g.index_of(id).is_some())
+            .map(|g| g.id())
+    }
+
+    fn dock(&self, id: ObjectId) -> Option<&DockWidget> {
+        self.groups
+            .iter()
+            .flat_map(|group| docks_of(group))
+            .find(|dock| dock.id() == id)
+    }
+
+    fn dock_id(&self, key: &str) -> Option<ObjectId> {
+        self.groups
+            .iter()
+            .flat_map(|group| docks_of(group))
+            .find(|dock| dock.key() == key)
+            .map(|dock| dock.id())
+    }
+
+    /// Get the group which holds the dock whose title bar was the drag source.
+    fn group_of_source(&self, source: ObjectId) -> Option<&TabWidget> {
+        self.groups
+            .iter()
+            .find(|group| docks_of(group).any(|dock| dock.title_bar_id() == source))
+            .map(|group| group.bind())
+    }
+
+    fn on_close_requested(&mut self, id: ObjectId) {
+        let Some(key) = self.dock(id).map(|dock| dock.key().to_string()) else {
+            return;
+        };
+        if self.remove_dock_by_id(id) {
+            emit!(self, dock_closed(key));
+        }
+    }
+
+    #[inline]
+    fn on_float_requested(&mut self, id: ObjectId) {
+        if self.origin.is_some() {
+            self.dock_back(id)
+        } else {
+            self.float_dock_by_id(id)
+        }
+    }
+
+    #[inline]
+    fn on_dragged_away(&mut self, id: ObjectId) {
+        self.remove_dock_by_id(id);
+    }
+
+    #[inline]
+    fn on_size_changed(&mut self, _: Size) {
+        self.sync_origin()
+    }
+}
+
+impl DockArea {
+    /// Get the drop zone under the position in `window coordinate`.
+    fn drop_zone_at(&self, pos: Point) -> Option<(Option<ObjectId>, DockPosition)> {
+        if self.groups.is_empty() {
+            return Some((None, DockPosition::Center));
+        }
+        let group = self
+            .groups
+            .iter()
+            .find(|group| group.visible() && group.rect().contains(&pos))?;
+
+        Some((Some(group.id()), drop_position(group.rect(), pos)))
+    }
+
+    fn update_drop_zone(&mut self, event: &DragEvent) -> DropAction {
+        if event.mime() != DOCK_MIME || self.factory.is_none() {
+            self.set_drop_zone(None);
+            return DropAction::Ignore;
+        }
+
+        let pos = self.map_to_global(&event.position());
+        let mut zone = self.drop_zone_at(pos);
+        // Dropping the dock onto its own group as a tab, or beside the group
+        // which only holds the dock, changes nothing.
+        if let (Some((Some(id), position)), Some(source)) =
+            (zone, self.group_of_source(event.source()))
+        {
+            if id == source.id() && (position == DockPosition::Center || source.len() == 1) {
+                zone = None;
+            }
+        }
+
+        self.set_drop_zone(zone);
+        if zone.is_some() {
+            DropAction::Move
+        } else {
+            DropAction::Ignore
+        }
+    }
+
+    fn set_drop_zone(&mut self, zone: Option<(Option<ObjectId>, DockPosition)>) {
+        if self.drop_zone == zone {
+            return;
+        }
+        self.drop_zone = zone;
+
+        let mut indicator = self.indicator.clone();
+        let Some((group, position)) = zone else {
+            if indicator.visible() {
+                indicator.hide();
+            }
+            return;
+        };
+
+        let rect = match group.and_then(|id| self.group(id)) {
+            Some(group) => group.rect(),
+            None => self.rect(),
+        };
+        let rect = zone_rect(rect, position);
+
+        if !indicator.initialized() {
+            indicator.set_supervisor(self);
+            ApplicationWindow::initialize_dynamic_component(indicator.as_widget_impl_mut(), true);
+        }
+        indicator.set_fixed_x(rect.x());
+        indicator.set_fixed_y(rect.y());
+        indicator.set_fixed_width(rect.width());
+        indicator.set_fixed_height(rect.height());
+        indicator.calc_relative_position();
+        if !indicator.visible() {
+            indicator.show();
+        }
+        self.window().layout_change(indicator.as_widget_impl_mut());
+    }
+}
+
+impl DropTarget for DockArea {
+    #[inline]
+    fn on_drag_enter(&mut self, event: &DragEvent) -> DropAction {
+        self.update_drop_zone(event)
+    }
+
+    #[inline]
+    fn on_drag_move(&mut self, event: &DragEvent, _: DropAction) -> DropAction {
+        self.update_drop_zone(event)
+    }
+
+    #[inline]
+    fn on_drag_leave(&mut self) {
+        self.set_drop_zone(None)
+    }
+
+    fn on_drop(&mut self, event: &DragEvent) -> bool {
+        let zone = self.drop_zone;
+        self.set_drop_zone(None);
+
+        let Some((group, position)) = zone else {
+            return false;
+        };
+        let Some(key) = dock_key(event.payload()) else {
+            return false;
+        };
+        let Some(dock) = self.create_dock(&key) else {
+            return false;
+        };
+        self.insert_dock(dock, group, position);
+        true
+    }
+}
+
+impl CloseHandler for DockArea {
+    /// Closing the floating window docks its panels back to the origin area.
+    fn handle(&mut self) {
+        let Some((origin, float_id)) = self.origin else {
+            return;
+        };
+        let keys = self.dock_keys();
+        if keys.is_empty() {
+            return;
+        }
+
+        self.window().call_response(move |window| {
+            if let Some(area) = area_of(window, origin) {
+                area.update_floating(float_id, None, Rect::default());
+                for key in keys {
+                    if let Some(dock) = area.create_dock(&key) {
+                        let group = area.groups.first().map(|g| g.id());
+                        area.insert_dock(dock, group, DockPosition::Center);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// The highlight of drop zone while dragging the dock.
+#[extends(Popup)]
+pub struct DockIndicator {}
+
+impl ObjectSubclass for DockIndicator {
+    const NAME: &'static str = "DockIndicator";
+}
+
+impl ObjectImpl for DockIndicator {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_background(INDICATOR_BACKGROUND);
+        self.set_borders(2., 2., 2., 2.);
+        self.set_border_color(INDICATOR_BORDER);
+    }
+}
+
+impl WidgetImpl for DockIndicator {}
+
+impl PopupImpl for DockIndicator {
+    #[inline]
+    fn hide_on_click(&self) -> bool {
+        false
+    }
+}
+
+#[inline]
+fn area_of(window: &mut ApplicationWindow, id: ObjectId) -> Option<&mut DockArea> {
+    window
+        .find_id_mut(id)
+        .and_then(|w| w.as_any_mut().downcast_mut::<DockArea>())
+}
+
+#[inline]
+fn docks_of(group: &TabWidget) -> impl Iterator<Item = &DockWidget> {
+    (0..group.len()).filter_map(|idx| {
+        group
+            .page(idx)
+            .and_then(|page| page.as_any().downcast_ref::<DockWidget>())
+    })
+}
+
+#[inline]
+fn dock_key(payload: &DragPayload) -> Option<String> {
+    payload
+        .bytes()
+        .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+}
+
+/// Get the drop position of point in the rect of group,
+/// the nearest edge zone was chosen, see [`EDGE_ZONE_RATIO`].
+fn drop_position(rect: Rect, pos: Point) -> DockPosition {
+    let fx = (pos.x() - rect.x()) as f32 / rect.width().max(1) as f32;
+    let fy = (pos.y() - rect.y()) as f32 / rect.height().max(1) as f32;
+
+    let (distance, position) = [
+        (fx, DockPosition::Left),
+        (1. - fx, DockPosition::Right),
+        (fy, DockPosition::Top),
+        (1. - fy, DockPosition::Bottom),
+    ]
+    .into_iter()
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .unwrap();
+
+    if distance < EDGE_ZONE_RATIO {
+        position
+    } else {
+        DockPosition::Center
+    }
+}
+
+/// Get the rect of drop zone, the half of group for edges, or the whole group for center.
+fn zone_rect(rect: Rect, position: DockPosition) -> Rect {
+    let (half_width, half_height) = (rect.width() / 2, rect.height() / 2);
+    match position {
+        DockPosition::Left => Rect::new(rect.x(), rect.y(), half_width, rect.height()),
+        DockPosition::Right => Rect::new(
+            rect.x() + rect.width() - half_width,
+            rect.y(),
+            half_width,
+            rect.height(),
+        ),
+        DockPosition::Top => Rect::new(rect.x(), rect.y(), rect.width(), half_height),
+        DockPosition::Bottom => Rect::new(
+            rect.x(),
+            rect.y() + rect.height() - half_height,
+            rect.width(),
+            half_height,
+        ),
+        DockPosition::Center => rect,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_position() {
+        let rect = Rect::new(100, 100, 200, 100);
+
+        assert_eq!(
+            drop_position(rect, Point::new(110, 150)),
+            DockPosition::Left
+        );
+        assert_eq!(
+            drop_position(rect, Point::new(290, 150)),
+            DockPosition::Right
+        );
+        assert_eq!(drop_position(rect, Point::new(200, 105)), DockPosition::Top);
+        assert_eq!(
+            drop_position(rect, Point::new(200, 195)),
+            DockPosition::Bottom
+        );
+        assert_eq!(
+            drop_position(rect, Point::new(200, 150)),
+            DockPosition::Center
+        );
+        // The nearest edge wins in the corner.
+        assert_eq!(
+            drop_position(rect, Point::new(104, 110)),
+            DockPosition::Left
+        );
+        assert_eq!(drop_position(rect, Point::new(130, 101)), DockPosition::Top);
+
+        assert_eq!(
+            zone_rect(rect, DockPosition::Right),
+            Rect::new(200, 100, 100, 100)
+        );
+        assert_eq!(
+            zone_rect(rect, DockPosition::Bottom),
+            Rect::new(100, 150, 200, 50)
+        );
+    }
+}
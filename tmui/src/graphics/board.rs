@@ -8,13 +8,18 @@ use crate::{
     primitive::{bitmap::Bitmap, frame::Frame},
     shared_widget::ReflectSharedWidgetImpl,
     skia_safe::Surface,
+    widget::ReflectWidgetImpl,
+};
+use std::{
+    cell::{Cell, RefCell},
+    ptr::NonNull,
+    sync::Arc,
 };
-use std::{cell::RefCell, ptr::NonNull, sync::Arc};
 use tipc::{
     parking_lot::RwLock,
     parking_lot::{lock_api::RwLockWriteGuard, RawRwLock},
 };
-use tlib::{nonnull_mut, nonnull_ref, prelude::*, ptr_ref};
+use tlib::{figure::Rect, nonnull_mut, nonnull_ref, prelude::*, ptr_ref};
 
 thread_local! {
     static NOTIFY_UPDATE: RefCell<bool> = const { RefCell::new(true) };
@@ -32,6 +37,8 @@ pub struct Board {
     backend: Box<dyn Backend>,
     surface: Surface,
     element_list: RefCell<Vec<Option<NonNull<dyn ElementImpl>>>>,
+    /// The elements were painted in logical pixels, scaled to the physical pixels of bitmap.
+    scale_factor: Cell<f32>,
 }
 
 impl Board {
@@ -44,6 +51,7 @@ impl Board {
             backend,
             surface,
             element_list: RefCell::new(vec![]),
+            scale_factor: Cell::new(1.),
         }
     }
 
@@ -72,6 +80,16 @@ impl Board {
         self.bitmap.read().height()
     }
 
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor.get()
+    }
+
+    #[inline]
+    pub(crate) fn set_scale_factor(&self, scale_factor: f32) {
+        self.scale_factor.set(scale_factor)
+    }
+
     #[inline]
     pub(crate) fn resize(&mut self) {
         // self.surface().flush_and_submit();
@@ -127,6 +145,14 @@ impl Board {
                     ptr_ref!(&bitmap_guard as *const RwLockWriteGuard<'_, RawRwLock, Bitmap>)
                         .ipc_write();
 
+                let scale_factor = self.scale_factor.get();
+                let canvas = self.surface.canvas();
+                canvas.reset_matrix();
+                canvas.scale((scale_factor, scale_factor));
+
+                // Only record the dirty rects on slave side of shared memory application.
+                let record_dirty = bitmap_guard.is_shared();
+
                 // The parent elements always at the begining of `element_list`.
                 // We should renderer the parent elements first.
                 for element in self.element_list.borrow_mut().iter_mut() {
@@ -149,6 +175,10 @@ impl Board {
                         element.after_renderer();
                         element.validate();
                         update = true;
+
+                        if record_dirty {
+                            bitmap_guard.add_shared_dirty_rect(dirty_rect(element, scale_factor));
+                        }
                     }
                 }
 
@@ -166,3 +196,18 @@ impl Board {
         })
     }
 }
+
+/// The area of bitmap effected by the element in physical pixels, includes the box shadow.
+#[inline]
+fn dirty_rect(element: &dyn ElementImpl, scale_factor: f32) -> Rect {
+    let rect = match cast!(element as WidgetImpl) {
+        Some(widget) => widget.visual_rect(),
+        None => element.rect_f(),
+    };
+
+    let left = (rect.x() * scale_factor).floor() as i32;
+    let top = (rect.y() * scale_factor).floor() as i32;
+    let right = ((rect.x() + rect.width()) * scale_factor).ceil() as i32;
+    let bottom = ((rect.y() + rect.height()) * scale_factor).ceil() as i32;
+    Rect::new(left, top, right - left, bottom - top)
+}
@@ -7,6 +7,7 @@ use crate::{
 };
 use ::tlib::{
     namespace::BlendMode,
+    object::{ObjectId, ObjectOperation},
     skia_safe::Vector,
     typedef::{
         SkiaBlendMode, SkiaClipOp, SkiaFont, SkiaImage, SkiaPaintStyle, SkiaPoint, SkiaRRect,
@@ -29,6 +30,8 @@ use tlib::{
 
 pub struct Painter<'a> {
     name: &'a str,
+    /// The id of widget painted by the painter.
+    widget_id: ObjectId,
     canvas: &'a Canvas,
     paint: Paint,
     font: Option<Font>,
@@ -47,6 +50,9 @@ pub struct Painter<'a> {
     y_offset: i32,
 
     transform: Matrix,
+    /// The transform of canvas when the painter was created, scales the logical pixels
+    /// to the physical pixels of the window.
+    base_transform: Matrix,
 
     paragraph: Option<Paragraph>,
     text_style: TextStyle,
@@ -66,6 +72,7 @@ impl<'a> Painter<'a> {
 
         Painter {
             name,
+            widget_id: widget.id(),
             canvas,
             paint,
             font: None,
@@ -80,6 +87,7 @@ impl<'a> Painter<'a> {
             x_offset: rect.x(),
             y_offset: rect.y(),
             transform: Matrix::new_identity(),
+            base_transform: canvas.local_to_device_as_3x3(),
             paragraph: None,
             text_style: TextStyle::new(),
             paragraph_style: ParagraphStyle::new(),
@@ -88,6 +96,12 @@ impl<'a> Painter<'a> {
         }
     }
 
+    /// Get the id of widget painted by the painter.
+    #[inline]
+    pub fn widget_id(&self) -> ObjectId {
+        self.widget_id
+    }
+
     #[inline]
     pub fn paint_ref(&self) -> &Paint {
         &self.paint
@@ -120,7 +134,8 @@ impl<'a> Painter<'a> {
         } else {
             self.transform = transform
         }
-        self.canvas.set_matrix(&self.transform.into());
+        self.canvas
+            .set_matrix(&(self.base_transform * self.transform).into());
     }
 
     /// Save the canvas status.
@@ -205,7 +220,7 @@ impl<'a> Painter<'a> {
 
     #[inline]
     pub fn reset_matrix(&self) {
-        self.canvas.reset_matrix();
+        self.canvas.set_matrix(&self.base_transform.into());
     }
 
     /// Set the antialiasing to true.
@@ -856,7 +871,15 @@ impl<'a> Painter<'a> {
     /// Clip the region to draw.
     #[inline]
     pub fn clip_region_global(&self, region: SkiaRegion, op: SkiaClipOp) {
-        self.canvas.clip_region(&region, Some(op));
+        if self.base_transform.is_identity() {
+            self.canvas.clip_region(&region, Some(op));
+        } else {
+            // The region was clipped in device space, so clip the boundary path of region
+            // in logical space instead.
+            let mut path = Path::new();
+            region.get_boundary_path(&mut path);
+            self.canvas.clip_path(&path, op, true);
+        }
     }
 
     /// Draw the path tho canvas.
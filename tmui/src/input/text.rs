@@ -7,6 +7,7 @@ use crate::{
     font::{FontCalculation, SkiaParagraphExt},
     input::{INPUT_DEFAULT_BORDER_COLOR, INPUT_DEFAULT_BORDER_RADIUS},
     input_ele_impl,
+    platform::ipc_clipboard,
     prelude::*,
     shortcut::ShortcutRegister,
     system::System,
@@ -431,47 +432,70 @@ pub trait TextExt: TextPropsAcquire + WidgetImpl + TextInnerExt {
         self.clear_selection();
     }
 
+    /// The text of os clipboard was pasted later when the clipboard was brokered by the
+    /// master, the ui thread of shared memory slave was not blocked.
     #[inline]
-    fn paste(&mut self) {
-        if let Some(cp) = System::clipboard().text(ClipboardLevel::Os) {
-            if !self.check_value(&cp) {
-                return;
+    fn paste(&mut self)
+    where
+        Self: Sized + StaticType + 'static,
+    {
+        if !ipc_clipboard::is_forwarded() {
+            if let Some(cp) = System::clipboard().text(ClipboardLevel::Os) {
+                self.paste_text(cp)
             }
-            self.save_revoke();
-
-            if self.has_selection() {
-                let (start, end) = self.selection_range();
-                self.value_remove_range(start, end);
+            return;
+        }
 
-                self.props_mut().cursor_index = start;
-                self.clear_selection();
+        let id = self.id();
+        System::clipboard().text_async(ClipboardLevel::Os, move |cp| {
+            let Some(cp) = cp else { return };
+            if let Some(w) = ApplicationWindow::window()
+                .find_id_mut(id)
+                .and_then(|w| w.downcast_mut::<Self>())
+            {
+                w.paste_text(cp)
             }
+        })
+    }
 
-            let mut cut = false;
-            {
-                let idx = self.map(self.props().cursor_index);
-                self.input_wrapper().value_mut().insert_str(idx, &cp);
+    fn paste_text(&mut self, cp: String) {
+        if !self.check_value(&cp) {
+            return;
+        }
+        self.save_revoke();
 
-                if let Some(max_length) = self.props().max_length {
-                    if self.value_chars_count() > max_length {
-                        let idx = self.map(max_length);
-                        self.input_wrapper().value_mut().replace_range(idx.., "");
-                        cut = true;
-                    }
+        if self.has_selection() {
+            let (start, end) = self.selection_range();
+            self.value_remove_range(start, end);
+
+            self.props_mut().cursor_index = start;
+            self.clear_selection();
+        }
+
+        let mut cut = false;
+        {
+            let idx = self.map(self.props().cursor_index);
+            self.input_wrapper().value_mut().insert_str(idx, &cp);
+
+            if let Some(max_length) = self.props().max_length {
+                if self.value_chars_count() > max_length {
+                    let idx = self.map(max_length);
+                    self.input_wrapper().value_mut().replace_range(idx.., "");
+                    cut = true;
                 }
             }
+        }
 
-            if cut {
-                self.props_mut().cursor_index = self.value_chars_count();
-            } else {
-                self.props_mut().cursor_index += cp.chars().count();
-            }
+        if cut {
+            self.props_mut().cursor_index = self.value_chars_count();
+        } else {
+            self.props_mut().cursor_index += cp.chars().count();
+        }
 
-            if !cp.is_empty() {
-                emit!(self, value_changed());
-            }
-            self.update();
+        if !cp.is_empty() {
+            emit!(self, value_changed());
         }
+        self.update();
     }
 
     #[inline]
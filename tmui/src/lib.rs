@@ -7,6 +7,8 @@ pub mod button;
 pub mod clipboard;
 pub mod container;
 pub mod cursor;
+pub mod dnd;
+pub mod dock;
 pub mod event_hints;
 pub mod font;
 pub mod graphics;
@@ -17,6 +19,7 @@ pub mod input;
 pub mod label;
 pub mod layout;
 pub mod loading;
+pub mod menu;
 pub mod opti;
 pub mod overlay;
 pub mod pane;
@@ -24,14 +27,20 @@ pub mod platform;
 pub mod popup;
 pub mod prelude;
 pub mod primitive;
+pub mod progress_bar;
 pub mod runtime;
+pub mod screen;
 pub mod scroll_area;
 pub mod scroll_bar;
 pub mod shared_widget;
 pub mod shortcut;
 pub mod split_pane;
 pub mod stack;
+pub mod status_bar;
 pub mod system;
+pub mod tab_bar;
+pub mod tab_widget;
+pub mod tool_bar;
 pub mod tooltip;
 pub mod vbox;
 pub mod views;
@@ -0,0 +1,106 @@
+use super::Menu;
+use crate::{prelude::*, widget::WidgetImpl};
+use nohash_hasher::IntMap;
+use std::cell::RefCell;
+use tlib::object::ObjectId;
+
+thread_local! {
+    static INSTANCE: RefCell<ContextMenuMgr> = RefCell::new(ContextMenuMgr::default());
+}
+
+/// Attach a [`Menu`] to the widget, the menu will popup at the cursor
+/// when the widget(or its descendant without context menu) was right-clicked.
+pub trait ContextMenu: WidgetImpl {
+    /// Set the context menu of widget, the previous one will be removed.
+    fn set_context_menu(&mut self, menu: Tr<Menu>) {
+        let old = ContextMenuMgr::with(|mgr| mgr.borrow_mut().menus.insert(self.id(), menu));
+
+        if let Some(old) = old {
+            let mut removed = vec![];
+            discard_menu(old, &mut removed);
+
+            let window = ApplicationWindow::window_of(self.window_id());
+            for menu in removed {
+                window._add_removed_widget(menu);
+            }
+        }
+    }
+
+    /// Detach the context menu from widget.
+    fn remove_context_menu(&mut self) -> Option<Tr<Menu>> {
+        ContextMenuMgr::with(|mgr| mgr.borrow_mut().menus.remove(&self.id()))
+    }
+
+    #[inline]
+    fn context_menu(&self) -> Option<Tr<Menu>> {
+        ContextMenuMgr::with(|mgr| mgr.borrow().menus.get(&self.id()).cloned())
+    }
+}
+
+impl<T: WidgetImpl> ContextMenu for T {}
+
+impl ContextMenu for dyn WidgetImpl {}
+
+#[derive(Default)]
+pub(crate) struct ContextMenuMgr {
+    menus: IntMap<ObjectId, Tr<Menu>>,
+}
+
+impl ContextMenuMgr {
+    #[inline]
+    pub(crate) fn with<F, R>(f: F) -> R
+    where
+        F: FnOnce(&RefCell<ContextMenuMgr>) -> R,
+    {
+        INSTANCE.with(f)
+    }
+
+    #[inline]
+    pub(crate) fn remove(&mut self, id: ObjectId) -> Option<Tr<Menu>> {
+        self.menus.remove(&id)
+    }
+}
+
+/// Popup the context menu of the right-clicked widget, or its nearest ancestor which has one.
+///
+/// point: `global coordinate` point needed.
+///
+/// @return true if the context menu was popuped.
+pub(crate) fn popup_context_menu(widget: &dyn WidgetImpl, point: Point) -> bool {
+    let mut found = None;
+    let mut current = Some(widget);
+    while let Some(w) = current {
+        if let Some(menu) = w.context_menu() {
+            found = Some((w.id(), menu));
+            break;
+        }
+        current = w.get_parent_ref();
+    }
+    let Some((owner, mut menu)) = found else {
+        return false;
+    };
+
+    if !menu.initialized() {
+        if let Some(owner) = ApplicationWindow::window_of(widget.window_id()).find_id_mut(owner) {
+            menu.set_supervisor(owner);
+        }
+    }
+    menu.popup(point);
+    true
+}
+
+/// Close the menu and collect it with its sub menus which should be removed from the window.
+pub(crate) fn discard_menu(mut menu: Tr<Menu>, removed: &mut Vec<DynTr>) {
+    if !menu.initialized() {
+        return;
+    }
+    if menu.visible() {
+        menu.hide();
+    }
+
+    let mut submenus = vec![];
+    menu.collect_submenus(&mut submenus);
+
+    removed.push(menu.into());
+    removed.extend(submenus.into_iter().map(DynTr::from));
+}
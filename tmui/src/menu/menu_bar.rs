@@ -0,0 +1,340 @@
+use super::{menu_item::parse_mnemonic, render_mnemonic, Menu, MenuSignals};
+use crate::{
+    font::FontCalculation,
+    graphics::painter::Painter,
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{RegionClear, WidgetImpl},
+};
+use tlib::{
+    connect, disconnect,
+    events::{KeyEvent, MouseEvent},
+    global_watch,
+    namespace::{KeyCode, KeyboardModifier, MouseButton},
+};
+
+const BAR_PADDING: i32 = 4;
+const TITLE_PADDING: i32 = 10;
+const TITLE_RADIUS: f32 = 3.;
+
+const HOVER_BACKGROUND: Color = Color::rgb(220, 220, 220);
+
+struct MenuBarEntry {
+    title: String,
+    mnemonic: Option<(usize, char)>,
+    menu: Tr<Menu>,
+}
+
+/// The horizontal bar of menu titles, clicking the title opens the menu below it.
+///
+/// The character after `&` in the title was the mnemonic, e.g. `"&File"`,
+/// press `Alt` with the mnemonic character opens the menu from keyboard,
+/// mnemonics were underlined when `Alt` was held.
+#[extends(Widget)]
+#[global_watch(KeyPressed, KeyReleased)]
+pub struct MenuBar {
+    entries: Vec<MenuBarEntry>,
+    hovered: Option<usize>,
+    /// The index of menu which was opened.
+    opened: Option<usize>,
+    show_mnemonic: bool,
+
+    #[derivative(Default(value = "Color::BLACK"))]
+    color: Color,
+}
+
+impl ObjectSubclass for MenuBar {
+    const NAME: &'static str = "MenuBar";
+}
+
+impl ObjectImpl for MenuBar {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_mouse_tracking(true);
+        self.set_hexpand(true);
+        self.calc_height();
+    }
+}
+
+impl WidgetImpl for MenuBar {
+    #[inline]
+    fn font_changed(&mut self) {
+        self.calc_height();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let contents = self.contents_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, contents);
+
+        let font_height = self.font().calc_font_dimension().1;
+
+        painter.save();
+        painter.save_pen();
+        painter.set_antialiasing(true);
+
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let rect: FRect = self.title_rect(idx).into();
+
+            if self.opened == Some(idx) || (self.opened.is_none() && self.hovered == Some(idx)) {
+                let r = TITLE_RADIUS;
+                painter.fill_round_rect(rect, (r, r, r, r), HOVER_BACKGROUND);
+            }
+
+            let origin = FPoint::new(
+                rect.x() + TITLE_PADDING as f32,
+                rect.y() + (rect.height() - font_height) / 2.,
+            );
+            painter.set_color(self.color);
+            painter.draw_paragraph(&entry.title, origin, 0., rect.width(), Some(1), false);
+            if self.show_mnemonic {
+                if let Some((pos, _)) = entry.mnemonic {
+                    render_mnemonic(
+                        painter,
+                        self.font(),
+                        &entry.title,
+                        pos,
+                        FPoint::new(origin.x(), origin.y() + font_height),
+                    );
+                }
+            }
+        }
+
+        painter.restore_pen();
+        painter.restore();
+    }
+
+    fn on_mouse_pressed(&mut self, event: &MouseEvent) {
+        if event.mouse_button() != MouseButton::LeftButton {
+            return;
+        }
+        if let Some(idx) = self.title_at(event.position().into()) {
+            self.open_menu(idx, false);
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseEvent) {
+        let idx = self.title_at(event.position().into());
+        if idx == self.hovered {
+            return;
+        }
+        self.hovered = idx;
+        self.update();
+
+        // Switch the opened menu when moving between titles.
+        if let (Some(idx), Some(_)) = (idx, self.opened) {
+            self.open_menu(idx, false);
+        }
+    }
+
+    fn on_mouse_leave(&mut self, _: &MouseEvent) {
+        if self.hovered.take().is_some() {
+            self.update();
+        }
+    }
+}
+
+impl GlobalWatchImpl for MenuBar {
+    fn on_global_key_pressed(&mut self, evt: &KeyEvent) -> bool {
+        if !self.visible() {
+            return false;
+        }
+
+        if evt.key_code() == KeyCode::KeyAlt {
+            if !self.show_mnemonic {
+                self.show_mnemonic = true;
+                self.update();
+            }
+            return false;
+        }
+        if !evt.modifier().has(KeyboardModifier::AltModifier) {
+            return false;
+        }
+
+        let mut name = evt.key_code().name().chars();
+        let (Some(c), None) = (name.next(), name.next()) else {
+            return false;
+        };
+        let Some(idx) = self
+            .entries
+            .iter()
+            .position(|entry| entry.mnemonic.map(|m| m.1) == Some(c))
+        else {
+            return false;
+        };
+
+        self.open_menu(idx, true);
+        true
+    }
+
+    fn on_global_key_released(&mut self, evt: &KeyEvent) -> bool {
+        if evt.key_code() == KeyCode::KeyAlt && self.show_mnemonic {
+            self.show_mnemonic = false;
+            self.update();
+        }
+        false
+    }
+}
+
+impl MenuBar {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// @return the index of added menu.
+    pub fn add_menu(&mut self, title: &str, menu: Tr<Menu>) -> usize {
+        let (title, mnemonic) = parse_mnemonic(title);
+        connect!(menu, about_to_hide(), self, menu_hidden());
+
+        self.entries.push(MenuBarEntry {
+            title,
+            mnemonic,
+            menu,
+        });
+        self.update();
+        self.entries.len() - 1
+    }
+
+    /// Remove the menu, the removed menu will be closed.
+    pub fn remove_menu(&mut self, idx: usize) -> Option<Tr<Menu>> {
+        if idx >= self.entries.len() {
+            return None;
+        }
+        if self.opened == Some(idx) {
+            self.close_menu();
+        }
+        self.hovered = None;
+
+        let mut menu = self.entries.remove(idx).menu;
+        disconnect!(menu, about_to_hide(), self, null);
+        menu.menu_bar = None;
+        self.update();
+        Some(menu)
+    }
+
+    #[inline]
+    pub fn menu(&self, idx: usize) -> Option<&Menu> {
+        self.entries.get(idx).map(|e| e.menu.bind())
+    }
+
+    #[inline]
+    pub fn menu_mut(&mut self, idx: usize) -> Option<&mut Menu> {
+        self.entries.get_mut(idx).map(|e| e.menu.bind_mut())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Set the color of titles.
+    #[inline]
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.update();
+    }
+
+    /// Open the menu below its title, `by_keyboard` is true: the first item of menu will be hovered.
+    pub fn open_menu(&mut self, idx: usize, by_keyboard: bool) {
+        let Some(mut menu) = self.entries.get(idx).map(|e| e.menu.clone()) else {
+            return;
+        };
+        if self.opened != Some(idx) {
+            self.close_menu();
+
+            menu.set_supervisor(self);
+            menu.parent_menu = None;
+            menu.menu_bar = Some(self.id());
+
+            let mut anchor = self.title_rect(idx);
+            let rect = self.rect();
+            anchor.offset(rect.x(), rect.y());
+            menu.popup_beside(anchor, false);
+
+            self.opened = Some(idx);
+            self.update();
+        }
+
+        if by_keyboard {
+            menu.hover_first();
+        }
+    }
+
+    /// Close the opened menu.
+    pub fn close_menu(&mut self) {
+        if let Some(idx) = self.opened.take() {
+            self.entries[idx].menu.hide();
+            self.update();
+        }
+    }
+}
+
+impl MenuBar {
+    /// Open the next(or previous) menu from keyboard.
+    pub(crate) fn open_adjacent(&mut self, forward: bool) {
+        let len = self.entries.len();
+        let Some(opened) = self.opened else {
+            return;
+        };
+        if len < 2 {
+            return;
+        }
+
+        let idx = if forward {
+            (opened + 1) % len
+        } else {
+            (opened + len - 1) % len
+        };
+        self.open_menu(idx, true);
+    }
+
+    fn menu_hidden(&mut self) {
+        if let Some(idx) = self.opened {
+            if !self.entries[idx].menu.visible() {
+                self.opened = None;
+                self.update();
+            }
+        }
+    }
+
+    /// Get the rectangle of title in widget coordinate.
+    fn title_rect(&self, idx: usize) -> Rect {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        let mut x = contents.x();
+        for entry in self.entries.iter().take(idx) {
+            x += self.title_width(entry);
+        }
+        let width = self.entries.get(idx).map_or(0, |e| self.title_width(e));
+
+        Rect::new(x, contents.y(), width, contents.height())
+    }
+
+    /// Get the index of title at the position in widget coordinate.
+    fn title_at(&self, pos: Point) -> Option<usize> {
+        (0..self.entries.len()).find(|&idx| self.title_rect(idx).contains(&pos))
+    }
+
+    #[inline]
+    fn title_width(&self, entry: &MenuBarEntry) -> i32 {
+        self.font().calc_text_dimension(&entry.title, 0.).0.ceil() as i32 + TITLE_PADDING * 2
+    }
+
+    fn calc_height(&mut self) {
+        let font_height = self.font().calc_font_dimension().1.ceil() as i32;
+        let (top, _, bottom, _) = self.paddings();
+        let (tb, _, bb, _) = self.borders();
+
+        self.height_request(font_height + BAR_PADDING * 2 + top + bottom + (tb + bb).ceil() as i32);
+    }
+}
@@ -0,0 +1,294 @@
+use super::Menu;
+use crate::{
+    icons::svg_dom::SvgDom,
+    prelude::*,
+    shortcut::{keymap::keys_for_action, Shortcut},
+};
+
+/// The entry of [`Menu`], which can be a normal action item, a checkable item,
+/// a radio item, a separator, or an item which opens the cascading sub menu.
+///
+/// The character after `&` in the text was the mnemonic of item, e.g. `"&Open"`,
+/// use `"&&"` to display a literal `&`.
+pub struct MenuItem {
+    text: String,
+    mnemonic: Option<(usize, char)>,
+    icon: Option<SvgDom>,
+    shortcut: Option<Shortcut>,
+    action: Option<String>,
+    checkable: bool,
+    checked: bool,
+    radio_group: Option<String>,
+    enabled: bool,
+    separator: bool,
+    pub(crate) submenu: Option<Tr<Menu>>,
+}
+
+impl MenuItem {
+    #[inline]
+    pub fn builder() -> MenuItemBuilder {
+        MenuItemBuilder::default()
+    }
+
+    #[inline]
+    pub fn new(text: &str) -> Self {
+        Self::builder().text(text).build()
+    }
+
+    #[inline]
+    pub fn separator() -> Self {
+        let mut item = Self::builder().build();
+        item.separator = true;
+        item
+    }
+
+    /// The item which opens the `submenu` when hovered or activated.
+    #[inline]
+    pub fn submenu(text: &str, submenu: Tr<Menu>) -> Self {
+        Self::builder().text(text).submenu(submenu).build()
+    }
+
+    /// The displayed text, without the mnemonic marker `&`.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[inline]
+    pub fn set_text(&mut self, text: &str) {
+        let (text, mnemonic) = parse_mnemonic(text);
+        self.text = text;
+        self.mnemonic = mnemonic;
+    }
+
+    /// Get the mnemonic character (in uppercase) and its char index in the displayed text.
+    #[inline]
+    pub fn mnemonic(&self) -> Option<(usize, char)> {
+        self.mnemonic
+    }
+
+    #[inline]
+    pub fn icon(&self) -> Option<&SvgDom> {
+        self.icon.as_ref()
+    }
+
+    #[inline]
+    pub fn set_icon(&mut self, icon: Option<SvgDom>) {
+        self.icon = icon
+    }
+
+    /// The shortcut displayed as hint, if it was not specified,
+    /// the first key binding of the item's action in keymap will be displayed.
+    #[inline]
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        self.shortcut.clone().or_else(|| {
+            self.action
+                .as_ref()
+                .and_then(|action| keys_for_action(action).into_iter().next())
+        })
+    }
+
+    #[inline]
+    pub fn set_shortcut(&mut self, shortcut: Option<Shortcut>) {
+        self.shortcut = shortcut
+    }
+
+    /// The name of action which will be triggered when the item was activated,
+    /// see [`ShortcutRegister::register_action`](crate::shortcut::ShortcutRegister::register_action).
+    #[inline]
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    #[inline]
+    pub fn is_checkable(&self) -> bool {
+        self.checkable || self.radio_group.is_some()
+    }
+
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    #[inline]
+    pub fn radio_group(&self) -> Option<&str> {
+        self.radio_group.as_deref()
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub fn is_separator(&self) -> bool {
+        self.separator
+    }
+
+    #[inline]
+    pub fn get_submenu(&self) -> Option<&Menu> {
+        self.submenu.as_deref()
+    }
+
+    #[inline]
+    pub fn get_submenu_mut(&mut self) -> Option<&mut Menu> {
+        self.submenu.as_deref_mut()
+    }
+
+    /// Whether the item can be hovered and activated.
+    #[inline]
+    pub(crate) fn is_selectable(&self) -> bool {
+        !self.separator && self.enabled
+    }
+
+    #[inline]
+    pub(crate) fn set_checked(&mut self, checked: bool) {
+        self.checked = checked
+    }
+
+    #[inline]
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled
+    }
+}
+
+#[derive(Default)]
+pub struct MenuItemBuilder {
+    text: String,
+    icon: Option<SvgDom>,
+    shortcut: Option<Shortcut>,
+    action: Option<String>,
+    checkable: bool,
+    checked: bool,
+    radio_group: Option<String>,
+    disabled: bool,
+    submenu: Option<Tr<Menu>>,
+}
+
+impl MenuItemBuilder {
+    #[inline]
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    #[inline]
+    pub fn icon(mut self, icon: SvgDom) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    #[inline]
+    pub fn shortcut(mut self, shortcut: Shortcut) -> Self {
+        self.shortcut = Some(shortcut);
+        self
+    }
+
+    #[inline]
+    pub fn action(mut self, action: &str) -> Self {
+        self.action = Some(action.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn checkable(mut self, checkable: bool) -> Self {
+        self.checkable = checkable;
+        self
+    }
+
+    #[inline]
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Only one item of the same radio group in a menu can be checked,
+    /// the radio item was always checkable.
+    #[inline]
+    pub fn radio_group(mut self, group: &str) -> Self {
+        self.radio_group = Some(group.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    #[inline]
+    pub fn submenu(mut self, submenu: Tr<Menu>) -> Self {
+        self.submenu = Some(submenu);
+        self
+    }
+
+    pub fn build(self) -> MenuItem {
+        let (text, mnemonic) = parse_mnemonic(&self.text);
+
+        MenuItem {
+            text,
+            mnemonic,
+            icon: self.icon,
+            shortcut: self.shortcut,
+            action: self.action,
+            checkable: self.checkable,
+            checked: self.checked,
+            radio_group: self.radio_group,
+            enabled: !self.disabled,
+            separator: false,
+            submenu: self.submenu,
+        }
+    }
+}
+
+/// Remove the mnemonic markers from text.
+///
+/// @return (the displayed text, the char index and uppercase of the first mnemonic character)
+pub(crate) fn parse_mnemonic(text: &str) -> (String, Option<(usize, char)>) {
+    let mut displayed = String::with_capacity(text.len());
+    let mut mnemonic = None;
+    let mut count = 0;
+
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            match chars.next() {
+                Some(next) => {
+                    if next != '&' && mnemonic.is_none() {
+                        mnemonic = Some((count, next.to_ascii_uppercase()));
+                    }
+                    displayed.push(next);
+                }
+                None => displayed.push(c),
+            }
+        } else {
+            displayed.push(c);
+        }
+        count += 1;
+    }
+
+    (displayed, mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mnemonic;
+
+    #[test]
+    fn test_parse_mnemonic() {
+        assert_eq!(
+            parse_mnemonic("&File"),
+            ("File".to_string(), Some((0, 'F')))
+        );
+        assert_eq!(
+            parse_mnemonic("Save &as..."),
+            ("Save as...".to_string(), Some((5, 'A')))
+        );
+        assert_eq!(
+            parse_mnemonic("Copy && &Paste"),
+            ("Copy & Paste".to_string(), Some((7, 'P')))
+        );
+        assert_eq!(parse_mnemonic("Tail&"), ("Tail&".to_string(), None));
+        assert_eq!(parse_mnemonic("Plain"), ("Plain".to_string(), None));
+    }
+}
@@ -0,0 +1,961 @@
+pub mod context_menu;
+pub mod menu_bar;
+pub mod menu_item;
+
+use crate::{
+    font::FontCalculation,
+    graphics::{box_shadow::BoxShadow, painter::Painter},
+    prelude::*,
+    shortcut::mgr::ShortcutMgr,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{widget_ext::FocusStrat, RegionClear, WidgetImpl},
+};
+use menu_bar::MenuBar;
+use menu_item::MenuItem;
+use std::{cell::Cell, time::Duration};
+use tlib::{
+    connect, emit,
+    events::{KeyEvent, MouseEvent},
+    namespace::{KeyCode, MouseButton},
+    object::ObjectId,
+    signals,
+    timer::Timer,
+};
+
+/// The delay of opening(or closing) the sub menu when the mouse hovers on the item.
+pub const SUBMENU_DELAY: Duration = Duration::from_millis(250);
+
+const MENU_PADDING: i32 = 4;
+const MENU_MIN_WIDTH: i32 = 120;
+const ITEM_PADDING: i32 = 4;
+const ITEM_RADIUS: f32 = 3.;
+const SEPARATOR_HEIGHT: i32 = 7;
+/// The width of the check mark column and the icon column.
+const COLUMN_WIDTH: i32 = 22;
+const ICON_SIZE: i32 = 16;
+const TEXT_PADDING: i32 = 8;
+const SHORTCUT_SPACING: i32 = 24;
+const ARROW_WIDTH: i32 = 16;
+
+const HOVER_BACKGROUND: Color = Color::rgb(220, 220, 220);
+const HINT_COLOR: Color = Color::GREY_DARK;
+const DISABLED_COLOR: Color = Color::GREY_MEDIUM;
+const SEPARATOR_COLOR: Color = Color::GREY_LIGHT;
+
+thread_local! {
+    /// The root menu which was popuped currently, only one root menu can be opened at the same time.
+    static ACTIVE_MENU: Cell<ObjectId> = Cell::new(0);
+}
+
+/// The popup menu contains a list of [`MenuItem`].
+///
+/// The item with sub menu opens the cascading menu when it was hovered for [`SUBMENU_DELAY`],
+/// or activated by `Right`/`Enter` key. Use `Up`/`Down` to move the hovered item, `Left`/`Escape`
+/// to close the current sub menu, the mnemonic character of item activates it directly.
+///
+/// When an item was activated, the whole menu chain will be closed, [`triggered`](MenuSignals::triggered)
+/// will be emitted, and the action of the item will be triggered on the widget which owned the focus before
+/// the menu popuped, see [`ShortcutRegister::register_action`](crate::shortcut::ShortcutRegister::register_action).
+///
+/// Notice: menus are always rendered inside the window, the `win_popup` mode was not supported.
+#[extends(Popup)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+    hovered: Option<usize>,
+    /// The index of item whose sub menu was opened.
+    opened: Option<usize>,
+    /// The index of hovered item when the hover timer started.
+    pending: Option<usize>,
+    hover_timer: Box<Timer>,
+
+    /// The id of parent menu if this was a sub menu.
+    parent_menu: Option<ObjectId>,
+    /// The id of menu bar which opened this menu.
+    menu_bar: Option<ObjectId>,
+    focus_taken: bool,
+
+    #[derivative(Default(value = "Color::BLACK"))]
+    color: Color,
+    item_height: i32,
+    menu_size: Size,
+}
+
+impl ObjectSubclass for Menu {
+    const NAME: &'static str = "Menu";
+}
+
+impl ObjectImpl for Menu {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_mouse_tracking(true);
+        self.set_hide_on_win_change(true);
+        self.set_background(Color::WHITE);
+        self.set_paddings(MENU_PADDING, MENU_PADDING, MENU_PADDING, MENU_PADDING);
+        self.set_borders(1., 1., 1., 1.);
+        self.set_border_color(Color::GREY_LIGHT);
+        self.set_box_shadow(BoxShadow::new(6., Color::BLACK, None, None, None, None));
+
+        self.hover_timer.set_single_shot(true);
+        connect!(self.hover_timer, timeout(), self, hover_timeout());
+
+        self.calc_size();
+    }
+}
+
+pub trait MenuSignals: ActionExt {
+    signals!(
+        MenuSignals:
+
+        /// Emitted when the item of menu was activated.
+        ///
+        /// @param [`usize`]: the index of item.
+        triggered(usize);
+
+        /// Emitted when the checked state of checkable item was changed.
+        ///
+        /// @param [`usize`]: the index of item.
+        /// @param [`bool`]: the new checked state.
+        toggled(usize, bool);
+
+        /// Emitted before the menu shows, the items can be updated in the slot.
+        about_to_show();
+
+        /// Emitted when the menu was being hidden.
+        about_to_hide();
+    );
+}
+impl MenuSignals for Menu {}
+
+impl WidgetImpl for Menu {
+    #[inline]
+    fn enable_focus(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn font_changed(&mut self) {
+        self.calc_size();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let contents = self.contents_rect_f(Some(Coordinate::Widget));
+        RegionClear::clear(self, painter, contents);
+
+        let (check_column, icon_column) = self.leading_columns();
+        let font_height = self.font().calc_font_dimension().1;
+
+        painter.save();
+        painter.save_pen();
+        painter.set_antialiasing(true);
+
+        for (idx, item) in self.items.iter().enumerate() {
+            let rect: FRect = self.item_rect(idx).into();
+
+            if item.is_separator() {
+                let y = rect.y() + rect.height() / 2.;
+                painter.set_line_width(1.);
+                painter.set_color(SEPARATOR_COLOR);
+                painter.draw_line_f(
+                    rect.x() + TEXT_PADDING as f32,
+                    y,
+                    rect.right() - TEXT_PADDING as f32,
+                    y,
+                );
+                continue;
+            }
+
+            if self.hovered == Some(idx) && item.is_enabled() {
+                let r = ITEM_RADIUS;
+                painter.fill_round_rect(rect, (r, r, r, r), HOVER_BACKGROUND);
+            }
+            let color = if item.is_enabled() {
+                self.color
+            } else {
+                DISABLED_COLOR
+            };
+
+            let mut x = rect.x();
+            if check_column {
+                if item.is_checked() {
+                    let column = FRect::new(x, rect.y(), COLUMN_WIDTH as f32, rect.height());
+                    if item.radio_group().is_some() {
+                        render_radio(painter, column, color);
+                    } else {
+                        render_check(painter, column, color);
+                    }
+                }
+                x += COLUMN_WIDTH as f32;
+            }
+            if icon_column {
+                if let Some(icon) = item.icon() {
+                    let size = icon.get_size();
+                    painter.save();
+                    painter.translate(
+                        x + (COLUMN_WIDTH - size.width()) as f32 / 2.,
+                        rect.y() + (rect.height() - size.height() as f32) / 2.,
+                    );
+                    painter.draw_dom(icon);
+                    painter.restore();
+                }
+                x += COLUMN_WIDTH as f32;
+            }
+            x += TEXT_PADDING as f32;
+
+            let text_y = rect.y() + (rect.height() - font_height) / 2.;
+            let text_width = rect.right() - x - ARROW_WIDTH as f32;
+            painter.set_color(color);
+            painter.draw_paragraph(
+                item.text(),
+                FPoint::new(x, text_y),
+                0.,
+                text_width,
+                Some(1),
+                true,
+            );
+            if let Some((pos, _)) = item.mnemonic() {
+                render_mnemonic(
+                    painter,
+                    self.font(),
+                    item.text(),
+                    pos,
+                    FPoint::new(x, text_y + font_height),
+                );
+            }
+
+            if let Some(shortcut) = item.shortcut() {
+                let hint = shortcut.to_string();
+                let hint_width = self.font().calc_text_dimension(&hint, 0.).0;
+                let hint_x = rect.right() - ARROW_WIDTH as f32 - hint_width;
+                painter.set_color(if item.is_enabled() {
+                    HINT_COLOR
+                } else {
+                    DISABLED_COLOR
+                });
+                painter.draw_paragraph(
+                    &hint,
+                    FPoint::new(hint_x, text_y),
+                    0.,
+                    hint_width + 1.,
+                    Some(1),
+                    false,
+                );
+            }
+
+            if item.get_submenu().is_some() {
+                let arrow = FRect::new(
+                    rect.right() - ARROW_WIDTH as f32,
+                    rect.y(),
+                    ARROW_WIDTH as f32,
+                    rect.height(),
+                );
+                render_arrow(painter, arrow, color);
+            }
+        }
+
+        painter.restore_pen();
+        painter.restore();
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseEvent) {
+        // The menu under the mouse becomes the active one which receives the key events.
+        if !self.is_focus() {
+            self.set_focus(true);
+        }
+
+        let idx = self
+            .item_at(event.position().into())
+            .filter(|&idx| self.items[idx].is_selectable());
+        if idx.is_none() || idx == self.hovered {
+            return;
+        }
+        self.hovered = idx;
+        self.update();
+
+        if idx == self.opened {
+            self.hover_timer.stop();
+            self.pending = None;
+        } else {
+            self.pending = idx;
+            self.hover_timer.start(SUBMENU_DELAY);
+        }
+    }
+
+    fn on_mouse_leave(&mut self, _: &MouseEvent) {
+        self.hover_timer.stop();
+        self.pending = None;
+        if self.hovered != self.opened {
+            self.hovered = self.opened;
+            self.update();
+        }
+    }
+
+    fn on_mouse_released(&mut self, event: &MouseEvent) {
+        if event.mouse_button() != MouseButton::LeftButton {
+            return;
+        }
+        if let Some(idx) = self.item_at(event.position().into()) {
+            self.activate(idx);
+        }
+    }
+
+    fn on_key_pressed(&mut self, event: &KeyEvent) {
+        match event.key_code() {
+            KeyCode::KeyUp => self.hover_next(false),
+            KeyCode::KeyDown => self.hover_next(true),
+            KeyCode::KeyRight => {
+                let submenu = self
+                    .hovered
+                    .filter(|&idx| self.items[idx].get_submenu().is_some());
+                if let Some(idx) = submenu {
+                    self.open_submenu(idx, true);
+                } else if let Some(menu_bar) = self.root_menu_bar() {
+                    menu_bar.open_adjacent(true);
+                }
+            }
+            KeyCode::KeyLeft => {
+                if let Some(parent) = self.parent_menu_mut() {
+                    parent.close_submenu();
+                } else if let Some(menu_bar) = self.root_menu_bar() {
+                    menu_bar.open_adjacent(false);
+                }
+            }
+            KeyCode::KeyEnter | KeyCode::KeyReturn | KeyCode::KeySpace => {
+                if let Some(idx) = self.hovered {
+                    self.activate(idx);
+                }
+            }
+            KeyCode::KeyEscape => {
+                if let Some(parent) = self.parent_menu_mut() {
+                    parent.close_submenu();
+                } else {
+                    self.hide();
+                }
+            }
+            key_code => {
+                if let Some(idx) = self.mnemonic_item(key_code) {
+                    self.hovered = Some(idx);
+                    self.activate(idx);
+                }
+            }
+        }
+    }
+
+    fn on_visibility_changed(&mut self, visible: bool) {
+        if visible || !self.initialized() {
+            return;
+        }
+
+        self.hover_timer.stop();
+        self.pending = None;
+        self.hovered = None;
+        self.close_submenu();
+
+        let is_focus = self.is_focus();
+        if let Some(parent) = self.parent_menu_mut() {
+            if is_focus {
+                parent.set_focus(true);
+            }
+        } else {
+            let id = self.id();
+            ACTIVE_MENU.with(|active| {
+                if active.get() == id {
+                    active.set(0)
+                }
+            });
+
+            if self.focus_taken {
+                self.focus_taken = false;
+                self.take_over_focus(FocusStrat::Restore);
+            }
+        }
+
+        emit!(self, about_to_hide());
+    }
+}
+
+impl PopupImpl for Menu {
+    #[inline]
+    fn calculate_position(&self, _: Rect, point: Point) -> Point {
+        self.fit_in_window(point)
+    }
+
+    /// Only the root menu handles the clicking, which closes the whole menu chain
+    /// when clicking outside all of the opened menus.
+    fn handle_global_mouse_pressed(&mut self, evt: &MouseEvent) -> bool {
+        if !self.visible() || self.parent_menu.is_some() {
+            return false;
+        }
+        let pos: Point = evt.position().into();
+        if self.chain_contains(&pos) {
+            return false;
+        }
+        self.hide();
+        true
+    }
+}
+
+impl Menu {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// @return the index of added item.
+    #[inline]
+    pub fn add_item(&mut self, item: MenuItem) -> usize {
+        self.insert_item(self.items.len(), item)
+    }
+
+    #[inline]
+    pub fn add_separator(&mut self) {
+        self.add_item(MenuItem::separator());
+    }
+
+    /// @return the index of added item.
+    #[inline]
+    pub fn add_submenu(&mut self, text: &str, submenu: Tr<Menu>) -> usize {
+        self.add_item(MenuItem::submenu(text, submenu))
+    }
+
+    /// @return the index of inserted item.
+    pub fn insert_item(&mut self, idx: usize, mut item: MenuItem) -> usize {
+        let idx = idx.min(self.items.len());
+        if let Some(submenu) = item.submenu.as_mut() {
+            submenu.parent_menu = Some(self.id());
+        }
+
+        self.close_submenu();
+        self.hovered = None;
+        self.items.insert(idx, item);
+        self.calc_size();
+        idx
+    }
+
+    /// Remove the item, the sub menu of item will be detached from this menu.
+    pub fn remove_item(&mut self, idx: usize) -> Option<MenuItem> {
+        if idx >= self.items.len() {
+            return None;
+        }
+        self.close_submenu();
+        self.hovered = None;
+
+        let mut item = self.items.remove(idx);
+        if let Some(submenu) = item.submenu.as_mut() {
+            submenu.parent_menu = None;
+        }
+        self.calc_size();
+        Some(item)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        while !self.items.is_empty() {
+            self.remove_item(self.items.len() - 1);
+        }
+    }
+
+    #[inline]
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    #[inline]
+    pub fn item(&self, idx: usize) -> Option<&MenuItem> {
+        self.items.get(idx)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    #[inline]
+    pub fn set_item_text(&mut self, idx: usize, text: &str) {
+        if let Some(item) = self.items.get_mut(idx) {
+            item.set_text(text);
+            self.calc_size();
+        }
+    }
+
+    #[inline]
+    pub fn set_item_enabled(&mut self, idx: usize, enabled: bool) {
+        if let Some(item) = self.items.get_mut(idx) {
+            item.set_enabled(enabled);
+            if !enabled && self.hovered == Some(idx) {
+                self.hovered = None;
+            }
+            self.update();
+        }
+    }
+
+    /// Set the checked state of checkable item, checking a radio item unchecks
+    /// the other items in the same radio group.
+    pub fn set_item_checked(&mut self, idx: usize, checked: bool) {
+        let Some(item) = self.items.get(idx) else {
+            return;
+        };
+        if !item.is_checkable() || item.is_checked() == checked {
+            return;
+        }
+
+        if checked {
+            if let Some(group) = item.radio_group().map(|g| g.to_string()) {
+                let others: Vec<usize> = self
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, item)| {
+                        *i != idx && item.is_checked() && item.radio_group() == Some(&group)
+                    })
+                    .map(|(i, _)| i)
+                    .collect();
+
+                for other in others {
+                    self.items[other].set_checked(false);
+                    emit!(self, toggled(other, false));
+                }
+            }
+        }
+
+        self.items[idx].set_checked(checked);
+        emit!(self, toggled(idx, checked));
+        self.update();
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Set the color of items' text.
+    #[inline]
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.update();
+    }
+
+    /// Whether the menu was the cascading menu of another menu.
+    #[inline]
+    pub fn is_submenu(&self) -> bool {
+        self.parent_menu.is_some()
+    }
+
+    /// Popup the menu at the position, the menu will be moved to fit in the window.
+    ///
+    /// The menu popuped before will be closed.
+    ///
+    /// point: `global coordinate` point needed.
+    pub fn popup(&mut self, point: Point) {
+        self.menu_bar = None;
+        self.prepare();
+        let point = self.fit_in_window(point);
+        self.show_at(point);
+        self.activate_root();
+    }
+
+    /// Close the whole menu chain which contains this menu.
+    pub fn close(&mut self) {
+        if let Some(parent) = self.parent_menu_mut() {
+            parent.close();
+        } else {
+            self.hide();
+        }
+    }
+}
+
+impl Menu {
+    /// Popup the menu beside the `anchor` rectangle in global coordinate,
+    /// `horizontal` is true: on the right(or left) side, otherwise below(or above) it.
+    pub(crate) fn popup_beside(&mut self, anchor: Rect, horizontal: bool) {
+        self.prepare();
+
+        let win_size = self.window().size();
+        let size = self.menu_size;
+        let point = if horizontal {
+            let mut x = anchor.x() + anchor.width();
+            if x + size.width() > win_size.width() {
+                x = anchor.x() - size.width();
+            }
+            let (top, _, _, _) = self.paddings();
+            Point::new(x, anchor.y() - top - self.borders().0.ceil() as i32)
+        } else {
+            let mut y = anchor.y() + anchor.height();
+            if y + size.height() > win_size.height() && anchor.y() >= size.height() {
+                y = anchor.y() - size.height();
+            }
+            Point::new(anchor.x(), y)
+        };
+        let point = self.fit_in_window(point);
+        self.show_at(point);
+
+        if self.parent_menu.is_none() {
+            self.activate_root();
+        }
+    }
+
+    /// Move the hovered item to the first selectable item.
+    pub(crate) fn hover_first(&mut self) {
+        self.hovered = None;
+        self.hover_next(true);
+    }
+
+    pub(crate) fn close_submenu(&mut self) {
+        if let Some(idx) = self.opened.take() {
+            if let Some(mut submenu) = self.items.get(idx).and_then(|i| i.submenu.clone()) {
+                submenu.hide();
+            }
+            self.update();
+        }
+    }
+
+    /// Collect all the initialized sub menus recursively.
+    pub(crate) fn collect_submenus(&self, menus: &mut Vec<Tr<Menu>>) {
+        for submenu in self.items.iter().filter_map(|i| i.submenu.as_ref()) {
+            if submenu.initialized() {
+                menus.push(submenu.clone());
+            }
+            submenu.collect_submenus(menus);
+        }
+    }
+
+    fn prepare(&mut self) {
+        if !self.initialized() {
+            ApplicationWindow::initialize_dynamic_component(self, true);
+        }
+        emit!(self, about_to_show());
+        self.calc_size();
+    }
+
+    fn show_at(&mut self, point: Point) {
+        self.set_fixed_x(point.x());
+        self.set_fixed_y(point.y());
+        self.calc_relative_position();
+
+        if self.visible() {
+            self.hovered = None;
+            self.close_submenu();
+        }
+        self.show();
+        self.window().layout_change(self);
+    }
+
+    /// Close the other root menu and take over the focus.
+    fn activate_root(&mut self) {
+        let id = self.id();
+        let active = ACTIVE_MENU.with(|active| active.replace(id));
+        if active != 0 && active != id {
+            if let Some(menu) = self
+                .window()
+                .find_id_mut(active)
+                .and_then(|w| w.downcast_mut::<Menu>())
+            {
+                menu.hide();
+            }
+        }
+
+        if !self.focus_taken {
+            self.focus_taken = true;
+            self.take_over_focus(FocusStrat::TakeOver);
+        }
+    }
+
+    fn activate(&mut self, idx: usize) {
+        let Some(item) = self.items.get(idx) else {
+            return;
+        };
+        if !item.is_selectable() {
+            return;
+        }
+        if item.get_submenu().is_some() {
+            self.open_submenu(idx, true);
+            return;
+        }
+
+        if item.is_checkable() {
+            let checked = item.radio_group().is_some() || !item.is_checked();
+            self.set_item_checked(idx, checked);
+        }
+        let action = self.items[idx].action().map(|a| a.to_string());
+
+        // Close the menus first, so the focus was restored to the origin widget.
+        self.close();
+        emit!(self, triggered(idx));
+
+        if let Some(action) = action {
+            let focused = self.window().focused_widget();
+            ShortcutMgr::with(|mgr| mgr.borrow_mut().trigger_action(&action, focused));
+        }
+    }
+
+    fn open_submenu(&mut self, idx: usize, by_keyboard: bool) {
+        let Some(mut submenu) = self.items.get(idx).and_then(|i| i.submenu.clone()) else {
+            return;
+        };
+        if submenu.is_empty() {
+            return;
+        }
+
+        if self.opened != Some(idx) {
+            self.close_submenu();
+
+            submenu.set_supervisor(self);
+            submenu.parent_menu = Some(self.id());
+            submenu.menu_bar = None;
+
+            self.opened = Some(idx);
+            self.hovered = Some(idx);
+            self.update();
+
+            let mut anchor = self.item_rect(idx);
+            let rect = self.rect();
+            anchor.offset(rect.x(), rect.y());
+            submenu.popup_beside(anchor, true);
+        }
+
+        if by_keyboard {
+            submenu.set_focus(true);
+            submenu.hover_first();
+        }
+    }
+
+    fn hover_timeout(&mut self) {
+        let Some(idx) = self.pending.take() else {
+            return;
+        };
+        if self.hovered != Some(idx) {
+            return;
+        }
+
+        if self.items[idx].get_submenu().is_some() {
+            self.open_submenu(idx, false);
+        } else {
+            self.close_submenu();
+        }
+    }
+
+    fn hover_next(&mut self, forward: bool) {
+        let len = self.items.len();
+        if len == 0 {
+            return;
+        }
+        self.hover_timer.stop();
+        self.pending = None;
+
+        let mut idx = self.hovered;
+        for _ in 0..len {
+            let next = match (idx, forward) {
+                (None, true) => 0,
+                (None, false) => len - 1,
+                (Some(i), true) => (i + 1) % len,
+                (Some(i), false) => (i + len - 1) % len,
+            };
+            if self.items[next].is_selectable() {
+                self.hovered = Some(next);
+                self.update();
+                return;
+            }
+            idx = Some(next);
+        }
+    }
+
+    fn mnemonic_item(&self, key_code: KeyCode) -> Option<usize> {
+        let mut name = key_code.name().chars();
+        let (Some(c), None) = (name.next(), name.next()) else {
+            return None;
+        };
+        self.items
+            .iter()
+            .position(|item| item.is_selectable() && item.mnemonic().map(|m| m.1) == Some(c))
+    }
+
+    fn parent_menu_mut(&self) -> Option<&'static mut Menu> {
+        self.parent_menu
+            .and_then(|id| self.window().find_id_mut(id))
+            .and_then(|w| w.downcast_mut::<Menu>())
+    }
+
+    /// Get the menu bar which opened the root menu of the menu chain.
+    fn root_menu_bar(&self) -> Option<&'static mut MenuBar> {
+        let window = self.window();
+        let mut menu_bar = self.menu_bar;
+        let mut parent = self.parent_menu;
+        while let Some(id) = parent {
+            let menu = window.find_id(id)?.downcast_ref::<Menu>()?;
+            menu_bar = menu.menu_bar;
+            parent = menu.parent_menu;
+        }
+
+        menu_bar
+            .and_then(|id| window.find_id_mut(id))
+            .and_then(|w| w.downcast_mut::<MenuBar>())
+    }
+
+    /// Whether the point in global coordinate was inside this menu or the opened sub menus.
+    fn chain_contains(&self, point: &Point) -> bool {
+        if self.visible() && self.rect().contains(point) {
+            return true;
+        }
+        self.opened
+            .and_then(|idx| self.items.get(idx))
+            .and_then(|item| item.get_submenu())
+            .is_some_and(|submenu| submenu.chain_contains(point))
+    }
+
+    fn fit_in_window(&self, point: Point) -> Point {
+        let win_size = self.window().size();
+        let size = self.menu_size;
+        Point::new(
+            point.x().min(win_size.width() - size.width()).max(0),
+            point.y().min(win_size.height() - size.height()).max(0),
+        )
+    }
+
+    /// @return (has the check mark column, has the icon column)
+    fn leading_columns(&self) -> (bool, bool) {
+        (
+            self.items.iter().any(|i| i.is_checkable()),
+            self.items.iter().any(|i| i.icon().is_some()),
+        )
+    }
+
+    /// Get the rectangle of item in widget coordinate.
+    fn item_rect(&self, idx: usize) -> Rect {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        let mut y = contents.y();
+        for item in self.items.iter().take(idx) {
+            y += self.height_of(item);
+        }
+        let height = self.items.get(idx).map_or(0, |item| self.height_of(item));
+
+        Rect::new(contents.x(), y, contents.width(), height)
+    }
+
+    /// Get the index of item at the position in widget coordinate.
+    fn item_at(&self, pos: Point) -> Option<usize> {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        if pos.x() < contents.x() || pos.x() >= contents.x() + contents.width() {
+            return None;
+        }
+
+        let mut y = contents.y();
+        for (idx, item) in self.items.iter().enumerate() {
+            let height = self.height_of(item);
+            if pos.y() >= y && pos.y() < y + height {
+                return Some(idx);
+            }
+            y += height;
+        }
+        None
+    }
+
+    #[inline]
+    fn height_of(&self, item: &MenuItem) -> i32 {
+        if item.is_separator() {
+            SEPARATOR_HEIGHT
+        } else {
+            self.item_height
+        }
+    }
+
+    fn calc_size(&mut self) {
+        let font = self.font().clone();
+        let font_height = font.calc_font_dimension().1.ceil() as i32;
+        self.item_height = font_height.max(ICON_SIZE) + ITEM_PADDING * 2;
+
+        let mut text_width = 0f32;
+        let mut hint_width = 0f32;
+        for item in self.items.iter().filter(|i| !i.is_separator()) {
+            text_width = text_width.max(font.calc_text_dimension(item.text(), 0.).0);
+            if let Some(shortcut) = item.shortcut() {
+                hint_width = hint_width.max(font.calc_text_dimension(&shortcut.to_string(), 0.).0);
+            }
+        }
+
+        let (check_column, icon_column) = self.leading_columns();
+        let mut width = text_width.ceil() as i32
+            + TEXT_PADDING
+            + ARROW_WIDTH
+            + COLUMN_WIDTH * check_column as i32
+            + COLUMN_WIDTH * icon_column as i32;
+        if hint_width > 0. {
+            width += SHORTCUT_SPACING + hint_width.ceil() as i32;
+        }
+        let mut height: i32 = self.items.iter().map(|item| self.height_of(item)).sum();
+
+        let (top, right, bottom, left) = self.paddings();
+        let (tb, rb, bb, lb) = self.borders();
+        width += left + right + (lb + rb).ceil() as i32;
+        height += top + bottom + (tb + bb).ceil() as i32;
+
+        self.menu_size = Size::new(width.max(MENU_MIN_WIDTH), height);
+        self.width_request(self.menu_size.width());
+        self.height_request(self.menu_size.height());
+
+        if self.initialized() && self.visible() {
+            self.window().layout_change(self);
+        }
+    }
+}
+
+/// Render the check mark in the center of `rect`.
+fn render_check(painter: &mut Painter, rect: FRect, color: Color) {
+    let size = 8.;
+    let x = rect.x() + (rect.width() - size) / 2.;
+    let y = rect.y() + (rect.height() - size) / 2.;
+
+    painter.set_color(color);
+    painter.set_line_width(1.5);
+    painter.draw_line_f(x, y + size * 0.5, x + size * 0.35, y + size * 0.85);
+    painter.draw_line_f(x + size * 0.35, y + size * 0.85, x + size, y + size * 0.1);
+}
+
+/// Render the radio dot in the center of `rect`.
+fn render_radio(painter: &mut Painter, rect: FRect, color: Color) {
+    let size = 6.;
+    let r = size / 2.;
+    let dot = FRect::new(
+        rect.x() + (rect.width() - size) / 2.,
+        rect.y() + (rect.height() - size) / 2.,
+        size,
+        size,
+    );
+    painter.fill_round_rect(dot, (r, r, r, r), color);
+}
+
+/// Render the arrow points to the right in the center of `rect`.
+fn render_arrow(painter: &mut Painter, rect: FRect, color: Color) {
+    let size = 4.;
+    let x = rect.x() + (rect.width() - size) / 2.;
+    let y = rect.y() + rect.height() / 2.;
+
+    painter.set_color(color);
+    painter.set_line_width(1.5);
+    painter.draw_line_f(x, y - size, x + size, y);
+    painter.draw_line_f(x + size, y, x, y + size);
+}
+
+/// Underline the mnemonic character, `origin` is the bottom left of the text.
+pub(crate) fn render_mnemonic(
+    painter: &mut Painter,
+    font: &Font,
+    text: &str,
+    pos: usize,
+    origin: FPoint,
+) {
+    let Some(c) = text.chars().nth(pos) else {
+        return;
+    };
+    let prefix: String = text.chars().take(pos).collect();
+    let x = origin.x() + font.calc_text_dimension(&prefix, 0.).0;
+    let width = font.calc_text_dimension(c.encode_utf8(&mut [0; 4]), 0.).0;
+    let y = origin.y() - 1.;
+
+    painter.set_line_width(1.);
+    painter.draw_line_f(x, y, x + width, y);
+}
@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use tipc::{
+    ipc_hub::IpcHub,
     ipc_master::IpcMaster,
     ipc_slave::IpcSlave,
+    mem::handshake::HandshakeError,
     mem::mem_rw_lock::{MemRwLock, MemRwLockGuard},
     IpcNode, parking_lot::RwLock, raw_sync::Timeout, 
 };
@@ -17,7 +19,11 @@ pub(crate) trait IpcBridge {
     #[allow(dead_code)]
     fn signal(&self);
 
-    fn add_shared_region(&self, id: &'static str, rect: Rect);
+    /// @return false if the region id was too long, or the regions reached the `max_regions` of master.
+    fn add_shared_region(&self, id: &str, rect: Rect) -> bool;
+
+    /// Remove the shared region when the `SharedWidget` was removed, the slave will be notified.
+    fn remove_shared_region(&self, id: &str);
 
     fn size(&self) -> (u32, u32);
 
@@ -30,11 +36,26 @@ pub(crate) trait IpcBridge {
 
     fn pretreat_resize(&self, width: i32, height: i32);
 
-    fn wait_prepared(&self);
+    /// @return false if the slave was lost or has not prepared in time.
+    fn wait_prepared(&self) -> bool;
 
     fn is_invalidate(&self) -> bool;
 
     fn set_invalidate(&self, invalidate: bool);
+
+    /// Take the dirty rects of shared buffer rendered by slave,
+    /// `None` if the whole shared buffer should be copied.
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>>;
+}
+
+/// The bridge to the [`IpcHub`] of window, each `SharedWidget` with the `shared_channel`
+/// was served by its own channel.
+pub(crate) trait IpcHubBridge {
+    /// Add the channel for the `SharedWidget`, and create the bridge to the master of channel.
+    fn add_channel(&self, channel: &str) -> Result<Box<dyn IpcBridge>, HandshakeError>;
+
+    /// Remove the channel when the `SharedWidget` was removed, the slave will find the master lost.
+    fn remove_channel(&self, channel: &str);
 }
 
 pub struct IpcBridgeModel<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> {
@@ -111,9 +132,16 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcBridge
         }
     }
 
-    fn add_shared_region(&self, id: &'static str, rect: Rect) {
+    fn add_shared_region(&self, id: &str, rect: Rect) -> bool {
+        match self.master {
+            Some(ref master) => master.read().add_rect(id, rect),
+            None => unreachable!(),
+        }
+    }
+
+    fn remove_shared_region(&self, id: &str) {
         if let Some(ref master) = self.master {
-            master.read().add_rect(id, rect)
+            master.read().remove_rect(id);
         }
         if self.slave.is_some() {
             unreachable!()
@@ -172,10 +200,9 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcBridge
     }
 
     #[inline]
-    fn wait_prepared(&self) {
+    fn wait_prepared(&self) -> bool {
         if let Some(ref master) = self.master {
-            master.read().wait_prepared();
-            return;
+            return master.read().wait_prepared();
         }
 
         unreachable!()
@@ -206,4 +233,145 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcBridge
 
         unreachable!()
     }
+
+    #[inline]
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>> {
+        if let Some(ref master) = self.master {
+            return master.read().take_dirty_rects();
+        }
+
+        unreachable!()
+    }
+}
+
+pub struct IpcHubBridgeModel<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> {
+    hub: Arc<RwLock<IpcHub<T, M>>>,
+}
+
+impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcHubBridgeModel<T, M> {
+    #[allow(clippy::new_ret_no_self)]
+    #[inline]
+    pub fn new(hub: Arc<RwLock<IpcHub<T, M>>>) -> Box<dyn IpcHubBridge> {
+        Self { hub }.boxed()
+    }
+}
+
+impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcHubBridge
+    for IpcHubBridgeModel<T, M>
+{
+    fn add_channel(&self, channel: &str) -> Result<Box<dyn IpcBridge>, HandshakeError> {
+        let lock = self.hub.write().add_channel(channel)?.buffer_lock();
+
+        Ok(IpcChannelBridge {
+            hub: self.hub.clone(),
+            channel: channel.to_string(),
+            lock,
+        }
+        .boxed())
+    }
+
+    #[inline]
+    fn remove_channel(&self, channel: &str) {
+        self.hub.write().remove_channel(channel);
+    }
+}
+
+/// The bridge to the master of one channel in [`IpcHub`], only used on the master side.
+pub struct IpcChannelBridge<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> {
+    hub: Arc<RwLock<IpcHub<T, M>>>,
+    channel: String,
+    lock: Arc<MemRwLock>,
+}
+
+impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcChannelBridge<T, M> {
+    #[inline]
+    fn with_master<R>(&self, f: impl FnOnce(&IpcMaster<T, M>) -> R) -> R {
+        let hub = self.hub.read();
+        f(hub
+            .channel(&self.channel)
+            .unwrap_or_else(|| panic!("The ipc channel `{}` was removed.", self.channel)))
+    }
+
+    #[inline]
+    fn with_master_mut<R>(&self, f: impl FnOnce(&mut IpcMaster<T, M>) -> R) -> R {
+        let mut hub = self.hub.write();
+        f(hub
+            .channel_mut(&self.channel)
+            .unwrap_or_else(|| panic!("The ipc channel `{}` was removed.", self.channel)))
+    }
+}
+
+impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcBridge
+    for IpcChannelBridge<T, M>
+{
+    fn region(&self) -> Rect {
+        unreachable!()
+    }
+
+    #[inline]
+    fn wait(&self, timeout: Timeout) {
+        self.with_master(|master| master.wait(timeout))
+    }
+
+    #[inline]
+    fn signal(&self) {
+        self.with_master(|master| master.signal())
+    }
+
+    #[inline]
+    fn add_shared_region(&self, id: &str, rect: Rect) -> bool {
+        self.with_master(|master| master.add_rect(id, rect))
+    }
+
+    #[inline]
+    fn remove_shared_region(&self, id: &str) {
+        self.with_master(|master| master.remove_rect(id));
+    }
+
+    #[inline]
+    fn size(&self) -> (u32, u32) {
+        self.with_master(|master| (master.width(), master.height()))
+    }
+
+    #[inline]
+    fn create_buffer(&self, width: u32, height: u32) {
+        self.with_master_mut(|master| master.create_buffer(width, height))
+    }
+
+    #[inline]
+    fn buffer(&self) -> (&'static [u8], MemRwLockGuard) {
+        let buffer = self.with_master(|master| master.buffer());
+        (buffer, self.lock.read())
+    }
+
+    #[inline]
+    fn buffer_mut(&self) -> (&'static mut [u8], MemRwLockGuard) {
+        let buffer = self.with_master(|master| master.buffer());
+        (buffer, self.lock.write())
+    }
+
+    #[inline]
+    fn pretreat_resize(&self, width: i32, height: i32) {
+        self.with_master_mut(|master| master.pretreat_resize(width as u32, height as u32))
+    }
+
+    #[inline]
+    fn wait_prepared(&self) -> bool {
+        self.with_master(|master| master.wait_prepared())
+    }
+
+    #[inline]
+    fn is_invalidate(&self) -> bool {
+        self.with_master(|master| master.is_invalidate())
+    }
+
+    #[inline]
+    fn set_invalidate(&self, invalidate: bool) {
+        self.with_master(|master| master.set_invalidate(invalidate))
+    }
+
+    #[inline]
+    fn take_dirty_rects(&self) -> Option<Vec<Rect>> {
+        self.with_master(|master| master.take_dirty_rects())
+    }
 }
@@ -0,0 +1,189 @@
+use crate::{
+    application_window::ApplicationWindow, clipboard::ClipboardLevel, primitive::Message,
+    system::System,
+};
+use log::{error, warn};
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+use tipc::{
+    ipc_event::IpcEvent,
+    mem::{
+        frame_channel::FrameChannel,
+        frame_codec::{FrameCodec, StringCodec},
+    },
+    parking_lot::{Condvar, Mutex},
+    IpcNode,
+};
+use tlib::{
+    r#async::{async_tasks, tokio_runtime, AsyncTask},
+    values::{FromValue, ToValue},
+};
+
+/// The maximum time the slave waits for the clipboard text responded by the master.
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The os clipboard of slave was brokered by the master, the slave has no window of its own.
+static FORWARDED: AtomicBool = AtomicBool::new(false);
+static REQUEST_ID: AtomicU32 = AtomicU32::new(0);
+static PENDING: Mutex<Vec<PendingText>> = Mutex::new(Vec::new());
+static RESPONDED: Condvar = Condvar::new();
+
+struct PendingText {
+    id: u32,
+    text: Option<String>,
+    finished: bool,
+}
+
+#[inline]
+pub(crate) fn set_forwarded(forwarded: bool) {
+    FORWARDED.store(forwarded, Ordering::Release)
+}
+
+#[inline]
+pub(crate) fn is_forwarded() -> bool {
+    FORWARDED.load(Ordering::Acquire)
+}
+
+/// Request the text of os clipboard from the master, block until responded or timeout.
+///
+/// Called in the ui thread of slave.
+#[inline]
+pub(crate) fn request_text() -> Option<String> {
+    wait_text(send_request())
+}
+
+/// Request the text of os clipboard from the master without blocking, `f` was invoked
+/// in the ui thread with the text responded, or `None` if timeout.
+///
+/// Called in the ui thread of slave.
+pub(crate) fn request_text_async<F: FnOnce(Option<String>) + 'static>(f: F) {
+    let id = send_request();
+    let join = tokio_runtime().spawn_blocking(move || wait_text(id).unwrap_or_default().to_value());
+    let task = AsyncTask::new(join)
+        .then(move |text| f(Some(String::from_value(&text)).filter(|text| !text.is_empty())));
+
+    async_tasks()
+        .entry(thread::current().id())
+        .or_insert(vec![])
+        .push(task);
+}
+
+fn send_request() -> u32 {
+    let id = REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    PENDING.lock().push(PendingText {
+        id,
+        text: None,
+        finished: false,
+    });
+    ApplicationWindow::window().send_message(Message::ClipboardGet(id));
+    id
+}
+
+fn wait_text(id: u32) -> Option<String> {
+    let mut pending = PENDING.lock();
+    let deadline = Instant::now() + CLIPBOARD_TIMEOUT;
+    let mut timed_out = false;
+    loop {
+        let idx = pending.iter().position(|p| p.id == id)?;
+        if pending[idx].finished {
+            return pending
+                .swap_remove(idx)
+                .text
+                .filter(|text| !text.is_empty());
+        }
+        if timed_out {
+            warn!("Request the clipboard text timeout, id = {}.", id);
+            pending.swap_remove(idx);
+            return None;
+        }
+        timed_out = RESPONDED.wait_until(&mut pending, deadline).timed_out();
+    }
+}
+
+/// Receive the clipboard text responded by the master from the clipboard frame sent before
+/// [`IpcEvent::ClipboardText`], the responses of requests already timeout were dropped.
+///
+/// Called in the ipc thread of slave.
+pub(crate) fn receive_text<T: 'static + Copy, M: 'static + Copy>(
+    slave: &impl IpcNode<T, M>,
+    id: u32,
+) {
+    let text = recv_text(slave);
+    let mut pending = PENDING.lock();
+    match pending.iter_mut().find(|p| p.id == id && !p.finished) {
+        Some(p) => {
+            p.text = text;
+            p.finished = true;
+            RESPONDED.notify_all();
+        }
+        None => warn!(
+            "The clipboard text responded by master was dropped, the request was timeout, id = {}.",
+            id
+        ),
+    }
+}
+
+/// Set the text of os clipboard through the master.
+///
+/// Called in the ui thread of slave.
+#[inline]
+pub(crate) fn set_text(text: String) {
+    ApplicationWindow::window().send_message(Message::ClipboardSet(text))
+}
+
+/// Respond the text of os clipboard to the slave.
+///
+/// Called in the ipc thread of master.
+#[inline]
+pub(crate) fn respond_text<T: 'static + Copy, M: 'static + Copy>(
+    master: &impl IpcNode<T, M>,
+    id: u32,
+) {
+    let text = System::clipboard()
+        .text(ClipboardLevel::Os)
+        .unwrap_or_default();
+    send_text(master, text, IpcEvent::ClipboardText(id))
+}
+
+/// Send the text as the frame of [`FrameChannel::Clipboard`] which has no length limit of the
+/// event, then send the event to notify the peer.
+pub(crate) fn send_text<T: 'static + Copy, M: 'static + Copy>(
+    node: &impl IpcNode<T, M>,
+    text: String,
+    evt: IpcEvent<T>,
+) {
+    let r = node
+        .try_send_channel_frame(FrameChannel::Clipboard, text.as_bytes())
+        .and_then(|_| node.try_send(evt));
+    if let Err(e) = r {
+        error!("Send the clipboard text failed: {}", e);
+    }
+}
+
+/// Receive the text from the clipboard frame sent before the clipboard event, the frames of
+/// application were never received here.
+pub(crate) fn recv_text<T: 'static + Copy, M: 'static + Copy>(
+    node: &impl IpcNode<T, M>,
+) -> Option<String> {
+    let frame = node.try_recv_channel_frame(FrameChannel::Clipboard)?;
+    match StringCodec.decode(&frame) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            error!("Receive the clipboard text failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Set the text of os clipboard set by the slave.
+///
+/// Called in the ipc thread of master.
+#[inline]
+pub(crate) fn receive_set_text<T: 'static + Copy, M: 'static + Copy>(master: &impl IpcNode<T, M>) {
+    if let Some(text) = recv_text(master) {
+        System::clipboard().set_text(text, ClipboardLevel::Os)
+    }
+}
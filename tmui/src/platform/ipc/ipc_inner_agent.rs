@@ -1,6 +1,6 @@
 use std::sync::Arc;
 use tipc::{ipc_master::IpcMaster, ipc_slave::IpcSlave, IpcNode, parking_lot::RwLock};
-use tlib::global::SemanticExt;
+use tlib::{figure::Rect, global::SemanticExt};
 
 pub(crate) trait IpcInnerAgent {
     fn release_retention(&self);
@@ -10,6 +10,8 @@ pub(crate) trait IpcInnerAgent {
     fn is_invalidate(&self) -> bool;
 
     fn set_invalidate(&self, invalidate: bool);
+
+    fn add_dirty_rect(&self, rect: Rect);
 }
 
 pub(crate) struct InnerAgent<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> {
@@ -89,4 +91,14 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> IpcInnerA
 
         unreachable!()
     }
+
+    #[inline]
+    fn add_dirty_rect(&self, rect: Rect) {
+        if let Some(ref slave) = self.slave {
+            slave.read().add_dirty_rect(rect);
+            return;
+        }
+
+        unreachable!()
+    }
 }
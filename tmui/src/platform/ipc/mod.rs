@@ -1,4 +1,5 @@
 pub(crate) mod ipc_bridge;
+pub(crate) mod ipc_clipboard;
 pub(crate) mod ipc_inner_agent;
 pub(crate) mod ipc_window;
 
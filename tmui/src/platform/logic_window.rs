@@ -1,6 +1,6 @@
 use super::{
     gl_bootstrap::GlEnv,
-    ipc_bridge::{IpcBridge, IpcBridgeModel},
+    ipc_bridge::{IpcBridge, IpcBridgeModel, IpcHubBridge, IpcHubBridgeModel},
     PlatformType,
 };
 use crate::{
@@ -14,8 +14,8 @@ use ahash::AHashMap;
 use glutin::config::Config;
 use std::sync::Arc;
 use tipc::{
-    ipc_master::IpcMaster, ipc_slave::IpcSlave, mem::mem_rw_lock::MemRwLock, parking_lot::RwLock,
-    IpcNode, IpcType,
+    ipc_hub::IpcHub, ipc_master::IpcMaster, ipc_slave::IpcSlave, mem::mem_rw_lock::MemRwLock,
+    parking_lot::RwLock, IpcNode, IpcType,
 };
 use tlib::{
     figure::{Point, Size},
@@ -40,8 +40,12 @@ pub(crate) struct LogicWindow<T: 'static + Copy + Sync + Send, M: 'static + Copy
     pub backend_type: BackendType,
     pub ipc_type: IpcType,
     pub min_size: Option<Size>,
+    /// The scale factor of the monitor which the window was created on.
+    pub scale_factor: f32,
 
     pub master: Option<Arc<RwLock<IpcMaster<T, M>>>>,
+    /// Serves the slaves of `SharedWidget` with the `shared_channel`, it has the same name with the master.
+    pub hub: Option<Arc<RwLock<IpcHub<T, M>>>>,
     pub shared_channel: Option<SharedChannel<T, M>>,
     pub context: Option<LogicWindowContext>,
 
@@ -74,6 +78,9 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> LogicWind
         min_size: Option<Size>,
     ) -> Self {
         let lock = master.as_ref().map(|m| m.read().buffer_lock());
+        let hub = master
+            .as_ref()
+            .map(|m| Arc::new(RwLock::new(IpcHub::new(m.read().name()))));
         Self {
             raw_window_handle: Some(raw_window_handle),
             window_id: Some(window_id),
@@ -88,7 +95,9 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> LogicWind
             backend_type: BackendType::default(),
             ipc_type: IpcType::Master,
             min_size,
+            scale_factor: 1.,
             master,
+            hub,
             shared_channel,
             context: Some(context),
             on_activate: None,
@@ -121,7 +130,9 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> LogicWind
             backend_type: BackendType::default(),
             ipc_type: IpcType::Slave,
             min_size: None,
+            scale_factor: 1.,
             master: None,
+            hub: None,
             shared_channel,
             context: Some(context),
             on_activate: None,
@@ -178,6 +189,13 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> LogicWind
             if let Some(ref master) = self.master {
                 master.write().recreate_buffer();
             }
+            if let Some(ref hub) = self.hub {
+                for (_, master) in hub.write().iter_mut() {
+                    let lock = master.buffer_lock();
+                    let _guard = lock.write();
+                    master.recreate_buffer();
+                }
+            }
         }
 
         if let Some(ref gl_env) = self.gl_env {
@@ -198,6 +216,11 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> LogicWind
         ))
     }
 
+    #[inline]
+    pub fn create_ipc_hub_bridge(&self) -> Option<Box<dyn IpcHubBridge>> {
+        self.hub.clone().map(IpcHubBridgeModel::<T, M>::new)
+    }
+
     #[inline]
     pub fn gl_make_current(&self) {
         if let Some(ref gl_env) = self.gl_env {
@@ -84,11 +84,6 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> PlatformC
         };
         let (width, height) = win_config.size();
         let min_size = win_config.min_size();
-        let bitmap = Arc::new(RwLock::new(if self.backend_type == BackendType::OpenGL {
-            Bitmap::empty(width, height)
-        } else {
-            Bitmap::new(width, height, inner_agent)
-        }));
 
         let (window, event_loop, gl_env) = if let Some(target) = target {
             let (window, gl_env) = super::make_window(win_config, target, self.backend_type);
@@ -103,6 +98,23 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> PlatformC
             (window, Some(event_loop), gl_env)
         };
 
+        // The bitmap was in physical pixels, the window may be minimized when it was restored.
+        let scale_factor = window.scale_factor();
+        let size = window.inner_size();
+        let (width, height) = if size.width > 0 && size.height > 0 {
+            (size.width, size.height)
+        } else {
+            (
+                (width as f64 * scale_factor).round() as u32,
+                (height as f64 * scale_factor).round() as u32,
+            )
+        };
+        let bitmap = Arc::new(RwLock::new(if self.backend_type == BackendType::OpenGL {
+            Bitmap::empty(width, height)
+        } else {
+            Bitmap::new(width, height, inner_agent)
+        }));
+
         unsafe {
             let ns_app = NSApp();
             ns_app.setActivationPolicy_(NSApplicationActivationPolicyRegular);
@@ -184,6 +196,7 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> PlatformC
 
         logic_window.platform_type = self.platform_type;
         logic_window.backend_type = self.backend_type;
+        logic_window.scale_factor = scale_factor as f32;
 
         (logic_window, physical_window)
     }
@@ -26,7 +26,11 @@ pub(crate) use win32::*;
 
 use crate::backend::BackendType;
 use crate::primitive::Message;
-use crate::window::win_config::{self, WindowConfig};
+use crate::screen;
+use crate::window::{
+    win_config::{self, WindowConfig},
+    win_state,
+};
 
 use self::gl_bootstrap::GlEnv;
 use self::logic_window::LogicWindow;
@@ -65,11 +69,16 @@ pub(crate) trait PlatformContext<T: 'static + Copy + Sync + Send, M: 'static + C
 }
 
 pub(crate) fn make_window(
-    win_config: WindowConfig,
+    mut win_config: WindowConfig,
     target: &EventLoopWindowTarget<Message>,
     backend_type: BackendType,
 ) -> (WinitWindow, Option<Arc<GlEnv>>) {
-    if backend_type == BackendType::OpenGL {
+    screen::refresh(target);
+
+    let name = win_config.name().map(|name| name.to_string());
+    let minimized = win_state::restore(&mut win_config, target);
+
+    let (window, gl_env) = if backend_type == BackendType::OpenGL {
         let (win, gl_env) =
             gl_bootstrap::bootstrap_gl_window(target, win_config.create_window_builder())
                 .expect("bootstrap gl window failed.");
@@ -78,5 +87,12 @@ pub(crate) fn make_window(
     } else {
         let window = win_config::build_window(win_config, target).expect("build_window failed.");
         (window, None)
+    };
+
+    if minimized {
+        window.set_minimized(true);
     }
+    win_state::register(&window, name);
+
+    (window, gl_env)
 }
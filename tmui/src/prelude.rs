@@ -15,13 +15,19 @@ pub use crate::animation::{
     state_holder::{RectHolder, ReflectRectHolder, ReflectTransparencyHolder, TransparencyHolder},
     Animatable, Animation, AnimationModel, ReflectAnimatable, {self},
 };
-pub use crate::application_window::{window_id, ApplicationWindow};
+pub use crate::application_window::{window_id, ApplicationWindow, WindowEdge};
 pub use crate::container::{
     ChildrenRegionAcquirer, Container, ContainerAcquire, ContainerExt, ContainerImpl,
     ContainerImplExt, ContainerLayoutEnum, ContainerPointEffective, ContainerPropsAcquire,
     ContainerScaleCalculate, ReflectContainerImpl, ReflectSizeUnifiedAdjust, ReflectSpacingCapable,
     SizeUnifiedAdjust, SpacingCapable, StaticContainerScaleCalculate, StaticSizeUnifiedAdjust,
 };
+pub use crate::dnd::{DragSource, DropTarget, ReflectDragSource, ReflectDropTarget};
+pub use crate::dock::{
+    dock_widget::{DockWidget, DockWidgetSignals},
+    layout::DockLayout,
+    DockArea, DockAreaSignals, DockPosition,
+};
 pub use crate::font::Font;
 pub use crate::graphics::board::Board;
 pub use crate::graphics::border::Border;
@@ -40,6 +46,7 @@ pub use crate::layout::{
     Composition, ContainerLayout, ContentAlignment, Layout, ReflectContentAlignment,
 };
 pub use crate::loading::{Loadable, LoadingModel, ReflectLoadable};
+pub use crate::menu::{context_menu::ContextMenu, MenuSignals};
 pub use crate::overlay::{Overlaid, ReflectOverlaid};
 pub use crate::pane::{Pane, PaneExt, ReflectPaneExt};
 pub use crate::popup::{Popup, PopupExt, PopupImpl, Popupable, ReflectPopupImpl, ReflectPopupable};
@@ -50,19 +57,25 @@ pub use crate::primitive::global_watch::{
     GlobalWatch, GlobalWatchEvent, GlobalWatchImpl, ReflectGlobalWatch,
 };
 pub use crate::primitive::obj::*;
+pub use crate::screen::{Monitor, Screen};
 pub use crate::scroll_area::{
     LayoutMode, ReflectScrollAreaExt, ScrollArea, ScrollAreaExt, ScrollAreaGenericExt,
     ScrollAreaSlots,
 };
 pub use crate::scroll_bar::{ScrollBar, ScrollBarPosition, ScrollBarSignal};
 pub use crate::shared_widget::{
-    ReflectSharedWidgetImpl, SharedWidget, SharedWidgetExt, SharedWidgetImpl,
+    ReflectSharedWidgetImpl, SharedWidget, SharedWidgetExt, SharedWidgetImpl, SharedWidgetSignals,
 };
-pub use crate::shortcut::Shortcut;
+pub use crate::shortcut::{KeyChord, Shortcut};
 pub use crate::split_pane::{
-    ReflectSplitInfosGetter, SplitInfo, SplitInfosGetter, SplitPane, SplitPaneExt, SplitType,
+    ReflectSplitInfosGetter, SplitHandle, SplitInfo, SplitInfosGetter, SplitLayout, SplitPane,
+    SplitPaneExt, SplitType,
 };
 pub use crate::stack::{ReflectStackImpl, Stack, StackImpl};
+pub use crate::status_bar::{StatusBar, StatusBarSignals};
+pub use crate::tab_bar::{TabBar, TabBarSignals, TabPosition};
+pub use crate::tab_widget::{TabWidget, TabWidgetSignals};
+pub use crate::tool_bar::{ToolBar, ToolButton, ToolButtonSignals, ToolSeparator};
 pub use crate::vbox::VBox;
 pub use crate::widget::{
     callbacks::Callbacks,
@@ -9,6 +9,7 @@ use std::{
     },
 };
 use tipc::mem::mem_rw_lock::{MemRwLock, MemRwLockGuard};
+use tlib::figure::Rect;
 
 use crate::platform::ipc_inner_agent::IpcInnerAgent;
 
@@ -348,4 +349,17 @@ impl Bitmap {
             inner_agent.set_invalidate(invalidate)
         }
     }
+
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared { .. })
+    }
+
+    /// Add the dirty rect in physical pixels, only effected on slave side of shared memory application.
+    #[inline]
+    pub fn add_shared_dirty_rect(&self, rect: Rect) {
+        if let Self::Shared { inner_agent, .. } = self {
+            inner_agent.add_dirty_rect(rect)
+        }
+    }
 }
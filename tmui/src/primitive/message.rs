@@ -2,7 +2,9 @@ use crate::{application_window::ApplicationWindow, window::Window};
 use std::{fmt::Debug, time::Instant};
 use tipc::ipc_event::IpcEvent;
 use tlib::{
-    events::{downcast_event_ref, Event, EventType::*, KeyEvent, MouseEvent, ResizeEvent},
+    events::{
+        downcast_event_ref, Event, EventType::*, FileEvent, KeyEvent, MouseEvent, ResizeEvent,
+    },
     figure::{Point, Rect, Size},
     namespace::AsNumeric,
     object::ObjectId,
@@ -62,6 +64,9 @@ pub(crate) enum Message {
     /// Window's visibility has changed.
     WindowVisibilityChanged(bool),
 
+    /// Window was moved to the monitor with different scale factor.
+    WindowScaleFactorChanged(f32),
+
     /// Request the child window correspondent to the id change the size and location.
     WinWidgetGeometryChangedRequest(ObjectId, Rect),
 
@@ -78,6 +83,17 @@ pub(crate) enum Message {
 
     /// @see [`WinWidgetSizeReverseRequest`](Message::WinWidgetSizeReverseRequest)
     WinWidgetSizeChanged(ObjectId, Size),
+
+    /// The dragging cursor was outside the source window.
+    /// (Source window id, cursor position in screen coordinate, dropped)
+    DragOutside(WindowId, Point, bool),
+
+    /// Request the text of os clipboard from the master, only sent by the shared memory slave.
+    /// (Request id)
+    ClipboardGet(u32),
+
+    /// Set the text of os clipboard through the master, only sent by the shared memory slave.
+    ClipboardSet(String),
 }
 
 impl Debug for Message {
@@ -133,6 +149,10 @@ impl Debug for Message {
                 .debug_tuple("WindowVisibilityChanged")
                 .field(arg0)
                 .finish(),
+            Self::WindowScaleFactorChanged(arg0) => f
+                .debug_tuple("WindowScaleFactorChanged")
+                .field(arg0)
+                .finish(),
             Self::WinWidgetGeometryChangedRequest(arg0, arg1) => f
                 .debug_tuple("WinWidgetGeometryChangedRequest")
                 .field(arg0)
@@ -158,6 +178,14 @@ impl Debug for Message {
                 .field(arg0)
                 .field(arg1)
                 .finish(),
+            Self::DragOutside(arg0, arg1, arg2) => f
+                .debug_tuple("DragOutside")
+                .field(arg0)
+                .field(arg1)
+                .field(arg2)
+                .finish(),
+            Self::ClipboardGet(arg0) => f.debug_tuple("ClipboardGet").field(arg0).finish(),
+            Self::ClipboardSet(arg0) => f.debug_tuple("ClipboardSet").field(arg0).finish(),
         }
     }
 }
@@ -183,6 +211,7 @@ impl<T: 'static + Copy + Sync + Send> From<Message> for IpcEvent<T> {
             Message::VSync(_, a) => IpcEvent::VSync(a),
             Message::SetCursorShape(a, _) => IpcEvent::SetCursorShape(a),
             Message::Event(evt) => convert_event(&evt),
+            Message::ClipboardGet(a) => IpcEvent::ClipboardGet(a),
             _ => unreachable!(),
         }
     }
@@ -272,6 +301,19 @@ pub(crate) fn convert_event<T: 'static + Copy + Sync + Send>(evt: &Event) -> Ipc
         }
         FocusIn => IpcEvent::RequestFocusEvent(true, Instant::now()),
         FocusOut => IpcEvent::RequestFocusEvent(false, Instant::now()),
+        DroppedFile | HoveredFile => {
+            let evt = downcast_event_ref::<FileEvent>(evt).unwrap();
+            let path = evt
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if ty == DroppedFile {
+                IpcEvent::DroppedFile(path, Instant::now())
+            } else {
+                IpcEvent::HoveredFile(path, Instant::now())
+            }
+        }
+        HoveredFileCancelled => IpcEvent::HoveredFileCancelled(Instant::now()),
         _ => unreachable!(),
     }
 }
@@ -49,6 +49,11 @@ impl DynPopupTr {
     pub fn get_ref_count(&self) -> usize {
         self.ref_count.get() as usize
     }
+
+    #[inline]
+    pub(crate) fn clone_ref_count(&self) -> Rc<Cell<i32>> {
+        self.ref_count.clone()
+    }
 }
 
 impl<R: PopupImpl> From<Tr<R>> for DynPopupTr {
@@ -1,4 +1,4 @@
-use super::{Tr, TrAllocater};
+use super::{DynPopupTr, Tr, TrAllocater};
 use crate::widget::WidgetImpl;
 use std::{
     cell::Cell,
@@ -63,6 +63,19 @@ impl<R: WidgetImpl> From<Tr<R>> for DynTr {
     }
 }
 
+impl From<DynPopupTr> for DynTr {
+    #[inline]
+    fn from(mut value: DynPopupTr) -> Self {
+        let ref_count = value.clone_ref_count();
+        ref_count.set(ref_count.get() + 1);
+
+        Self {
+            raw: value.as_widget_impl_mut(),
+            ref_count,
+        }
+    }
+}
+
 impl Clone for DynTr {
     #[inline]
     fn clone(&self) -> Self {
@@ -1,5 +1,6 @@
 use std::{sync::{mpsc::Receiver, Arc}, time::Instant};
 
+use log::error;
 use tipc::{ipc_master::IpcMaster, ipc_slave::IpcSlave, IpcNode, parking_lot::RwLock, ipc_event::IpcEvent};
 use tlib::events::Event;
 use super::convert_event;
@@ -81,30 +82,36 @@ impl<T: 'static + Copy + Sync + Send, M: 'static + Copy + Sync + Send> SharedSen
 
     #[inline]
     pub(crate) fn send_event_ipc(&self, event: &Event) {
-        match self.ty {
+        let result = match self.ty {
             SharedType::Master => self
                 .master
                 .as_ref()
                 .unwrap()
                 .read()
-                .try_send(convert_event::<T>(event))
-                .unwrap(),
+                .try_send(convert_event::<T>(event)),
             SharedType::Slave => self
                 .slave
                 .as_ref()
                 .unwrap()
                 .read()
-                .try_send(convert_event::<T>(event))
-                .unwrap(),
+                .try_send(convert_event::<T>(event)),
+        };
+        // e.g. the path of dropped file exceeds the limit of event.
+        if let Err(e) = result {
+            error!("Ipc send event failed. {}", e);
         }
     }
 
     #[inline]
     pub(crate) fn send_request(&self, request: M) -> Option<M> {
-        match self.ty {
-            SharedType::Master => self.master.as_ref().unwrap().read().send_request(request).unwrap(),
-            SharedType::Slave => self.slave.as_ref().unwrap().read().send_request(request).unwrap(),
-        }
+        let result = match self.ty {
+            SharedType::Master => self.master.as_ref().unwrap().read().send_request(request),
+            SharedType::Slave => self.slave.as_ref().unwrap().read().send_request(request),
+        };
+        result.unwrap_or_else(|e| {
+            error!("Ipc send request failed. {}", e);
+            None
+        })
     }
 
     #[inline]
@@ -0,0 +1,326 @@
+use crate::{
+    animation::frame_animator::FrameAnimator,
+    graphics::painter::Painter,
+    prelude::*,
+    primitive::frame::Frame,
+    widget::{RegionClear, WidgetImpl},
+};
+use tlib::{
+    emit, frame_animator,
+    object::{ObjectImpl, ObjectSubclass},
+    signals,
+    skia_safe::{PaintCap, PaintStyle},
+};
+
+pub(crate) const DEFAULT_PROGRESS_COLOR: Color = Color::rgb(51, 167, 255);
+pub(crate) const DEFAULT_TRACK_COLOR: Color = Color::rgb(225, 225, 225);
+
+/// The ratio of the moving chunk's length to the whole track in indeterminate mode.
+const INDETERMINATE_CHUNK: f32 = 0.3;
+/// Milliseconds for the moving chunk to pass through the track in indeterminate mode.
+const INDETERMINATE_PERIOD: f32 = 1500.;
+/// Milliseconds for the progress to catch up the changed value in determinate mode.
+const VALUE_TRANSITION: f32 = 200.;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStyle {
+    /// Horizontal bar from left to right.
+    #[default]
+    Bar,
+    /// Clockwise ring starts from the top.
+    Ring,
+}
+
+/// Widget displays the progress of an operation.
+///
+/// In determinate mode, the progress was calculated by
+/// `(value - minimum) / (maximum - minimum)`, changes of the value was animated.
+///
+/// In indeterminate mode, a chunk moves through the track repeatedly,
+/// which indicates the operation was in progress but the amount of work was unknown.
+#[extends(Widget)]
+#[frame_animator]
+pub struct ProgressBar {
+    style: ProgressStyle,
+    minimum: i32,
+    #[derivative(Default(value = "100"))]
+    maximum: i32,
+    value: i32,
+    indeterminate: bool,
+    #[derivative(Default(value = "DEFAULT_PROGRESS_COLOR"))]
+    color: Color,
+    #[derivative(Default(value = "DEFAULT_TRACK_COLOR"))]
+    track_color: Color,
+
+    /// The ratio of progress currently displayed, animates towards the ratio of value.
+    displayed: f32,
+    /// The phase of the moving chunk in indeterminate mode, range [0, 1).
+    phase: f32,
+    last_frame: u64,
+}
+
+impl ObjectSubclass for ProgressBar {
+    const NAME: &'static str = "ProgressBar";
+}
+
+impl ObjectImpl for ProgressBar {}
+
+pub trait ProgressBarSignals: ActionExt {
+    signals!(
+        ProgressBarSignals:
+
+        /// Emitted when the value of progress bar was changed.
+        ///
+        /// @param [`i32`]
+        value_changed(i32);
+    );
+}
+impl ProgressBarSignals for ProgressBar {}
+
+impl WidgetImpl for ProgressBar {
+    fn paint(&mut self, painter: &mut Painter) {
+        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        let (from, to) = if self.indeterminate {
+            let head = self.phase * (1. + INDETERMINATE_CHUNK);
+            ((head - INDETERMINATE_CHUNK).max(0.), head.min(1.))
+        } else {
+            (0., self.displayed)
+        };
+
+        match self.style {
+            ProgressStyle::Bar => {
+                let thickness = rect.height();
+                render_bar(
+                    painter,
+                    rect,
+                    thickness,
+                    (from, to),
+                    self.color,
+                    self.track_color,
+                )
+            }
+            ProgressStyle::Ring => {
+                render_ring(painter, rect, (from, to), self.color, self.track_color)
+            }
+        }
+    }
+}
+
+impl FrameAnimator for ProgressBar {
+    fn on_frame(&mut self, frame: Frame) {
+        let elapsed = if self.last_frame == 0 {
+            0.
+        } else {
+            frame.timestamp().saturating_sub(self.last_frame) as f32
+        };
+        self.last_frame = frame.timestamp();
+
+        if !self.visible() {
+            return;
+        }
+
+        if self.indeterminate {
+            self.phase = (self.phase + elapsed / INDETERMINATE_PERIOD).fract();
+            self.update();
+            return;
+        }
+
+        let target = self.ratio();
+        if self.displayed == target {
+            return;
+        }
+        let step = elapsed / VALUE_TRANSITION;
+        self.displayed = if self.displayed < target {
+            (self.displayed + step).min(target)
+        } else {
+            (self.displayed - step).max(target)
+        };
+        self.update();
+    }
+}
+
+impl ProgressBar {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    #[inline]
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// The value will be clamped in the range of [minimum, maximum].
+    pub fn set_value(&mut self, value: i32) {
+        let value = value.clamp(self.minimum, self.maximum);
+        if self.value == value {
+            return;
+        }
+        self.value = value;
+
+        emit!(ProgressBar::set_value => self, value_changed(value));
+    }
+
+    #[inline]
+    pub fn minimum(&self) -> i32 {
+        self.minimum
+    }
+
+    #[inline]
+    pub fn maximum(&self) -> i32 {
+        self.maximum
+    }
+
+    pub fn set_range(&mut self, minimum: i32, maximum: i32) {
+        self.minimum = minimum;
+        self.maximum = maximum.max(minimum);
+        self.set_value(self.value);
+    }
+
+    /// Get the ratio of progress, range [0, 1].
+    #[inline]
+    pub fn ratio(&self) -> f32 {
+        if self.maximum == self.minimum {
+            return 0.;
+        }
+        (self.value - self.minimum) as f32 / (self.maximum - self.minimum) as f32
+    }
+
+    /// Reset the value to the minimum without animation, `value_changed` was emitted
+    /// if the value was changed.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.displayed = 0.;
+        self.set_value(self.minimum);
+        self.update();
+    }
+
+    #[inline]
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate
+    }
+
+    #[inline]
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        self.indeterminate = indeterminate;
+        self.phase = 0.;
+        self.update();
+    }
+
+    #[inline]
+    pub fn style(&self) -> ProgressStyle {
+        self.style
+    }
+
+    #[inline]
+    pub fn set_style(&mut self, style: ProgressStyle) {
+        self.style = style;
+        self.update();
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    #[inline]
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.update();
+    }
+
+    #[inline]
+    pub fn track_color(&self) -> Color {
+        self.track_color
+    }
+
+    #[inline]
+    pub fn set_track_color(&mut self, track_color: Color) {
+        self.track_color = track_color;
+        self.update();
+    }
+}
+
+/// Render the progress bar with the `thickness` vertically centered in `rect`,
+/// the part of `range`(ratios in [0, 1]) was filled with `color`.
+pub(crate) fn render_bar(
+    painter: &mut Painter,
+    rect: FRect,
+    thickness: f32,
+    range: (f32, f32),
+    color: Color,
+    track_color: Color,
+) {
+    let thickness = thickness.min(rect.height());
+    if thickness <= 0. || rect.width() <= 0. {
+        return;
+    }
+    let radius = thickness / 2.;
+    let radius = (radius, radius, radius, radius);
+    let y = rect.y() + (rect.height() - thickness) / 2.;
+
+    painter.save();
+    painter.set_antialiasing(true);
+    painter.fill_round_rect(
+        FRect::new(rect.x(), y, rect.width(), thickness),
+        radius,
+        track_color,
+    );
+
+    let (from, to) = range;
+    if to > from {
+        let x = rect.x() + rect.width() * from;
+        let width = rect.width() * (to - from);
+        painter.fill_round_rect(FRect::new(x, y, width, thickness), radius, color);
+    }
+    painter.restore();
+}
+
+/// Render the progress ring in the center of `rect`,
+/// the part of `range`(ratios in [0, 1]) was stroked with `color`.
+pub(crate) fn render_ring(
+    painter: &mut Painter,
+    rect: FRect,
+    range: (f32, f32),
+    color: Color,
+    track_color: Color,
+) {
+    let line_width = (rect.width().min(rect.height()) / 8.).max(2.);
+    let size = rect.width().min(rect.height()) - line_width;
+    if size <= 0. {
+        return;
+    }
+    let x = rect.x() + (rect.width() - size) / 2.;
+    let y = rect.y() + (rect.height() - size) / 2.;
+
+    painter.save();
+    painter.save_pen();
+    painter.set_antialiasing(true);
+    painter.set_line_width(line_width);
+    painter.set_style(PaintStyle::Stroke);
+
+    painter.set_color(track_color);
+    painter.draw_arc_f(x, y, size, size, 0., 360., false);
+
+    let (from, to) = range;
+    if to > from {
+        painter.set_color(color);
+        painter.set_stroke_cap(PaintCap::Round);
+        painter.draw_arc_f(
+            x,
+            y,
+            size,
+            size,
+            -90. + 360. * from,
+            360. * (to - from),
+            false,
+        );
+        painter.set_stroke_cap(PaintCap::Butt);
+    }
+
+    painter.set_style(PaintStyle::Fill);
+    painter.restore_pen();
+    painter.restore();
+}
@@ -100,9 +100,16 @@ where
 
     // Prepare ApplicationWindow env: Create the `Board`.
     let mut board = Box::new(Board::new(logic_window.bitmap(), backend));
-
-    let mut window =
-        ApplicationWindow::new(logic_window.platform_type, width as i32, height as i32);
+    let scale_factor = logic_window.scale_factor;
+    board.set_scale_factor(scale_factor);
+
+    // The bitmap was in physical pixels, the window was layouted in logical pixels.
+    let mut window = ApplicationWindow::new(
+        logic_window.platform_type,
+        (width as f32 / scale_factor).round() as i32,
+        (height as f32 / scale_factor).round() as i32,
+    );
+    window.set_scale_factor(scale_factor);
     if let Some(parent_win) = logic_window.get_parent_window() {
         window.set_parent_window(parent_win);
     }
@@ -112,6 +119,7 @@ where
     window.set_board(board.as_mut());
     window.register_output(output_sender);
     window.set_ipc_bridge(logic_window.create_ipc_bridge());
+    window.set_ipc_hub(logic_window.create_ipc_hub_bridge());
     let init_pos = logic_window.initial_position;
     window.set_outer_position(init_pos.0);
     window.set_client_position(init_pos.1);
@@ -134,8 +142,7 @@ where
     let mut cpu_balance = CpuBalance::new();
     let mut frame_manager = FrameMgr::new();
     let mut resized = false;
-    let size = window.size();
-    let mut size_record = (size.width() as u32, size.height() as u32);
+    let mut size_record = (width, height);
 
     Application::<T, M>::set_app_started();
     drop(track);
@@ -181,6 +188,9 @@ where
                         if logic_window.ipc_type == IpcType::Master {
                             Application::<T, M>::send_event_ipc(evt);
                         }
+                        if let Some(ref hub) = logic_window.hub {
+                            Application::<T, M>::send_event_ipc_hub(hub, evt);
+                        }
                     }
                 }
                 Message::WindowClosed => {
@@ -216,6 +226,14 @@ where
                 on_user_event_receive,
             );
         }
+        if let Some(ref hub) = logic_window.hub {
+            Application::<T, M>::process_ipc_hub(
+                &mut window,
+                &mut cpu_balance,
+                hub,
+                on_user_event_receive.as_ref(),
+            );
+        }
         if let Some(ref on_rqst_receive) = on_request_receive {
             Application::<T, M>::process_request(&mut window, &mut cpu_balance, on_rqst_receive);
         } else {
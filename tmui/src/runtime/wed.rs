@@ -1,19 +1,21 @@
 use crate::{
     application,
     application_window::ApplicationWindow,
+    dnd,
     graphics::element::{ElementInner, HierachyZ},
     input::focus_mgr::FocusMgr,
+    menu::context_menu::popup_context_menu,
     prelude::*,
     primitive::{global_watch::GlobalWatchEvent, Message},
-    shortcut::mgr::ShortcutMgr,
+    shortcut::mgr::{KeyContext, ShortcutMgr},
 };
 use log::{debug, warn};
 use std::ptr::NonNull;
 use tlib::{
-    events::{downcast_event, Event, EventType, KeyEvent, MouseEvent, ResizeEvent},
-    namespace::KeyCode,
-    nonnull_mut,
-    object::ObjectOperation,
+    events::{downcast_event, Event, EventType, FileEvent, KeyEvent, MouseEvent, ResizeEvent},
+    namespace::{KeyCode, MouseButton},
+    nonnull_mut, nonnull_ref,
+    object::{ObjectOperation, TypeName},
     types::StaticType,
     values::ToValue,
 };
@@ -69,6 +71,10 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
                     widget.inner_mouse_pressed(evt.as_ref(), false);
                     widget.on_mouse_pressed(evt.as_ref());
 
+                    if evt.mouse_button() == MouseButton::RightButton {
+                        popup_context_menu(widget, pos);
+                    }
+
                     if widget.super_type().is_a(SharedWidget::static_type()) {
                         event = Some(evt);
                     }
@@ -83,6 +89,10 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
             let widgets_map = ApplicationWindow::widgets_of(window.id());
             let pos = evt.position().into();
 
+            if dnd::handle_mouse_release(window, pos) {
+                return event;
+            }
+
             let pressed_widget = window.pressed_widget();
             let prevent = window.handle_global_watch(GlobalWatchEvent::MouseReleased, |handle| {
                 handle.on_global_mouse_released(&evt)
@@ -145,6 +155,10 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
             let shadow_mouse_watch = ApplicationWindow::window_of(window.id()).shadow_mouse_watch();
             let pos = evt.position().into();
 
+            if dnd::handle_mouse_move(window, pos) {
+                return event;
+            }
+
             let prevent = window.handle_global_watch(GlobalWatchEvent::MouseMove, |handle| {
                 let prevent = handle.on_global_mouse_move(&evt);
                 if prevent {
@@ -302,16 +316,28 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
             let evt = downcast_event::<KeyEvent>(evt).unwrap();
             let widgets_map = ApplicationWindow::widgets_of(window.id());
 
+            if dnd::handle_key_pressed(window, &evt) {
+                return event;
+            }
+
             let prevent = window.handle_global_watch(GlobalWatchEvent::KeyPressed, |handle| {
                 handle.on_global_key_pressed(&evt)
             });
             if prevent {
                 return event;
             }
+            let window_name = window.name();
+            let key_ctx = KeyContext {
+                focused: window.focused_widget(),
+                widget: widgets_map
+                    .get(&window.focused_widget())
+                    .map(|widget| nonnull_ref!(widget).type_name()),
+                window: &window_name,
+            };
             let global_shorcut_triggered = ShortcutMgr::with(|shortcut_manager| {
                 let mut shortcut_manager = shortcut_manager.borrow_mut();
                 shortcut_manager.receive_key_event(&evt);
-                shortcut_manager.trigger_global()
+                shortcut_manager.trigger_global(&key_ctx)
             });
             if global_shorcut_triggered {
                 return None;
@@ -323,7 +349,7 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
 
                 if widget.id() == window.focused_widget() {
                     if !ShortcutMgr::with(|shortcut_manager| {
-                        shortcut_manager.borrow_mut().trigger(widget.id())
+                        shortcut_manager.borrow_mut().trigger(&key_ctx)
                     }) {
                         widget.inner_key_pressed(&evt);
                         widget.on_key_pressed(&evt);
@@ -425,10 +451,28 @@ pub(crate) fn win_evt_dispatch(window: &mut ApplicationWindow, evt: Event) -> Op
             event = Some(evt);
         }
 
+        // The file events carry no position, deliver to the widget under the cursor,
+        // or the focused widget.
+        EventType::DroppedFile | EventType::HoveredFile | EventType::HoveredFileCancelled => {
+            let evt = downcast_event::<FileEvent>(evt).unwrap();
+            let mut mouse_over = window.mouse_over_widget();
+            let widget = if mouse_over.is_some() {
+                Some(nonnull_mut!(mouse_over))
+            } else {
+                let focused = window.focused_widget();
+                window.find_id_mut(focused)
+            };
+
+            if let Some(widget) = widget {
+                widget.on_file_event(&evt);
+
+                if widget.super_type().is_a(SharedWidget::static_type()) {
+                    event = Some(evt);
+                }
+            }
+        }
+
         EventType::Moved => {}
-        EventType::DroppedFile => {}
-        EventType::HoveredFile => {}
-        EventType::HoveredFileCancelled => {}
         EventType::ReceivedCharacter => {}
         EventType::InputMethod => {}
         EventType::None => {}
@@ -453,6 +497,21 @@ pub(crate) fn message_handle(window: &mut ApplicationWindow, msg: Message) {
             window.set_client_position(inner_position);
         }
 
+        Message::WindowScaleFactorChanged(scale_factor) => {
+            window.set_scale_factor(scale_factor);
+            window.board().set_scale_factor(scale_factor);
+
+            // Re-layout with the logical size of current bitmap, the following resize event
+            // will correct the size if the platform changed the physical size of window.
+            let board = window.board();
+            let (width, height) = (board.width() as i32, board.height() as i32);
+            let size = window.to_logical_size(Size::new(width, height));
+            window.set_fixed_width(size.width());
+            window.set_fixed_height(size.height());
+            window.when_size_change(size);
+            Board::force_update();
+        }
+
         Message::WindowVisibilityChanged(bool) => {
             if !bool {
                 application::request_high_load(false);
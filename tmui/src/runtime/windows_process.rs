@@ -1,8 +1,10 @@
 use crate::{
     application::{self, Application, APP_STOPPED},
     cursor::Cursor,
+    dnd,
     opti::tracker::Tracker,
     platform::{
+        ipc_clipboard,
         ipc_window::IpcWindow,
         physical_window::{PhysWindow, PhysicalWindow},
         platform_win_op::set_undecoration_window,
@@ -10,6 +12,8 @@ use crate::{
     },
     primitive::{cpu_balance::CpuBalance, Message},
     runtime::{runtime_track::RuntimeTrack, window_context::OutputReceiver},
+    screen,
+    window::win_state,
     winit::{
         self,
         event::{Event, WindowEvent},
@@ -27,10 +31,10 @@ use std::{
 use tipc::{ipc_event::IpcEvent, raw_sync::Timeout, IpcNode};
 use tlib::{
     events::{
-        DeltaType, EventType, FocusEvent, KeyEvent, MouseEvent, ResizeEvent, WindowMaximized,
-        WindowMinimized, WindowRestored,
+        DeltaType, EventType, FileEvent, FocusEvent, KeyEvent, MouseEvent, ResizeEvent,
+        WindowMaximized, WindowMinimized, WindowRestored,
     },
-    figure::Point,
+    figure::{Point, Rect},
     global::to_static,
     namespace::{KeyCode, KeyboardModifier, MouseButton},
     object::ObjectId,
@@ -38,7 +42,7 @@ use tlib::{
     prelude::SystemCursorShape,
     typedef::WrappedWindowId,
     winit::{
-        dpi::{PhysicalPosition, PhysicalSize},
+        dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
         event::{ElementState, MouseScrollDelta},
         event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget},
         keyboard::{Key, ModifiersState, NamedKey, PhysicalKey},
@@ -151,12 +155,22 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                         cpu_balance.loop_start();
 
                         let mut user_events = vec![];
-                        for evt in master.read().try_recv_vec() {
+                        let events = master.read().try_recv_vec();
+                        for evt in events {
                             cpu_balance.add_payload(evt.payload_wieght());
 
-                            #[allow(clippy::single_match)]
                             match evt {
                                 IpcEvent::UserEvent(evt, _timestamp) => user_events.push(evt),
+                                IpcEvent::PeerLost => {
+                                    warn!("The ipc slave process was lost.")
+                                }
+                                // The master brokers the os clipboard for the slave.
+                                IpcEvent::ClipboardGet(id) => {
+                                    ipc_clipboard::respond_text(&*master.read(), id)
+                                }
+                                IpcEvent::ClipboardSet => {
+                                    ipc_clipboard::receive_set_text(&*master.read())
+                                }
                                 _ => {}
                             }
                         }
@@ -201,6 +215,8 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                     window: &mut PhysWindow<T, M>,
                     target: &EventLoopWindowTarget<Message>,
                 ) {
+                    win_state::record_closed(window.winit_window());
+
                     if window_id != main_window_id {
                         // Close the sub-window.
                         window.send_input(Message::WindowClosed);
@@ -301,7 +317,8 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
 
                                 let inner = window.winit_window().inner_position().expect("Get window inner_position failed.");
                                 let inner_position = Point::new(inner.x, inner.y);
-                                window.send_input(Message::WindowMoved(outer_position, inner_position))
+                                window.send_input(Message::WindowMoved(outer_position, inner_position));
+                                win_state::record(window.winit_window())
                             }
 
                             // Modifier change event.
@@ -473,6 +490,25 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                                 window.send_input(Message::Event(Box::new(FocusEvent::new(focus))));
                             }
 
+                            // Window was moved to the monitor with different scale factor,
+                            // the following resized event will carry the new physical size.
+                            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                                screen::refresh(target);
+                                window.send_input(Message::WindowScaleFactorChanged(scale_factor as f32));
+                            }
+
+                            WindowEvent::DroppedFile(path) => {
+                                window.send_input(Message::Event(Box::new(FileEvent::dropped(path))));
+                            }
+
+                            WindowEvent::HoveredFile(path) => {
+                                window.send_input(Message::Event(Box::new(FileEvent::hovered(path))));
+                            }
+
+                            WindowEvent::HoveredFileCancelled => {
+                                window.send_input(Message::Event(Box::new(FileEvent::hovered_cancel())));
+                            }
+
                             WindowEvent::Ime(_ime) => {}
 
                             _ => {}
@@ -633,7 +669,7 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
 
                                 let winit_window = window.winit_window();
                                 let old_size = winit_window.inner_size();
-                                let _ = winit_window.request_inner_size(PhysicalSize::new(size.width(), size.height()));
+                                let _ = winit_window.request_inner_size(LogicalSize::new(size.width(), size.height()));
                                 if self.is_windowed_widget_window(window_id) {
                                     self.send_resize_event(window_id, winit_window.inner_size());
                                 }
@@ -665,18 +701,20 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                                 let winit_window = window.winit_window();
                                 winit_window.set_outer_position(PhysicalPosition::new(rect.x(), rect.y()));
 
+                                // The size of widget was in logical pixels.
+                                let scale_factor = winit_window.scale_factor();
                                 let window_size = winit_window.inner_size();
                                 let width = rect.width();
                                 let width = if width == 0 {
                                     window_size.width
                                 } else {
-                                    width as u32
+                                    (width as f64 * scale_factor).round() as u32
                                 };
                                 let height = rect.height();
                                 let height = if height == 0 {
                                     window_size.height
                                 } else {
-                                    height as u32
+                                    (height as f64 * scale_factor).round() as u32
                                 };
                                 let new_win_size = PhysicalSize::new(width, height);
 
@@ -703,6 +741,25 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                                 debug!("[o2s] Receive child-window widget visibility changed. Correspondent widget id = {}, child window id = {:?}, visible = {}", id, win_id, visible);
                             }
 
+                            Message::DragOutside(source, pos, dropped) => {
+                                let target = self.windows.values().find_map(|w| {
+                                    let winit_window = w.winit_window();
+                                    if w.window_id() == source || winit_window.is_visible() == Some(false) {
+                                        return None;
+                                    }
+                                    let inner = winit_window.inner_position().ok()?;
+                                    let size = winit_window.inner_size();
+                                    let rect = Rect::new(inner.x, inner.y, size.width as i32, size.height as i32);
+                                    rect.contains(&pos).then(|| w.window_id())
+                                });
+
+                                for (window_id, msg) in dnd::route_outside(source, target, pos, dropped) {
+                                    if let Some(window) = self.windows.get(&window_id.into()) {
+                                        window.send_input(msg)
+                                    }
+                                }
+                            }
+
                             Message::WinWidgetSizeReverseRequest(win_id, size) => {
                                 let w_id = self.win_widget_map.iter().find_map(|(k, v)| {
                                     if v.eq(&win_id) {
@@ -748,6 +805,9 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
             window.context.1 .0,
         );
 
+        // The slave has no window of its own, the os clipboard was brokered by the master.
+        ipc_clipboard::set_forwarded(true);
+
         let ipc_slave_clone = window.slave.clone();
         thread::Builder::new()
             .name("ipc-thread".to_string())
@@ -757,7 +817,14 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                         return;
                     }
                     // Send to master
-                    ipc_slave_clone.read().try_send(message.into()).unwrap()
+                    match message {
+                        Message::ClipboardSet(text) => ipc_clipboard::send_text(
+                            &*ipc_slave_clone.read(),
+                            text,
+                            IpcEvent::ClipboardSet,
+                        ),
+                        message => ipc_slave_clone.read().try_send(message.into()).unwrap(),
+                    }
                 }
             })
             .unwrap();
@@ -776,11 +843,14 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
                 cpu_balance.add_payload(evt.payload_wieght());
 
                 match evt {
-                    IpcEvent::Exit => {
+                    IpcEvent::Exit | IpcEvent::PeerLost => {
                         APP_STOPPED.store(true, Ordering::Release);
                         break 'main;
                     }
                     IpcEvent::UserEvent(evt, _timestamp) => user_events.push(evt),
+                    // The slave renders the whole window, the regions were queried when needed.
+                    IpcEvent::RegionChanged(..) => {}
+                    IpcEvent::ClipboardText(id) => ipc_clipboard::receive_text(&*ipc_slave, id),
                     evt => input_sender
                         .send(Message::Event(evt.into()))
                         .unwrap_or_else(|_| {
@@ -832,6 +902,7 @@ impl<'a, T: 'static + Copy + Send + Sync, M: 'static + Copy + Send + Sync>
 
         let evt = ResizeEvent::new(size.width as i32, size.height as i32);
         window.send_input(Message::Event(Box::new(evt)));
+        win_state::record(window.winit_window());
 
         application::request_high_load(true);
     }
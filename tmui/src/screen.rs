@@ -0,0 +1,149 @@
+use crate::primitive::Message;
+use lazy_static::lazy_static;
+use tipc::parking_lot::Mutex;
+use tlib::{
+    figure::{Point, Rect},
+    winit::{event_loop::EventLoopWindowTarget, monitor::MonitorHandle},
+};
+
+lazy_static! {
+    /// The monitors were enumerated in the main thread when the windows were created
+    /// or the scale factor of window was changed, the primary monitor was at the first.
+    static ref MONITORS: Mutex<Vec<Monitor>> = Mutex::new(vec![]);
+}
+
+/// The information of a monitor.
+///
+/// The geometry and work area were in physical pixels on the `screen coordinate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    name: Option<String>,
+    geometry: Rect,
+    work_area: Rect,
+    scale_factor: f32,
+    primary: bool,
+}
+
+impl Monitor {
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The full area of the monitor.
+    #[inline]
+    pub fn geometry(&self) -> Rect {
+        self.geometry
+    }
+
+    /// The area of the monitor excluding the taskbar, dock or menu bar.
+    ///
+    /// Same as the geometry on the platforms which can not query the work area.
+    #[inline]
+    pub fn work_area(&self) -> Rect {
+        self.work_area
+    }
+
+    /// The scale factor to map logical pixels to physical pixels on the monitor.
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    #[inline]
+    pub fn is_primary(&self) -> bool {
+        self.primary
+    }
+
+    fn from_handle(handle: &MonitorHandle, primary: bool) -> Self {
+        let pos = handle.position();
+        let size = handle.size();
+        let geometry = Rect::new(pos.x, pos.y, size.width as i32, size.height as i32);
+
+        Self {
+            name: handle.name(),
+            geometry,
+            work_area: work_area(handle).unwrap_or(geometry),
+            scale_factor: handle.scale_factor() as f32,
+            primary,
+        }
+    }
+}
+
+/// The information of the monitors connected to the system.
+///
+/// The information was refreshed when the windows were created, or the scale factor
+/// of window was changed, so it was empty before the first window was created.
+pub struct Screen;
+
+impl Screen {
+    /// Get all the monitors, the primary monitor was at the first.
+    #[inline]
+    pub fn monitors() -> Vec<Monitor> {
+        MONITORS.lock().clone()
+    }
+
+    /// Get the primary monitor, or the first monitor if the platform has no
+    /// concept of primary monitor.
+    #[inline]
+    pub fn primary() -> Option<Monitor> {
+        MONITORS.lock().first().cloned()
+    }
+
+    /// Get the monitor contains the point on the `screen coordinate`.
+    #[inline]
+    pub fn monitor_at(point: Point) -> Option<Monitor> {
+        MONITORS
+            .lock()
+            .iter()
+            .find(|m| m.geometry.contains(&point))
+            .cloned()
+    }
+}
+
+/// Enumerate the monitors, should be called in the main thread.
+pub(crate) fn refresh(target: &EventLoopWindowTarget<Message>) {
+    let primary = target.primary_monitor();
+
+    let mut monitors: Vec<Monitor> = primary
+        .iter()
+        .map(|handle| Monitor::from_handle(handle, true))
+        .collect();
+    monitors.extend(
+        target
+            .available_monitors()
+            .filter(|handle| Some(handle) != primary.as_ref())
+            .map(|handle| Monitor::from_handle(&handle, false)),
+    );
+
+    *MONITORS.lock() = monitors;
+}
+
+#[cfg(windows_platform)]
+fn work_area(handle: &MonitorHandle) -> Option<Rect> {
+    use tlib::winit::platform::windows::MonitorHandleExtWindows;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, HMONITOR, MONITORINFO};
+
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    let ok = unsafe { GetMonitorInfoW(HMONITOR(handle.hmonitor()), &mut info) };
+    if !ok.as_bool() {
+        return None;
+    }
+
+    let rc = info.rcWork;
+    Some(Rect::new(
+        rc.left,
+        rc.top,
+        rc.right - rc.left,
+        rc.bottom - rc.top,
+    ))
+}
+
+#[cfg(not(windows_platform))]
+#[inline]
+fn work_area(_: &MonitorHandle) -> Option<Rect> {
+    None
+}
@@ -1,11 +1,12 @@
-use lazy_static::lazy_static;
-use tlib::{connect, skia_safe::ImageInfo};
+use log::error;
+use tipc::mem::region_table::MAX_REGION_ID_LEN;
+use tlib::{connect, emit, signals, skia_safe::ImageInfo};
 
 use crate::{
     application,
     backend::create_image_info,
     opti::tracker::Tracker,
-    platform::PlatformType,
+    platform::{ipc_bridge::IpcBridge, PlatformType},
     prelude::*,
     tlib::{
         object::{ObjectImpl, ObjectSubclass},
@@ -14,33 +15,30 @@ use crate::{
     widget::WidgetImpl,
 };
 
-lazy_static! {
-    static ref SUPPORTED_CHARACTER: Vec<u8> = {
-        let mut chs = vec![];
-        for c in b'0'..=b'9' {
-            chs.push(c)
-        }
-        for c in b'a'..=b'z' {
-            chs.push(c)
-        }
-        for c in b'A'..=b'Z' {
-            chs.push(c)
-        }
-        for c in b"!@#$%^&*()-_+=/\\" {
-            chs.push(*c)
-        }
-        chs
-    };
-}
-
 #[extends(Widget)]
 #[run_after]
 pub struct SharedWidget {
-    shared_id: Option<&'static str>,
+    shared_id: Option<String>,
+    /// The channel of the `IpcHub` which serves the slave, `None` for the master of application.
+    shared_channel: Option<String>,
+    channel_bridge: Option<Box<dyn IpcBridge>>,
     image_info: ImageInfo,
     run_aftered: bool,
 }
 
+pub trait SharedWidgetSignals: ActionExt {
+    signals!(
+        SharedWidgetSignals:
+
+        /// Emit when the slave was connected to the `shared_channel`.
+        slave_connected();
+
+        /// Emit when the slave of the `shared_channel` was disconnected, or it was lost.
+        slave_disconnected();
+    );
+}
+impl SharedWidgetSignals for SharedWidget {}
+
 impl ObjectSubclass for SharedWidget {
     const NAME: &'static str = "SharedWidget";
 }
@@ -62,12 +60,19 @@ impl WidgetImpl for SharedWidget {
         if ApplicationWindow::window_of(self.window_id()).platform_type() == PlatformType::Ipc {
             panic!("`SharedWidget` can not be used on `PlatformType::Ipc`")
         }
-        let bridge = self.window().ipc_bridge().unwrap();
+        if let Some(ref channel) = self.shared_channel {
+            let hub = self.window().ipc_hub().unwrap();
+            let bridge = hub
+                .add_channel(channel)
+                .unwrap_or_else(|e| panic!("Add the channel `{}` failed: {}", channel, e));
+            self.channel_bridge = Some(bridge);
+        }
+        let bridge = self.bridge();
 
         let size = self.size();
         bridge.create_buffer(size.width() as u32, size.height() as u32);
 
-        bridge.add_shared_region(self.shared_id(), self.rect());
+        self.add_shared_region();
 
         self.image_info = create_image_info((size.width(), size.height()));
         self.run_aftered = true;
@@ -75,9 +80,9 @@ impl WidgetImpl for SharedWidget {
 }
 
 pub trait SharedWidgetExt {
-    fn shared_id(&self) -> &'static str;
+    fn shared_id(&self) -> &str;
 
-    fn set_shared_id(&mut self, id: &'static str);
+    fn set_shared_id(&mut self, id: &str);
 
     fn image_info(&self) -> &ImageInfo;
 
@@ -86,19 +91,34 @@ pub trait SharedWidgetExt {
     fn shared_validate(&self);
 
     fn pixels_render(&mut self, painter: &mut Painter);
+
+    /// The channel of [`IpcHub`](tipc::ipc_hub::IpcHub) which serves the slave of this widget,
+    /// `None` if the slave was served by the master of application.
+    fn shared_channel(&self) -> Option<&str>;
+
+    /// Serve the slave of this widget by its own channel, so that each `SharedWidget`
+    /// can embed a different slave application. The slave application should be built with
+    /// the same [`shared_channel`](crate::application::ApplicationBuilder::shared_channel).
+    ///
+    /// Should be set before the widget was shown.
+    fn set_shared_channel(&mut self, channel: &str);
+
+    /// Called when the slave of `shared_channel` was connected or disconnected.
+    fn on_channel_changed(&mut self, connected: bool);
 }
 
 impl SharedWidgetExt for SharedWidget {
     #[inline]
-    fn shared_id(&self) -> &'static str {
+    fn shared_id(&self) -> &str {
         self.shared_id
+            .as_deref()
             .expect("`SharedWidget` should set the `shared_id`")
     }
 
     #[inline]
-    fn set_shared_id(&mut self, id: &'static str) {
+    fn set_shared_id(&mut self, id: &str) {
         self.check_shared_id(id);
-        self.shared_id = Some(id)
+        self.shared_id = Some(id.to_string())
     }
 
     #[inline]
@@ -108,53 +128,109 @@ impl SharedWidgetExt for SharedWidget {
 
     #[inline]
     fn is_shared_invalidate(&self) -> bool {
-        ApplicationWindow::window_of(self.window_id())
-            .ipc_bridge()
-            .unwrap()
-            .is_invalidate()
+        self.bridge().is_invalidate()
     }
 
     #[inline]
     fn shared_validate(&self) {
-        ApplicationWindow::window_of(self.window_id())
-            .ipc_bridge()
-            .unwrap()
-            .set_invalidate(false)
+        self.bridge().set_invalidate(false)
     }
 
     fn pixels_render(&mut self, painter: &mut Painter) {
-        let bridge = ApplicationWindow::window_of(self.window_id())
-            .ipc_bridge()
-            .unwrap();
+        let bridge = self.bridge();
 
         let tracker = Tracker::start("shared_widget_wait_prepared");
-        bridge.wait_prepared();
+        let prepared = bridge.wait_prepared();
         drop(tracker);
+        if !prepared {
+            return;
+        }
 
         let tracker = Tracker::start("shared_widget_locked_read_buffer");
         let (buffer, _guard) = bridge.buffer();
+        // Should be taken under the lock, the slave records them under the write lock.
+        let dirty_rects = bridge.take_dirty_rects();
         drop(tracker);
 
         let size = self.size();
         let row_bytes = size.width() as usize * 4;
 
         let _tracker = Tracker::start("shared_widget_draw_pixels");
-        painter.draw_pixels(self.image_info(), buffer, row_bytes, (0, 0));
+        match dirty_rects {
+            // Only copy the dirty rects, unless the widget itself was updated on master side.
+            Some(rects) if !self.invalidate() => {
+                let bounds = Rect::new(0, 0, size.width(), size.height());
+                for rect in rects.iter().filter_map(|r| r.intersects(&bounds)) {
+                    if !rect.is_valid() {
+                        continue;
+                    }
+                    let offset = rect.y() as usize * row_bytes + rect.x() as usize * 4;
+                    let info = self
+                        .image_info()
+                        .with_dimensions((rect.width(), rect.height()));
+                    painter.draw_pixels(&info, &buffer[offset..], row_bytes, (rect.x(), rect.y()));
+                }
+            }
+            _ => painter.draw_pixels(self.image_info(), buffer, row_bytes, (0, 0)),
+        }
+    }
+
+    #[inline]
+    fn shared_channel(&self) -> Option<&str> {
+        self.shared_channel.as_deref()
+    }
+
+    #[inline]
+    fn set_shared_channel(&mut self, channel: &str) {
+        if self.run_aftered {
+            panic!("The `shared_channel` of `SharedWidget` should be set before it was shown.")
+        }
+        self.shared_channel = Some(channel.to_string())
+    }
+
+    #[inline]
+    fn on_channel_changed(&mut self, connected: bool) {
+        if connected {
+            emit!(self, slave_connected())
+        } else {
+            emit!(self, slave_disconnected())
+        }
     }
 }
 
 impl SharedWidget {
+    /// The region table of master was full, the slave could not query the region of this widget.
+    #[inline]
+    fn add_shared_region(&self) {
+        if !self.bridge().add_shared_region(self.shared_id(), self.rect()) {
+            error!(
+                "Add the shared region `{}` failed, the regions reached the `max_regions` of master.",
+                self.shared_id()
+            )
+        }
+    }
+
+    /// The bridge to the master which serves the slave of this widget.
+    #[inline]
+    fn bridge(&self) -> &dyn IpcBridge {
+        match self.channel_bridge {
+            Some(ref bridge) => bridge.as_ref(),
+            None => self.window().ipc_bridge().unwrap(),
+        }
+    }
+
     #[inline]
     fn on_geometry_changed(&mut self, _: FRect) {
-        self.window()
-            .ipc_bridge()
-            .unwrap()
-            .add_shared_region(self.shared_id(), self.rect());
+        // The channel was added when the widget run after.
+        if self.shared_channel.is_some() && self.channel_bridge.is_none() {
+            return;
+        }
+        self.add_shared_region();
 
         if self.run_aftered {
             let size = self.size();
 
-            let bridge = self.window().ipc_bridge().unwrap();
+            let bridge = self.bridge();
 
             bridge.pretreat_resize(size.width(), size.height());
 
@@ -165,13 +241,11 @@ impl SharedWidget {
 
     #[inline]
     fn check_shared_id(&self, shared_id: &str) {
-        if shared_id.len() > 18 {
-            panic!("The maximum length of `shared_id` of SharedWidget is 18.")
-        }
-        for b in shared_id.as_bytes() {
-            if !SUPPORTED_CHARACTER.contains(b) {
-                panic!("Unsupported character {}, `shared_id` only support character: [0-9][a-z][A-Z][!@#$%^&*()-_+=/\\]", *b as char)
-            }
+        if shared_id.len() > MAX_REGION_ID_LEN {
+            panic!(
+                "The maximum bytes length of `shared_id` of SharedWidget is {}.",
+                MAX_REGION_ID_LEN
+            )
         }
     }
 }
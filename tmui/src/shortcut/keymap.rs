@@ -0,0 +1,596 @@
+use super::{KeyChord, Shortcut};
+use lazy_static::lazy_static;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tipc::parking_lot::Mutex;
+
+lazy_static! {
+    /// The keymaps were shared by all the windows, every window has it's own UI thread,
+    /// the `ShortcutMgr` of each UI thread picks up the effective keymap from here.
+    static ref KEYMAPS: Mutex<Keymaps> = Mutex::new(Keymaps::default());
+}
+
+/// The prefix of action name in user keymap, which removes the bindings of the action.
+///
+/// For example: `{ key = "Control+S", action = "-file.save" }` removes the binding `Control+S`
+/// of action `file.save`, if `key` was empty, all the bindings of `file.save` will be removed.
+pub const UNBIND_PREFIX: char = '-';
+
+/// The condition under which a key binding takes effect,
+/// all the specified fields should be satisfied.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BindingContext {
+    /// The type name of focused widget, the [`ObjectSubclass::NAME`](tlib::object::ObjectSubclass::NAME), e.g. `"Text"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget: Option<String>,
+
+    /// The name of window which receives the key event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+}
+
+impl BindingContext {
+    #[inline]
+    pub fn widget(mut self, widget: impl ToString) -> Self {
+        self.widget = Some(widget.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn window(mut self, window: impl ToString) -> Self {
+        self.window = Some(window.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn matches(&self, widget: Option<&str>, window: &str) -> bool {
+        self.widget
+            .as_ref()
+            .map_or(true, |w| Some(w.as_str()) == widget)
+            && self.window.as_ref().map_or(true, |w| w == window)
+    }
+
+    /// Whether there was a situation satisfies both of the contexts.
+    #[inline]
+    pub fn overlaps(&self, other: &BindingContext) -> bool {
+        fn compatible(a: &Option<String>, b: &Option<String>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        }
+        compatible(&self.widget, &other.widget) && compatible(&self.window, &other.window)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub shortcut: Shortcut,
+    pub action: String,
+    pub context: BindingContext,
+}
+
+/// Two bindings which can not be distinguished by the key strokes in some situations,
+/// either the shortcuts were the same, or one was the beginning of the other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapConflict {
+    pub first: KeyBinding,
+    pub second: KeyBinding,
+}
+
+impl Display for KeymapConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` of action `{}` conflicts with `{}` of action `{}`",
+            self.first.shortcut, self.first.action, self.second.shortcut, self.second.action
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct KeymapError {
+    msg: String,
+}
+impl KeymapError {
+    pub fn new(msg: impl ToString) -> Self {
+        Self {
+            msg: msg.to_string(),
+        }
+    }
+}
+impl Error for KeymapError {}
+impl Display for KeymapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+/// The entry of keymap file.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyBindingEntry {
+    #[serde(default)]
+    key: String,
+    action: String,
+    #[serde(flatten)]
+    context: BindingContext,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeymapFile {
+    #[serde(default)]
+    bindings: Vec<KeyBindingEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonKeymapFile {
+    List(Vec<KeyBindingEntry>),
+    File(KeymapFile),
+}
+
+pub(crate) enum KeymapMatch<'a> {
+    /// The chords matched the binding of the action.
+    Action(&'a str),
+    /// The chords was the beginning of some bindings.
+    Pending,
+    None,
+}
+
+/// The bindings between the shortcuts and the named actions.
+///
+/// The bindings added later take precedence over the earlier ones.
+///
+/// The keymap file can be written in TOML:
+/// ```toml
+/// [[bindings]]
+/// key = "Control+K, Control+S"
+/// action = "file.save_all"
+///
+/// [[bindings]]
+/// key = "Control+Enter"
+/// action = "editor.insert_line_below"
+/// widget = "CodeEditor"
+/// window = "main"
+///
+/// # Remove the default binding.
+/// [[bindings]]
+/// key = "Control+S"
+/// action = "-file.save"
+/// ```
+///
+/// Or in JSON, both the list of bindings and the object with `bindings` field are acceptable:
+/// ```json
+/// [
+///     { "key": "Control+K, Control+S", "action": "file.save_all" },
+///     { "key": "Control+Enter", "action": "editor.insert_line_below", "widget": "CodeEditor" }
+/// ]
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Keymap {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn bind(&mut self, shortcut: Shortcut, action: impl ToString, context: BindingContext) {
+        self.bindings.push(KeyBinding {
+            shortcut,
+            action: action.to_string(),
+            context,
+        })
+    }
+
+    /// Remove the bindings of the action, if `shortcut` was `None`, remove all of them.
+    #[inline]
+    pub fn unbind(&mut self, action: &str, shortcut: Option<&Shortcut>) {
+        self.bindings
+            .retain(|b| b.action != action || shortcut.is_some_and(|s| *s != b.shortcut))
+    }
+
+    #[inline]
+    pub fn bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    /// Apply the bindings of `other` on this keymap, the bindings with action
+    /// prefixed by [`UNBIND_PREFIX`] remove the existing bindings.
+    pub fn merge(&mut self, other: &Keymap) {
+        for binding in other.bindings.iter() {
+            if let Some(action) = binding.action.strip_prefix(UNBIND_PREFIX) {
+                let shortcut = (!binding.shortcut.is_empty()).then_some(&binding.shortcut);
+                self.unbind(action, shortcut);
+            } else {
+                self.bindings.push(binding.clone());
+            }
+        }
+    }
+
+    /// Get all the shortcuts bound to the action.
+    #[inline]
+    pub fn keys_for(&self, action: &str) -> Vec<&Shortcut> {
+        self.bindings
+            .iter()
+            .rev()
+            .filter(|b| b.action == action)
+            .map(|b| &b.shortcut)
+            .collect()
+    }
+
+    /// Get all the bindings of the shortcut.
+    #[inline]
+    pub fn bindings_of(&self, shortcut: &Shortcut) -> Vec<&KeyBinding> {
+        self.bindings
+            .iter()
+            .rev()
+            .filter(|b| b.shortcut == *shortcut)
+            .collect()
+    }
+
+    /// Find the bindings of different actions that can not be distinguished.
+    pub fn conflicts(&self) -> Vec<KeymapConflict> {
+        let mut conflicts = vec![];
+        for (i, first) in self.bindings.iter().enumerate() {
+            for second in self.bindings[i + 1..].iter() {
+                if first.action == second.action || !first.context.overlaps(&second.context) {
+                    continue;
+                }
+                if first.shortcut.starts_with(second.shortcut.chords())
+                    || second.shortcut.starts_with(first.shortcut.chords())
+                {
+                    conflicts.push(KeymapConflict {
+                        first: first.clone(),
+                        second: second.clone(),
+                    })
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, KeymapError> {
+        let file: KeymapFile = toml::from_str(content).map_err(KeymapError::new)?;
+        Self::from_entries(file.bindings)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, KeymapError> {
+        let entries = match serde_json::from_str(content).map_err(KeymapError::new)? {
+            JsonKeymapFile::List(entries) => entries,
+            JsonKeymapFile::File(file) => file.bindings,
+        };
+        Self::from_entries(entries)
+    }
+
+    /// Load the keymap file, the format was determined by the extension, `.json` or `.toml`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeymapError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            KeymapError::new(format!("Read keymap `{}` failed: {}", path.display(), e))
+        })?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&content),
+            Some("toml") => Self::from_toml(&content),
+            _ => Err(KeymapError::new(format!(
+                "Unsupported keymap file `{}`, expected `.toml` or `.json`.",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String, KeymapError> {
+        toml::to_string_pretty(&self.to_file()).map_err(KeymapError::new)
+    }
+
+    pub fn to_json(&self) -> Result<String, KeymapError> {
+        serde_json::to_string_pretty(&self.to_file().bindings).map_err(KeymapError::new)
+    }
+
+    fn from_entries(entries: Vec<KeyBindingEntry>) -> Result<Self, KeymapError> {
+        let mut keymap = Self::new();
+        for entry in entries {
+            let shortcut = if entry.key.trim().is_empty() {
+                if !entry.action.starts_with(UNBIND_PREFIX) {
+                    return Err(KeymapError::new(format!(
+                        "Missing key of action `{}`.",
+                        entry.action
+                    )));
+                }
+                Shortcut::default()
+            } else {
+                entry.key.parse()?
+            };
+            keymap.bind(shortcut, entry.action, entry.context);
+        }
+        Ok(keymap)
+    }
+
+    fn to_file(&self) -> KeymapFile {
+        KeymapFile {
+            bindings: self
+                .bindings
+                .iter()
+                .map(|b| KeyBindingEntry {
+                    key: b.shortcut.to_string(),
+                    action: b.action.clone(),
+                    context: b.context.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Find the action bound to `chords` in the context,
+    /// only the actions satisfy `available` will be considered.
+    pub(crate) fn resolve<F: Fn(&str) -> bool>(
+        &self,
+        chords: &[KeyChord],
+        widget: Option<&str>,
+        window: &str,
+        available: F,
+    ) -> KeymapMatch<'_> {
+        let mut pending = false;
+        for binding in self.bindings.iter().rev() {
+            if !binding.shortcut.starts_with(chords)
+                || !binding.context.matches(widget, window)
+                || !available(&binding.action)
+            {
+                continue;
+            }
+            if binding.shortcut.len() == chords.len() {
+                return KeymapMatch::Action(&binding.action);
+            }
+            pending = true;
+        }
+
+        if pending {
+            KeymapMatch::Pending
+        } else {
+            KeymapMatch::None
+        }
+    }
+}
+
+#[derive(Default)]
+struct Keymaps {
+    default_keymap: Keymap,
+    user_keymap: Keymap,
+    user_keymap_path: Option<PathBuf>,
+    /// The default keymap merged with the user keymap.
+    keymap: Arc<Keymap>,
+    /// Increased every time the effective keymap was rebuilt.
+    generation: usize,
+}
+
+impl Keymaps {
+    fn rebuild(&mut self) -> Vec<KeymapConflict> {
+        let mut keymap = self.default_keymap.clone();
+        keymap.merge(&self.user_keymap);
+        self.keymap = Arc::new(keymap);
+        self.generation += 1;
+
+        let conflicts = self.keymap.conflicts();
+        for conflict in conflicts.iter() {
+            warn!("[ShortcutMgr] Keymap conflict: {}", conflict);
+        }
+        conflicts
+    }
+}
+
+/// Get the effective keymap shared by all the UI threads and it's generation.
+#[inline]
+pub(crate) fn effective_keymap() -> (Arc<Keymap>, usize) {
+    let keymaps = KEYMAPS.lock();
+    (keymaps.keymap.clone(), keymaps.generation)
+}
+
+/// Set the default keymap of application, normally contains the bindings of all the actions.
+/// The keymap takes effect in all the windows.
+///
+/// @return the conflicts in the effective keymap(default keymap merged with user keymap).
+#[inline]
+pub fn set_default_keymap(keymap: Keymap) -> Vec<KeymapConflict> {
+    let mut keymaps = KEYMAPS.lock();
+    keymaps.default_keymap = keymap;
+    keymaps.rebuild()
+}
+
+/// Load the user keymap file, which will be merged over the default keymap.
+/// The path was remembered for [`reload_keymap`].
+///
+/// @return the conflicts in the effective keymap.
+#[inline]
+pub fn load_keymap(path: impl AsRef<Path>) -> Result<Vec<KeymapConflict>, KeymapError> {
+    let keymap = Keymap::load(path.as_ref())?;
+
+    let mut keymaps = KEYMAPS.lock();
+    keymaps.user_keymap = keymap;
+    keymaps.user_keymap_path = Some(path.as_ref().to_path_buf());
+    Ok(keymaps.rebuild())
+}
+
+/// Reload the user keymap file loaded by [`load_keymap`] at runtime,
+/// e.g. when the file was modified.
+///
+/// If the reloading failed, the previous keymap will be kept.
+pub fn reload_keymap() -> Result<Vec<KeymapConflict>, KeymapError> {
+    let path = KEYMAPS
+        .lock()
+        .user_keymap_path
+        .clone()
+        .ok_or_else(|| KeymapError::new("No user keymap file was loaded."))?;
+    load_keymap(path)
+}
+
+/// Get the shortcuts which trigger the action, the effective one comes first.
+#[inline]
+pub fn keys_for_action(action: &str) -> Vec<Shortcut> {
+    effective_keymap()
+        .0
+        .keys_for(action)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// Get the conflicts in the effective keymap.
+#[inline]
+pub fn keymap_conflicts() -> Vec<KeymapConflict> {
+    effective_keymap().0.conflicts()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        keys_for_action, set_default_keymap, BindingContext, Keymap, KEYMAPS, UNBIND_PREFIX,
+    };
+    use crate::prelude::*;
+    use std::thread;
+    use tlib::shortcut;
+
+    #[test]
+    fn test_keymap_file() {
+        let toml = r#"
+            [[bindings]]
+            key = "Control+K, Control+S"
+            action = "file.save_all"
+
+            [[bindings]]
+            key = "ctrl+enter"
+            action = "editor.insert_line_below"
+            widget = "CodeEditor"
+
+            [[bindings]]
+            key = "Control+S"
+            action = "-file.save"
+        "#;
+        let user = Keymap::from_toml(toml).unwrap();
+        assert_eq!(user.bindings().len(), 3);
+        assert_eq!(
+            user.bindings()[1].context,
+            BindingContext::default().widget("CodeEditor")
+        );
+
+        let json = r#"[
+            { "key": "Control+K, Control+S", "action": "file.save_all" },
+            { "key": "ctrl+enter", "action": "editor.insert_line_below", "widget": "CodeEditor" },
+            { "key": "Control+S", "action": "-file.save" }
+        ]"#;
+        assert_eq!(Keymap::from_json(json).unwrap(), user);
+        assert_eq!(Keymap::from_json(&user.to_json().unwrap()).unwrap(), user);
+        assert_eq!(Keymap::from_toml(&user.to_toml().unwrap()).unwrap(), user);
+
+        assert!(Keymap::from_toml("[[bindings]]\naction = \"file.save\"").is_err());
+        assert!(
+            Keymap::from_toml("[[bindings]]\nkey = \"Control+Foo\"\naction = \"file.save\"")
+                .is_err()
+        );
+
+        let mut keymap = Keymap::new();
+        keymap.bind(
+            shortcut!(Control + S),
+            "file.save",
+            BindingContext::default(),
+        );
+        keymap.bind(
+            shortcut!(Control + Shift + S),
+            "file.save",
+            BindingContext::default(),
+        );
+        keymap.merge(&user);
+        assert_eq!(
+            keymap.keys_for("file.save"),
+            vec![&shortcut!(Control + Shift + S)]
+        );
+        assert_eq!(
+            keymap.keys_for("file.save_all"),
+            vec![&shortcut!(Control + K, Control + S)]
+        );
+
+        let mut unbind_all = Keymap::new();
+        unbind_all.bind(
+            Shortcut::default(),
+            format!("{}file.save", UNBIND_PREFIX),
+            BindingContext::default(),
+        );
+        keymap.merge(&unbind_all);
+        assert!(keymap.keys_for("file.save").is_empty());
+    }
+
+    #[test]
+    fn test_keymap_conflicts() {
+        let mut keymap = Keymap::new();
+        keymap.bind(
+            shortcut!(Control + K),
+            "editor.delete_line",
+            BindingContext::default(),
+        );
+        keymap.bind(
+            shortcut!(Control + K, Control + S),
+            "file.save_all",
+            BindingContext::default(),
+        );
+        keymap.bind(
+            shortcut!(Control + D),
+            "editor.duplicate",
+            BindingContext::default().widget("CodeEditor"),
+        );
+        keymap.bind(
+            shortcut!(Control + D),
+            "list.deselect",
+            BindingContext::default().widget("ListView"),
+        );
+        keymap.bind(
+            shortcut!(Control + D),
+            "bookmark.add",
+            BindingContext::default().window("browser"),
+        );
+
+        let conflicts = keymap.conflicts();
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts[0].first.action, "editor.delete_line");
+        assert_eq!(conflicts[0].second.action, "file.save_all");
+        assert_eq!(conflicts[1].second.action, "bookmark.add");
+        assert_eq!(conflicts[2].first.action, "list.deselect");
+    }
+
+    #[test]
+    fn test_keymap_shared_across_threads() {
+        // The default keymap was global, restored even if the test failed.
+        struct RestoreKeymap(Keymap);
+        impl Drop for RestoreKeymap {
+            fn drop(&mut self) {
+                set_default_keymap(std::mem::take(&mut self.0));
+            }
+        }
+        let _restore = RestoreKeymap(KEYMAPS.lock().default_keymap.clone());
+
+        // Each window has it's own UI thread, the keymap set in one of them takes effect in all.
+        thread::spawn(|| {
+            let mut keymap = Keymap::new();
+            keymap.bind(
+                shortcut!(Control + Alt + F12),
+                "test.shared_keymap",
+                BindingContext::default(),
+            );
+            set_default_keymap(keymap);
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(
+            keys_for_action("test.shared_keymap"),
+            vec![shortcut!(Control + Alt + F12)]
+        );
+    }
+}
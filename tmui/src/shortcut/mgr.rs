@@ -1,34 +1,74 @@
-use super::{Shortcut, ShortcutTrigger};
+use super::{
+    keymap::{effective_keymap, Keymap, KeymapMatch},
+    KeyChord, Shortcut, ShortcutTrigger,
+};
 use crate::widget::{WidgetHnd, WidgetImpl};
-use nohash_hasher::IntMap;
-use std::{cell::RefCell, ptr::NonNull};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ptr::NonNull,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tlib::{
     events::{EventTrait, EventType, KeyEvent},
     nonnull_mut, nonnull_ref,
     object::ObjectId,
 };
 
+/// The default maximum interval between two chords of a shortcut sequence.
+pub(crate) const DEFAULT_CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
 thread_local! {
     static INSTANCE: RefCell<ShortcutMgr> = RefCell::new(ShortcutMgr::new());
 }
 
-type ShortcutMap = IntMap<Shortcut, Vec<(WidgetHnd, Box<dyn Fn(&mut dyn WidgetImpl)>)>>;
+type Handlers = Vec<(WidgetHnd, Box<dyn Fn(&mut dyn WidgetImpl)>)>;
+type ShortcutMap = HashMap<Shortcut, Handlers>;
+type ActionMap = HashMap<String, Handlers>;
+
+/// The situation in which the key event was received.
+pub(crate) struct KeyContext<'a> {
+    /// The id of focused widget.
+    pub(crate) focused: ObjectId,
+    /// The type name of focused widget.
+    pub(crate) widget: Option<&'static str>,
+    /// The name of window.
+    pub(crate) window: &'a str,
+}
 
 pub(crate) struct ShortcutMgr {
-    shortcut: Shortcut,
+    /// The chords pressed so far, waiting for the rest chords of a shortcut sequence.
+    pending: Vec<KeyChord>,
+    last_stroke: Option<Instant>,
+    chord_timeout: Duration,
 
     shortcuts: ShortcutMap,
 
     global_shortcuts: ShortcutMap,
+
+    actions: ActionMap,
+
+    global_actions: ActionMap,
+
+    /// The effective keymap shared by all the UI threads, see [`effective_keymap()`].
+    keymap: Arc<Keymap>,
+    keymap_generation: usize,
 }
 
 impl ShortcutMgr {
     #[inline]
     pub(crate) fn new() -> Self {
         Self {
-            shortcut: Shortcut::empty(),
+            pending: vec![],
+            last_stroke: None,
+            chord_timeout: DEFAULT_CHORD_TIMEOUT,
             shortcuts: Default::default(),
             global_shortcuts: Default::default(),
+            actions: Default::default(),
+            global_actions: Default::default(),
+            keymap: Default::default(),
+            keymap_generation: 0,
         }
     }
 
@@ -40,6 +80,11 @@ impl ShortcutMgr {
         INSTANCE.with(f)
     }
 
+    #[inline]
+    pub(crate) fn set_chord_timeout(&mut self, timeout: Duration) {
+        self.chord_timeout = timeout
+    }
+
     #[inline]
     pub(crate) fn register_shortcut<F: Fn(&mut dyn WidgetImpl) + 'static>(
         &mut self,
@@ -47,6 +92,9 @@ impl ShortcutMgr {
         widget: &mut dyn WidgetImpl,
         f: F,
     ) {
+        if shortcut.is_empty() {
+            return;
+        }
         self.shortcuts
             .entry(shortcut)
             .or_default()
@@ -60,27 +108,82 @@ impl ShortcutMgr {
         widget: &mut dyn WidgetImpl,
         f: F,
     ) {
+        if shortcut.is_empty() {
+            return;
+        }
         self.global_shortcuts
             .entry(shortcut)
             .or_default()
             .push((NonNull::new(widget), Box::new(f)));
     }
 
+    #[inline]
+    pub(crate) fn register_action<F: Fn(&mut dyn WidgetImpl) + 'static>(
+        &mut self,
+        action: &str,
+        widget: &mut dyn WidgetImpl,
+        f: F,
+    ) {
+        self.actions
+            .entry(action.to_string())
+            .or_default()
+            .push((NonNull::new(widget), Box::new(f)));
+    }
+
+    #[inline]
+    pub(crate) fn register_global_action<F: Fn(&mut dyn WidgetImpl) + 'static>(
+        &mut self,
+        action: &str,
+        widget: &mut dyn WidgetImpl,
+        f: F,
+    ) {
+        self.global_actions
+            .entry(action.to_string())
+            .or_default()
+            .push((NonNull::new(widget), Box::new(f)));
+    }
+
     #[inline]
     pub(crate) fn remove_shortcut_all(&mut self, id: ObjectId) {
-        for shortcuts in self.shortcuts.values_mut() {
-            shortcuts.retain(|(r, _)| nonnull_ref!(r).id() != id);
+        for handlers in self
+            .shortcuts
+            .values_mut()
+            .chain(self.global_shortcuts.values_mut())
+            .chain(self.actions.values_mut())
+            .chain(self.global_actions.values_mut())
+        {
+            handlers.retain(|(r, _)| nonnull_ref!(r).id() != id);
         }
-        for shortcuts in self.global_shortcuts.values_mut() {
-            shortcuts.retain(|(r, _)| nonnull_ref!(r).id() != id);
+        self.shortcuts.retain(|_, v| !v.is_empty());
+        self.global_shortcuts.retain(|_, v| !v.is_empty());
+        self.actions.retain(|_, v| !v.is_empty());
+        self.global_actions.retain(|_, v| !v.is_empty());
+    }
+
+    /// Pick up the effective keymap if it was changed by other UI threads,
+    /// the pending chords were discarded since the bindings have changed.
+    fn sync_keymap(&mut self) {
+        let (keymap, generation) = effective_keymap();
+        if generation != self.keymap_generation {
+            self.keymap = keymap;
+            self.keymap_generation = generation;
+            self.pending.clear();
         }
     }
 
-    #[inline]
-    pub(crate) fn trigger(&mut self, id: ObjectId) -> bool {
-        let mut trigged = false;
+    /// Trigger the shortcut of the focused widget which matches the pending chords.
+    ///
+    /// @return true if the shortcut was triggered, or the pending chords was the beginning of
+    ///         a global shortcut, a shortcut of the widget or a key binding in the context,
+    ///         the key event should not be delivered to the widget in both cases.
+    pub(crate) fn trigger(&mut self, ctx: &KeyContext) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        let id = ctx.focused;
 
-        if let Some(widgets) = self.shortcuts.get_mut(&self.shortcut) {
+        let mut trigged = false;
+        if let Some(widgets) = self.shortcuts.get_mut(self.pending.as_slice()) {
             widgets.iter_mut().for_each(|(widget, f)| {
                 let widget = nonnull_mut!(widget);
                 if id == widget.id() {
@@ -89,34 +192,130 @@ impl ShortcutMgr {
                 }
             })
         }
+        if trigged {
+            self.pending.clear();
+            return true;
+        }
+
+        let waiting = self
+            .global_shortcuts
+            .keys()
+            .any(|s| s.len() > self.pending.len() && s.starts_with(&self.pending))
+            || self.shortcuts.iter().any(|(s, widgets)| {
+                s.len() > self.pending.len()
+                    && s.starts_with(&self.pending)
+                    && widgets.iter().any(|(w, _)| nonnull_ref!(w).id() == id)
+            })
+            || matches!(
+                self.keymap
+                    .resolve(&self.pending, ctx.widget, ctx.window, |action| {
+                        self.action_available(action, id)
+                    }),
+                KeymapMatch::Pending
+            );
+        if !waiting {
+            self.pending.clear();
+        }
+
+        waiting
+    }
+
+    /// Trigger the global shortcut, or the action bound in keymap which matches the pending chords.
+    pub(crate) fn trigger_global(&mut self, ctx: &KeyContext) -> bool {
+        let mut trigged = false;
+
+        if let Some(widgets) = self.global_shortcuts.get_mut(self.pending.as_slice()) {
+            widgets.iter_mut().for_each(|(widget, f)| {
+                f(nonnull_mut!(widget));
+                trigged = true;
+            })
+        }
+
+        if !trigged {
+            let action =
+                match self
+                    .keymap
+                    .resolve(&self.pending, ctx.widget, ctx.window, |action| {
+                        self.action_available(action, ctx.focused)
+                    }) {
+                    KeymapMatch::Action(action) => Some(action.to_string()),
+                    _ => None,
+                };
+            if let Some(action) = action {
+                trigged = self.trigger_action(&action, ctx.focused);
+            }
+        }
+
+        if trigged {
+            self.pending.clear();
+        }
 
         trigged
     }
 
-    #[inline]
-    pub(crate) fn trigger_global(&mut self) -> bool {
+    /// Trigger the global handlers of action, and the handlers registered by the focused widget.
+    pub(crate) fn trigger_action(&mut self, action: &str, focused: ObjectId) -> bool {
         let mut trigged = false;
 
-        if let Some(widgets) = self.global_shortcuts.get_mut(&self.shortcut) {
+        if let Some(widgets) = self.global_actions.get_mut(action) {
             widgets.iter_mut().for_each(|(widget, f)| {
                 f(nonnull_mut!(widget));
                 trigged = true;
             })
         }
+        if let Some(widgets) = self.actions.get_mut(action) {
+            widgets.iter_mut().for_each(|(widget, f)| {
+                let widget = nonnull_mut!(widget);
+                if focused == widget.id() {
+                    f(widget);
+                    trigged = true;
+                }
+            })
+        }
 
         trigged
     }
 
-    #[inline]
+    fn action_available(&self, action: &str, focused: ObjectId) -> bool {
+        self.global_actions.contains_key(action)
+            || self
+                .actions
+                .get(action)
+                .is_some_and(|widgets| widgets.iter().any(|(w, _)| nonnull_ref!(w).id() == focused))
+    }
+
     pub(crate) fn receive_key_event(&mut self, evt: &KeyEvent) {
-        match evt.event_type() {
-            EventType::KeyPress => {
-                self.shortcut.insert(evt.trigger_shortcut());
-            }
-            EventType::KeyRelease => {
-                self.shortcut = Shortcut::empty();
-            }
-            _ => {}
+        if evt.event_type() != EventType::KeyPress {
+            return;
+        }
+        let Some(chord) = evt.trigger_chord() else {
+            return;
+        };
+        self.sync_keymap();
+
+        let now = Instant::now();
+        if self
+            .last_stroke
+            .is_some_and(|last| now.duration_since(last) > self.chord_timeout)
+        {
+            self.pending.clear();
         }
+        self.last_stroke = Some(now);
+
+        self.pending.push(chord);
+
+        // The sequence was broken, restart from the current chord.
+        if self.pending.len() > 1 && !self.is_registered_prefix(&self.pending) {
+            self.pending.clear();
+            self.pending.push(chord);
+        }
+    }
+
+    fn is_registered_prefix(&self, chords: &[KeyChord]) -> bool {
+        self.global_shortcuts
+            .keys()
+            .chain(self.shortcuts.keys())
+            .chain(self.keymap.bindings().iter().map(|b| &b.shortcut))
+            .any(|s| s.starts_with(chords))
     }
 }
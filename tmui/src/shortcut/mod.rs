@@ -1,91 +1,277 @@
+pub mod keymap;
 pub mod mgr;
 
-use self::mgr::ShortcutMgr;
+use self::{keymap::KeymapError, mgr::ShortcutMgr};
 use crate::widget::WidgetImpl;
-use nohash_hasher::IsEnabled;
+use std::{borrow::Borrow, fmt::Display, str::FromStr, time::Duration};
+use strum::IntoEnumIterator;
 use tlib::{
-    bitflags::bitflags,
     events::KeyEvent,
     namespace::{KeyCode, KeyboardModifier},
 };
 
-bitflags! {
-    /// To simplify construction, use proc-macro [`shortcut!`](crate::tlib::shortcut).
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    pub struct Shortcut: u64 {
-        // Modifiers.
-        const Control = 1;
-        const Alt = 1 << 1;
-        const Shift = 1 << 2;
-        const Meta = 1 << 3;
-
-        // Letters.
-        const A = 1 << 4;
-        const B = 1 << 5;
-        const C = 1 << 6;
-        const D = 1 << 7;
-        const E = 1 << 8;
-        const F = 1 << 9;
-        const G = 1 << 10;
-        const H = 1 << 11;
-        const I = 1 << 12;
-        const J = 1 << 13;
-        const K = 1 << 14;
-        const L = 1 << 15;
-        const M = 1 << 16;
-        const N = 1 << 17;
-        const O = 1 << 18;
-        const P = 1 << 19;
-        const Q = 1 << 20;
-        const R = 1 << 21;
-        const S = 1 << 22;
-        const T = 1 << 23;
-        const U = 1 << 24;
-        const V = 1 << 25;
-        const W = 1 << 26;
-        const X = 1 << 27;
-        const Y = 1 << 28;
-        const Z = 1 << 29;
-
-        // The number keys over the letters.
-        const Key0 = 1 << 30;
-        const Key1 = 1 << 31;
-        const Key2 = 1 << 32;
-        const Key3 = 1 << 33;
-        const Key4 = 1 << 34;
-        const Key5 = 1 << 35;
-        const Key6 = 1 << 36;
-        const Key7 = 1 << 37;
-        const Key8 = 1 << 38;
-        const Key9 = 1 << 39;
-
-        const F1 = 1 << 40;
-        const F2 = 1 << 41;
-        const F3 = 1 << 42;
-        const F4 = 1 << 43;
-        const F5 = 1 << 44;
-        const F6 = 1 << 45;
-        const F7 = 1 << 46;
-        const F8 = 1 << 47;
-        const F9 = 1 << 48;
-        const F10 = 1 << 49;
-        const F11 = 1 << 50;
-        const F12 = 1 << 51;
-
-        // Some funcional keys:
-        const Insert = 1 << 52;
-        const Delete = 1 << 53;
-        const Home = 1 << 54;
-        const End = 1 << 55;
-        const PageUp = 1 << 56;
-        const PageDown = 1 << 57;
-        const Left = 1 << 58;
-        const Up = 1 << 59;
-        const Right = 1 << 60;
-        const Down = 1 << 61;
+/// The modifiers which take effect in shortcuts.
+const SHORTCUT_MODIFIERS: [KeyboardModifier; 4] = [
+    KeyboardModifier::ShiftModifier,
+    KeyboardModifier::ControlModifier,
+    KeyboardModifier::AltModifier,
+    KeyboardModifier::MetaModifier,
+];
+
+/// One key stroke of the shortcut, a non-modifier key pressed with some modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    key: KeyCode,
+    modifier: KeyboardModifier,
+}
+
+impl KeyChord {
+    /// Only the `Control`, `Alt`, `Shift` and `Meta` of `modifier` are kept.
+    #[inline]
+    pub fn new(key: KeyCode, modifier: KeyboardModifier) -> Self {
+        let mask = SHORTCUT_MODIFIERS
+            .iter()
+            .fold(0, |mask, m| mask | m.as_u32());
+
+        Self {
+            key,
+            modifier: KeyboardModifier::from(modifier.as_u32() & mask),
+        }
+    }
+
+    #[inline]
+    pub fn key(&self) -> KeyCode {
+        self.key
+    }
+
+    #[inline]
+    pub fn modifier(&self) -> KeyboardModifier {
+        self.modifier
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifier.ctrl() {
+            f.write_str("Control+")?;
+        }
+        if self.modifier.alt() {
+            f.write_str("Alt+")?;
+        }
+        if self.modifier.shift() {
+            f.write_str("Shift+")?;
+        }
+        if self.modifier.meta() {
+            f.write_str("Meta+")?;
+        }
+        f.write_str(self.key.name())
+    }
+}
+
+/// The sequence of key chords, e.g. `Control+K, Control+S`.
+/// A shortcut triggered when all the chords were pressed in order,
+/// and each chord was pressed within the timeout after the previous one.
+///
+/// To simplify construction, use proc-macro [`shortcut!`](crate::tlib::shortcut).
+///
+/// Shortcut can also be parsed from string, e.g. `"Control+K, Control+S"`, `"ctrl+k ctrl+s"`,
+/// the names of keys were case insensitive.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    chords: Vec<KeyChord>,
+}
+
+impl Shortcut {
+    #[inline]
+    pub fn new(chords: Vec<KeyChord>) -> Self {
+        Self { chords }
+    }
+
+    /// Append the next chord to the sequence.
+    #[inline]
+    pub fn then(mut self, chord: KeyChord) -> Self {
+        self.chords.push(chord);
+        self
+    }
+
+    #[inline]
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.chords
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chords.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chords.is_empty()
+    }
+
+    /// Whether the `chords` was the beginning part of this shortcut(includes the whole).
+    #[inline]
+    pub fn starts_with(&self, chords: &[KeyChord]) -> bool {
+        self.chords.starts_with(chords)
+    }
+}
+
+impl From<KeyChord> for Shortcut {
+    #[inline]
+    fn from(value: KeyChord) -> Self {
+        Self {
+            chords: vec![value],
+        }
+    }
+}
+
+impl Borrow<[KeyChord]> for Shortcut {
+    #[inline]
+    fn borrow(&self) -> &[KeyChord] {
+        &self.chords
     }
 }
-impl IsEnabled for Shortcut {}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, chord) in self.chords.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            chord.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = KeymapError;
+
+    /// Chords were separated by `,` or whitespaces, keys in chord were joined by `+`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut chords = vec![];
+        let mut keys: Vec<String> = vec![];
+        let mut i = 0;
+
+        loop {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i == chars.len() {
+                break;
+            }
+
+            // Read the name of key, or a single punctuation like `,` and `+`.
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            if i == start {
+                i += 1;
+            }
+            keys.push(chars[start..i].iter().collect());
+
+            let mut next = i;
+            while next < chars.len() && chars[next].is_whitespace() {
+                next += 1;
+            }
+            match chars.get(next) {
+                Some('+') => {
+                    i = next + 1;
+                    continue;
+                }
+                Some(',') => i = next + 1,
+                _ => i = next,
+            }
+            chords.push(parse_chord(&std::mem::take(&mut keys))?);
+        }
+        if !keys.is_empty() {
+            return Err(KeymapError::new(format!("Incomplete shortcut `{}`.", s)));
+        }
+
+        Ok(Self { chords })
+    }
+}
+
+fn parse_chord(keys: &[String]) -> Result<KeyChord, KeymapError> {
+    let mut modifier = KeyboardModifier::NoModifier;
+    let mut key_code = None;
+
+    for key in keys {
+        let m = match key.to_ascii_lowercase().as_str() {
+            "control" | "ctrl" => Some(KeyboardModifier::ControlModifier),
+            "alt" | "option" => Some(KeyboardModifier::AltModifier),
+            "shift" => Some(KeyboardModifier::ShiftModifier),
+            "meta" | "cmd" | "super" | "win" => Some(KeyboardModifier::MetaModifier),
+            _ => None,
+        };
+        if let Some(m) = m {
+            modifier = modifier.or(m);
+            continue;
+        }
+
+        if key_code.is_some() {
+            return Err(KeymapError::new(format!(
+                "Only one non-modifier key was allowed in chord `{}`.",
+                keys.join("+")
+            )));
+        }
+        key_code = Some(
+            key_code_of(key).ok_or_else(|| KeymapError::new(format!("Unknown key `{}`.", key)))?,
+        );
+    }
+
+    key_code
+        .map(|key| KeyChord::new(key, modifier))
+        .ok_or_else(|| {
+            KeymapError::new(format!(
+                "Missing non-modifier key in chord `{}`.",
+                keys.join("+")
+            ))
+        })
+}
+
+/// Find the key code by the name of key, or the name of [`KeyCode`] variant without the `Key` prefix.
+fn key_code_of(name: &str) -> Option<KeyCode> {
+    let name = match name.to_ascii_lowercase().as_str() {
+        "esc" => "Escape".to_string(),
+        "del" => "Delete".to_string(),
+        _ => name.to_string(),
+    };
+
+    KeyCode::iter()
+        .filter(|code| !is_modifier_key(*code))
+        .find(|code| {
+            code.name().eq_ignore_ascii_case(&name)
+                || format!("{:?}", code)
+                    .strip_prefix("Key")
+                    .is_some_and(|n| n.eq_ignore_ascii_case(&name))
+        })
+}
+
+/// The modifier keys(and the unknown key) can't be the key of chord, they were never
+/// triggered as a chord.
+#[inline]
+fn is_modifier_key(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Unknown
+            | KeyCode::KeyShift
+            | KeyCode::KeyControl
+            | KeyCode::KeyAlt
+            | KeyCode::KeyMeta
+            | KeyCode::KeySuperL
+            | KeyCode::KeySuperR
+    )
+}
+
+/// Set the maximum interval between two chords of a shortcut sequence,
+/// the pressed chords will be discarded after the timeout, default was 1500ms.
+#[inline]
+pub fn set_chord_timeout(timeout: Duration) {
+    ShortcutMgr::with(|shortcut_manager| shortcut_manager.borrow_mut().set_chord_timeout(timeout))
+}
 
 pub trait ShortcutRegister: WidgetImpl + Sized {
     /// Widget register the shortcut handdle. <br>
@@ -116,96 +302,42 @@ pub trait ShortcutRegister: WidgetImpl + Sized {
                 .register_global_shortcut(shortcut, self, f)
         });
     }
+
+    /// Widget register the handle of named action, e.g. `"file.save"`, which was triggered
+    /// by the key bindings in [`Keymap`](keymap::Keymap) when the widget was focused.
+    #[inline]
+    fn register_action<F: Fn(&mut dyn WidgetImpl) + 'static>(&mut self, action: &str, f: F) {
+        ShortcutMgr::with(|shortcut_manager| {
+            shortcut_manager
+                .borrow_mut()
+                .register_action(action, self, f)
+        });
+    }
+
+    /// Widget register the global handle of named action, which was triggered
+    /// by the key bindings in [`Keymap`](keymap::Keymap) regardless of the focus.
+    #[inline]
+    fn register_global_action<F: Fn(&mut dyn WidgetImpl) + 'static>(&mut self, action: &str, f: F) {
+        ShortcutMgr::with(|shortcut_manager| {
+            shortcut_manager
+                .borrow_mut()
+                .register_global_action(action, self, f)
+        });
+    }
 }
 impl<T: WidgetImpl> ShortcutRegister for T {}
 
 pub(crate) trait ShortcutTrigger {
-    fn trigger_shortcut(&self) -> Shortcut;
+    /// @return `None` if the pressed key was a modifier key itself.
+    fn trigger_chord(&self) -> Option<KeyChord>;
 }
 impl ShortcutTrigger for KeyEvent {
-    fn trigger_shortcut(&self) -> Shortcut {
-        let mut shortcut = Shortcut::empty();
-
-        let modifier = self.modifier();
-        if modifier.has(KeyboardModifier::ShiftModifier) {
-            shortcut.insert(Shortcut::Shift)
-        }
-        if modifier.has(KeyboardModifier::ControlModifier) {
-            shortcut.insert(Shortcut::Control)
+    fn trigger_chord(&self) -> Option<KeyChord> {
+        let key = self.key_code();
+        if is_modifier_key(key) {
+            return None;
         }
-        if modifier.has(KeyboardModifier::AltModifier) {
-            shortcut.insert(Shortcut::Alt)
-        }
-        if modifier.has(KeyboardModifier::MetaModifier) {
-            shortcut.insert(Shortcut::Meta)
-        }
-
-        match self.key_code() {
-            KeyCode::KeyA => shortcut.insert(Shortcut::A),
-            KeyCode::KeyB => shortcut.insert(Shortcut::B),
-            KeyCode::KeyC => shortcut.insert(Shortcut::C),
-            KeyCode::KeyD => shortcut.insert(Shortcut::D),
-            KeyCode::KeyE => shortcut.insert(Shortcut::E),
-            KeyCode::KeyF => shortcut.insert(Shortcut::F),
-            KeyCode::KeyG => shortcut.insert(Shortcut::G),
-            KeyCode::KeyH => shortcut.insert(Shortcut::H),
-            KeyCode::KeyI => shortcut.insert(Shortcut::I),
-            KeyCode::KeyJ => shortcut.insert(Shortcut::J),
-            KeyCode::KeyK => shortcut.insert(Shortcut::K),
-            KeyCode::KeyL => shortcut.insert(Shortcut::L),
-            KeyCode::KeyM => shortcut.insert(Shortcut::M),
-            KeyCode::KeyN => shortcut.insert(Shortcut::N),
-            KeyCode::KeyO => shortcut.insert(Shortcut::O),
-            KeyCode::KeyP => shortcut.insert(Shortcut::P),
-            KeyCode::KeyQ => shortcut.insert(Shortcut::Q),
-            KeyCode::KeyR => shortcut.insert(Shortcut::R),
-            KeyCode::KeyS => shortcut.insert(Shortcut::S),
-            KeyCode::KeyT => shortcut.insert(Shortcut::T),
-            KeyCode::KeyU => shortcut.insert(Shortcut::U),
-            KeyCode::KeyV => shortcut.insert(Shortcut::V),
-            KeyCode::KeyW => shortcut.insert(Shortcut::W),
-            KeyCode::KeyX => shortcut.insert(Shortcut::X),
-            KeyCode::KeyY => shortcut.insert(Shortcut::Y),
-            KeyCode::KeyZ => shortcut.insert(Shortcut::Z),
-
-            KeyCode::Key0 => shortcut.insert(Shortcut::Key0),
-            KeyCode::Key1 => shortcut.insert(Shortcut::Key1),
-            KeyCode::Key2 => shortcut.insert(Shortcut::Key2),
-            KeyCode::Key3 => shortcut.insert(Shortcut::Key3),
-            KeyCode::Key4 => shortcut.insert(Shortcut::Key4),
-            KeyCode::Key5 => shortcut.insert(Shortcut::Key5),
-            KeyCode::Key6 => shortcut.insert(Shortcut::Key6),
-            KeyCode::Key7 => shortcut.insert(Shortcut::Key7),
-            KeyCode::Key8 => shortcut.insert(Shortcut::Key8),
-            KeyCode::Key9 => shortcut.insert(Shortcut::Key9),
-
-            KeyCode::KeyF1 => shortcut.insert(Shortcut::F1),
-            KeyCode::KeyF2 => shortcut.insert(Shortcut::F2),
-            KeyCode::KeyF3 => shortcut.insert(Shortcut::F3),
-            KeyCode::KeyF4 => shortcut.insert(Shortcut::F4),
-            KeyCode::KeyF5 => shortcut.insert(Shortcut::F5),
-            KeyCode::KeyF6 => shortcut.insert(Shortcut::F6),
-            KeyCode::KeyF7 => shortcut.insert(Shortcut::F7),
-            KeyCode::KeyF8 => shortcut.insert(Shortcut::F8),
-            KeyCode::KeyF9 => shortcut.insert(Shortcut::F9),
-            KeyCode::KeyF10 => shortcut.insert(Shortcut::F10),
-            KeyCode::KeyF11 => shortcut.insert(Shortcut::F11),
-            KeyCode::KeyF12 => shortcut.insert(Shortcut::F12),
-
-            KeyCode::KeyInsert => shortcut.insert(Shortcut::Insert),
-            KeyCode::KeyDelete => shortcut.insert(Shortcut::Delete),
-            KeyCode::KeyHome => shortcut.insert(Shortcut::Home),
-            KeyCode::KeyEnd => shortcut.insert(Shortcut::End),
-            KeyCode::KeyPageUp => shortcut.insert(Shortcut::PageUp),
-            KeyCode::KeyPageDown => shortcut.insert(Shortcut::PageDown),
-            KeyCode::KeyLeft => shortcut.insert(Shortcut::Left),
-            KeyCode::KeyUp => shortcut.insert(Shortcut::Up),
-            KeyCode::KeyRight => shortcut.insert(Shortcut::Right),
-            KeyCode::KeyDown => shortcut.insert(Shortcut::Down),
-            _ => {}
-        }
-
-        shortcut
+        Some(KeyChord::new(key, self.modifier()))
     }
 }
 
@@ -222,7 +354,7 @@ macro_rules! cast_do {
 mod tests {
     use crate::shortcut::ShortcutTrigger;
 
-    use super::Shortcut;
+    use super::{KeyChord, Shortcut};
     use tlib::{
         events::{EventType, KeyEvent},
         namespace::{KeyCode, KeyboardModifier},
@@ -231,19 +363,81 @@ mod tests {
 
     #[test]
     fn test_shortcut_macro() {
-        let shortcut = shortcut!(Control + Alt + A + F1 + 0);
-
-        assert!(shortcut.contains(Shortcut::Control));
-        assert!(shortcut.contains(Shortcut::Alt));
-        assert!(shortcut.contains(Shortcut::A));
-        assert!(shortcut.contains(Shortcut::F1));
-        assert!(shortcut.contains(Shortcut::Key0));
-
-        assert!(!shortcut.contains(Shortcut::Meta));
-        assert!(!shortcut.contains(Shortcut::Shift));
-        assert!(!shortcut.contains(Shortcut::Q));
-        assert!(!shortcut.contains(Shortcut::F11));
-        assert!(!shortcut.contains(Shortcut::Key1));
+        let shortcut = shortcut!(Control + Alt + F1);
+        let modifier = KeyboardModifier::ControlModifier.or(KeyboardModifier::AltModifier);
+        assert_eq!(
+            shortcut.chords(),
+            &[KeyChord::new(KeyCode::KeyF1, modifier)]
+        );
+
+        let shortcut = shortcut!(Shift + 0);
+        assert_eq!(
+            shortcut.chords(),
+            &[KeyChord::new(
+                KeyCode::Key0,
+                KeyboardModifier::ShiftModifier
+            )]
+        );
+
+        let shortcut = shortcut!(Enter);
+        assert_eq!(
+            shortcut.chords(),
+            &[KeyChord::new(
+                KeyCode::KeyEnter,
+                KeyboardModifier::NoModifier
+            )]
+        );
+
+        let shortcut = shortcut!(Control + K, Control + S);
+        assert_eq!(
+            shortcut,
+            Shortcut::from(KeyChord::new(
+                KeyCode::KeyK,
+                KeyboardModifier::ControlModifier
+            ))
+            .then(KeyChord::new(
+                KeyCode::KeyS,
+                KeyboardModifier::ControlModifier
+            ))
+        );
+        assert!(shortcut.starts_with(shortcut!(Control + K).chords()));
+        assert!(!shortcut.starts_with(shortcut!(Control + S).chords()));
+        assert_eq!(shortcut.to_string(), "Control+K, Control+S");
+
+        // The order of modifiers does not matter.
+        assert_eq!(
+            shortcut!(Shift + Alt + Control + Numpad1),
+            shortcut!(Control + Alt + Shift + Numpad1)
+        );
+    }
+
+    #[test]
+    fn test_shortcut_parse() {
+        let parse = |s: &str| s.parse::<Shortcut>().unwrap();
+
+        assert_eq!(
+            parse("Control+K, Control+S"),
+            shortcut!(Control + K, Control + S)
+        );
+        assert_eq!(parse("ctrl+k ctrl+s"), shortcut!(Control + K, Control + S));
+        assert_eq!(
+            parse("Shift + Alt + Numpad1"),
+            shortcut!(Alt + Shift + Numpad1)
+        );
+        assert_eq!(parse("ctrl+,"), shortcut!(Control + Comma));
+        assert_eq!(parse("Control++"), shortcut!(Control + Plus));
+        assert_eq!(parse("esc"), shortcut!(Escape));
+        assert_eq!(parse("F13"), shortcut!(F13));
+
+        let shortcut = shortcut!(Control + Shift + PageDown, Alt + Space);
+        assert_eq!(parse(&shortcut.to_string()), shortcut);
+
+        assert!("Control+".parse::<Shortcut>().is_err());
+        assert!("Control+Shift".parse::<Shortcut>().is_err());
+        assert!("Control+A+B".parse::<Shortcut>().is_err());
+        assert!("Control+Foo".parse::<Shortcut>().is_err());
+        assert!("Control+SuperL".parse::<Shortcut>().is_err());
+        assert!("SuperR".parse::<Shortcut>().is_err());
     }
 
     #[test]
@@ -251,9 +445,21 @@ mod tests {
         let key_code = KeyCode::KeyA;
         let modifier = KeyboardModifier::ControlModifier
             .or(KeyboardModifier::AltModifier)
-            .or(KeyboardModifier::ShiftModifier);
+            .or(KeyboardModifier::ShiftModifier)
+            .or(KeyboardModifier::KeypadModifier);
         let evt = KeyEvent::new(EventType::KeyPress, key_code, modifier, "A");
 
-        assert_eq!(evt.trigger_shortcut(), shortcut!(Control + Alt + Shift + A))
+        assert_eq!(
+            Shortcut::from(evt.trigger_chord().unwrap()),
+            shortcut!(Control + Alt + Shift + A)
+        );
+
+        let evt = KeyEvent::new(
+            EventType::KeyPress,
+            KeyCode::KeyControl,
+            KeyboardModifier::ControlModifier,
+            "",
+        );
+        assert!(evt.trigger_chord().is_none());
     }
 }
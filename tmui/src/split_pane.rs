@@ -15,8 +15,19 @@ use crate::{
 };
 use log::debug;
 use nohash_hasher::IntMap;
-use std::{mem::size_of, ptr::NonNull};
-use tlib::{implements_enum_value, namespace::AsNumeric, nonnull_mut, split_pane_impl};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display, mem::size_of, ptr::NonNull};
+use tlib::{
+    implements_enum_value, namespace::AsNumeric, nonnull_mut, nonnull_ref, split_pane_impl,
+    split_pane_init,
+};
+
+/// The default ratio of the widget splited off.
+pub const DEFAULT_SPLIT_RATIO: f32 = 0.5;
+/// The default minimum width/height of the split panes while resizing.
+pub const DEFAULT_MIN_PANE_SIZE: i32 = 30;
+/// The extent of splitter handle on each side of the boundary between panes.
+const HANDLE_EXTENT: i32 = 2;
 
 #[extends(Container)]
 pub struct SplitPane {
@@ -24,6 +35,8 @@ pub struct SplitPane {
     split_infos: IntMap<ObjectId, Box<SplitInfo>>,
     /// The vector to hold all the raw pointer of SplitInfo, ensure execution order.
     split_infos_vec: Vec<Option<NonNull<SplitInfo>>>,
+    /// The state of splitter handles.
+    split_handle: SplitHandle,
 }
 
 impl ObjectSubclass for SplitPane {
@@ -31,9 +44,14 @@ impl ObjectSubclass for SplitPane {
 }
 
 impl ObjectImpl for SplitPane {
+    fn initialize(&mut self) {
+        split_pane_init!();
+    }
+
     fn type_register(&self, type_registry: &mut TypeRegistry) {
         type_registry.register::<SplitPane, ReflectSplitInfosGetter>();
         type_registry.register::<SplitPane, ReflectSizeUnifiedAdjust>();
+        type_registry.register::<SplitPane, ReflectInnerCustomizeEventProcess>();
     }
 }
 
@@ -134,8 +152,9 @@ impl ContainerLayout for SplitPane {
 
         let parent_rect = widget.contents_rect(None);
         let split_infos_getter = cast_mut!(widget as SplitInfosGetter).unwrap();
+        let min_size = split_infos_getter.split_handle().min_pane_size;
         for split_info in split_infos_getter.split_infos_vec() {
-            nonnull_mut!(split_info).calculate_layout(parent_rect, true)
+            nonnull_mut!(split_info).calculate_layout(parent_rect, min_size, true)
         }
         for split_info in split_infos_getter.split_infos_vec() {
             let split_info = nonnull_mut!(split_info);
@@ -152,6 +171,9 @@ pub trait SplitInfosGetter {
 
     /// Get the plit infos deque.
     fn split_infos_vec(&mut self) -> &mut Vec<Option<NonNull<SplitInfo>>>;
+
+    /// Get the state of splitter handles.
+    fn split_handle(&mut self) -> &mut SplitHandle;
 }
 
 pub trait SplitPaneExt {
@@ -189,13 +211,56 @@ pub trait SplitPaneExt {
 
     /// **Do not call this function directly, use `remove_children()` instead.**
     ///
-    /// Close the split pane, the widgets were splited from this pane will be closed automatically.
+    /// Close the split pane, the first widget splited from this pane will take its place,
+    /// the other widgets splited from this pane will be splited from the first one.
     ///
     /// @param: id the id of target widget to close. <br>
     fn close_pane(&mut self, id: ObjectId);
 
     /// Common function of split().
     fn split<T: WidgetImpl>(&mut self, id: ObjectId, widget: Tr<T>, ty: SplitType);
+
+    /// Get the ratio of the widget in the area shared with the widget it was splited from.
+    ///
+    /// @param: id the id of target widget. <br>
+    ///
+    /// @return `None` if the widget was not exist.
+    fn split_ratio(&self, id: ObjectId) -> Option<f32>;
+
+    /// Set the ratio of the widget in the area shared with the widget it was splited from,
+    /// the ratio was clamped in `[0., 1.]`, and the panes will not be smaller than
+    /// [`min_pane_size()`](SplitPaneExt::min_pane_size).
+    ///
+    /// The ratio was also changed by dragging the splitter handles between panes.
+    ///
+    /// @param: id the id of target widget. <br>
+    /// @param ratio the new ratio. <br>
+    fn set_split_ratio(&mut self, id: ObjectId, ratio: f32);
+
+    /// Get the minimum width/height of the panes.
+    fn min_pane_size(&self) -> i32;
+
+    /// Set the minimum width/height of the panes.
+    ///
+    /// The default value was [`DEFAULT_MIN_PANE_SIZE`].
+    fn set_min_pane_size(&mut self, size: i32);
+
+    /// Get the serializable layout of the split tree, `None` if the split pane was empty.
+    ///
+    /// @param: key_of get the key of widget, which was used to recreate the widget when restoring. <br>
+    fn split_layout<F: Fn(&dyn WidgetImpl) -> String>(&self, key_of: F) -> Option<SplitLayout>;
+
+    /// Remove all the widgets, and recreate the split tree by the layout.
+    ///
+    /// The widget whose key was unknown(factory returns `None`) was skipped,
+    /// the widgets splited from it will be splited from its base widget.
+    ///
+    /// @param: layout the layout to restore. <br>
+    /// @param: factory create the widget by key. <br>
+    fn restore_split_layout<T, F>(&mut self, layout: &SplitLayout, factory: F)
+    where
+        T: WidgetImpl,
+        F: FnMut(&str) -> Option<Tr<T>>;
 }
 
 split_pane_impl!(SplitPane);
@@ -211,6 +276,10 @@ pub struct SplitInfo {
     pub split_to: Vec<Option<NonNull<SplitInfo>>>,
     /// The split type.
     pub ty: SplitType,
+    /// The ratio of the widget in the area shared with the widget it was splited from.
+    pub ratio: f32,
+    /// The area shared with the widget it was splited from, recorded when calculating layout.
+    pub area: Rect,
 }
 
 impl SplitInfo {
@@ -227,10 +296,76 @@ impl SplitInfo {
             split_from,
             split_to: vec![],
             ty,
+            ratio: DEFAULT_SPLIT_RATIO,
+            area: Rect::default(),
+        }
+    }
+
+    /// Get the layout of this widget and the widgets splited from it.
+    pub fn layout(&self, key_of: &dyn Fn(&dyn WidgetImpl) -> String) -> SplitLayout {
+        let widget = unsafe { self.widget.as_ref().unwrap().as_ref() };
+        SplitLayout {
+            key: key_of(widget),
+            ty: self.ty,
+            ratio: self.ratio,
+            splits: self
+                .split_to
+                .iter()
+                .map(|split| nonnull_ref!(split).layout(key_of))
+                .collect(),
+        }
+    }
+
+    /// Get the rect of splitter handle between the widget and the widget it was splited from,
+    /// in `window coordinate`.
+    pub fn handle_rect(&self) -> Option<Rect> {
+        let area = self.area;
+        let size = unsafe { self.widget.as_ref().unwrap().as_ref() }.size();
+
+        let (x, y, width, height) = match self.ty {
+            SplitType::SplitNone => return None,
+            SplitType::SplitLeft => (area.x() + size.width(), area.y(), 0, area.height()),
+            SplitType::SplitRight => (
+                area.x() + area.width() - size.width(),
+                area.y(),
+                0,
+                area.height(),
+            ),
+            SplitType::SplitUp => (area.x(), area.y() + size.height(), area.width(), 0),
+            SplitType::SplitDown => (
+                area.x(),
+                area.y() + area.height() - size.height(),
+                area.width(),
+                0,
+            ),
+        };
+
+        Some(if width == 0 {
+            Rect::new(x - HANDLE_EXTENT, y, HANDLE_EXTENT * 2, height)
+        } else {
+            Rect::new(x, y - HANDLE_EXTENT, width, HANDLE_EXTENT * 2)
+        })
+    }
+
+    /// Get the ratio when the splitter handle was dragged to the position in `window coordinate`.
+    pub fn ratio_at(&self, pos: Point, min_size: i32) -> f32 {
+        let area = self.area;
+        let (size, total) = match self.ty {
+            SplitType::SplitNone => return self.ratio,
+            SplitType::SplitLeft => (pos.x() - area.x(), area.width()),
+            SplitType::SplitRight => (area.x() + area.width() - pos.x(), area.width()),
+            SplitType::SplitUp => (pos.y() - area.y(), area.height()),
+            SplitType::SplitDown => (area.y() + area.height() - pos.y(), area.height()),
+        };
+        if total <= 0 {
+            return self.ratio;
         }
+
+        let min_size = min_size.min(total / 2).max(0);
+        size.clamp(min_size, total - min_size) as f32 / total as f32
     }
 
-    pub fn calculate_layout(&mut self, parent_rect: Rect, calc_position: bool) {
+    pub fn calculate_layout(&mut self, parent_rect: Rect, min_size: i32, calc_position: bool) {
         let widget = split_widget!(self);
         debug!(
             "Split-widget {} calcualte_layout, parent_rect {:?}",
@@ -248,89 +383,92 @@ impl SplitInfo {
                 }
             }
 
-            SplitType::SplitLeft => {
+            SplitType::SplitLeft | SplitType::SplitRight => {
                 let split_from = split_from!(self);
                 let split_from_widget = split_widget!(split_from);
                 let from_rect = split_from_widget.rect();
-                let new_width = from_rect.width() / 2;
+                self.area = from_rect;
+
+                let new_width = split_size(from_rect.width(), self.ratio, min_size);
+                let from_width = from_rect.width() - new_width;
+                let (x, from_x) = if self.ty == SplitType::SplitLeft {
+                    (from_rect.x(), from_rect.x() + new_width)
+                } else {
+                    (from_rect.x() + from_width, from_rect.x())
+                };
 
                 widget.set_fixed_width(new_width);
                 widget.set_fixed_height(from_rect.height());
                 if calc_position {
-                    widget.set_fixed_x(from_rect.x());
+                    widget.set_fixed_x(x);
                     widget.set_fixed_y(from_rect.y());
                 }
 
-                split_from_widget.set_fixed_width(new_width);
+                split_from_widget.set_fixed_width(from_width);
                 split_from_widget.set_fixed_height(from_rect.height());
                 if calc_position {
-                    split_from_widget.set_fixed_x(from_rect.x() + new_width);
+                    split_from_widget.set_fixed_x(from_x);
                     split_from_widget.set_fixed_y(from_rect.y());
                 }
             }
 
-            SplitType::SplitUp => {
+            SplitType::SplitUp | SplitType::SplitDown => {
                 let split_from = split_from!(self);
                 let split_from_widget = split_widget!(split_from);
                 let from_rect = split_from_widget.rect();
-                let new_height = from_rect.height() / 2;
+                self.area = from_rect;
+
+                let new_height = split_size(from_rect.height(), self.ratio, min_size);
+                let from_height = from_rect.height() - new_height;
+                let (y, from_y) = if self.ty == SplitType::SplitUp {
+                    (from_rect.y(), from_rect.y() + new_height)
+                } else {
+                    (from_rect.y() + from_height, from_rect.y())
+                };
 
                 widget.set_fixed_width(from_rect.width());
                 widget.set_fixed_height(new_height);
                 if calc_position {
                     widget.set_fixed_x(from_rect.x());
-                    widget.set_fixed_y(from_rect.y());
+                    widget.set_fixed_y(y);
                 }
 
                 split_from_widget.set_fixed_width(from_rect.width());
-                split_from_widget.set_fixed_height(new_height);
+                split_from_widget.set_fixed_height(from_height);
                 if calc_position {
                     split_from_widget.set_fixed_x(from_rect.x());
-                    split_from_widget.set_fixed_y(from_rect.y() + new_height);
-                }
-            }
-
-            SplitType::SplitRight => {
-                let split_from = split_from!(self);
-                let split_from_widget = split_widget!(split_from);
-                let from_rect = split_from_widget.rect();
-                let new_width = from_rect.width() / 2;
-
-                split_from_widget.set_fixed_width(new_width);
-                split_from_widget.set_fixed_height(from_rect.height());
-                if calc_position {
-                    split_from_widget.set_fixed_x(from_rect.x());
-                    split_from_widget.set_fixed_y(from_rect.y());
-                }
-
-                widget.set_fixed_width(new_width);
-                widget.set_fixed_height(from_rect.height());
-                if calc_position {
-                    widget.set_fixed_x(from_rect.x() + new_width);
-                    widget.set_fixed_y(from_rect.y());
+                    split_from_widget.set_fixed_y(from_y);
                 }
             }
+        }
+    }
+}
 
-            SplitType::SplitDown => {
-                let split_from = split_from!(self);
-                let split_from_widget = split_widget!(split_from);
-                let from_rect = split_from_widget.rect();
-                let new_height = from_rect.height() / 2;
+/// Get the size of the widget splited off from the `total` size by `ratio`,
+/// both the panes will not be smaller than `min_size` if there was enough space.
+fn split_size(total: i32, ratio: f32, min_size: i32) -> i32 {
+    let min_size = min_size.min(total / 2).max(0);
+    ((total as f32 * ratio.clamp(0., 1.)).round() as i32).clamp(min_size, total - min_size)
+}
 
-                split_from_widget.set_fixed_width(from_rect.width());
-                split_from_widget.set_fixed_height(new_height);
-                if calc_position {
-                    split_from_widget.set_fixed_x(from_rect.x());
-                    split_from_widget.set_fixed_y(from_rect.y());
-                }
+/// The state of splitter handles between the panes of split pane.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitHandle {
+    /// The minimum width/height of the panes.
+    pub min_pane_size: i32,
+    /// The id of the widget whose splitter handle was hovered.
+    pub hovered: Option<ObjectId>,
+    /// Whether the hovered splitter handle was pressed.
+    pub pressed: bool,
+}
 
-                widget.set_fixed_width(from_rect.width());
-                widget.set_fixed_height(new_height);
-                if calc_position {
-                    widget.set_fixed_x(from_rect.x());
-                    widget.set_fixed_y(from_rect.y() + new_height);
-                }
-            }
+impl Default for SplitHandle {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            min_pane_size: DEFAULT_MIN_PANE_SIZE,
+            hovered: None,
+            pressed: false,
         }
     }
 }
@@ -339,7 +477,8 @@ impl SplitInfo {
 /// Enums
 ///////////////////////////////////////////////////////////////////////////////////////////
 #[repr(u8)]
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SplitType {
     #[default]
     SplitNone = 0,
@@ -395,3 +534,132 @@ impl StaticContainerScaleCalculate for SplitPane {
         SCALE_DISMISS
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////////////////
+/// Layout serialization
+///////////////////////////////////////////////////////////////////////////////////////////
+/// The serializable split tree of [`SplitPane`], widgets were recorded by their keys,
+/// and will be recreated by the factory when the layout was restored.
+///
+/// ```ignore
+/// let json = split_pane.split_layout(|w| key_of(w)).unwrap().to_json()?;
+/// // On the next startup:
+/// split_pane.restore_split_layout(&SplitLayout::from_json(&json)?, |key| create(key));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitLayout {
+    /// The key of widget.
+    pub key: String,
+
+    /// The split type relative to the widget it was splited from,
+    /// [`SplitType::SplitNone`] for the root widget.
+    #[serde(default)]
+    pub ty: SplitType,
+
+    /// The ratio of the widget in the area shared with the widget it was splited from.
+    #[serde(default = "default_split_ratio")]
+    pub ratio: f32,
+
+    /// The widgets splited from this widget, in the splitting order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<SplitLayout>,
+}
+
+#[inline]
+fn default_split_ratio() -> f32 {
+    DEFAULT_SPLIT_RATIO
+}
+
+#[derive(Debug)]
+pub struct SplitLayoutError {
+    msg: String,
+}
+impl SplitLayoutError {
+    pub fn new(msg: impl ToString) -> Self {
+        Self {
+            msg: msg.to_string(),
+        }
+    }
+}
+impl Error for SplitLayoutError {}
+impl Display for SplitLayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl SplitLayout {
+    #[inline]
+    pub fn from_json(content: &str) -> Result<Self, SplitLayoutError> {
+        serde_json::from_str(content).map_err(SplitLayoutError::new)
+    }
+
+    #[inline]
+    pub fn to_json(&self) -> Result<String, SplitLayoutError> {
+        serde_json::to_string_pretty(self).map_err(SplitLayoutError::new)
+    }
+
+    #[inline]
+    pub fn from_ron(content: &str) -> Result<Self, SplitLayoutError> {
+        ron::from_str(content).map_err(SplitLayoutError::new)
+    }
+
+    #[inline]
+    pub fn to_ron(&self) -> Result<String, SplitLayoutError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SplitLayoutError::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_size() {
+        assert_eq!(split_size(200, 0.5, 30), 100);
+        assert_eq!(split_size(200, 0.3, 30), 60);
+        assert_eq!(split_size(200, 0.05, 30), 30);
+        assert_eq!(split_size(200, 0.99, 30), 170);
+        assert_eq!(split_size(200, 1.5, 0), 200);
+        // Not enough space for the minimum size.
+        assert_eq!(split_size(40, 0.1, 30), 20);
+    }
+
+    #[test]
+    fn test_split_layout_serialize() {
+        let layout = SplitLayout {
+            key: "terminal-1".to_string(),
+            ty: SplitType::SplitNone,
+            ratio: DEFAULT_SPLIT_RATIO,
+            splits: vec![
+                SplitLayout {
+                    key: "terminal-2".to_string(),
+                    ty: SplitType::SplitRight,
+                    ratio: 0.25,
+                    splits: vec![],
+                },
+                SplitLayout {
+                    key: "terminal-3".to_string(),
+                    ty: SplitType::SplitDown,
+                    ratio: 0.75,
+                    splits: vec![],
+                },
+            ],
+        };
+
+        let json = layout.to_json().unwrap();
+        assert_eq!(SplitLayout::from_json(&json).unwrap(), layout);
+        let ron = layout.to_ron().unwrap();
+        assert_eq!(SplitLayout::from_ron(&ron).unwrap(), layout);
+
+        let layout = SplitLayout::from_json(
+            r#"{ "key": "a", "splits": [{ "key": "b", "ty": "split_left" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(layout.ty, SplitType::SplitNone);
+        assert_eq!(layout.splits[0].ty, SplitType::SplitLeft);
+        assert_eq!(layout.splits[0].ratio, DEFAULT_SPLIT_RATIO);
+        assert!(SplitLayout::from_json(r#"{ "ty": "split_up" }"#).is_err());
+    }
+}
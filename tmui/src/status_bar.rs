@@ -0,0 +1,353 @@
+use crate::{
+    container::{ContainerLayoutEnum, ScaleStrat, SCALE_ADAPTION},
+    font::FontCalculation,
+    graphics::painter::Painter,
+    label::Label,
+    layout::LayoutMgr,
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{RegionClear, WidgetImpl},
+};
+use std::time::Duration;
+use tlib::{connect, emit, object::ObjectId, signals, timer::Timer};
+
+const BAR_PADDING: i32 = 3;
+const SECTION_PADDING: i32 = 6;
+const ITEM_SPACING: i32 = 8;
+
+const SEPARATOR_COLOR: Color = Color::rgb(210, 210, 210);
+
+/// The bar at the bottom of window to show the status information.
+///
+/// Widgets can be added to the left section by [`add_widget()`](StatusBar::add_widget),
+/// or the right section by [`add_permanent_widget()`](StatusBar::add_permanent_widget).
+/// The temporary message shows on the left section, the widgets of left section will be
+/// hidden while the message was shown, the widgets of right section are always shown.
+///
+/// Status bar can be placed on the edge of window,
+/// see [`ApplicationWindow::add_edge_widget`](crate::application_window::ApplicationWindow::add_edge_widget).
+#[extends(Container)]
+pub struct StatusBar {
+    #[derivative(Default(value = "Label::new(None)"))]
+    message_label: Tr<Label>,
+    /// The number of widgets in the left section,
+    /// children were ordered as: `[message label, left section.., right section..]`.
+    left_count: usize,
+    /// The widgets of left section which were hidden by the temporary message.
+    hidden: Vec<ObjectId>,
+    message_timer: Box<Timer>,
+}
+
+pub trait StatusBarSignals: ActionExt {
+    signals!(
+        StatusBarSignals:
+
+        /// Emitted when the temporary message was changed.
+        ///
+        /// @param [`String`]: the new message, empty if the message was cleared.
+        message_changed(String);
+    );
+}
+impl StatusBarSignals for StatusBar {}
+
+impl ObjectSubclass for StatusBar {
+    const NAME: &'static str = "StatusBar";
+}
+
+impl ObjectImpl for StatusBar {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_paddings(0, SECTION_PADDING, 0, SECTION_PADDING);
+        self.set_hexpand(true);
+        self.calc_height();
+
+        let mut message_label = self.message_label.clone();
+        message_label.set_parent(self);
+        message_label.hide();
+        self.container.children.push(message_label.into());
+
+        self.message_timer.set_single_shot(true);
+        connect!(self.message_timer, timeout(), self, clear_message());
+    }
+
+    #[inline]
+    fn type_register(&self, type_registry: &mut TypeRegistry) {
+        type_registry.register::<StatusBar, ReflectSizeUnifiedAdjust>();
+    }
+}
+
+impl WidgetImpl for StatusBar {
+    #[inline]
+    fn font_changed(&mut self) {
+        self.calc_height();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let rect = self.origin_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        painter.save_pen();
+        painter.set_color(SEPARATOR_COLOR);
+        painter.set_line_width(1.);
+        painter.draw_line_f(rect.x(), rect.y() + 0.5, rect.right(), rect.y() + 0.5);
+        painter.restore_pen();
+    }
+}
+
+impl StatusBar {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// Add the widget to the left section,
+    /// the widget will be hidden while the temporary message was shown.
+    pub fn add_widget<T: WidgetImpl>(&mut self, widget: Tr<T>) {
+        let idx = 1 + self.left_count;
+        self.left_count += 1;
+        self.insert(idx, widget);
+
+        if self.is_message_shown() {
+            let child = &mut self.container.children[idx];
+            if child.visible() {
+                child.hide();
+                self.hidden.push(child.id());
+            }
+        }
+    }
+
+    /// Add the widget to the right section, which was always shown.
+    #[inline]
+    pub fn add_permanent_widget<T: WidgetImpl>(&mut self, widget: Tr<T>) {
+        let idx = self.container.children.len();
+        self.insert(idx, widget);
+    }
+
+    /// Show the temporary message, the message will be cleared after `timeout` if specified.
+    ///
+    /// The previous message will be replaced.
+    pub fn show_message(&mut self, message: &str, timeout: Option<Duration>) {
+        self.message_timer.stop();
+        if message.is_empty() {
+            self.clear_message();
+            return;
+        }
+
+        self.message_label.set_text(message);
+        if !self.is_message_shown() {
+            self.message_label.show();
+            for child in self.container.children[1..1 + self.left_count].iter_mut() {
+                if child.visible() {
+                    child.hide();
+                    self.hidden.push(child.id());
+                }
+            }
+        }
+        if let Some(timeout) = timeout {
+            self.message_timer.start(timeout);
+        }
+
+        emit!(self, message_changed(message.to_string()));
+        self.window().layout_change(self);
+    }
+
+    /// Clear the temporary message, the hidden widgets of left section will be shown.
+    pub fn clear_message(&mut self) {
+        self.message_timer.stop();
+        if !self.is_message_shown() {
+            return;
+        }
+
+        self.message_label.set_text("");
+        self.message_label.hide();
+        let hidden = std::mem::take(&mut self.hidden);
+        for child in self.container.children.iter_mut() {
+            if hidden.contains(&child.id()) {
+                child.show();
+            }
+        }
+
+        emit!(self, message_changed(String::new()));
+        self.window().layout_change(self);
+    }
+
+    /// Get the temporary message, empty if there is no message.
+    #[inline]
+    pub fn current_message(&self) -> &str {
+        if self.is_message_shown() {
+            self.message_label.text()
+        } else {
+            ""
+        }
+    }
+
+    #[inline]
+    pub fn is_message_shown(&self) -> bool {
+        self.message_label.visible()
+    }
+
+    /// Set the color of temporary message.
+    #[inline]
+    pub fn set_message_color(&mut self, color: Color) {
+        self.message_label.set_color(color)
+    }
+}
+
+impl StatusBar {
+    fn insert<T: WidgetImpl>(&mut self, idx: usize, mut widget: Tr<T>) {
+        widget.set_parent(self);
+        self.container.children.insert(idx, widget.clone().into());
+        ApplicationWindow::initialize_dynamic_component(widget.as_dyn_mut(), self.is_in_tree());
+        self.update();
+    }
+
+    fn calc_height(&mut self) {
+        let font_height = self.font().calc_font_dimension().1.ceil() as i32;
+        let (top, _, bottom, _) = self.paddings();
+        let (tb, _, bb, _) = self.borders();
+
+        self.height_request(font_height + BAR_PADDING * 2 + top + bottom + (tb + bb).ceil() as i32);
+    }
+}
+
+impl ContainerImpl for StatusBar {
+    fn children(&self) -> Vec<&dyn WidgetImpl> {
+        self.container.children.iter().map(|c| c.bind()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn WidgetImpl> {
+        self.container
+            .children
+            .iter_mut()
+            .map(|c| c.bind_mut())
+            .collect()
+    }
+
+    fn container_layout(&self) -> ContainerLayoutEnum {
+        ContainerLayoutEnum::StatusBar
+    }
+}
+
+impl ContainerImplExt for StatusBar {
+    #[inline]
+    fn add_child<T>(&mut self, child: Tr<T>)
+    where
+        T: WidgetImpl,
+    {
+        self.add_widget(child)
+    }
+
+    fn remove_children(&mut self, id: ObjectId) {
+        if id == self.message_label.id() {
+            return;
+        }
+        if let Some(index) = self.container.children.iter().position(|w| w.id() == id) {
+            let removed = self.container.children.remove(index);
+            if index <= self.left_count {
+                self.left_count -= 1;
+            }
+            self.hidden.retain(|h| *h != id);
+
+            let window = ApplicationWindow::window();
+            window._add_removed_widget(removed);
+            window.layout_change(self);
+        }
+    }
+}
+
+impl ContainerScaleCalculate for StatusBar {
+    #[inline]
+    fn container_hscale_calculate(&self) -> f32 {
+        Self::static_container_hscale_calculate(self)
+    }
+
+    #[inline]
+    fn container_vscale_calculate(&self) -> f32 {
+        Self::static_container_vscale_calculate(self)
+    }
+}
+impl StaticContainerScaleCalculate for StatusBar {
+    fn static_container_hscale_calculate(c: &dyn ContainerImpl) -> f32 {
+        match c.scale_strat() {
+            ScaleStrat::Sum => c
+                .children()
+                .iter()
+                .filter(|c| !c.fixed_width())
+                .map(|c| if c.visible() { c.hscale() } else { 0. })
+                .sum(),
+            ScaleStrat::Direct => 1.,
+        }
+    }
+
+    #[inline]
+    fn static_container_vscale_calculate(_: &dyn ContainerImpl) -> f32 {
+        SCALE_ADAPTION
+    }
+}
+
+impl SizeUnifiedAdjust for StatusBar {
+    #[inline]
+    fn size_unified_adjust(&mut self) {
+        Self::static_size_unified_adjust(self)
+    }
+}
+impl StaticSizeUnifiedAdjust for StatusBar {
+    #[inline]
+    fn static_size_unified_adjust(container: &mut dyn ContainerImpl) {
+        HBox::static_size_unified_adjust(container)
+    }
+}
+
+impl Layout for StatusBar {
+    #[inline]
+    fn composition(&self) -> Composition {
+        Self::static_composition(self)
+    }
+
+    #[inline]
+    fn position_layout(&mut self, parent: Option<&dyn WidgetImpl>) {
+        Self::container_position_layout(self, parent)
+    }
+}
+
+impl ContainerLayout for StatusBar {
+    #[inline]
+    fn static_composition<T: WidgetImpl + ContainerImpl>(_: &T) -> Composition {
+        Composition::HorizontalArrange
+    }
+
+    /// The message label and the left section were arranged from the left,
+    /// the right section was arranged to the right, all centered vertically.
+    fn container_position_layout<T: WidgetImpl + ContainerImpl>(
+        widget: &mut T,
+        parent: Option<&dyn WidgetImpl>,
+    ) {
+        LayoutMgr::base_widget_position_layout(widget, parent);
+
+        let left_count = widget
+            .as_any()
+            .downcast_ref::<StatusBar>()
+            .map_or(0, |bar| bar.left_count);
+        let contents = widget.contents_rect(None);
+        let mut children = widget.children_mut();
+        let right = children.split_off((1 + left_count).min(children.len()));
+
+        let mut x = contents.x();
+        for child in children.into_iter().filter(|c| c.visible()) {
+            let rect = child.image_rect();
+            child.set_fixed_x(x + child.margin_left());
+            child.set_fixed_y(contents.y() + (contents.height() - rect.height()) / 2);
+            x += rect.width() + child.margin_left() + child.margin_right() + ITEM_SPACING;
+        }
+
+        let mut x = contents.right();
+        for child in right.into_iter().rev().filter(|c| c.visible()) {
+            let rect = child.image_rect();
+            x -= rect.width() + child.margin_right();
+            child.set_fixed_x(x);
+            child.set_fixed_y(contents.y() + (contents.height() - rect.height()) / 2);
+            x -= child.margin_left() + ITEM_SPACING;
+        }
+    }
+}
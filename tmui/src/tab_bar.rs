@@ -0,0 +1,863 @@
+use crate::{
+    dnd::Drag,
+    font::FontCalculation,
+    graphics::painter::Painter,
+    icons::svg_dom::SvgDom,
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{widget_inner::WidgetInnerExt, RegionClear, WidgetImpl},
+};
+use tlib::{emit, events::MouseEvent, namespace::MouseButton, signals, skia_safe::ClipOp};
+
+const BAR_PADDING: i32 = 6;
+const TAB_PADDING: i32 = 10;
+const TAB_SPACING: i32 = 6;
+const TAB_MIN_WIDTH: i32 = 48;
+const TAB_MAX_WIDTH: i32 = 220;
+const ICON_SIZE: i32 = 16;
+const CLOSE_SIZE: i32 = 14;
+const SCROLL_BUTTON_SIZE: i32 = 20;
+const WHEEL_STEP: i32 = 30;
+const INDICATOR_WIDTH: f32 = 2.;
+
+const HOVER_BACKGROUND: Color = Color::rgb(230, 230, 230);
+const CURRENT_BACKGROUND: Color = Color::WHITE;
+const CLOSE_HOVER_BACKGROUND: Color = Color::rgb(205, 205, 205);
+const INDICATOR_COLOR: Color = Color::rgb(0, 120, 215);
+const SEPARATOR_COLOR: Color = Color::rgb(210, 210, 210);
+const DISABLED_COLOR: Color = Color::rgb(170, 170, 170);
+
+/// The side of [`TabBar`] relative to the pages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabPosition {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl TabPosition {
+    /// Tabs were arranged horizontally on `Top` and `Bottom`.
+    #[inline]
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+}
+
+struct Tab {
+    text: String,
+    icon: Option<SvgDom>,
+}
+
+/// The bar of tabs, tabs can be arranged on all four sides of pages, see [`TabPosition`].
+///
+/// When there is not enough space to show all the tabs, two scroll arrows will be
+/// shown at the end of the bar, the mouse wheel can also scroll the tabs.
+///
+/// Click the close button or middle-click the tab emits `tab_close_requested()`,
+/// the tab will not be removed automatically.
+#[extends(Widget)]
+pub struct TabBar {
+    tabs: Vec<Tab>,
+    current: Option<usize>,
+    hovered: Option<usize>,
+    close_hovered: bool,
+
+    position: TabPosition,
+    tabs_closable: bool,
+    #[derivative(Default(value = "true"))]
+    movable: bool,
+
+    /// The scrolled distance of tabs along the arrangement direction.
+    offset: i32,
+    /// The tab pressed by left button and the pressed position.
+    pressed: Option<(usize, Point)>,
+    /// The tab whose close button was pressed.
+    close_pressed: Option<usize>,
+    /// The tab pressed by middle button.
+    middle_pressed: Option<usize>,
+    dragging: bool,
+
+    #[derivative(Default(value = "Color::BLACK"))]
+    color: Color,
+}
+
+pub trait TabBarSignals: ActionExt {
+    signals!(
+        TabBarSignals:
+
+        /// Emit when the index of current tab was changed, including the index shifted by
+        /// inserting, removing or moving the other tabs.
+        ///
+        /// @param [`usize`] the index of the current tab, [`usize::MAX`] if there was no tab.
+        current_changed(usize);
+
+        /// Emit when the close button of tab was clicked, or the tab was middle-clicked.
+        ///
+        /// @param [`usize`] the index of the tab.
+        tab_close_requested(usize);
+
+        /// Emit when the tab was moved.
+        ///
+        /// @param [`usize`] the index before moving.
+        /// @param [`usize`] the index after moving.
+        tab_moved(usize, usize);
+    );
+}
+impl TabBarSignals for TabBar {}
+
+impl ObjectSubclass for TabBar {
+    const NAME: &'static str = "TabBar";
+}
+
+impl ObjectImpl for TabBar {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_mouse_tracking(true);
+        self.update_cross_size();
+    }
+}
+
+impl WidgetImpl for TabBar {
+    #[inline]
+    fn font_changed(&mut self) {
+        self.update_cross_size();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        self.clear(painter, contents);
+
+        self.clamp_offset();
+        let font_height = self.font().calc_font_dimension().1;
+        let overflow = self.is_overflow();
+
+        painter.save();
+        painter.save_pen();
+        painter.set_antialiasing(true);
+
+        // The separator line between tabs and pages.
+        let edge: FRect = contents.into();
+        painter.set_color(SEPARATOR_COLOR);
+        painter.set_line_width(1.);
+        let (x1, y1, x2, y2) = self.page_side_line(edge);
+        painter.draw_line_f(x1, y1, x2, y2);
+
+        painter.save();
+        painter.clip_rect(self.tabs_area(), ClipOp::Intersect);
+        for (idx, tab) in self.tabs.iter().enumerate() {
+            let rect = self.tab_rect(idx);
+            if !rect.is_intersects(&contents) {
+                continue;
+            }
+            let rect: FRect = rect.into();
+
+            if self.current == Some(idx) {
+                painter.fill_rect(rect, CURRENT_BACKGROUND);
+                painter.set_color(INDICATOR_COLOR);
+                painter.set_line_width(INDICATOR_WIDTH);
+                let (x1, y1, x2, y2) = self.page_side_line(rect);
+                painter.draw_line_f(x1, y1, x2, y2);
+            } else if self.hovered == Some(idx) {
+                painter.fill_rect(rect, HOVER_BACKGROUND);
+            }
+
+            let mut x = rect.x() + TAB_PADDING as f32;
+            if let Some(icon) = tab.icon.as_ref() {
+                let size = icon.get_size();
+                painter.save();
+                painter.translate(x, rect.y() + (rect.height() - size.height() as f32) / 2.);
+                painter.draw_dom(icon);
+                painter.restore();
+                x += (ICON_SIZE + TAB_SPACING) as f32;
+            }
+
+            let mut text_width = rect.right() - TAB_PADDING as f32 - x;
+            if self.tabs_closable {
+                text_width -= (CLOSE_SIZE + TAB_SPACING) as f32;
+            }
+            painter.set_color(self.color);
+            painter.draw_paragraph(
+                &tab.text,
+                (x, rect.y() + (rect.height() - font_height) / 2.),
+                0.,
+                text_width.max(0.),
+                Some(1),
+                true,
+            );
+
+            if self.tabs_closable {
+                let close: FRect = self.close_rect(idx).into();
+                if self.hovered == Some(idx) && self.close_hovered {
+                    painter.fill_round_rect(close, (3., 3., 3., 3.), CLOSE_HOVER_BACKGROUND);
+                }
+                let cross = 3.5;
+                let (cx, cy) = (
+                    close.x() + close.width() / 2.,
+                    close.y() + close.height() / 2.,
+                );
+                painter.set_color(self.color);
+                painter.set_line_width(1.2);
+                painter.draw_line_f(cx - cross, cy - cross, cx + cross, cy + cross);
+                painter.draw_line_f(cx + cross, cy - cross, cx - cross, cy + cross);
+            }
+        }
+        painter.restore();
+
+        if overflow {
+            let (prev, next) = self.scroll_button_rects();
+            let prev_color = if self.offset > 0 {
+                self.color
+            } else {
+                DISABLED_COLOR
+            };
+            let next_color = if self.offset < self.max_offset() {
+                self.color
+            } else {
+                DISABLED_COLOR
+            };
+            let horizontal = self.position.is_horizontal();
+            render_arrow(painter, prev.into(), horizontal, false, prev_color);
+            render_arrow(painter, next.into(), horizontal, true, next_color);
+        }
+
+        painter.restore_pen();
+        painter.restore();
+    }
+
+    fn on_mouse_pressed(&mut self, event: &MouseEvent) {
+        let pos: Point = event.position().into();
+
+        match event.mouse_button() {
+            MouseButton::LeftButton => {
+                if self.is_overflow() {
+                    let (prev, next) = self.scroll_button_rects();
+                    if prev.contains(&pos) {
+                        self.scroll_by(-self.scroll_step());
+                        return;
+                    } else if next.contains(&pos) {
+                        self.scroll_by(self.scroll_step());
+                        return;
+                    }
+                }
+
+                let Some(idx) = self.tab_at(pos) else {
+                    return;
+                };
+                if self.tabs_closable && self.close_rect(idx).contains(&pos) {
+                    self.close_pressed = Some(idx);
+                    return;
+                }
+
+                self.set_current_index(idx);
+                self.pressed = Some((idx, pos));
+            }
+            MouseButton::MiddleButton => self.middle_pressed = self.tab_at(pos),
+            _ => {}
+        }
+    }
+
+    fn on_mouse_released(&mut self, event: &MouseEvent) {
+        let pos: Point = event.position().into();
+
+        match event.mouse_button() {
+            MouseButton::LeftButton => {
+                self.pressed = None;
+                self.dragging = false;
+
+                if let Some(idx) = self.close_pressed.take() {
+                    if self.close_rect(idx).contains(&pos) {
+                        emit!(self, tab_close_requested(idx));
+                    }
+                }
+            }
+            MouseButton::MiddleButton => {
+                if let Some(idx) = self.middle_pressed.take() {
+                    if self.tab_at(pos) == Some(idx) {
+                        emit!(self, tab_close_requested(idx));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseEvent) {
+        let pos: Point = event.position().into();
+
+        if let Some((idx, pressed)) = self.pressed {
+            if self.movable && !self.dragging && Drag::reached_start_distance(pressed, pos) {
+                self.dragging = true;
+            }
+            if self.dragging {
+                self.drag_to(idx, pos);
+            }
+        }
+
+        let hovered = self.tab_at(pos);
+        let close_hovered =
+            self.tabs_closable && hovered.map_or(false, |idx| self.close_rect(idx).contains(&pos));
+        if hovered != self.hovered || close_hovered != self.close_hovered {
+            self.hovered = hovered;
+            self.close_hovered = close_hovered;
+            self.update();
+        }
+    }
+
+    fn on_mouse_leave(&mut self, _: &MouseEvent) {
+        if self.hovered.take().is_some() {
+            self.close_hovered = false;
+            self.update();
+        }
+    }
+
+    fn on_mouse_wheel(&mut self, event: &MouseEvent) {
+        if !self.is_overflow() {
+            return;
+        }
+        let delta = event.delta();
+        let delta = if delta.y() != 0 { delta.y() } else { delta.x() };
+        self.scroll_by(-delta.signum() * WHEEL_STEP);
+    }
+}
+
+impl TabBar {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// @return the index of added tab.
+    #[inline]
+    pub fn add_tab(&mut self, text: &str) -> usize {
+        self.insert_tab(self.tabs.len(), text)
+    }
+
+    /// @return the index of added tab.
+    #[inline]
+    pub fn add_tab_with_icon(&mut self, text: &str, icon: SvgDom) -> usize {
+        let idx = self.add_tab(text);
+        self.set_tab_icon(idx, Some(icon));
+        idx
+    }
+
+    /// Insert the tab at `idx`, the tab will be appended if `idx` was out of range.
+    ///
+    /// The first inserted tab becomes the current tab.
+    ///
+    /// @return the index of inserted tab.
+    pub fn insert_tab(&mut self, idx: usize, text: &str) -> usize {
+        let idx = idx.min(self.tabs.len());
+        self.tabs.insert(
+            idx,
+            Tab {
+                text: text.to_string(),
+                icon: None,
+            },
+        );
+        self.reset_interaction();
+
+        match self.current {
+            Some(current) if current >= idx => self.set_current(Some(current + 1)),
+            None => self.set_current(Some(idx)),
+            _ => {}
+        }
+
+        self.update_cross_size();
+        self.update();
+        idx
+    }
+
+    /// Remove the tab, if the current tab was removed,
+    /// the next tab(or the previous one if it was the last) becomes the current tab.
+    pub fn remove_tab(&mut self, idx: usize) {
+        if idx >= self.tabs.len() {
+            return;
+        }
+        self.tabs.remove(idx);
+        self.reset_interaction();
+
+        match self.current {
+            Some(current) if current > idx => self.set_current(Some(current - 1)),
+            Some(current) if current == idx => {
+                // Emitted even if the next tab takes the index of removed one.
+                self.current = (!self.tabs.is_empty()).then(|| idx.min(self.tabs.len() - 1));
+                self.emit_current_changed();
+            }
+            _ => {}
+        }
+
+        self.update_cross_size();
+        self.update();
+    }
+
+    /// Move the tab from `from` to `to`, the current tab keeps unchanged, but its index
+    /// may be changed.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let len = self.tabs.len();
+        if from == to || from >= len || to >= len {
+            return;
+        }
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+
+        emit!(self, tab_moved(from, to));
+
+        if let Some(current) = self.current {
+            self.set_current(Some(moved_index(current, from, to)));
+        }
+        self.update();
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    #[inline]
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn set_current_index(&mut self, idx: usize) {
+        if idx >= self.tabs.len() {
+            return;
+        }
+        self.ensure_visible(idx);
+        if self.current == Some(idx) {
+            return;
+        }
+        self.current = Some(idx);
+        emit!(self, current_changed(idx));
+        self.update();
+    }
+
+    #[inline]
+    pub fn tab_text(&self, idx: usize) -> Option<&str> {
+        self.tabs.get(idx).map(|t| t.text.as_str())
+    }
+
+    pub fn set_tab_text(&mut self, idx: usize, text: &str) {
+        if let Some(tab) = self.tabs.get_mut(idx) {
+            tab.text = text.to_string();
+            self.update_cross_size();
+            self.update();
+        }
+    }
+
+    #[inline]
+    pub fn tab_icon(&self, idx: usize) -> Option<&SvgDom> {
+        self.tabs.get(idx).and_then(|t| t.icon.as_ref())
+    }
+
+    pub fn set_tab_icon(&mut self, idx: usize, icon: Option<SvgDom>) {
+        if let Some(tab) = self.tabs.get_mut(idx) {
+            tab.icon = icon;
+            self.update_cross_size();
+            self.update();
+        }
+    }
+
+    #[inline]
+    pub fn tab_position(&self) -> TabPosition {
+        self.position
+    }
+
+    /// Set the side of tab bar relative to the pages.
+    ///
+    /// The default value was [`TabPosition::Top`].
+    pub fn set_tab_position(&mut self, position: TabPosition) {
+        if self.position == position {
+            return;
+        }
+        let horizontal_changed = self.position.is_horizontal() != position.is_horizontal();
+        self.position = position;
+        if horizontal_changed {
+            self.offset = 0;
+            self.update_cross_size();
+        }
+        self.update();
+    }
+
+    #[inline]
+    pub fn tabs_closable(&self) -> bool {
+        self.tabs_closable
+    }
+
+    /// Show the close button on tabs or not.
+    #[inline]
+    pub fn set_tabs_closable(&mut self, closable: bool) {
+        self.tabs_closable = closable;
+        self.update_cross_size();
+        self.update();
+    }
+
+    #[inline]
+    pub fn is_movable(&self) -> bool {
+        self.movable
+    }
+
+    /// The tabs can be reordered by dragging or not, the default value was `true`.
+    #[inline]
+    pub fn set_movable(&mut self, movable: bool) {
+        self.movable = movable
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Set the color of tab texts.
+    #[inline]
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.update();
+    }
+}
+
+impl TabBar {
+    /// Set the index of current tab, `current_changed` was emitted if it was changed.
+    fn set_current(&mut self, current: Option<usize>) {
+        if self.current != current {
+            self.current = current;
+            self.emit_current_changed();
+        }
+    }
+
+    #[inline]
+    fn emit_current_changed(&mut self) {
+        let current = self.current.unwrap_or(usize::MAX);
+        emit!(self, current_changed(current));
+    }
+
+    #[inline]
+    fn reset_interaction(&mut self) {
+        self.hovered = None;
+        self.close_hovered = false;
+        self.pressed = None;
+        self.close_pressed = None;
+        self.middle_pressed = None;
+        self.dragging = false;
+    }
+
+    /// Move the dragged tab to the tab under the cursor,
+    /// only when the cursor will still be inside the dragged tab after moving,
+    /// to avoid moving back and forth between tabs with different extents.
+    fn drag_to(&mut self, idx: usize, pos: Point) {
+        let Some(target) = self.tab_at(pos) else {
+            return;
+        };
+        if target == idx {
+            return;
+        }
+
+        let main = self.main_pos(pos) + self.offset;
+        let extent = self.tab_extent(idx);
+        let start: i32 = if target > idx {
+            (0..=target).map(|i| self.tab_extent(i)).sum::<i32>() - extent
+        } else {
+            (0..target).map(|i| self.tab_extent(i)).sum()
+        };
+        let start = start + self.main_start();
+        if main < start || main >= start + extent {
+            return;
+        }
+
+        self.move_tab(idx, target);
+        if let Some((pressed, _)) = self.pressed.as_mut() {
+            *pressed = target;
+        }
+    }
+
+    #[inline]
+    fn scroll_by(&mut self, delta: i32) {
+        let offset = (self.offset + delta).clamp(0, self.max_offset());
+        if offset != self.offset {
+            self.offset = offset;
+            self.update();
+        }
+    }
+
+    #[inline]
+    fn scroll_step(&self) -> i32 {
+        if self.position.is_horizontal() {
+            TAB_MIN_WIDTH
+        } else {
+            self.vertical_tab_height()
+        }
+    }
+
+    fn ensure_visible(&mut self, idx: usize) {
+        if !self.is_overflow() {
+            return;
+        }
+        let start: i32 = (0..idx).map(|i| self.tab_extent(i)).sum();
+        let end = start + self.tab_extent(idx);
+        let visible = self.visible_extent();
+
+        if start < self.offset {
+            self.offset = start;
+        } else if end > self.offset + visible {
+            self.offset = end - visible;
+        }
+        self.clamp_offset();
+        self.update();
+    }
+
+    #[inline]
+    fn clamp_offset(&mut self) {
+        self.offset = self.offset.clamp(0, self.max_offset());
+    }
+
+    #[inline]
+    fn max_offset(&self) -> i32 {
+        (self.total_extent() - self.visible_extent()).max(0)
+    }
+
+    #[inline]
+    fn is_overflow(&self) -> bool {
+        self.total_extent() > self.main_extent()
+    }
+
+    #[inline]
+    fn total_extent(&self) -> i32 {
+        (0..self.tabs.len()).map(|i| self.tab_extent(i)).sum()
+    }
+
+    /// The extent of contents along the arrangement direction.
+    #[inline]
+    fn main_extent(&self) -> i32 {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        if self.position.is_horizontal() {
+            contents.width()
+        } else {
+            contents.height()
+        }
+    }
+
+    #[inline]
+    fn main_start(&self) -> i32 {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        if self.position.is_horizontal() {
+            contents.x()
+        } else {
+            contents.y()
+        }
+    }
+
+    #[inline]
+    fn main_pos(&self, pos: Point) -> i32 {
+        if self.position.is_horizontal() {
+            pos.x()
+        } else {
+            pos.y()
+        }
+    }
+
+    /// The extent can show tabs along the arrangement direction, excluding the scroll buttons.
+    #[inline]
+    fn visible_extent(&self) -> i32 {
+        let extent = self.main_extent();
+        if self.total_extent() > extent {
+            (extent - SCROLL_BUTTON_SIZE * 2).max(0)
+        } else {
+            extent
+        }
+    }
+
+    #[inline]
+    fn tab_extent(&self, idx: usize) -> i32 {
+        if self.position.is_horizontal() {
+            self.tab_width(idx)
+        } else {
+            self.vertical_tab_height()
+        }
+    }
+
+    fn tab_width(&self, idx: usize) -> i32 {
+        let Some(tab) = self.tabs.get(idx) else {
+            return 0;
+        };
+        let mut width =
+            TAB_PADDING * 2 + self.font().calc_text_dimension(&tab.text, 0.).0.ceil() as i32;
+        if tab.icon.is_some() {
+            width += ICON_SIZE + TAB_SPACING;
+        }
+        if self.tabs_closable {
+            width += CLOSE_SIZE + TAB_SPACING;
+        }
+        width.clamp(TAB_MIN_WIDTH, TAB_MAX_WIDTH)
+    }
+
+    #[inline]
+    fn vertical_tab_height(&self) -> i32 {
+        let font_height = self.font().calc_font_dimension().1.ceil() as i32;
+        font_height.max(ICON_SIZE) + BAR_PADDING * 2
+    }
+
+    /// Get the area to show tabs in widget coordinate.
+    fn tabs_area(&self) -> Rect {
+        let mut area = self.contents_rect(Some(Coordinate::Widget));
+        if self.position.is_horizontal() {
+            area.set_width(self.visible_extent());
+        } else {
+            area.set_height(self.visible_extent());
+        }
+        area
+    }
+
+    /// Get the rectangle of tab in widget coordinate.
+    fn tab_rect(&self, idx: usize) -> Rect {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        let start: i32 = (0..idx).map(|i| self.tab_extent(i)).sum::<i32>() - self.offset;
+        let extent = self.tab_extent(idx);
+
+        if self.position.is_horizontal() {
+            Rect::new(
+                contents.x() + start,
+                contents.y(),
+                extent,
+                contents.height(),
+            )
+        } else {
+            Rect::new(contents.x(), contents.y() + start, contents.width(), extent)
+        }
+    }
+
+    /// Get the rectangle of close button in widget coordinate.
+    fn close_rect(&self, idx: usize) -> Rect {
+        let rect = self.tab_rect(idx);
+        Rect::new(
+            rect.right() - TAB_PADDING - CLOSE_SIZE,
+            rect.y() + (rect.height() - CLOSE_SIZE) / 2,
+            CLOSE_SIZE,
+            CLOSE_SIZE,
+        )
+    }
+
+    /// Get the rectangles of scroll buttons in widget coordinate.
+    ///
+    /// @return (the button scrolls backward, the button scrolls forward)
+    fn scroll_button_rects(&self) -> (Rect, Rect) {
+        let contents = self.contents_rect(Some(Coordinate::Widget));
+        let s = SCROLL_BUTTON_SIZE;
+
+        if self.position.is_horizontal() {
+            let x = contents.right() - s * 2;
+            (
+                Rect::new(x, contents.y(), s, contents.height()),
+                Rect::new(x + s, contents.y(), s, contents.height()),
+            )
+        } else {
+            let y = contents.bottom() - s * 2;
+            (
+                Rect::new(contents.x(), y, contents.width(), s),
+                Rect::new(contents.x(), y + s, contents.width(), s),
+            )
+        }
+    }
+
+    /// Get the index of tab at the position in widget coordinate.
+    fn tab_at(&self, pos: Point) -> Option<usize> {
+        if !self.tabs_area().contains(&pos) {
+            return None;
+        }
+        (0..self.tabs.len()).find(|&idx| self.tab_rect(idx).contains(&pos))
+    }
+
+    /// Get the line on the side of rect which faces the pages.
+    fn page_side_line(&self, rect: FRect) -> (f32, f32, f32, f32) {
+        let (l, t, r, b) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+        let h = INDICATOR_WIDTH / 2.;
+        match self.position {
+            TabPosition::Top => (l, b - h, r, b - h),
+            TabPosition::Bottom => (l, t + h, r, t + h),
+            TabPosition::Left => (r - h, t, r - h, b),
+            TabPosition::Right => (l + h, t, l + h, b),
+        }
+    }
+
+    /// Fix the size of tab bar perpendicular to the arrangement direction,
+    /// and expand along the arrangement direction.
+    fn update_cross_size(&mut self) {
+        let (top, right, bottom, left) = self.paddings();
+        let (tb, rb, bb, lb) = self.borders();
+
+        if self.position.is_horizontal() {
+            let font_height = self.font().calc_font_dimension().1.ceil() as i32;
+            let height = font_height.max(ICON_SIZE) + BAR_PADDING * 2;
+
+            self.cancel_fixed_width();
+            self.set_hexpand(true);
+            self.set_vexpand(false);
+            self.height_request(height + top + bottom + (tb + bb).ceil() as i32);
+        } else {
+            let width = (0..self.tabs.len())
+                .map(|i| self.tab_width(i))
+                .max()
+                .unwrap_or(TAB_MIN_WIDTH);
+
+            self.cancel_fixed_height();
+            self.set_vexpand(true);
+            self.set_hexpand(false);
+            self.width_request(width + left + right + (lb + rb).ceil() as i32);
+        }
+    }
+}
+
+/// Get the new index of the item at `idx` after moving the item from `from` to `to`.
+fn moved_index(idx: usize, from: usize, to: usize) -> usize {
+    if idx == from {
+        to
+    } else if from < idx && idx <= to {
+        idx - 1
+    } else if to <= idx && idx < from {
+        idx + 1
+    } else {
+        idx
+    }
+}
+
+fn render_arrow(painter: &mut Painter, rect: FRect, horizontal: bool, forward: bool, color: Color) {
+    let size = 4.;
+    let (cx, cy) = (rect.x() + rect.width() / 2., rect.y() + rect.height() / 2.);
+    let d = if forward { size / 2. } else { -size / 2. };
+
+    painter.set_color(color);
+    painter.set_line_width(1.5);
+    if horizontal {
+        painter.draw_line_f(cx - d, cy - size, cx + d, cy);
+        painter.draw_line_f(cx + d, cy, cx - d, cy + size);
+    } else {
+        painter.draw_line_f(cx - size, cy - d, cx, cy + d);
+        painter.draw_line_f(cx, cy + d, cx + size, cy - d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::moved_index;
+
+    #[test]
+    fn test_moved_index() {
+        // [a, b, c, d] move b to 3 => [a, c, d, b]
+        assert_eq!(moved_index(0, 1, 3), 0);
+        assert_eq!(moved_index(1, 1, 3), 3);
+        assert_eq!(moved_index(2, 1, 3), 1);
+        assert_eq!(moved_index(3, 1, 3), 2);
+
+        // [a, b, c, d] move d to 1 => [a, d, b, c]
+        assert_eq!(moved_index(0, 3, 1), 0);
+        assert_eq!(moved_index(1, 3, 1), 2);
+        assert_eq!(moved_index(2, 3, 1), 3);
+        assert_eq!(moved_index(3, 3, 1), 1);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{
+    container::{ContainerLayoutEnum, ScaleStrat, SCALE_ADAPTION},
+    hbox::hbox_layout_homogeneous,
+    icons::svg_dom::SvgDom,
+    layout::LayoutMgr,
+    prelude::*,
+    tab_bar::{TabBar, TabBarSignals, TabPosition},
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    vbox::vbox_layout_homogeneous,
+    widget::WidgetImpl,
+};
+use tlib::{connect, emit, signals};
+
+/// The container of pages, shows one page at a time selected by the [`TabBar`].
+///
+/// Pages were held by an inner [`Stack`], use [`add_tab()`](TabWidget::add_tab)
+/// instead of `add_child()` to add pages.
+#[extends(Container)]
+pub struct TabWidget {
+    #[derivative(Default(value = "TabBar::new_alloc()"))]
+    tab_bar: Tr<TabBar>,
+    #[derivative(Default(value = "Stack::new_alloc()"))]
+    stack: Tr<Stack>,
+    /// The id of page of each tab, in the order of tabs.
+    pages: Vec<ObjectId>,
+}
+
+pub trait TabWidgetSignals: ActionExt {
+    signals!(
+        TabWidgetSignals:
+
+        /// Emit when the index of current page was changed.
+        ///
+        /// @param [`usize`] the index of the current tab, [`usize::MAX`] if there was no tab.
+        current_changed(usize);
+
+        /// Emit when the close button of tab was clicked, or the tab was middle-clicked.
+        /// The tab will not be removed automatically, call [`remove_tab()`](TabWidget::remove_tab) if needed.
+        ///
+        /// @param [`usize`] the index of the tab.
+        tab_close_requested(usize);
+    );
+}
+impl TabWidgetSignals for TabWidget {}
+
+impl ObjectSubclass for TabWidget {
+    const NAME: &'static str = "TabWidget";
+}
+
+impl ObjectImpl for TabWidget {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        let mut tab_bar = self.tab_bar.clone();
+        let mut stack = self.stack.clone();
+        tab_bar.set_parent(self);
+        stack.set_parent(self);
+        stack.set_hexpand(true);
+        stack.set_vexpand(true);
+
+        self.container.children.push(tab_bar.clone().into());
+        self.container.children.push(stack.into());
+
+        connect!(tab_bar, current_changed(), self, on_current_changed(usize));
+        connect!(
+            tab_bar,
+            tab_close_requested(),
+            self,
+            on_close_requested(usize)
+        );
+        connect!(tab_bar, tab_moved(), self, on_tab_moved(usize, usize));
+    }
+
+    #[inline]
+    fn type_register(&self, type_registry: &mut TypeRegistry) {
+        type_registry.register::<TabWidget, ReflectSizeUnifiedAdjust>();
+    }
+}
+
+impl WidgetImpl for TabWidget {}
+
+impl TabWidget {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// @return the index of added tab.
+    #[inline]
+    pub fn add_tab<T: WidgetImpl>(&mut self, page: Tr<T>, text: &str) -> usize {
+        self.insert_tab(self.pages.len(), page, text)
+    }
+
+    /// @return the index of added tab.
+    #[inline]
+    pub fn add_tab_with_icon<T: WidgetImpl>(
+        &mut self,
+        page: Tr<T>,
+        text: &str,
+        icon: SvgDom,
+    ) -> usize {
+        let idx = self.add_tab(page, text);
+        self.tab_bar.set_tab_icon(idx, Some(icon));
+        idx
+    }
+
+    /// Insert the page at `idx`, the page will be appended if `idx` was out of range.
+    ///
+    /// @return the index of inserted tab.
+    pub fn insert_tab<T: WidgetImpl>(&mut self, idx: usize, page: Tr<T>, text: &str) -> usize {
+        let idx = idx.min(self.pages.len());
+        self.pages.insert(idx, page.id());
+        self.stack.add_child(page);
+
+        self.tab_bar.insert_tab(idx, text)
+    }
+
+    /// Remove the tab and its page, the removed page will be destroyed.
+    pub fn remove_tab(&mut self, idx: usize) {
+        if idx >= self.pages.len() {
+            return;
+        }
+        let id = self.pages.remove(idx);
+        self.tab_bar.remove_tab(idx);
+        self.stack.remove_children(id);
+        self.sync_current_page();
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Get the page of tab.
+    pub fn page(&self, idx: usize) -> Option<&dyn WidgetImpl> {
+        let id = *self.pages.get(idx)?;
+        self.stack.children().into_iter().find(|c| c.id() == id)
+    }
+
+    /// Get the mutable page of tab.
+    pub fn page_mut(&mut self, idx: usize) -> Option<&mut dyn WidgetImpl> {
+        let id = *self.pages.get(idx)?;
+        self.stack.children_mut().into_iter().find(|c| c.id() == id)
+    }
+
+    /// Get the index of tab which holds the page.
+    #[inline]
+    pub fn index_of(&self, page: ObjectId) -> Option<usize> {
+        self.pages.iter().position(|id| *id == page)
+    }
+
+    #[inline]
+    pub fn current_index(&self) -> Option<usize> {
+        self.tab_bar.current_index()
+    }
+
+    #[inline]
+    pub fn set_current_index(&mut self, idx: usize) {
+        self.tab_bar.set_current_index(idx)
+    }
+
+    #[inline]
+    pub fn tab_bar(&self) -> &TabBar {
+        self.tab_bar.bind()
+    }
+
+    /// Get the mutable tab bar to set the tab texts, icons and so on.
+    #[inline]
+    pub fn tab_bar_mut(&mut self) -> &mut TabBar {
+        self.tab_bar.bind_mut()
+    }
+
+    #[inline]
+    pub fn tab_position(&self) -> TabPosition {
+        self.tab_bar.tab_position()
+    }
+
+    /// Set the side of tab bar relative to the pages.
+    ///
+    /// The default value was [`TabPosition::Top`].
+    pub fn set_tab_position(&mut self, position: TabPosition) {
+        if self.tab_position() == position {
+            return;
+        }
+        self.tab_bar.set_tab_position(position);
+
+        let bar_first = matches!(position, TabPosition::Top | TabPosition::Left);
+        let is_bar_first = self.container.children[0].id() == self.tab_bar.id();
+        if bar_first != is_bar_first {
+            self.container.children.swap(0, 1);
+        }
+
+        self.window().layout_change(self)
+    }
+
+    #[inline]
+    pub fn tabs_closable(&self) -> bool {
+        self.tab_bar.tabs_closable()
+    }
+
+    /// Show the close button on tabs or not.
+    #[inline]
+    pub fn set_tabs_closable(&mut self, closable: bool) {
+        self.tab_bar.set_tabs_closable(closable)
+    }
+}
+
+impl TabWidget {
+    fn on_current_changed(&mut self, idx: usize) {
+        self.sync_current_page();
+        emit!(self, current_changed(idx));
+    }
+
+    #[inline]
+    fn on_close_requested(&mut self, idx: usize) {
+        emit!(self, tab_close_requested(idx));
+    }
+
+    #[inline]
+    fn on_tab_moved(&mut self, from: usize, to: usize) {
+        if from < self.pages.len() && to < self.pages.len() {
+            let id = self.pages.remove(from);
+            self.pages.insert(to, id);
+        }
+    }
+
+    /// Switch the stack to the page of current tab.
+    fn sync_current_page(&mut self) {
+        let Some(idx) = self.tab_bar.current_index() else {
+            return;
+        };
+        let Some(id) = self.pages.get(idx).copied() else {
+            return;
+        };
+        let Some(index) = self.stack.children().iter().position(|c| c.id() == id) else {
+            return;
+        };
+        if self.stack.current_index() != index || !self.stack.children()[index].visible() {
+            self.stack.switch_index(index);
+        }
+    }
+}
+
+impl ContainerImpl for TabWidget {
+    fn children(&self) -> Vec<&dyn WidgetImpl> {
+        self.container.children.iter().map(|c| c.bind()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn WidgetImpl> {
+        self.container
+            .children
+            .iter_mut()
+            .map(|c| c.bind_mut())
+            .collect()
+    }
+
+    fn container_layout(&self) -> ContainerLayoutEnum {
+        ContainerLayoutEnum::TabWidget
+    }
+}
+
+impl ContainerImplExt for TabWidget {
+    fn add_child<T>(&mut self, _child: Tr<T>)
+    where
+        T: WidgetImpl,
+    {
+        panic!("Use function `add_tab()` instead.")
+    }
+
+    fn remove_children(&mut self, id: ObjectId) {
+        if let Some(idx) = self.index_of(id) {
+            self.remove_tab(idx)
+        }
+    }
+}
+
+impl ContainerScaleCalculate for TabWidget {
+    #[inline]
+    fn container_hscale_calculate(&self) -> f32 {
+        Self::static_container_hscale_calculate(self)
+    }
+
+    #[inline]
+    fn container_vscale_calculate(&self) -> f32 {
+        Self::static_container_vscale_calculate(self)
+    }
+}
+impl StaticContainerScaleCalculate for TabWidget {
+    fn static_container_hscale_calculate(c: &dyn ContainerImpl) -> f32 {
+        if tab_position_of(c).is_horizontal() {
+            return SCALE_ADAPTION;
+        }
+        match c.scale_strat() {
+            ScaleStrat::Sum => c
+                .children()
+                .iter()
+                .filter(|c| !c.fixed_width())
+                .map(|c| if c.visible() { c.hscale() } else { 0. })
+                .sum(),
+            ScaleStrat::Direct => 1.,
+        }
+    }
+
+    fn static_container_vscale_calculate(c: &dyn ContainerImpl) -> f32 {
+        if !tab_position_of(c).is_horizontal() {
+            return SCALE_ADAPTION;
+        }
+        match c.scale_strat() {
+            ScaleStrat::Sum => c
+                .children()
+                .iter()
+                .filter(|c| !c.fixed_height())
+                .map(|c| if c.visible() { c.vscale() } else { 0. })
+                .sum(),
+            ScaleStrat::Direct => 1.,
+        }
+    }
+}
+
+impl SizeUnifiedAdjust for TabWidget {
+    #[inline]
+    fn size_unified_adjust(&mut self) {
+        Self::static_size_unified_adjust(self)
+    }
+}
+impl StaticSizeUnifiedAdjust for TabWidget {
+    #[inline]
+    fn static_size_unified_adjust(container: &mut dyn ContainerImpl) {
+        if tab_position_of(container).is_horizontal() {
+            VBox::static_size_unified_adjust(container)
+        } else {
+            HBox::static_size_unified_adjust(container)
+        }
+    }
+}
+
+impl Layout for TabWidget {
+    #[inline]
+    fn composition(&self) -> Composition {
+        Self::static_composition(self)
+    }
+
+    #[inline]
+    fn position_layout(&mut self, parent: Option<&dyn WidgetImpl>) {
+        Self::container_position_layout(self, parent)
+    }
+}
+
+impl ContainerLayout for TabWidget {
+    #[inline]
+    fn static_composition<T: WidgetImpl + ContainerImpl>(widget: &T) -> Composition {
+        if tab_position_of(widget).is_horizontal() {
+            Composition::VerticalArrange
+        } else {
+            Composition::HorizontalArrange
+        }
+    }
+
+    fn container_position_layout<T: WidgetImpl + ContainerImpl>(
+        widget: &mut T,
+        parent: Option<&dyn WidgetImpl>,
+    ) {
+        LayoutMgr::base_widget_position_layout(widget, parent);
+
+        if tab_position_of(widget).is_horizontal() {
+            vbox_layout_homogeneous(widget, Align::Start, Align::Start)
+        } else {
+            hbox_layout_homogeneous(widget, Align::Start, Align::Start)
+        }
+    }
+}
+
+#[inline]
+fn tab_position_of<T: ContainerImpl + ?Sized>(container: &T) -> TabPosition {
+    container
+        .as_any()
+        .downcast_ref::<TabWidget>()
+        .map(|w| w.tab_position())
+        .unwrap_or_default()
+}
@@ -0,0 +1,742 @@
+use crate::{
+    container::{ContainerLayoutEnum, ScaleStrat, SCALE_ADAPTION},
+    font::FontCalculation,
+    graphics::painter::Painter,
+    layout::LayoutMgr,
+    menu::{menu_item::MenuItem, Menu, MenuSignals},
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{RegionClear, WidgetImpl},
+};
+use tlib::{
+    connect, emit,
+    events::MouseEvent,
+    namespace::{MouseButton, Orientation},
+    object::ObjectId,
+    signals,
+};
+
+const BAR_PADDING: i32 = 3;
+const ITEM_SPACING: i32 = 2;
+const BUTTON_PADDING: i32 = 5;
+const BUTTON_RADIUS: f32 = 3.;
+const SEPARATOR_EXTENT: i32 = 9;
+const OVERFLOW_TEXT: &str = "»";
+
+const HOVER_BACKGROUND: Color = Color::rgb(225, 225, 225);
+const PRESSED_BACKGROUND: Color = Color::rgb(205, 205, 205);
+const CHECKED_BACKGROUND: Color = Color::rgb(204, 228, 247);
+const SEPARATOR_COLOR: Color = Color::rgb(200, 200, 200);
+
+/// The button of [`ToolBar`], shows an icon widget(e.g. [`SvgIcon`](crate::icons::svg_icon::SvgIcon),
+/// [`FontIcon`](crate::icons::font_icon::FontIcon)) or the text if there is no icon.
+///
+/// The text was also used as the entry text in the overflow menu of tool bar.
+#[extends(Widget)]
+pub struct ToolButton {
+    text: String,
+    checkable: bool,
+    checked: bool,
+    hovered: bool,
+    pressed: bool,
+
+    #[derivative(Default(value = "Color::BLACK"))]
+    color: Color,
+}
+
+pub trait ToolButtonSignals: ActionExt {
+    signals!(
+        ToolButtonSignals:
+
+        /// Emitted when the button was clicked, or activated from the overflow menu.
+        triggered();
+
+        /// Emitted when the checked state of checkable button was changed.
+        ///
+        /// @param [`bool`]: the new checked state.
+        toggled(bool);
+    );
+}
+impl ToolButtonSignals for ToolButton {}
+
+impl ObjectSubclass for ToolButton {
+    const NAME: &'static str = "ToolButton";
+}
+
+impl ObjectImpl for ToolButton {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_paddings(
+            BUTTON_PADDING,
+            BUTTON_PADDING,
+            BUTTON_PADDING,
+            BUTTON_PADDING,
+        );
+    }
+}
+
+impl WidgetImpl for ToolButton {
+    #[inline]
+    fn font_changed(&mut self) {
+        self.calc_text_size();
+    }
+
+    fn paint(&mut self, painter: &mut Painter) {
+        let rect = self.origin_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        let background = if self.pressed {
+            Some(PRESSED_BACKGROUND)
+        } else if self.checked {
+            Some(CHECKED_BACKGROUND)
+        } else if self.hovered {
+            Some(HOVER_BACKGROUND)
+        } else {
+            None
+        };
+
+        painter.save_pen();
+        painter.set_antialiasing(true);
+        if let Some(background) = background {
+            let r = BUTTON_RADIUS;
+            painter.fill_round_rect(rect, (r, r, r, r), background);
+        }
+
+        if self.get_child_ref().is_none() {
+            let contents = self.contents_rect_f(Some(Coordinate::Widget));
+            let font_height = self.font().calc_font_dimension().1;
+            painter.set_color(self.color);
+            painter.draw_paragraph(
+                &self.text,
+                (
+                    contents.x(),
+                    contents.y() + (contents.height() - font_height) / 2.,
+                ),
+                0.,
+                contents.width(),
+                Some(1),
+                false,
+            );
+        }
+        painter.restore_pen();
+    }
+
+    #[inline]
+    fn on_mouse_enter(&mut self, _: &MouseEvent) {
+        self.hovered = true;
+        self.update();
+    }
+
+    #[inline]
+    fn on_mouse_leave(&mut self, _: &MouseEvent) {
+        self.hovered = false;
+        self.pressed = false;
+        self.update();
+    }
+
+    fn on_mouse_pressed(&mut self, event: &MouseEvent) {
+        if event.mouse_button() == MouseButton::LeftButton {
+            self.pressed = true;
+            self.update();
+        }
+    }
+
+    fn on_mouse_released(&mut self, event: &MouseEvent) {
+        if event.mouse_button() != MouseButton::LeftButton || !self.pressed {
+            return;
+        }
+        self.pressed = false;
+        self.update();
+
+        let rect = self.origin_rect_f(Some(Coordinate::Widget));
+        if rect.contains(&event.position().into()) {
+            self.trigger();
+        }
+    }
+}
+
+impl ToolButton {
+    /// Create the button shows the icon widget.
+    pub fn new<T: WidgetImpl>(icon: Tr<T>, text: &str) -> Tr<Self> {
+        let mut button = Self::new_alloc();
+        button.text = text.to_string();
+        button.child(icon);
+        button
+    }
+
+    /// Create the checkable button shows the icon widget.
+    #[inline]
+    pub fn new_toggle<T: WidgetImpl>(icon: Tr<T>, text: &str) -> Tr<Self> {
+        let mut button = Self::new(icon, text);
+        button.checkable = true;
+        button
+    }
+
+    /// Create the button shows the text.
+    pub fn with_text(text: &str) -> Tr<Self> {
+        let mut button = Self::new_alloc();
+        button.text = text.to_string();
+        button.calc_text_size();
+        button
+    }
+
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.calc_text_size();
+        self.update();
+    }
+
+    #[inline]
+    pub fn is_checkable(&self) -> bool {
+        self.checkable
+    }
+
+    #[inline]
+    pub fn set_checkable(&mut self, checkable: bool) {
+        self.checkable = checkable;
+        if !checkable {
+            self.set_checked(false);
+        }
+    }
+
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Set the checked state of checkable button, emit `toggled()` if the state was changed.
+    pub fn set_checked(&mut self, checked: bool) {
+        if (!self.checkable && checked) || self.checked == checked {
+            return;
+        }
+        self.checked = checked;
+        emit!(self, toggled(checked));
+        self.update();
+    }
+
+    /// Activate the button as if it was clicked,
+    /// the checked state of checkable button will be toggled.
+    pub fn trigger(&mut self) {
+        if self.checkable {
+            self.set_checked(!self.checked);
+        }
+        emit!(self, triggered());
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Set the color of text.
+    #[inline]
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.update();
+    }
+
+    /// The button without icon was sized by the text.
+    fn calc_text_size(&mut self) {
+        if self.get_child_ref().is_some() {
+            return;
+        }
+        let (width, height) = self.font().calc_text_dimension(&self.text, 0.);
+        self.width_request(width.ceil() as i32 + BUTTON_PADDING * 2);
+        self.height_request(height.ceil() as i32 + BUTTON_PADDING * 2);
+    }
+}
+
+/// The separator line between the items of [`ToolBar`].
+#[extends(Widget)]
+pub struct ToolSeparator {
+    orientation: Orientation,
+}
+
+impl ObjectSubclass for ToolSeparator {
+    const NAME: &'static str = "ToolSeparator";
+}
+
+impl ObjectImpl for ToolSeparator {
+    #[inline]
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_orientation(Orientation::Horizontal);
+    }
+}
+
+impl WidgetImpl for ToolSeparator {
+    fn paint(&mut self, painter: &mut Painter) {
+        let rect = self.origin_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        painter.save_pen();
+        painter.set_color(SEPARATOR_COLOR);
+        painter.set_line_width(1.);
+        match self.orientation {
+            Orientation::Horizontal => {
+                let x = rect.x() + rect.width() / 2.;
+                painter.draw_line_f(x, rect.y() + 2., x, rect.bottom() - 2.);
+            }
+            Orientation::Vertical => {
+                let y = rect.y() + rect.height() / 2.;
+                painter.draw_line_f(rect.x() + 2., y, rect.right() - 2., y);
+            }
+        }
+        painter.restore_pen();
+    }
+}
+
+impl ToolSeparator {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// Set the orientation of the tool bar which the separator belongs to.
+    pub(crate) fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+        match orientation {
+            Orientation::Horizontal => {
+                self.width_request(SEPARATOR_EXTENT);
+                self.height_request(BUTTON_PADDING * 2);
+                self.set_vexpand(true);
+                self.set_hexpand(false);
+            }
+            Orientation::Vertical => {
+                self.height_request(SEPARATOR_EXTENT);
+                self.width_request(BUTTON_PADDING * 2);
+                self.set_hexpand(true);
+                self.set_vexpand(false);
+            }
+        }
+        self.update();
+    }
+}
+
+/// The bar of tool buttons, separators and other widgets, arranged horizontally or vertically.
+///
+/// When there is not enough space to show all the items, the trailing items will be hidden,
+/// and a chevron button will be shown at the end of the bar, which opens the overflow menu
+/// to activate the hidden [`ToolButton`]s.
+///
+/// Tool bars can be placed on the edges of window,
+/// see [`ApplicationWindow::add_edge_widget`](crate::application_window::ApplicationWindow::add_edge_widget).
+#[extends(Container)]
+pub struct ToolBar {
+    orientation: Orientation,
+
+    #[derivative(Default(value = "ToolButton::with_text(OVERFLOW_TEXT)"))]
+    overflow_button: Tr<ToolButton>,
+    #[derivative(Default(value = "Menu::new()"))]
+    overflow_menu: Tr<Menu>,
+    /// The items hidden because of the lack of space.
+    overflowed: Vec<ObjectId>,
+    /// The tool buttons of the overflow menu items.
+    overflow_items: Vec<ObjectId>,
+}
+
+impl ObjectSubclass for ToolBar {
+    const NAME: &'static str = "ToolBar";
+}
+
+impl ObjectImpl for ToolBar {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        self.set_paddings(BAR_PADDING, BAR_PADDING, BAR_PADDING, BAR_PADDING);
+        self.set_hexpand(true);
+
+        let mut overflow_button = self.overflow_button.clone();
+        overflow_button.set_parent(self);
+        overflow_button.hide();
+        self.container.children.push(overflow_button.clone().into());
+
+        connect!(overflow_button, triggered(), self, show_overflow_menu());
+        connect!(
+            self.overflow_menu,
+            triggered(),
+            self,
+            overflow_triggered(usize)
+        );
+        connect!(self, size_changed(), self, update_overflow());
+    }
+
+    #[inline]
+    fn type_register(&self, type_registry: &mut TypeRegistry) {
+        type_registry.register::<ToolBar, ReflectSizeUnifiedAdjust>();
+    }
+}
+
+impl WidgetImpl for ToolBar {}
+
+impl ToolBar {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// Add the tool button, see [`ToolButton::new`].
+    #[inline]
+    pub fn add_button(&mut self, button: Tr<ToolButton>) {
+        self.add_item(button)
+    }
+
+    #[inline]
+    pub fn add_separator(&mut self) {
+        let mut separator = ToolSeparator::new();
+        separator.set_orientation(self.orientation);
+        self.add_item(separator)
+    }
+
+    /// Add the arbitrary widget to the tool bar, e.g. the input box.
+    pub fn add_item<T: WidgetImpl>(&mut self, mut item: Tr<T>) {
+        item.set_parent(self);
+        connect!(item, size_changed(), self, update_overflow());
+
+        // The overflow button was always the last child.
+        let idx = self.container.children.len() - 1;
+        self.container.children.insert(idx, item.clone().into());
+        ApplicationWindow::initialize_dynamic_component(item.as_dyn_mut(), self.is_in_tree());
+        self.update();
+    }
+
+    /// Remove all the items.
+    pub fn clear(&mut self) {
+        let ids: Vec<ObjectId> = self.items().iter().map(|item| item.id()).collect();
+        for id in ids {
+            self.remove_children(id);
+        }
+    }
+
+    /// Get the items of tool bar, excluding the overflow chevron button.
+    pub fn items(&self) -> Vec<&dyn WidgetImpl> {
+        let children = &self.container.children;
+        children[..children.len() - 1]
+            .iter()
+            .map(|c| c.bind())
+            .collect()
+    }
+
+    #[inline]
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Set the orientation of tool bar.
+    ///
+    /// The default value was [`Orientation::Horizontal`].
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        if self.orientation == orientation {
+            return;
+        }
+        self.orientation = orientation;
+        match orientation {
+            Orientation::Horizontal => {
+                self.set_hexpand(true);
+                self.set_vexpand(false);
+            }
+            Orientation::Vertical => {
+                self.set_vexpand(true);
+                self.set_hexpand(false);
+            }
+        }
+
+        for child in self.container.children.iter_mut() {
+            if let Some(separator) = child.as_any_mut().downcast_mut::<ToolSeparator>() {
+                separator.set_orientation(orientation);
+            }
+        }
+
+        self.window().layout_change(self)
+    }
+}
+
+impl ToolBar {
+    /// Hide the trailing items which can not be shown in the tool bar,
+    /// and show the overflow button if there are hidden items.
+    fn update_overflow(&mut self) {
+        let horizontal = self.orientation == Orientation::Horizontal;
+        let contents = self.contents_rect(None);
+        let available = if horizontal {
+            contents.width()
+        } else {
+            contents.height()
+        };
+        let extent_of = |w: &dyn WidgetImpl| {
+            let rect = w.image_rect();
+            if horizontal {
+                rect.width() + w.margin_left() + w.margin_right()
+            } else {
+                rect.height() + w.margin_top() + w.margin_bottom()
+            }
+        };
+
+        let len = self.container.children.len() - 1;
+        let overflow_extent = extent_of(self.overflow_button.bind()) + ITEM_SPACING;
+        let items: Vec<(ObjectId, i32)> = self.container.children[..len]
+            .iter()
+            .filter(|c| c.visible() || self.overflowed.contains(&c.id()))
+            .map(|c| (c.id(), extent_of(c.bind())))
+            .collect();
+
+        let total: i32 = items.iter().map(|(_, e)| e + ITEM_SPACING).sum();
+        let mut overflowed = vec![];
+        if total > available {
+            let mut offset = 0;
+            for (id, extent) in items {
+                offset += extent + ITEM_SPACING;
+                if offset > available - overflow_extent {
+                    overflowed.push(id);
+                }
+            }
+        }
+
+        if overflowed == self.overflowed {
+            return;
+        }
+        for child in self.container.children[..len].iter_mut() {
+            let id = child.id();
+            if overflowed.contains(&id) {
+                child.hide();
+            } else if self.overflowed.contains(&id) {
+                child.show();
+            }
+        }
+        if overflowed.is_empty() {
+            self.overflow_button.hide();
+        } else {
+            self.overflow_button.show();
+        }
+
+        self.overflowed = overflowed;
+        self.window().layout_change(self);
+    }
+
+    fn show_overflow_menu(&mut self) {
+        let mut menu = self.overflow_menu.clone();
+        menu.clear();
+        self.overflow_items.clear();
+
+        for child in self.container.children.iter() {
+            if !self.overflowed.contains(&child.id()) {
+                continue;
+            }
+            let Some(button) = child.as_any().downcast_ref::<ToolButton>() else {
+                continue;
+            };
+            let item = MenuItem::builder()
+                .text(&button.text().replace('&', "&&"))
+                .checkable(button.is_checkable())
+                .checked(button.is_checked())
+                .build();
+            menu.add_item(item);
+            self.overflow_items.push(button.id());
+        }
+        if menu.is_empty() {
+            return;
+        }
+
+        if !menu.initialized() {
+            menu.set_supervisor(self);
+        }
+        menu.popup_beside(self.overflow_button.rect(), false);
+    }
+
+    fn overflow_triggered(&mut self, idx: usize) {
+        let Some(id) = self.overflow_items.get(idx).copied() else {
+            return;
+        };
+        if let Some(button) = self
+            .container
+            .children
+            .iter_mut()
+            .find(|c| c.id() == id)
+            .and_then(|c| c.as_any_mut().downcast_mut::<ToolButton>())
+        {
+            button.trigger();
+        }
+    }
+}
+
+impl ContainerImpl for ToolBar {
+    fn children(&self) -> Vec<&dyn WidgetImpl> {
+        self.container.children.iter().map(|c| c.bind()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn WidgetImpl> {
+        self.container
+            .children
+            .iter_mut()
+            .map(|c| c.bind_mut())
+            .collect()
+    }
+
+    fn container_layout(&self) -> ContainerLayoutEnum {
+        ContainerLayoutEnum::ToolBar
+    }
+}
+
+impl ContainerImplExt for ToolBar {
+    #[inline]
+    fn add_child<T>(&mut self, child: Tr<T>)
+    where
+        T: WidgetImpl,
+    {
+        self.add_item(child)
+    }
+
+    fn remove_children(&mut self, id: ObjectId) {
+        if id == self.overflow_button.id() {
+            return;
+        }
+        if let Some(index) = self.container.children.iter().position(|w| w.id() == id) {
+            let removed = self.container.children.remove(index);
+            self.overflowed.retain(|o| *o != id);
+
+            let window = ApplicationWindow::window();
+            window._add_removed_widget(removed);
+            window.layout_change(self);
+        }
+    }
+}
+
+impl ContainerScaleCalculate for ToolBar {
+    #[inline]
+    fn container_hscale_calculate(&self) -> f32 {
+        Self::static_container_hscale_calculate(self)
+    }
+
+    #[inline]
+    fn container_vscale_calculate(&self) -> f32 {
+        Self::static_container_vscale_calculate(self)
+    }
+}
+impl StaticContainerScaleCalculate for ToolBar {
+    fn static_container_hscale_calculate(c: &dyn ContainerImpl) -> f32 {
+        match orientation_of(c) {
+            Orientation::Horizontal => match c.scale_strat() {
+                ScaleStrat::Sum => c
+                    .children()
+                    .iter()
+                    .filter(|c| !c.fixed_width())
+                    .map(|c| if c.visible() { c.hscale() } else { 0. })
+                    .sum(),
+                ScaleStrat::Direct => 1.,
+            },
+            Orientation::Vertical => SCALE_ADAPTION,
+        }
+    }
+
+    fn static_container_vscale_calculate(c: &dyn ContainerImpl) -> f32 {
+        match orientation_of(c) {
+            Orientation::Horizontal => SCALE_ADAPTION,
+            Orientation::Vertical => match c.scale_strat() {
+                ScaleStrat::Sum => c
+                    .children()
+                    .iter()
+                    .filter(|c| !c.fixed_height())
+                    .map(|c| if c.visible() { c.vscale() } else { 0. })
+                    .sum(),
+                ScaleStrat::Direct => 1.,
+            },
+        }
+    }
+}
+
+impl SizeUnifiedAdjust for ToolBar {
+    #[inline]
+    fn size_unified_adjust(&mut self) {
+        Self::static_size_unified_adjust(self)
+    }
+}
+impl StaticSizeUnifiedAdjust for ToolBar {
+    #[inline]
+    fn static_size_unified_adjust(container: &mut dyn ContainerImpl) {
+        match orientation_of(container) {
+            Orientation::Horizontal => HBox::static_size_unified_adjust(container),
+            Orientation::Vertical => VBox::static_size_unified_adjust(container),
+        }
+    }
+}
+
+impl Layout for ToolBar {
+    #[inline]
+    fn composition(&self) -> Composition {
+        Self::static_composition(self)
+    }
+
+    #[inline]
+    fn position_layout(&mut self, parent: Option<&dyn WidgetImpl>) {
+        Self::container_position_layout(self, parent)
+    }
+}
+
+impl ContainerLayout for ToolBar {
+    #[inline]
+    fn static_composition<T: WidgetImpl + ContainerImpl>(widget: &T) -> Composition {
+        match orientation_of(widget) {
+            Orientation::Horizontal => Composition::HorizontalArrange,
+            Orientation::Vertical => Composition::VerticalArrange,
+        }
+    }
+
+    /// The visible items were arranged from the start, centered on the cross axis,
+    /// the overflow button was placed at the end.
+    fn container_position_layout<T: WidgetImpl + ContainerImpl>(
+        widget: &mut T,
+        parent: Option<&dyn WidgetImpl>,
+    ) {
+        LayoutMgr::base_widget_position_layout(widget, parent);
+
+        let horizontal = orientation_of(widget) == Orientation::Horizontal;
+        let contents = widget.contents_rect(None);
+        let mut children = widget.children_mut();
+        let Some(overflow_button) = children.pop() else {
+            return;
+        };
+
+        let mut offset = if horizontal {
+            contents.x()
+        } else {
+            contents.y()
+        };
+        for child in children.into_iter().filter(|c| c.visible()) {
+            let rect = child.image_rect();
+            if horizontal {
+                child.set_fixed_x(offset + child.margin_left());
+                child.set_fixed_y(contents.y() + (contents.height() - rect.height()) / 2);
+                offset += rect.width() + child.margin_left() + child.margin_right();
+            } else {
+                child.set_fixed_y(offset + child.margin_top());
+                child.set_fixed_x(contents.x() + (contents.width() - rect.width()) / 2);
+                offset += rect.height() + child.margin_top() + child.margin_bottom();
+            }
+            offset += ITEM_SPACING;
+        }
+
+        let rect = overflow_button.image_rect();
+        if horizontal {
+            overflow_button.set_fixed_x(contents.right() - rect.width());
+            overflow_button.set_fixed_y(contents.y() + (contents.height() - rect.height()) / 2);
+        } else {
+            overflow_button.set_fixed_y(contents.bottom() - rect.height());
+            overflow_button.set_fixed_x(contents.x() + (contents.width() - rect.width()) / 2);
+        }
+    }
+}
+
+#[inline]
+fn orientation_of<T: ContainerImpl + ?Sized>(container: &T) -> Orientation {
+    container
+        .as_any()
+        .downcast_ref::<ToolBar>()
+        .map(|w| w.orientation())
+        .unwrap_or(Orientation::Horizontal)
+}
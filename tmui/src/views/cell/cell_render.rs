@@ -1,5 +1,11 @@
 #![allow(dead_code)]
-use crate::{graphics::painter::Painter, icons::svg_dom::SvgDom, views::node::Status};
+use super::image_cache;
+use crate::{
+    graphics::painter::Painter,
+    icons::svg_dom::SvgDom,
+    progress_bar::{render_bar, render_ring, DEFAULT_PROGRESS_COLOR, DEFAULT_TRACK_COLOR},
+    views::node::Status,
+};
 use derivative::Derivative;
 use log::warn;
 use std::fmt::Debug;
@@ -12,7 +18,7 @@ use tlib::{
         textlayout::{
             FontCollection, ParagraphBuilder, ParagraphStyle, TextStyle, TypefaceFontProvider,
         },
-        ClipOp,
+        ClipOp, PaintCap, PaintStyle,
     },
     Type, Value,
 };
@@ -22,6 +28,8 @@ pub enum CellRenderType {
     Text,
     Image,
     Svg,
+    Progress,
+    Checkbox,
 }
 
 pub trait CellRender: Debug + 'static + Send + Sync {
@@ -269,6 +277,13 @@ type OptColor = Option<Color>;
 cell_render_struct!(TextCellRender, TextCellRenderBuilder, Text, color:Color, hover_color:OptColor, selection_color:OptColor, letter_spacing:f32);
 cell_render_struct!(ImageCellRender, ImageCellRenderBuilder, Image);
 cell_render_struct!(SvgCellRender, SvgCellRenderBuilder, Svg, dom:OptSvgDom, hover_dom:OptSvgDom, selection_dom:OptSvgDom);
+cell_render_struct!(ProgressCellRender, ProgressCellRenderBuilder, Progress, style:ProgressStyle, color:OptColor, track_color:OptColor, thickness:f32);
+cell_render_struct!(CheckboxCellRender, CheckboxCellRenderBuilder, Checkbox, color:OptColor, check_color:OptColor);
+
+pub use crate::progress_bar::ProgressStyle;
+
+const DEFAULT_CHECKBOX_SIZE: f32 = 14.;
+const DEFAULT_CHECKBOX_BORDER: Color = Color::rgb(160, 160, 160);
 
 impl CellRender for TextCellRender {
     fn render(&self, painter: &mut Painter, geometry: FRect, val: Option<&Value>, status: Status) {
@@ -428,7 +443,31 @@ impl TextCellRender {
 }
 
 impl CellRender for ImageCellRender {
-    fn render(&self, _painter: &mut Painter, _geometry: FRect, _val: Option<&Value>, _status: Status) {}
+    fn render(&self, painter: &mut Painter, rect: FRect, val: Option<&Value>, _: Status) {
+        let address = match val {
+            Some(val) => val.get::<String>(),
+            None => return,
+        };
+        if address.is_empty() {
+            return;
+        }
+
+        // The image was decoded in the background, painted after the widget was updated.
+        image_cache::with_image(&address, painter.widget_id(), |image| {
+            // Scale the image to fit the rect, keeping the aspect ratio.
+            let (w, h) = (image.width() as f32, image.height() as f32);
+            let scale = (rect.width() / w).min(rect.height() / h).min(1.);
+            let (w, h) = (w * scale, h * scale);
+            let dst = FRect::new(
+                rect.x() + (rect.width() - w) / 2.,
+                rect.y() + (rect.height() - h) / 2.,
+                w,
+                h,
+            );
+
+            painter.draw_image_rect(image, None, dst.into());
+        });
+    }
 
     impl_cell_render_common!();
 }
@@ -467,3 +506,141 @@ impl CellRender for SvgCellRender {
 
     impl_cell_render_common!();
 }
+
+/// Render the progress of `f32` value in range [0, 1] or `i32` value in range [0, 100].
+impl CellRender for ProgressCellRender {
+    fn render(&self, painter: &mut Painter, geometry: FRect, val: Option<&Value>, _: Status) {
+        if let Some(background) = self.background {
+            painter.fill_rect(geometry, background);
+        }
+
+        let progress = match progress_value(val) {
+            Some(progress) => progress,
+            None => {
+                warn!("`ProgressCellRender` only support the `f32` and `i32` value.");
+                return;
+            }
+        };
+        let color = self.color.unwrap_or(DEFAULT_PROGRESS_COLOR);
+        let track_color = self.track_color.unwrap_or(DEFAULT_TRACK_COLOR);
+
+        painter.save();
+        painter.clip_rect(geometry, ClipOp::Intersect);
+
+        // Leave some padding so that the progress does not touch the edges of cell.
+        let rect = FRect::new(
+            geometry.x() + 4.,
+            geometry.y() + 2.,
+            (geometry.width() - 8.).max(0.),
+            (geometry.height() - 4.).max(0.),
+        );
+        match self.style {
+            ProgressStyle::Bar => {
+                let thickness = if self.thickness > 0. {
+                    self.thickness
+                } else {
+                    (geometry.height() / 3.).clamp(2., 8.)
+                };
+                render_bar(painter, rect, thickness, (0., progress), color, track_color)
+            }
+            ProgressStyle::Ring => render_ring(painter, rect, (0., progress), color, track_color),
+        }
+
+        painter.restore();
+    }
+
+    impl_cell_render_common!();
+}
+
+/// Render the checkbox of `bool` value, the value will be toggled when the checkbox was clicked
+/// in `ListView`/`TreeView`.
+impl CellRender for CheckboxCellRender {
+    fn render(&self, painter: &mut Painter, geometry: FRect, val: Option<&Value>, _: Status) {
+        if let Some(background) = self.background {
+            painter.fill_rect(geometry, background);
+        }
+
+        let checked = match val {
+            Some(val) if val.ty() == Type::BOOL => val.get::<bool>(),
+            _ => {
+                warn!("`CheckboxCellRender` only support the `bool` value.");
+                return;
+            }
+        };
+        let rect = checkbox_rect(self, geometry);
+        if rect.width() <= 0. {
+            return;
+        }
+        let color = self.color.unwrap_or(DEFAULT_PROGRESS_COLOR);
+        let radius = (2., 2., 2., 2.);
+
+        painter.save();
+        painter.save_pen();
+        painter.clip_rect(geometry, ClipOp::Intersect);
+        painter.set_antialiasing(true);
+
+        if checked {
+            painter.fill_round_rect(rect, radius, color);
+
+            let (x, y, size) = (rect.x(), rect.y(), rect.width());
+            painter.set_color(self.check_color.unwrap_or(Color::WHITE));
+            painter.set_line_width((size / 7.).max(1.5));
+            painter.set_stroke_cap(PaintCap::Round);
+            painter.set_style(PaintStyle::Stroke);
+            painter.draw_line_f(
+                x + size * 0.22,
+                y + size * 0.52,
+                x + size * 0.42,
+                y + size * 0.72,
+            );
+            painter.draw_line_f(
+                x + size * 0.42,
+                y + size * 0.72,
+                x + size * 0.78,
+                y + size * 0.3,
+            );
+            painter.set_stroke_cap(PaintCap::Butt);
+            painter.set_style(PaintStyle::Fill);
+        } else {
+            painter.set_color(DEFAULT_CHECKBOX_BORDER);
+            painter.set_line_width(1.);
+            painter.draw_round_rect(rect, radius);
+        }
+
+        painter.restore_pen();
+        painter.restore();
+    }
+
+    impl_cell_render_common!();
+}
+
+/// Get the progress in range [0, 1] of the `f32` value in range [0, 1] or `i32` value in range [0, 100],
+/// the value out of range will be clamped.
+pub(crate) fn progress_value(val: Option<&Value>) -> Option<f32> {
+    let progress = match val {
+        Some(val) if val.ty() == Type::F32 => val.get::<f32>(),
+        Some(val) if val.ty() == Type::I32 => val.get::<i32>() as f32 / 100.,
+        _ => return None,
+    };
+    Some(progress.clamp(0., 1.))
+}
+
+/// Get the rect of the checkbox square in the cell's `geometry`, aligned by `halign`/`valign` of render.
+pub(crate) fn checkbox_rect(render: &dyn CellRender, geometry: FRect) -> FRect {
+    let size = DEFAULT_CHECKBOX_SIZE
+        .min(geometry.width() - 2.)
+        .min(geometry.height() - 2.)
+        .max(0.);
+
+    let x = match render.halign() {
+        Align::Start => geometry.x() + 1.,
+        Align::Center => geometry.x() + (geometry.width() - size) / 2.,
+        Align::End => geometry.right() - size - 1.,
+    };
+    let y = match render.valign() {
+        Align::Start => geometry.y() + 1.,
+        Align::Center => geometry.y() + (geometry.height() - size) / 2.,
+        Align::End => geometry.bottom() - size - 1.,
+    };
+    FRect::new(x, y, size, size)
+}
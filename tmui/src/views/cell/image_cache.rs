@@ -0,0 +1,249 @@
+use crate::application_window::ApplicationWindow;
+use log::warn;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+};
+use tlib::{
+    object::ObjectId,
+    r#async::{async_tasks, tokio_runtime, AsyncTask},
+    skia_safe::{Data, Image},
+    Value,
+};
+
+/// The maximum count of images kept by the cache of cell images.
+pub const IMAGE_CACHE_CAPACITY: usize = 256;
+
+thread_local! {
+    static IMAGE_CACHE: RefCell<LruCache<CachedImage>> = RefCell::new(LruCache::new(IMAGE_CACHE_CAPACITY));
+}
+
+enum CachedImage {
+    /// Decoding in the background, the widgets painted the image were updated when finished.
+    Loading {
+        generation: u64,
+        widgets: Vec<ObjectId>,
+    },
+    Loaded(Image),
+    /// The image was failed to decode, decoded again after invalidated or evicted.
+    Failed,
+}
+
+/// Get the decoded image of `address`, start decoding in the background if absent.
+///
+/// The widget `widget` was updated when the decoding finished.
+///
+/// @return None if the image was decoding or failed to decode.
+pub(crate) fn with_image<R>(
+    address: &str,
+    widget: ObjectId,
+    f: impl FnOnce(&Image) -> R,
+) -> Option<R> {
+    IMAGE_CACHE.with_borrow_mut(|cache| {
+        match cache.get_mut(address) {
+            Some(CachedImage::Loaded(image)) => return Some(f(image)),
+            Some(CachedImage::Loading { widgets, .. }) => {
+                if !widgets.contains(&widget) {
+                    widgets.push(widget)
+                }
+                return None;
+            }
+            Some(CachedImage::Failed) => return None,
+            None => {}
+        }
+
+        let generation = cache.next_generation();
+        cache.insert(
+            address.to_string(),
+            CachedImage::Loading {
+                generation,
+                widgets: vec![widget],
+            },
+        );
+        decode(address.to_string(), generation);
+        None
+    })
+}
+
+/// Invalidate the cached image of `address`, the image was decoded again when it was
+/// painted next time.
+///
+/// Should be called when the image file of `address` was changed.
+#[inline]
+pub fn invalidate_image(address: &str) {
+    IMAGE_CACHE.with_borrow_mut(|cache| cache.remove(address));
+}
+
+/// Invalidate all the cached images.
+#[inline]
+pub fn clear_images() {
+    IMAGE_CACHE.with_borrow_mut(|cache| cache.clear());
+}
+
+/// Decode the image in the blocking thread of tokio runtime, the result was put into
+/// the cache in the ui thread.
+fn decode(address: String, generation: u64) {
+    let decoded = Arc::new(Mutex::new(None));
+    let slot = decoded.clone();
+    let path = address.clone();
+    let join = tokio_runtime().spawn_blocking(move || {
+        *slot.lock().unwrap() = std::fs::read(&path).ok().and_then(|bytes| {
+            Image::from_encoded(Data::new_copy(&bytes))?.make_raster_image(None, None)
+        });
+        Value::empty()
+    });
+
+    let task = AsyncTask::new(join).then(move |_| {
+        let image = decoded.lock().unwrap().take();
+        if image.is_none() {
+            warn!("Load image of cell failed, address: {}", address);
+        }
+        finish(address, generation, image)
+    });
+    async_tasks()
+        .entry(thread::current().id())
+        .or_insert(vec![])
+        .push(task);
+}
+
+fn finish(address: String, generation: u64, image: Option<Image>) {
+    let widgets = IMAGE_CACHE.with_borrow_mut(|cache| {
+        // The image was invalidated or evicted while decoding.
+        match cache.peek_mut(&address) {
+            Some(CachedImage::Loading {
+                generation: g,
+                widgets,
+            }) if *g == generation => {
+                let widgets = std::mem::take(widgets);
+                cache.replace(
+                    &address,
+                    image.map_or(CachedImage::Failed, CachedImage::Loaded),
+                );
+                widgets
+            }
+            _ => vec![],
+        }
+    });
+
+    let window = ApplicationWindow::window();
+    for id in widgets {
+        if let Some(widget) = window.find_id_mut(id) {
+            widget.update()
+        }
+    }
+}
+
+/// The cache with the least recently used entries evicted when reached the capacity.
+struct LruCache<V> {
+    capacity: usize,
+    tick: u64,
+    generation: u64,
+    entries: HashMap<String, (u64, V)>,
+}
+
+impl<V> LruCache<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            generation: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn next_generation(&mut self) -> u64 {
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Get the entry, marked as the most recently used.
+    fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(t, v)| {
+            *t = tick;
+            v
+        })
+    }
+
+    /// Get the entry without changing the order of use.
+    #[inline]
+    fn peek_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.entries.get_mut(key).map(|(_, v)| v)
+    }
+
+    /// Replace the value of existing entry without changing the order of use.
+    #[inline]
+    fn replace(&mut self, key: &str, value: V) {
+        if let Some((_, v)) = self.entries.get_mut(key) {
+            *v = value
+        }
+    }
+
+    fn insert(&mut self, key: String, value: V) {
+        if !self.entries.contains_key(&key) && self.len() >= self.capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (tick, _))| *tick)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+
+        self.tick += 1;
+        self.entries.insert(key, (self.tick, value));
+    }
+
+    #[inline]
+    fn remove(&mut self, key: &str) -> Option<V> {
+        self.entries.remove(key).map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LruCache;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+
+        // `a` was used recently, `b` was evicted.
+        assert_eq!(cache.get_mut("a"), Some(&mut 1));
+        cache.insert("c".to_string(), 3);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_mut("b").is_none());
+
+        // Replacing and peeking keep the order of use, `c` was evicted.
+        cache.get_mut("c");
+        cache.get_mut("a");
+        cache.replace("c", 4);
+        assert_eq!(cache.peek_mut("c"), Some(&mut 4));
+        cache.insert("d".to_string(), 5);
+        assert!(cache.peek_mut("c").is_none());
+        assert_eq!(cache.peek_mut("a"), Some(&mut 1));
+
+        // The invalidated entry was removed.
+        assert_eq!(cache.remove("a"), Some(1));
+        assert!(cache.get_mut("a").is_none());
+        cache.clear();
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -1,9 +1,15 @@
 pub mod cell_index;
 pub mod cell_render;
+pub mod image_cache;
 
-use self::cell_render::{CellRender, CellRenderType, CellRenderType::*};
+use self::cell_render::{checkbox_rect, CellRender, CellRenderType, CellRenderType::*};
 use crate::graphics::painter::Painter;
-use tlib::{figure::FRect, types::StaticType, values::ToValue, Type, Value};
+use tlib::{
+    figure::{FPoint, FRect},
+    types::StaticType,
+    values::ToValue,
+    Type, Value,
+};
 
 use super::node::Status;
 
@@ -216,18 +222,18 @@ impl Cell {
     pub(crate) fn support_render_types(&self) -> Vec<CellRenderType> {
         match self {
             Self::String { .. } => vec![Text],
-            Self::Bool { .. } => vec![Text],
+            Self::Bool { .. } => vec![Text, Checkbox],
             Self::U8 { .. } => vec![Text],
             Self::I8 { .. } => vec![Text],
             Self::U16 { .. } => vec![Text],
             Self::I16 { .. } => vec![Text],
             Self::U32 { .. } => vec![Text],
-            Self::I32 { .. } => vec![Text],
+            Self::I32 { .. } => vec![Text, Progress],
             Self::U64 { .. } => vec![Text],
             Self::I64 { .. } => vec![Text],
             Self::U128 { .. } => vec![Text],
             Self::I128 { .. } => vec![Text],
-            Self::F32 { .. } => vec![Text],
+            Self::F32 { .. } => vec![Text, Progress],
             Self::F64 { .. } => vec![Text],
             Self::Image { .. } => vec![Image],
             Self::Svg { .. } => vec![Svg],
@@ -239,6 +245,60 @@ impl Cell {
     pub fn is_render_cell(&self) -> bool {
         self.get_render().is_some()
     }
+
+    /// Toggle the value of the checkbox cell.
+    ///
+    /// @return the new value, `None` if the cell was not rendered by [`CheckboxCellRender`](cell_render::CheckboxCellRender).
+    pub(crate) fn toggle_checkbox(&mut self) -> Option<bool> {
+        match self {
+            Self::Bool {
+                val,
+                render: Some(render),
+                ..
+            } if render.ty() == Checkbox => {
+                let checked = !val.get::<bool>();
+                *val = checked.to_value();
+                Some(checked)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lay out the render cells horizontally in the `geometry` of node, the same as the nodes
+/// rendered in `ListView`/`TreeView`, the cells without specified width share the width equally.
+///
+/// @return the iterator of the index of cell in `cells`, the cell and it's rect.
+pub(crate) fn layout_cells(
+    cells: &[Cell],
+    geometry: FRect,
+) -> impl Iterator<Item = (usize, &Cell, FRect)> {
+    let render_cells = cells.iter().filter(|cell| cell.is_render_cell()).count();
+    let gapping = geometry.width() / render_cells.max(1) as f32;
+    let mut offset = geometry.x();
+
+    cells.iter().enumerate().filter_map(move |(idx, cell)| {
+        let cell_render = cell.get_render()?;
+        let mut cell_rect = geometry;
+
+        cell_rect.set_x(offset);
+        cell_rect.set_width(cell_render.width().map_or(gapping, |w| w as f32));
+        if let Some(height) = cell_render.height() {
+            cell_rect.set_height(height as f32);
+        }
+        offset += cell_rect.width();
+
+        Some((idx, cell, cell_rect))
+    })
+}
+
+/// Get the index of the checkbox cell clicked at `point`, see [`layout_cells()`].
+pub(crate) fn checkbox_at(cells: &[Cell], geometry: FRect, point: FPoint) -> Option<usize> {
+    layout_cells(cells, geometry).find_map(|(idx, cell, cell_rect)| {
+        let cell_render = cell.get_render()?;
+        (cell_render.ty() == Checkbox && checkbox_rect(cell_render, cell_rect).contains(&point))
+            .then_some(idx)
+    })
 }
 
 macro_rules! cell_builder {
@@ -427,3 +487,108 @@ impl Cell {
     cell_builder_func!(svg, CellSvgBuilder);
     cell_builder_func!(value_cell, CellValueBuilder);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cell_render::{progress_value, CheckboxCellRender, ProgressCellRender, TextCellRender},
+        checkbox_at, layout_cells, Cell,
+    };
+    use tlib::{
+        figure::{FPoint, FRect},
+        namespace::Align,
+        values::ToValue,
+    };
+
+    fn cells() -> Vec<Cell> {
+        vec![
+            Cell::string()
+                .value("text".to_string())
+                .cell_render(TextCellRender::builder().build())
+                .build(),
+            Cell::value_cell().value(1u32).build(),
+            Cell::bool()
+                .value(false)
+                .cell_render(
+                    CheckboxCellRender::builder()
+                        .width(40)
+                        .halign(Align::Center)
+                        .valign(Align::Center)
+                        .build(),
+                )
+                .build(),
+            Cell::i32()
+                .value(50)
+                .cell_render(ProgressCellRender::builder().build())
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn test_layout_cells() {
+        let cells = cells();
+        let geometry = FRect::new(10., 0., 240., 20.);
+
+        let layout: Vec<(usize, FRect)> = layout_cells(&cells, geometry)
+            .map(|(idx, _, rect)| (idx, rect))
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                (0, FRect::new(10., 0., 80., 20.)),
+                (2, FRect::new(90., 0., 40., 20.)),
+                (3, FRect::new(130., 0., 80., 20.)),
+            ]
+        );
+
+        assert_eq!(layout_cells(&cells[1..2], geometry).count(), 0);
+    }
+
+    #[test]
+    fn test_checkbox_at() {
+        let mut cells = cells();
+        let geometry = FRect::new(10., 0., 240., 20.);
+
+        // The checkbox square was centered in the cell: (103, 3, 14, 14).
+        assert_eq!(
+            checkbox_at(&cells, geometry, FPoint::new(110., 10.)),
+            Some(2)
+        );
+        assert_eq!(
+            checkbox_at(&cells, geometry, FPoint::new(103., 3.)),
+            Some(2)
+        );
+        assert_eq!(checkbox_at(&cells, geometry, FPoint::new(95., 10.)), None);
+        assert_eq!(checkbox_at(&cells, geometry, FPoint::new(50., 10.)), None);
+        assert_eq!(checkbox_at(&cells, geometry, FPoint::new(110., 30.)), None);
+        assert_eq!(
+            checkbox_at(&cells[..2], geometry, FPoint::new(110., 10.)),
+            None
+        );
+
+        assert_eq!(cells[2].toggle_checkbox(), Some(true));
+        assert!(cells[2].value().get::<bool>());
+        assert_eq!(cells[2].toggle_checkbox(), Some(false));
+        assert!(!cells[2].value().get::<bool>());
+
+        assert_eq!(cells[0].toggle_checkbox(), None);
+        assert_eq!(cells[3].toggle_checkbox(), None);
+        let mut plain = Cell::bool()
+            .value(false)
+            .cell_render(TextCellRender::builder().build())
+            .build();
+        assert_eq!(plain.toggle_checkbox(), None);
+    }
+
+    #[test]
+    fn test_progress_value() {
+        assert_eq!(progress_value(Some(&0.5f32.to_value())), Some(0.5));
+        assert_eq!(progress_value(Some(&1.5f32.to_value())), Some(1.));
+        assert_eq!(progress_value(Some(&(-0.2f32).to_value())), Some(0.));
+        assert_eq!(progress_value(Some(&50i32.to_value())), Some(0.5));
+        assert_eq!(progress_value(Some(&150i32.to_value())), Some(1.));
+        assert_eq!(progress_value(Some(&(-10i32).to_value())), Some(0.));
+        assert_eq!(progress_value(Some(&"50".to_string().to_value())), None);
+        assert_eq!(progress_value(None), None);
+    }
+}
@@ -0,0 +1,451 @@
+use super::tile_layout::TileLayout;
+use crate::{
+    font::FontCalculation,
+    prelude::*,
+    scroll_bar::ScrollBar,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    views::{
+        list_view::{
+            list_item::{ItemType, ListItem, ListItemCast},
+            list_node::ListNode,
+            list_store::{ConcurrentStoreMutexGuard, ListStore, ListStoreSignals},
+        },
+        node::{MouseEffect, RenderCtx, Status},
+    },
+    widget::{IterExecutor, RegionClear, WidgetImpl},
+};
+use std::ptr::NonNull;
+use tlib::{
+    connect, disconnect, events::MouseEvent, iter_executor, nonnull_mut, ptr_mut, run_after,
+    skia_safe::ClipOp,
+};
+
+const DEFAULT_TILE_SIZE: (i32, i32) = (96, 96);
+const DEFAULT_SPACING: i32 = 8;
+
+type FnNodeAction = Box<dyn Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent)>;
+type FnAreaAction = Box<dyn Fn(&mut dyn WidgetImpl, &MouseEvent)>;
+
+#[extends(Widget)]
+#[loadable]
+#[run_after]
+#[iter_executor]
+pub(crate) struct GridViewImage {
+    pub(crate) store: ListStore,
+    scroll_bar: Option<NonNull<ScrollBar>>,
+
+    /// The indexes of nodes in items, group separators were not displayed in grid.
+    tiles: Vec<usize>,
+    #[derivative(Default(
+        value = "TileLayout::new(DEFAULT_TILE_SIZE.into(), DEFAULT_SPACING, 0)"
+    ))]
+    layout: TileLayout,
+    scroll_offset: i32,
+
+    #[derivative(Default(value = "DEFAULT_TILE_SIZE.into()"))]
+    pub(crate) tile_size: Size,
+    #[derivative(Default(value = "DEFAULT_SPACING"))]
+    pub(crate) spacing: i32,
+    pub(crate) caption_height: i32,
+    custom_caption_height: bool,
+    #[derivative(Default(value = "MouseEffect::all()"))]
+    pub(crate) mouse_effect: MouseEffect,
+    pub(crate) reset_effect_node_on_hide: bool,
+
+    pub(crate) on_node_enter: Option<FnNodeAction>,
+    pub(crate) on_node_leave: Option<FnNodeAction>,
+    pub(crate) on_node_pressed: Option<FnNodeAction>,
+    pub(crate) on_node_released: Option<FnNodeAction>,
+    pub(crate) on_free_area_pressed: Option<FnAreaAction>,
+    pub(crate) on_free_area_released: Option<FnAreaAction>,
+}
+
+impl ObjectSubclass for GridViewImage {
+    const NAME: &'static str = "GridViewImage";
+}
+impl ObjectImpl for GridViewImage {
+    fn initialize(&mut self) {
+        self.set_mouse_tracking(true);
+        self.store.initialize();
+        self.font_changed();
+
+        connect!(
+            self.store,
+            items_len_changed(),
+            self,
+            on_items_changed(usize)
+        );
+        connect!(
+            nonnull_mut!(self.scroll_bar),
+            value_changed(),
+            self,
+            scroll_bar_value_changed(i32)
+        );
+        connect!(self, size_changed(), self, on_size_changed(Size));
+    }
+}
+
+impl WidgetImpl for GridViewImage {
+    #[inline]
+    fn run_after(&mut self) {
+        self.on_items_changed(self.store.get_items_len());
+    }
+
+    #[inline]
+    fn paint(&mut self, painter: &mut Painter) {
+        self.draw_image(painter)
+    }
+
+    #[inline]
+    fn font_changed(&mut self) {
+        if self.custom_caption_height {
+            return;
+        }
+        let (_, h) = self.font().calc_font_dimension();
+
+        self.caption_height = h as i32;
+        self.update();
+    }
+
+    #[inline]
+    fn on_mouse_move(&mut self, event: &MouseEvent) {
+        self.handle_mouse_move(event)
+    }
+
+    #[inline]
+    fn on_mouse_pressed(&mut self, event: &MouseEvent) {
+        self.handle_mouse_pressed(event)
+    }
+
+    #[inline]
+    fn on_mouse_released(&mut self, event: &MouseEvent) {
+        self.handle_mouse_released(event)
+    }
+
+    #[inline]
+    fn on_mouse_wheel(&mut self, event: &MouseEvent) {
+        nonnull_mut!(self.scroll_bar).on_mouse_wheel(event)
+    }
+
+    #[inline]
+    fn on_visibility_changed(&mut self, visible: bool) {
+        if !visible && self.reset_effect_node_on_hide {
+            self.store.remove_effected_node_status();
+            nonnull_mut!(self.scroll_bar).set_value(0);
+        }
+    }
+}
+
+impl GridViewImage {
+    #[inline]
+    pub(crate) fn new(scroll_bar: &mut ScrollBar) -> Tr<Self> {
+        let mut img = Self::new_alloc();
+        img.scroll_bar = NonNull::new(scroll_bar);
+        img
+    }
+
+    #[inline]
+    pub(crate) fn set_scroll_bar(&mut self, scroll_bar: &mut ScrollBar) {
+        self.scroll_bar = NonNull::new(scroll_bar)
+    }
+
+    #[inline]
+    pub(crate) fn set_tile_size(&mut self, tile_size: Size) {
+        self.tile_size = tile_size;
+        self.relayout();
+    }
+
+    #[inline]
+    pub(crate) fn set_spacing(&mut self, spacing: i32) {
+        self.spacing = spacing;
+        self.relayout();
+    }
+
+    #[inline]
+    pub(crate) fn set_caption_height(&mut self, caption_height: i32) {
+        self.caption_height = caption_height;
+        self.custom_caption_height = true;
+        self.update();
+    }
+
+    #[inline]
+    pub(crate) fn columns(&self) -> usize {
+        self.layout.columns()
+    }
+
+    /// Scroll to the row which contains the node with the index of nodes.
+    pub(crate) fn scroll_to(&mut self, idx: usize) {
+        let view_height = self.contents_rect(Some(Coordinate::Widget)).height();
+        let max_scroll = self.layout.max_scroll(self.tiles.len(), view_height);
+        let value = self.layout.offset_of(idx).min(max_scroll);
+
+        let scroll_bar = nonnull_mut!(self.scroll_bar);
+        disconnect!(scroll_bar, value_changed(), null, null);
+        scroll_bar.set_value(value);
+        connect!(
+            scroll_bar,
+            value_changed(),
+            self,
+            scroll_bar_value_changed(i32)
+        );
+
+        self.scroll_offset = value;
+        self.update();
+    }
+}
+
+impl IterExecutor for GridViewImage {
+    #[inline]
+    fn iter_execute(&mut self) {
+        self.store.check_lock();
+    }
+}
+
+impl GridViewImage {
+    fn draw_image(&mut self, painter: &mut Painter) {
+        if self.store.occupied() {
+            return;
+        }
+
+        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        self.clear(painter, rect);
+
+        let background = self.opaque_background();
+        let (start, end) =
+            self.layout
+                .visible_range(self.tiles.len(), self.scroll_offset, rect.height() as i32);
+        let caption_height = self.caption_height as f32;
+
+        painter.save();
+        painter.clip_rect(rect, ClipOp::Intersect);
+
+        self.store.with_items(|items| {
+            for (tile, &idx) in self.tiles[start..end].iter().enumerate() {
+                let node = match items
+                    .get(idx)
+                    .and_then(|item| item.downcast_ref::<ListNode>())
+                {
+                    Some(node) => node,
+                    None => continue,
+                };
+
+                let mut geometry: FRect = self.layout.tile_rect(start + tile).into();
+                geometry.offset(rect.x(), rect.y() - self.scroll_offset as f32);
+
+                node.render_tile(
+                    painter,
+                    RenderCtx::new(geometry, background, self.mouse_effect),
+                    caption_height,
+                );
+            }
+        });
+
+        painter.restore();
+    }
+
+    #[inline]
+    fn on_size_changed(&mut self, _: Size) {
+        self.relayout();
+    }
+
+    fn relayout(&mut self) {
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        self.layout = TileLayout::new(self.tile_size, self.spacing, rect.width());
+
+        let scroll_bar = nonnull_mut!(self.scroll_bar);
+        scroll_bar.set_single_step((self.layout.row_stride() / 2).max(1));
+        scroll_bar.set_page_step(rect.height());
+        scroll_bar.set_visible_area(rect.height());
+
+        self.on_items_changed(self.store.get_items_len());
+    }
+
+    #[inline]
+    fn scroll_bar_value_changed(&mut self, value: i32) {
+        if self.scroll_offset != value {
+            self.scroll_offset = value;
+            self.update();
+        }
+    }
+
+    fn on_items_changed(&mut self, _: usize) {
+        let tiles = &mut self.tiles;
+        self.store.with_items(|items| {
+            tiles.clear();
+            tiles.extend(
+                items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| item.item_type() == ItemType::Node)
+                    .map(|(i, _)| i),
+            );
+        });
+
+        let view_height = self.contents_rect(Some(Coordinate::Widget)).height();
+        let max_scroll = self.layout.max_scroll(self.tiles.len(), view_height);
+        nonnull_mut!(self.scroll_bar).set_range(0, max_scroll);
+        self.scroll_offset = self.scroll_offset.min(max_scroll);
+
+        self.update();
+    }
+
+    /// Get the index of node in items under the point.
+    fn hit_test(&self, x: i32, y: i32) -> Option<usize> {
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        if !rect.contains(&Point::new(x, y)) {
+            return None;
+        }
+
+        let tile = self.layout.index_at(
+            self.tiles.len(),
+            x - rect.x(),
+            y - rect.y() + self.scroll_offset,
+        )?;
+        self.tiles.get(tile).copied()
+    }
+
+    fn handle_mouse_move(&mut self, event: &MouseEvent) {
+        let scroll_bar = nonnull_mut!(self.scroll_bar);
+        if scroll_bar.slider_pressed() {
+            scroll_bar.on_mouse_move(event);
+            return;
+        }
+
+        let (x, y) = event.position();
+        let hit = self.hit_test(x, y);
+
+        let update = self
+            .store
+            .with_items_mut(|mut mutex, entered_node, hovered_node, _| {
+                let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
+                let node = hit
+                    .and_then(|idx| mutex.items.get_mut(idx))
+                    .and_then(|item| item.downcast_mut::<ListNode>());
+
+                if let Some(node) = node {
+                    let mut update = false;
+
+                    // Handle node hover:
+                    if !node.is_hovered() {
+                        if hovered_node.is_some() {
+                            let node = nonnull_mut!(hovered_node);
+                            node.remove_status(Status::Hovered);
+                            if !node.is_selected() {
+                                update = true;
+                            }
+                        }
+
+                        node.add_status(Status::Hovered);
+                        *hovered_node = NonNull::new(node);
+                        if !node.is_selected() {
+                            update = true;
+                        }
+                    }
+
+                    let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
+                    // Handle node enter/leave:
+                    if entered_node.is_none() {
+                        *entered_node = NonNull::new(node);
+                        if let Some(ref on_node_enter) = self.on_node_enter {
+                            on_node_enter(node, mutex_mut, event)
+                        }
+                    } else {
+                        let previous_node = nonnull_mut!(entered_node);
+
+                        if previous_node.id() != node.id() {
+                            *entered_node = NonNull::new(node);
+
+                            if let Some(ref on_node_leave) = self.on_node_leave {
+                                on_node_leave(previous_node, mutex_mut, event)
+                            }
+                            if let Some(ref on_node_enter) = self.on_node_enter {
+                                on_node_enter(node, mutex_mut, event);
+                            }
+                        }
+                    }
+
+                    update
+                } else {
+                    let mut old_hover = hovered_node.take();
+                    if old_hover.is_some() {
+                        let node = nonnull_mut!(old_hover);
+                        if node.is_hovered() {
+                            node.remove_status(Status::Hovered);
+                            return true;
+                        }
+                    }
+
+                    false
+                }
+            });
+
+        if update {
+            self.update();
+        }
+    }
+
+    fn handle_mouse_pressed(&mut self, event: &MouseEvent) {
+        let (x, y) = event.position();
+        let hit = self.hit_test(x, y);
+        let parent = ptr_mut!(self.get_raw_parent_mut().unwrap());
+
+        let update = self.store.with_items_mut(|mut mutex, _, _, selected_node| {
+            let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
+            let node = hit
+                .and_then(|idx| mutex.items.get_mut(idx))
+                .and_then(|item| item.downcast_mut::<ListNode>());
+
+            if let Some(node) = node {
+                if selected_node.is_some() {
+                    let node = nonnull_mut!(selected_node);
+                    node.remove_status(Status::Selected);
+                }
+
+                node.add_status(Status::Selected);
+                *selected_node = NonNull::new(node);
+
+                if let Some(ref on_node_pressed) = self.on_node_pressed {
+                    let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
+                    on_node_pressed(node, mutex_mut, event);
+                }
+                true
+            } else {
+                let mut update = false;
+                let mut old_select = selected_node.take();
+                if old_select.is_some() {
+                    let node = nonnull_mut!(old_select);
+                    node.remove_status(Status::Selected);
+                    update = true;
+                }
+
+                if let Some(ref on_free_area_pressed) = self.on_free_area_pressed {
+                    on_free_area_pressed(parent, event);
+                }
+
+                update
+            }
+        });
+
+        if update {
+            self.update();
+        }
+    }
+
+    fn handle_mouse_released(&mut self, event: &MouseEvent) {
+        let parent = ptr_mut!(self.get_raw_parent_mut().unwrap());
+        let mut selected_node = self.store.get_selected_node();
+        if selected_node.is_none() {
+            if let Some(ref on_free_area_released) = self.on_free_area_released {
+                on_free_area_released(parent, event);
+            }
+            return;
+        }
+
+        if let Some(ref on_node_released) = self.on_node_released {
+            let node = nonnull_mut!(selected_node);
+            let cs = self.store.concurrent_store();
+            let mut mutex = cs.lock();
+            on_node_released(node, &mut mutex, event)
+        }
+    }
+}
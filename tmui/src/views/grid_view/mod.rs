@@ -0,0 +1,340 @@
+pub mod grid_view_image;
+pub(crate) mod tile_layout;
+
+use super::{
+    list_view::{
+        list_node::ListNode,
+        list_store::{ConcurrentStore, ConcurrentStoreMutexGuard, ListStore},
+        list_view_object::ListViewObject,
+    },
+    node::MouseEffect,
+};
+use crate::{
+    prelude::*,
+    tlib::object::{ObjectImpl, ObjectSubclass},
+    widget::{InnerEventProcess, WidgetHndAsable, WidgetImpl},
+};
+use grid_view_image::GridViewImage;
+use std::sync::Arc;
+use tipc::parking_lot::Mutex;
+use tlib::connect;
+
+/// UI component displays data in wrapping rows of fixed-size tiles,
+/// only the visible tiles will be painted.
+///
+/// The data was stored in [`ListStore`], the first render cell of node is the thumbnail
+/// (normally the [`Cell::image()`](crate::views::cell::Cell::image) or [`Cell::svg()`](crate::views::cell::Cell::svg)),
+/// the rest render cells are the captions below the thumbnail.
+/// Group separators were not displayed in `GridView`.
+///
+/// Certain functions (such as node hover display, handle mouse enter/leave) need to invoke [`set_mouse_tracking(true)`](crate::widget::widget_ext::WidgetExt::set_mouse_tracking).
+///
+/// Usage:
+/// ```
+/// use tmui::{
+///    prelude::*,
+///    views::{
+///        cell::{
+///            cell_render::{ImageCellRender, TextCellRender},
+///            Cell,
+///        },
+///        grid_view::GridView,
+///        list_view::list_view_object::ListViewObject,
+///        node::node_render::NodeRender,
+///    },
+/// };
+///
+/// struct Icon {
+///    path: String,
+///    name: String,
+/// }
+/// impl ListViewObject for Icon {
+///    #[inline]
+///    fn cells(&self) -> Vec<Cell> {
+///        vec![
+///            Cell::image()
+///                .image_address(self.path.clone())
+///                .cell_render(ImageCellRender::builder().build())
+///                .build(),
+///            Cell::string()
+///                .value(self.name.clone())
+///                .cell_render(TextCellRender::builder().color(Color::BLACK).halign(Align::Center).build())
+///                .build(),
+///        ]
+///    }
+///
+///    #[inline]
+///    fn node_render(&self) -> NodeRender {
+///        NodeRender::builder().build()
+///    }
+/// }
+///
+/// fn test_build_ui() {
+///     let mut grid_view = GridView::new();
+///     grid_view.set_tile_size(Size::new(80, 80));
+///     grid_view.add_node(&Icon { path: "icon.png".to_string(), name: "icon".to_string() });
+/// }
+/// ```
+#[extends(Widget, Layout(ScrollArea))]
+#[popupable]
+pub struct GridView {}
+
+impl ObjectSubclass for GridView {
+    const NAME: &'static str = "GridView";
+}
+
+impl ObjectImpl for GridView {
+    fn construct(&mut self) {
+        self.parent_construct();
+
+        let mut img = GridViewImage::new(self.scroll_bar_mut());
+        img.store.set_view(self.as_hnd());
+        img.set_scroll_bar(self.scroll_bar_mut());
+
+        connect!(self, background_changed(), img, set_background(Color));
+        connect!(self, invalid_area_changed(), img, set_invalid_area(FRect));
+        connect!(img, mouse_leave(), self, image_mouse_leave(MouseEvent));
+        connect!(img, mouse_enter(), self, image_mouse_enter(MouseEvent));
+
+        self.set_area(img);
+    }
+
+    #[allow(clippy::single_match)]
+    fn on_property_set(&mut self, name: &str, value: &Value) {
+        self.parent_on_property_set(name, value);
+
+        match name {
+            "mouse_tracking" => {
+                let is_tracking = value.get::<bool>();
+                self.get_image_mut().set_mouse_tracking(is_tracking);
+            }
+            _ => {}
+        };
+    }
+}
+
+impl WidgetImpl for GridView {
+    #[inline]
+    fn enable_focus(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn font_changed(&mut self) {
+        let font = self.font().clone();
+        self.get_image_mut().set_font(font);
+    }
+}
+
+impl GridView {
+    #[inline]
+    pub fn new() -> Tr<Self> {
+        Self::new_alloc()
+    }
+
+    /// @return the index of added node.
+    #[inline]
+    pub fn add_node(&mut self, obj: &dyn ListViewObject) -> usize {
+        self.get_store_mut().add_node(obj)
+    }
+
+    /// @return the index of added node.
+    #[inline]
+    pub fn add_node_directly(&mut self, node: ListNode) -> usize {
+        self.get_store_mut().add_node_directly(node)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        let store = self.get_store_mut();
+        store.remove_effected_node_status();
+        store.clear();
+    }
+
+    #[inline]
+    pub fn get_store(&self) -> &ListStore {
+        &self.get_image().store
+    }
+
+    #[inline]
+    pub fn get_store_mut(&mut self) -> &mut ListStore {
+        &mut self.get_image_mut().store
+    }
+
+    /// The nodes can be populated in background through the [`ConcurrentStore`],
+    /// the tiles will be relayouted in the next iteration of UI thread after the lock released.
+    #[inline]
+    pub fn concurrent_store(&mut self) -> Arc<Mutex<ConcurrentStore>> {
+        self.get_store_mut().concurrent_store()
+    }
+
+    #[inline]
+    pub fn start_loading(&mut self) {
+        self.get_image_mut().start_loading()
+    }
+
+    #[inline]
+    pub fn stop_loading(&mut self) {
+        self.get_image_mut().stop_loading()
+    }
+
+    /// Scroll to the row which contains the node with the index of nodes.
+    #[inline]
+    pub fn scroll_to(&mut self, idx: usize) {
+        self.get_image_mut().scroll_to(idx)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.get_store().nodes_len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of tiles in one row, depends on the width of view.
+    #[inline]
+    pub fn columns(&self) -> usize {
+        self.get_image().columns()
+    }
+
+    #[inline]
+    pub fn get_tile_size(&self) -> Size {
+        self.get_image().tile_size
+    }
+
+    /// Set the size of each tile, includes the thumbnail and captions.
+    #[inline]
+    pub fn set_tile_size(&mut self, tile_size: Size) {
+        self.get_image_mut().set_tile_size(tile_size)
+    }
+
+    #[inline]
+    pub fn get_spacing(&self) -> i32 {
+        self.get_image().spacing
+    }
+
+    /// Set the spacing between tiles, both horizontal and vertical.
+    #[inline]
+    pub fn set_spacing(&mut self, spacing: i32) {
+        self.get_image_mut().set_spacing(spacing)
+    }
+
+    #[inline]
+    pub fn get_caption_height(&self) -> i32 {
+        self.get_image().caption_height
+    }
+
+    /// Set the height of each caption line, default was the height of font.
+    #[inline]
+    pub fn set_caption_height(&mut self, caption_height: i32) {
+        self.get_image_mut().set_caption_height(caption_height)
+    }
+
+    #[inline]
+    pub fn mouse_effect(&self) -> MouseEffect {
+        self.get_image().mouse_effect
+    }
+
+    #[inline]
+    pub fn set_mouse_effect(&mut self, mouse_effect: MouseEffect) {
+        self.get_image_mut().mouse_effect = mouse_effect
+    }
+
+    #[inline]
+    pub fn disable_mouse_effect(&mut self, mouse_effect: MouseEffect) {
+        self.get_image_mut().mouse_effect.remove(mouse_effect)
+    }
+
+    #[inline]
+    pub fn enable_mouse_effect(&mut self, mouse_effect: MouseEffect) {
+        self.get_image_mut().mouse_effect.insert(mouse_effect)
+    }
+
+    #[inline]
+    pub fn set_reset_effect_node_on_hide(&mut self, reset_effect_node_on_hide: bool) {
+        self.get_image_mut().reset_effect_node_on_hide = reset_effect_node_on_hide;
+    }
+
+    #[inline]
+    pub fn register_node_enter<
+        F: 'static + Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent),
+    >(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_node_enter = Some(Box::new(f));
+    }
+
+    #[inline]
+    pub fn register_node_leave<
+        F: 'static + Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent),
+    >(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_node_leave = Some(Box::new(f));
+    }
+
+    #[inline]
+    pub fn register_node_pressed<
+        F: 'static + Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent),
+    >(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_node_pressed = Some(Box::new(f));
+    }
+
+    #[inline]
+    pub fn register_node_released<
+        F: 'static + Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent),
+    >(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_node_released = Some(Box::new(f));
+    }
+
+    #[inline]
+    pub fn register_free_area_pressed<F: 'static + Fn(&mut dyn WidgetImpl, &MouseEvent)>(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_free_area_pressed = Some(Box::new(f));
+    }
+
+    #[inline]
+    pub fn register_free_area_released<F: 'static + Fn(&mut dyn WidgetImpl, &MouseEvent)>(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_free_area_released = Some(Box::new(f));
+    }
+}
+
+impl GridView {
+    #[inline]
+    pub(crate) fn get_image(&self) -> &GridViewImage {
+        self.get_area_cast::<GridViewImage>().unwrap()
+    }
+
+    #[inline]
+    pub(crate) fn get_image_mut(&mut self) -> &mut GridViewImage {
+        self.get_area_cast_mut::<GridViewImage>().unwrap()
+    }
+
+    #[inline]
+    pub(crate) fn image_mouse_enter(&mut self, evt: MouseEvent) {
+        self.inner_mouse_enter(&evt);
+        self.on_mouse_enter(&evt);
+    }
+
+    #[inline]
+    pub(crate) fn image_mouse_leave(&mut self, evt: MouseEvent) {
+        self.inner_mouse_leave(&evt);
+        self.on_mouse_leave(&evt);
+    }
+}
@@ -0,0 +1,163 @@
+use tlib::figure::{Rect, Size};
+
+/// The layout of tiles in `GridView`, tiles were placed in wrapping rows
+/// from left to right, top to bottom.
+///
+/// All the coordinates are relative to the top-left of the first tile,
+/// without the scroll offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TileLayout {
+    tile_width: i32,
+    tile_height: i32,
+    spacing: i32,
+    columns: usize,
+}
+
+impl TileLayout {
+    pub(crate) fn new(tile_size: Size, spacing: i32, view_width: i32) -> Self {
+        let tile_width = tile_size.width().max(1);
+        let tile_height = tile_size.height().max(1);
+        let spacing = spacing.max(0);
+        let columns = ((view_width + spacing) / (tile_width + spacing)).max(1) as usize;
+
+        Self {
+            tile_width,
+            tile_height,
+            spacing,
+            columns,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The number of rows to place `len` tiles.
+    #[inline]
+    pub(crate) fn rows(&self, len: usize) -> usize {
+        len.div_ceil(self.columns)
+    }
+
+    /// The height of one row, includes the spacing.
+    #[inline]
+    pub(crate) fn row_stride(&self) -> i32 {
+        self.tile_height + self.spacing
+    }
+
+    #[inline]
+    fn column_stride(&self) -> i32 {
+        self.tile_width + self.spacing
+    }
+
+    /// The total height of `len` tiles.
+    #[inline]
+    pub(crate) fn total_height(&self, len: usize) -> i32 {
+        let rows = self.rows(len) as i32;
+        (rows * self.row_stride() - self.spacing).max(0)
+    }
+
+    /// The maximum scroll offset that the tiles can fill the view with the `view_height`.
+    #[inline]
+    pub(crate) fn max_scroll(&self, len: usize, view_height: i32) -> i32 {
+        (self.total_height(len) - view_height).max(0)
+    }
+
+    /// The y offset of the row's top edge which contains the `idx`th tile.
+    #[inline]
+    pub(crate) fn offset_of(&self, idx: usize) -> i32 {
+        (idx / self.columns) as i32 * self.row_stride()
+    }
+
+    /// Get the geometry of the `idx`th tile.
+    #[inline]
+    pub(crate) fn tile_rect(&self, idx: usize) -> Rect {
+        let column = (idx % self.columns) as i32;
+
+        Rect::new(
+            column * self.column_stride(),
+            self.offset_of(idx),
+            self.tile_width,
+            self.tile_height,
+        )
+    }
+
+    /// Get the index of tile which contains the point, `y` was relative to the first row.
+    ///
+    /// @return `None` if the point was in the spacing or out of the tiles.
+    pub(crate) fn index_at(&self, len: usize, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        if x % self.column_stride() >= self.tile_width || y % self.row_stride() >= self.tile_height
+        {
+            return None;
+        }
+
+        let column = (x / self.column_stride()) as usize;
+        if column >= self.columns {
+            return None;
+        }
+
+        let idx = (y / self.row_stride()) as usize * self.columns + column;
+        (idx < len).then_some(idx)
+    }
+
+    /// Get the range of tiles which are visible in the view.
+    ///
+    /// @return (start, end), `end` was exclusive.
+    pub(crate) fn visible_range(
+        &self,
+        len: usize,
+        scroll: i32,
+        view_height: i32,
+    ) -> (usize, usize) {
+        let stride = self.row_stride() as usize;
+        let first_row = scroll.max(0) as usize / stride;
+        let last_row = ((scroll + view_height).max(0) as usize).div_ceil(stride);
+
+        let start = (first_row * self.columns).min(len);
+        let end = (last_row * self.columns).min(len);
+        (start, end.max(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TileLayout;
+    use tlib::figure::{Rect, Size};
+
+    #[test]
+    fn test_tile_layout() {
+        // Three columns: 3 * 100 + 2 * 10 <= 330 < 4 * 100 + 3 * 10
+        let layout = TileLayout::new(Size::new(100, 50), 10, 330);
+        assert_eq!(layout.columns(), 3);
+        assert_eq!(layout.rows(0), 0);
+        assert_eq!(layout.rows(7), 3);
+        assert_eq!(layout.total_height(0), 0);
+        assert_eq!(layout.total_height(7), 170);
+        assert_eq!(layout.max_scroll(7, 100), 70);
+        assert_eq!(layout.max_scroll(7, 200), 0);
+
+        assert_eq!(layout.tile_rect(0), Rect::new(0, 0, 100, 50));
+        assert_eq!(layout.tile_rect(4), Rect::new(110, 60, 100, 50));
+        assert_eq!(layout.offset_of(5), 60);
+
+        assert_eq!(layout.index_at(7, 0, 0), Some(0));
+        assert_eq!(layout.index_at(7, 105, 0), None);
+        assert_eq!(layout.index_at(7, 115, 65), Some(4));
+        assert_eq!(layout.index_at(7, 15, 55), None);
+        assert_eq!(layout.index_at(7, 115, 125), None);
+        assert_eq!(layout.index_at(7, 335, 0), None);
+        assert_eq!(layout.index_at(7, -1, 0), None);
+
+        assert_eq!(layout.visible_range(7, 0, 50), (0, 3));
+        assert_eq!(layout.visible_range(7, 0, 61), (0, 6));
+        assert_eq!(layout.visible_range(7, 65, 60), (3, 7));
+        assert_eq!(layout.visible_range(0, 0, 100), (0, 0));
+
+        // At least one column even if the view was narrower than a tile.
+        let layout = TileLayout::new(Size::new(100, 50), 10, 20);
+        assert_eq!(layout.columns(), 1);
+    }
+}
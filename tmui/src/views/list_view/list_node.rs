@@ -7,12 +7,13 @@ use super::{
 use crate::{
     application::is_ui_thread,
     views::{
-        cell::{cell_index::CellIndex, cell_render::CellRender, Cell},
+        cell::{cell_index::CellIndex, cell_render::CellRender, checkbox_at, layout_cells, Cell},
         node::{node_render::NodeRender, RenderCtx, Status},
     },
 };
 use log::warn;
 use tlib::{
+    figure::{FPoint, FRect},
     global::AsAny,
     object::ObjectId,
     types::StaticType,
@@ -24,6 +25,7 @@ pub struct ListNode {
     id: ObjectId,
     status: Status,
     group_managed: bool,
+    height: Option<i32>,
 
     cells: Vec<Cell>,
     node_render: NodeRender,
@@ -32,13 +34,16 @@ pub struct ListNode {
 impl ListNode {
     #[inline]
     pub fn from(obj: &dyn ListViewObject) -> Self {
+        let node_render = obj.node_render();
+
         Self {
             store: 0,
             id: 0,
             status: Status::empty(),
             group_managed: false,
+            height: node_render.height(),
             cells: obj.cells(),
-            node_render: obj.node_render(),
+            node_render,
         }
     }
 
@@ -67,6 +72,26 @@ impl ListNode {
         self.group_managed
     }
 
+    /// The specified height of node, `None` means using the line height of view.
+    #[inline]
+    pub fn height(&self) -> Option<i32> {
+        self.height
+    }
+
+    /// Specify the height of node, `None` means using the line height of view.
+    ///
+    /// The rows of view will be relayouted in the next iteration of UI thread.
+    pub fn set_height(&mut self, height: Option<i32>) {
+        if self.height == height {
+            return;
+        }
+        self.height = height;
+
+        if let Some(store) = ListStore::store_ref(self.store) {
+            store.mark_heights_dirty();
+        }
+    }
+
     #[inline]
     pub fn store_ref(&self) -> &ListStore {
         ListStore::store_ref(self.store)
@@ -79,6 +104,10 @@ impl ListNode {
             .expect("Call `store_mut()` after adding this node to `ListStore`.")
     }
 
+    /// Get the [`ListView`] which the node belongs to.
+    ///
+    /// Panics if the node belongs to a [`GridView`](crate::views::grid_view::GridView),
+    /// use [`store_mut()`](ListNode::store_mut) to get the view instead.
     #[inline]
     pub fn get_view(&mut self) -> &mut ListView {
         self.store_mut()
@@ -191,6 +220,66 @@ impl ListNode {
         size
     }
 
+    /// Toggle the checkbox cell at the `point`, `geometry` was the rect of node.
+    ///
+    /// @return the index of toggled cell and its new value.
+    pub(crate) fn toggle_checkbox(
+        &mut self,
+        geometry: FRect,
+        point: FPoint,
+    ) -> Option<(usize, bool)> {
+        let idx = checkbox_at(&self.cells, geometry, point)?;
+        let checked = self.cells[idx].toggle_checkbox()?;
+        Some((idx, checked))
+    }
+
+    /// Render the node as a tile of [`GridView`](crate::views::grid_view::GridView),
+    /// the first render cell is the thumbnail filling the tile above the captions,
+    /// the rest render cells are the captions stacked at the bottom of the tile.
+    pub(crate) fn render_tile(
+        &self,
+        painter: &mut Painter,
+        render_ctx: RenderCtx,
+        caption_height: f32,
+    ) {
+        let geometry = render_ctx.geometry;
+
+        self.node_render.render(painter, render_ctx, self.status);
+
+        let status = self.status();
+        let captions = self.render_cell_size().saturating_sub(1) as f32;
+        let mut cells = self.cells.iter().filter(|cell| cell.is_render_cell());
+
+        let mut thumbnail_rect = geometry;
+        thumbnail_rect.set_height((geometry.height() - caption_height * captions).max(0.));
+        if let Some(thumbnail) = cells.next() {
+            let cell_render = thumbnail.get_render().unwrap();
+            let mut cell_rect = thumbnail_rect;
+            if let Some(width) = cell_render.width() {
+                let width = (width as f32).min(thumbnail_rect.width());
+                cell_rect.set_x(thumbnail_rect.x() + (thumbnail_rect.width() - width) / 2.);
+                cell_rect.set_width(width);
+            }
+            if let Some(height) = cell_render.height() {
+                let height = (height as f32).min(thumbnail_rect.height());
+                cell_rect.set_y(thumbnail_rect.y() + (thumbnail_rect.height() - height) / 2.);
+                cell_rect.set_height(height);
+            }
+
+            thumbnail.render_cell(painter, cell_rect, status);
+        }
+
+        let mut offset = thumbnail_rect.bottom();
+        for caption in cells {
+            let mut cell_rect = geometry;
+            cell_rect.set_y(offset);
+            cell_rect.set_height(caption_height);
+            offset += caption_height;
+
+            caption.render_cell(painter, cell_rect, status);
+        }
+    }
+
     #[inline]
     pub(crate) fn add_status(&mut self, status: Status) {
         self.status.insert(status)
@@ -213,28 +302,10 @@ impl ListItem for ListNode {
 
         self.node_render.render(painter, render_ctx, self.status);
 
-        let gapping = geometry.width() / self.render_cell_size() as f32;
-        let mut offset = geometry.x();
         let status = self.status();
 
-        for cell in self.cells.iter() {
-            if let Some(cell_render) = cell.get_render() {
-                let mut cell_rect = geometry;
-
-                cell_rect.set_x(offset);
-                if let Some(width) = cell_render.width() {
-                    cell_rect.set_width(width as f32);
-                } else {
-                    cell_rect.set_width(gapping);
-                }
-                if let Some(height) = cell_render.height() {
-                    cell_rect.set_height(height as f32);
-                }
-
-                offset += cell_rect.width();
-
-                cell.render_cell(painter, cell_rect, status);
-            }
+        for (_, cell, cell_rect) in layout_cells(&self.cells, geometry) {
+            cell.render_cell(painter, cell_rect, status);
         }
     }
 }
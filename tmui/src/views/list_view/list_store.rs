@@ -1,20 +1,26 @@
 use super::{
     list_group::ListGroup,
-    list_item::ListItem,
+    list_item::{ItemType, ListItem},
     list_node::ListNode,
     list_separator::{GroupSeparator, DEFAULT_SEPARATOR_HEIGHT},
     list_view_object::ListViewObject,
     WidgetHnd,
 };
 use crate::{
-    views::{list_view::list_item::ListItemCast, node::Status},
+    views::{
+        list_view::list_item::ListItemCast,
+        node::{height_index::HeightIndex, node_drag::DropPosition, Status},
+    },
     widget::WidgetImpl,
 };
 use nohash_hasher::IntMap;
 use once_cell::sync::Lazy;
 use std::{
     ptr::{addr_of_mut, NonNull},
-    sync::{atomic::Ordering, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 use tipc::parking_lot::{lock_api::MutexGuard, Mutex, RawMutex};
 use tlib::{
@@ -145,6 +151,185 @@ impl ConcurrentStore {
     pub fn separator_pos(&self) -> &[usize] {
         &self.separator_pos
     }
+
+    /// Move the nodes with the specified `ids` to the position relative to the `target` node,
+    /// the relative order of moved nodes will be kept.
+    ///
+    /// [`DropPosition::Into`] was treated as [`DropPosition::After`] in `ListView`.
+    ///
+    /// @return `false` if the target was not exist or was one of the moved nodes.
+    pub fn move_nodes(
+        &mut self,
+        ids: &[ObjectId],
+        target: ObjectId,
+        position: DropPosition,
+    ) -> bool {
+        if ids.is_empty() || ids.contains(&target) || self.index_of(target).is_none() {
+            return false;
+        }
+
+        let is_moved = |item: &dyn ListItem| {
+            item.downcast_ref::<ListNode>()
+                .map_or(false, |node| ids.contains(&node.id()))
+        };
+        if !self.items.iter().any(|item| is_moved(&**item)) {
+            return false;
+        }
+
+        // Split the items into groups by the separators, the separators were kept
+        // attached to the groups they were separating.
+        let mut groups: Vec<Vec<Box<dyn ListItem>>> = vec![vec![]];
+        let mut separators = vec![];
+        for item in self.items.drain(..) {
+            if item.item_type() == ItemType::Separator {
+                separators.push(item);
+                groups.push(vec![]);
+            } else {
+                groups.last_mut().unwrap().push(item);
+            }
+        }
+
+        let mut moved = vec![];
+        for group in groups.iter_mut() {
+            let (m, kept): (Vec<_>, Vec<_>) = group.drain(..).partition(|item| is_moved(&**item));
+            moved.extend(m);
+            *group = kept;
+        }
+
+        let (group_idx, target_idx) = groups
+            .iter()
+            .enumerate()
+            .find_map(|(g, group)| {
+                group
+                    .iter()
+                    .position(|item| item.downcast_ref::<ListNode>().unwrap().id() == target)
+                    .map(|i| (g, i))
+            })
+            .unwrap();
+        let group_managed = groups[group_idx][target_idx]
+            .downcast_ref::<ListNode>()
+            .unwrap()
+            .is_group_managed();
+        let insert_idx = match position {
+            DropPosition::Before => target_idx,
+            DropPosition::After | DropPosition::Into => target_idx + 1,
+        };
+
+        for item in moved.iter_mut() {
+            item.downcast_mut::<ListNode>()
+                .unwrap()
+                .set_group_managed(group_managed);
+        }
+        groups[group_idx].splice(insert_idx..insert_idx, moved);
+
+        self.join_groups(groups, separators);
+        true
+    }
+
+    /// Get the index of node with the specified id in items.
+    pub fn index_of(&self, id: ObjectId) -> Option<usize> {
+        self.items.iter().position(|item| {
+            item.downcast_ref::<ListNode>()
+                .map_or(false, |node| node.id() == id)
+        })
+    }
+
+    /// Get the ids of all selected nodes, in the order of items.
+    pub fn selected_ids(&self) -> Vec<ObjectId> {
+        self.items
+            .iter()
+            .filter_map(|item| item.downcast_ref::<ListNode>())
+            .filter(|node| node.is_selected())
+            .map(|node| node.id())
+            .collect()
+    }
+}
+
+impl ConcurrentStore {
+    #[inline]
+    pub(crate) fn clear_selection(&mut self) {
+        self.retain_selection(None)
+    }
+
+    pub(crate) fn retain_selection(&mut self, retain: Option<ObjectId>) {
+        for item in self.items.iter_mut() {
+            if let Some(node) = item.downcast_mut::<ListNode>() {
+                if Some(node.id()) != retain {
+                    node.remove_status(Status::Selected);
+                }
+            }
+        }
+    }
+
+    /// Join the groups with separators, the emptied groups were removed and the adjoining
+    /// groups of nodes not managed by group were merged, as they were added by `add_node`.
+    fn join_groups(
+        &mut self,
+        groups: Vec<Vec<Box<dyn ListItem>>>,
+        mut separators: Vec<Box<dyn ListItem>>,
+    ) {
+        let is_managed = |group: &[Box<dyn ListItem>]| {
+            group[0]
+                .downcast_ref::<ListNode>()
+                .unwrap()
+                .is_group_managed()
+        };
+
+        let mut joined: Vec<Vec<Box<dyn ListItem>>> = vec![];
+        for group in groups.into_iter().filter(|group| !group.is_empty()) {
+            match joined.last_mut() {
+                Some(last) if !is_managed(last) && !is_managed(&group) => last.extend(group),
+                _ => joined.push(group),
+            }
+        }
+
+        self.separator_pos.clear();
+        for (i, group) in joined.into_iter().enumerate() {
+            if i > 0 {
+                let separator = separators
+                    .pop()
+                    .unwrap_or_else(|| self.separator.clone().boxed().as_list_item());
+                self.items.push(separator);
+                self.separator_pos.push(self.items.len() - 1);
+            }
+            self.items.extend(group);
+        }
+        self.separator_cnt = self.separator_pos.len();
+    }
+}
+
+/// The heights of items in `ListView`, indexed by [`HeightIndex`].
+#[derive(Default)]
+pub(crate) struct ItemHeights {
+    index: HeightIndex,
+    dirty: AtomicBool,
+    line_height: i32,
+    line_spacing: i32,
+}
+
+impl ItemHeights {
+    fn refresh(&mut self, items: &[Box<dyn ListItem>]) {
+        let (line_height, line_spacing) = (self.line_height, self.line_spacing);
+
+        self.index.rebuild(items.iter().map(|item| {
+            match item.item_type() {
+                ItemType::Node => {
+                    let node = item.downcast_ref::<ListNode>().unwrap();
+                    node.height().unwrap_or(line_height) + line_spacing
+                }
+                ItemType::Separator => item
+                    .downcast_ref::<GroupSeparator>()
+                    .unwrap()
+                    .separator_height(),
+            }
+        }));
+        self.dirty.store(false, Ordering::Release);
+    }
+
+    #[inline]
+    fn is_dirty(&self, len: usize) -> bool {
+        self.dirty.load(Ordering::Acquire) || self.index.len() != len
+    }
 }
 
 #[extends(Object, ignore_default = true)]
@@ -154,9 +339,9 @@ pub struct ListStore {
 
     scroll_index_rec: Option<usize>,
 
-    window_lines: i32,
-    current_line: i32,
-    y_offset: i32,
+    heights: ItemHeights,
+    window_height: i32,
+    scroll_offset: i32,
     len_rec: usize,
     separator_height: i32,
 
@@ -188,6 +373,7 @@ impl ListStore {
     pub fn add_node(&mut self, obj: &dyn ListViewObject) -> usize {
         let mut mutex = self.concurrent_store.lock();
         let idx = mutex.add_node(obj);
+        self.heights.refresh(&mutex.items);
 
         emit!(self, items_len_changed(mutex.len()));
         idx
@@ -197,6 +383,7 @@ impl ListStore {
     pub fn add_node_directly(&mut self, node: ListNode) -> usize {
         let mut mutex = self.concurrent_store.lock();
         let idx = mutex.add_node_directly(node);
+        self.heights.refresh(&mutex.items);
 
         emit!(self, items_len_changed(mutex.len()));
         idx
@@ -206,6 +393,7 @@ impl ListStore {
     pub fn add_group(&mut self, group: ListGroup) -> Option<usize> {
         let mut mutex = self.concurrent_store.lock();
         let idx = mutex.add_group(group);
+        self.heights.refresh(&mutex.items);
 
         emit!(self, items_len_changed(mutex.len()));
         idx
@@ -214,6 +402,8 @@ impl ListStore {
     #[inline]
     pub fn clear(&mut self) {
         self.concurrent_store.lock().clear();
+        self.heights.index.rebuild([]);
+        self.scroll_offset = 0;
 
         emit!(self, items_len_changed(0usize));
     }
@@ -235,6 +425,27 @@ impl ListStore {
     pub fn nodes_len(&self) -> usize {
         self.concurrent_store.lock().nodes_len()
     }
+
+    /// See [`ConcurrentStore::move_nodes`].
+    #[inline]
+    pub fn move_nodes(
+        &mut self,
+        ids: &[ObjectId],
+        target: ObjectId,
+        position: DropPosition,
+    ) -> bool {
+        let mut mutex = self.concurrent_store.lock();
+        if !mutex.move_nodes(ids, target, position) {
+            return false;
+        }
+        self.heights.refresh(&mutex.items);
+        let len = mutex.len();
+        drop(mutex);
+
+        self.len_rec = len;
+        emit!(self, items_len_changed(len));
+        true
+    }
 }
 
 impl ListStore {
@@ -269,9 +480,9 @@ impl ListStore {
             concurrent_store: Arc::new(Mutex::new(ConcurrentStore::new(id))),
             scroll_index_rec: None,
             view: None,
-            window_lines: 0,
-            current_line: 0,
-            y_offset: 0,
+            heights: ItemHeights::default(),
+            window_height: 0,
+            scroll_offset: 0,
             len_rec: 0,
             separator_height: DEFAULT_SEPARATOR_HEIGHT,
             entered_node: None,
@@ -280,18 +491,15 @@ impl ListStore {
         }
     }
 
+    /// @param f: the closure with parameters:
+    /// - the visible items.
+    /// - the y offset of the first visible item in pixels, the part was scrolled out of view.
     #[inline]
     pub(crate) fn with_image<F: FnOnce(&[Box<dyn ListItem>], i32)>(&self, f: F) {
-        let start = if self.y_offset == 0 {
-            self.current_line
-        } else {
-            (self.current_line - 1).max(0)
-        } as usize;
-
         if let Some(mutex) = self.concurrent_store.try_lock() {
-            let end = (start + self.window_lines as usize + mutex.separator_cnt).min(mutex.len());
+            let (start, end) = self.image_range(mutex.len());
 
-            f(&mutex.items[start..end], self.y_offset);
+            f(&mutex.items[start..end], self.y_offset(start));
         }
     }
 
@@ -310,21 +518,51 @@ impl ListStore {
         &mut self,
         f: F,
     ) -> bool {
-        let start = if self.y_offset == 0 {
-            self.current_line
-        } else {
-            (self.current_line - 1).max(0)
-        } as usize;
-
         if let Some(mutex) = self.concurrent_store.try_lock() {
-            let end = (start + self.window_lines as usize + mutex.separator_cnt).min(mutex.len());
+            let (start, end) = self.image_range(mutex.len());
+            let y_offset = self.y_offset(start);
             self.len_rec = mutex.len();
 
             f(
                 mutex,
                 start,
                 end,
-                self.y_offset,
+                y_offset,
+                &mut self.entered_node,
+                &mut self.hovered_node,
+                &mut self.selected_node,
+            )
+        } else {
+            false
+        }
+    }
+
+    /// Same as [`with_image()`](ListStore::with_image), but with all the items.
+    #[inline]
+    pub(crate) fn with_items<F: FnOnce(&[Box<dyn ListItem>])>(&self, f: F) {
+        if let Some(mutex) = self.concurrent_store.try_lock() {
+            f(&mutex.items);
+        }
+    }
+
+    /// Same as [`with_image_mut()`](ListStore::with_image_mut), but with all the items.
+    #[inline]
+    pub(crate) fn with_items_mut<
+        F: FnOnce(
+            ConcurrentStoreMutexGuard,
+            &mut Option<NonNull<ListNode>>,
+            &mut Option<NonNull<ListNode>>,
+            &mut Option<NonNull<ListNode>>,
+        ) -> bool,
+    >(
+        &mut self,
+        f: F,
+    ) -> bool {
+        if let Some(mutex) = self.concurrent_store.try_lock() {
+            self.len_rec = mutex.len();
+
+            f(
+                mutex,
                 &mut self.entered_node,
                 &mut self.hovered_node,
                 &mut self.selected_node,
@@ -364,6 +602,14 @@ impl ListStore {
         self.selected_node
     }
 
+    #[inline]
+    pub(crate) fn selected_ids(&self) -> Vec<ObjectId> {
+        self.concurrent_store
+            .try_lock()
+            .map(|mutex| mutex.selected_ids())
+            .unwrap_or_default()
+    }
+
     #[inline]
     pub(crate) fn set_group_separator(&mut self, group_separator: GroupSeparator) {
         self.separator_height = group_separator.separator_height();
@@ -376,13 +622,61 @@ impl ListStore {
     }
 
     #[inline]
-    pub(crate) fn set_window_lines(&mut self, window_lines: i32) {
-        self.window_lines = window_lines
+    pub(crate) fn set_window_height(&mut self, window_height: i32) {
+        self.window_height = window_height
+    }
+
+    /// Set the default height of nodes and the spacing between nodes,
+    /// the heights of items will be recalculated.
+    #[inline]
+    pub(crate) fn set_line_metrics(&mut self, line_height: i32, line_spacing: i32) {
+        self.heights.line_height = line_height;
+        self.heights.line_spacing = line_spacing;
+        self.mark_heights_dirty();
+        self.check_lock();
+    }
+
+    /// Mark the heights of items should be recalculated,
+    /// it will be done in the next iteration of UI thread.
+    #[inline]
+    pub(crate) fn mark_heights_dirty(&self) {
+        self.heights.dirty.store(true, Ordering::Release)
+    }
+
+    /// The maximum scroll value in pixels.
+    #[inline]
+    pub(crate) fn max_scroll(&self) -> i32 {
+        self.heights.index.max_scroll(self.window_height)
+    }
+
+    /// Get the item at the `y` coordinate relative to the top of view.
+    ///
+    /// @return (index of item, y coordinate of the item's top relative to the top of view, height of item)
+    pub(crate) fn hit_test(&self, y: i32) -> Option<(usize, i32, i32)> {
+        if y < 0 || y >= self.window_height {
+            return None;
+        }
+
+        let idx = self.heights.index.index_at(self.scroll_offset + y)?;
+        Some((
+            idx,
+            self.heights.index.offset_of(idx) - self.scroll_offset,
+            self.heights.index.height_of(idx),
+        ))
+    }
+
+    #[inline]
+    fn image_range(&self, len: usize) -> (usize, usize) {
+        let (start, end) = self
+            .heights
+            .index
+            .visible_range(self.scroll_offset, self.window_height);
+        (start.min(len), end.min(len))
     }
 
     #[inline]
-    pub(crate) fn get_window_lines(&self) -> i32 {
-        self.window_lines
+    fn y_offset(&self, start: usize) -> i32 {
+        self.scroll_offset - self.heights.index.offset_of(start)
     }
 
     #[inline]
@@ -401,46 +695,29 @@ impl ListStore {
     /// @return `true` if scroll value has updated, should update the image.
     #[inline]
     pub(crate) fn scroll_to(&mut self, value: i32) -> bool {
-        let move_to = value / 10;
-
-        let len = if let Some(mutex) = self.concurrent_store.try_lock() {
-            mutex.len()
-        } else {
-            self.len_rec
-        };
-
-        if move_to < 0 || move_to > len as i32 {
+        if value < 0 || value > self.heights.index.total() {
             return false;
         }
-        self.y_offset = value % 10;
-        if self.current_line == move_to {
+        if self.scroll_offset == value {
             return true;
         }
-        self.current_line = move_to;
+        self.scroll_offset = value;
 
         true
     }
 
+    /// Scroll to the item with the index of items.
     pub(crate) fn scroll_to_index(&mut self, idx: usize) {
-        let idx_offset = if let Some(mutex) = self.concurrent_store.try_lock() {
-            let mut offset = 0;
-            for &i in mutex.separator_pos().iter() {
-                if idx >= i {
-                    offset += 1;
-                } else {
-                    break;
-                }
+        if let Some(mutex) = self.concurrent_store.try_lock() {
+            if self.heights.is_dirty(mutex.len()) {
+                self.heights.refresh(&mutex.items);
             }
-            offset
         } else {
             self.scroll_index_rec = Some(idx);
             return;
-        };
+        }
 
-        let win_lines = self.get_window_lines();
-        let item_len = self.get_items_len() as i32;
-        let mut scroll_to = (idx as i32 + idx_offset).max(0).min(item_len - win_lines);
-        scroll_to *= 10;
+        let scroll_to = self.heights.index.offset_of(idx).min(self.max_scroll());
 
         let scrolled = self.scroll_to(scroll_to);
 
@@ -456,7 +733,12 @@ impl ListStore {
         if let Some(mutex) = self.concurrent_store.try_lock() {
             let new_len = mutex.len();
 
-            if self.len_rec != new_len {
+            let heights_changed = self.heights.is_dirty(new_len);
+            if heights_changed {
+                self.heights.refresh(&mutex.items);
+            }
+
+            if self.len_rec != new_len || heights_changed {
                 self.len_rec = new_len;
                 emit!(self, items_len_changed(new_len));
 
@@ -490,3 +772,130 @@ impl Default for ListStore {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::{cell::Cell, node::node_render::NodeRender};
+
+    struct Node;
+    impl ListViewObject for Node {
+        fn cells(&self) -> Vec<Cell> {
+            vec![]
+        }
+
+        fn node_render(&self) -> NodeRender {
+            NodeRender::builder().build()
+        }
+    }
+
+    fn group(len: usize) -> ListGroup {
+        let mut group = ListGroup::new();
+        for _ in 0..len {
+            group.add_node(&Node);
+        }
+        group
+    }
+
+    /// The ids of items, the separators were represented by `|`.
+    fn items(store: &ConcurrentStore) -> String {
+        store
+            .items
+            .iter()
+            .map(|item| match item.downcast_ref::<ListNode>() {
+                Some(node) => node.id().to_string(),
+                None => "|".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn is_group_managed(store: &ConcurrentStore, id: ObjectId) -> bool {
+        store.items[store.index_of(id).unwrap()]
+            .downcast_ref::<ListNode>()
+            .unwrap()
+            .is_group_managed()
+    }
+
+    fn check_separators(store: &ConcurrentStore) {
+        let pos: Vec<usize> = store
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.item_type() == ItemType::Separator)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(store.separator_pos(), pos);
+        assert_eq!(store.nodes_len() + pos.len(), store.len());
+    }
+
+    #[test]
+    fn test_move_nodes() {
+        let mut store = ConcurrentStore::new(0);
+        store.add_group(group(2));
+        store.add_group(group(2));
+        store.add_node(&Node);
+        store.add_node(&Node);
+        assert_eq!(items(&store), "0 1 | 2 3 | 4 5");
+        check_separators(&store);
+
+        // Forward move.
+        assert!(store.move_nodes(&[0], 1, DropPosition::After));
+        assert_eq!(items(&store), "1 0 | 2 3 | 4 5");
+        check_separators(&store);
+
+        // Backward move.
+        assert!(store.move_nodes(&[3], 2, DropPosition::Before));
+        assert_eq!(items(&store), "1 0 | 3 2 | 4 5");
+        check_separators(&store);
+
+        // Multi-node move, the emptied group was removed with it's separator.
+        assert!(store.move_nodes(&[0, 1], 2, DropPosition::After));
+        assert_eq!(items(&store), "3 2 1 0 | 4 5");
+        check_separators(&store);
+
+        // Move across the separator, the node joined the group of target.
+        assert!(store.move_nodes(&[4], 3, DropPosition::Before));
+        assert_eq!(items(&store), "4 3 2 1 0 | 5");
+        assert!(is_group_managed(&store, 4));
+        check_separators(&store);
+
+        assert!(store.move_nodes(&[0], 5, DropPosition::Into));
+        assert_eq!(items(&store), "4 3 2 1 | 5 0");
+        assert!(!is_group_managed(&store, 0));
+        check_separators(&store);
+
+        // Invalid targets.
+        assert!(!store.move_nodes(&[], 5, DropPosition::Before));
+        assert!(!store.move_nodes(&[0, 5], 5, DropPosition::Before));
+        assert!(!store.move_nodes(&[0], 9999, DropPosition::Before));
+        assert!(!store.move_nodes(&[9999], 5, DropPosition::Before));
+        assert_eq!(items(&store), "4 3 2 1 | 5 0");
+        check_separators(&store);
+    }
+
+    #[test]
+    fn test_move_nodes_merge_groups() {
+        let mut store = ConcurrentStore::new(0);
+        store.add_node(&Node);
+        store.add_group(group(2));
+        store.add_node(&Node);
+        assert_eq!(items(&store), "0 | 1 2 | 3");
+
+        // The nodes around the emptied group were not managed by group, merged without
+        // separator between them.
+        assert!(store.move_nodes(&[1, 2], 3, DropPosition::Before));
+        assert_eq!(items(&store), "0 1 2 3");
+        check_separators(&store);
+
+        // The separators were kept between the remaining groups.
+        let mut store = ConcurrentStore::new(0);
+        store.add_group(group(2));
+        store.add_node(&Node);
+        store.add_group(group(1));
+        assert_eq!(items(&store), "0 1 | 2 | 3");
+        assert!(store.move_nodes(&[2], 0, DropPosition::Before));
+        assert_eq!(items(&store), "2 0 1 | 3");
+        check_separators(&store);
+    }
+}
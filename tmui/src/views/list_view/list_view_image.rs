@@ -3,22 +3,28 @@ use super::{
     list_node::ListNode,
     list_separator::GroupSeparator,
     list_store::{ConcurrentStoreMutexGuard, ListStore, ListStoreSignals},
+    ListView, ListViewSignals,
 };
 use crate::{
     font::FontCalculation,
     prelude::*,
     scroll_bar::ScrollBar,
     tlib::object::{ObjectImpl, ObjectSubclass},
-    views::node::{MouseEffect, RenderCtx, Status},
+    views::node::{
+        node_drag::{DropPosition, NodeDrag, NodeDrop},
+        MouseEffect, RenderCtx, Status,
+    },
     widget::{IterExecutor, RegionClear, WidgetImpl},
 };
 use std::ptr::NonNull;
 use tlib::{
-    connect, disconnect, events::MouseEvent, iter_executor, nonnull_mut, ptr_mut, run_after,
+    connect, disconnect, events::MouseEvent, iter_executor, namespace::MouseButton, nonnull_mut,
+    object::ObjectId, ptr_mut, run_after,
 };
 
 type FnNodeAction = Box<dyn Fn(&mut ListNode, &mut ConcurrentStoreMutexGuard, &MouseEvent)>;
 type FnAreaAction = Box<dyn Fn(&mut dyn WidgetImpl, &MouseEvent)>;
+type FnDropVeto = Box<dyn Fn(&[ObjectId], &ListNode, DropPosition) -> bool>;
 
 #[extends(Widget)]
 #[loadable]
@@ -36,6 +42,7 @@ pub(crate) struct ListViewImage {
     #[derivative(Default(value = "MouseEffect::all()"))]
     pub(crate) mouse_effect: MouseEffect,
     pub(crate) reset_effect_node_on_hide: bool,
+    pub(crate) drag: NodeDrag,
 
     pub(crate) on_node_enter: Option<FnNodeAction>,
     pub(crate) on_node_leave: Option<FnNodeAction>,
@@ -43,6 +50,7 @@ pub(crate) struct ListViewImage {
     pub(crate) on_node_released: Option<FnNodeAction>,
     pub(crate) on_free_area_pressed: Option<FnAreaAction>,
     pub(crate) on_free_area_released: Option<FnAreaAction>,
+    pub(crate) on_drop_veto: Option<FnDropVeto>,
 }
 
 impl ObjectSubclass for ListViewImage {
@@ -139,6 +147,14 @@ impl ListViewImage {
     #[inline]
     pub(crate) fn set_line_spacing(&mut self, line_spacing: i32) {
         self.line_spacing = line_spacing;
+        self.calc_window_lines();
+    }
+
+    #[inline]
+    pub(crate) fn set_line_height(&mut self, line_height: i32) {
+        self.line_height = line_height;
+        self.custom_line_height = true;
+        self.calc_window_lines();
     }
 }
 
@@ -163,25 +179,20 @@ impl ListViewImage {
         rect.set_x(rect.x() + self.indent_length as f32);
         rect.set_width(rect.width() - self.indent_length as f32);
 
-        let separator_height = self.store.separator_height() as f32;
-
         self.store.with_image(|image, y_offset| {
-            let height = if let Some(first) = image.first() {
-                match first.item_type() {
-                    ItemType::Node => (self.line_height + self.line_spacing) as f32,
-                    ItemType::Separator => separator_height,
-                }
-            } else {
-                0.
-            };
-            let mut offset = rect.y() - (y_offset as f32 / 10. * height);
+            let mut offset = rect.y() - y_offset as f32;
 
             for item in image {
                 rect.set_y(offset);
                 match item.item_type() {
                     ItemType::Node => {
-                        offset += self.line_height as f32 + self.line_spacing as f32;
-                        rect.set_height(self.line_height as f32);
+                        let height =
+                            item.downcast_ref::<ListNode>()
+                                .unwrap()
+                                .height()
+                                .unwrap_or(self.line_height) as f32;
+                        offset += height + self.line_spacing as f32;
+                        rect.set_height(height);
                     }
                     ItemType::Separator => {
                         let separator_height =
@@ -196,6 +207,8 @@ impl ListViewImage {
                 item.render(painter, RenderCtx::new(rect, background, self.mouse_effect))
             }
         });
+
+        self.drag.render_indicator(painter);
     }
 
     #[inline]
@@ -216,14 +229,15 @@ impl ListViewImage {
     }
 
     fn calc_window_lines(&mut self) {
-        let size = self.size();
-        let window_lines = size.height() / (self.line_height + self.line_spacing);
-        self.store.set_window_lines(window_lines);
+        let window_height = self.contents_rect(Some(Coordinate::Widget)).height();
+        self.store.set_window_height(window_height);
+        self.store
+            .set_line_metrics(self.line_height, self.line_spacing);
 
         let scroll_bar = nonnull_mut!(self.scroll_bar);
-        scroll_bar.set_single_step(5);
-        scroll_bar.set_page_step(window_lines * 10);
-        scroll_bar.set_visible_area(window_lines * 10);
+        scroll_bar.set_single_step(((self.line_height + self.line_spacing) / 2).max(1));
+        scroll_bar.set_page_step(window_height);
+        scroll_bar.set_visible_area(window_height);
     }
 
     #[inline]
@@ -248,9 +262,8 @@ impl ListViewImage {
     }
 
     #[inline]
-    fn on_items_changed(&mut self, len: usize) {
-        nonnull_mut!(self.scroll_bar)
-            .set_range(0, (len as i32 - self.store.get_window_lines()) * 10);
+    fn on_items_changed(&mut self, _: usize) {
+        nonnull_mut!(self.scroll_bar).set_range(0, self.store.max_scroll());
         self.update();
     }
 
@@ -261,91 +274,92 @@ impl ListViewImage {
             return;
         }
 
-        let (_, y) = event.position();
-        let separator_height = self.store.separator_height() as f32;
-        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        let (x, y) = event.position();
+        if self.drag.is_pressed() {
+            if self.drag.try_start(Point::new(x, y)) {
+                self.start_drag();
+            }
+            if self.drag.is_dragging() {
+                self.handle_drag_move(y);
+                return;
+            }
+        }
 
-        let update = self.store.with_image_mut(
-            |mut mutex, start, end, y_offset, entered_node, hovered_node, _| {
-                let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
-                let image = &mut mutex.items[start..end];
-                let item = index_item(
-                    image,
-                    y_offset as f32,
-                    rect,
-                    separator_height,
-                    self.line_height,
-                    self.line_spacing,
-                    y as f32,
-                );
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        let hit = self.store.hit_test(y - rect.y());
 
-                if let Some(item) = item {
-                    if item.item_type() == ItemType::Separator {
-                        if hovered_node.is_some() {
-                            let node = nonnull_mut!(hovered_node.take());
-                            node.remove_status(Status::Hovered);
-                            return true;
+        let update =
+            self.store
+                .with_image_mut(|mut mutex, start, end, _, entered_node, hovered_node, _| {
+                    let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
+                    let item = index_item(&mut mutex.items, start, end, hit);
+
+                    if let Some(item) = item {
+                        if item.item_type() == ItemType::Separator {
+                            if hovered_node.is_some() {
+                                let node = nonnull_mut!(hovered_node.take());
+                                node.remove_status(Status::Hovered);
+                                return true;
+                            }
+                            return false;
                         }
-                        return false;
-                    }
 
-                    let mut update = false;
-                    let node = item.downcast_mut::<ListNode>().unwrap();
+                        let mut update = false;
+                        let node = item.downcast_mut::<ListNode>().unwrap();
+
+                        // Handle node hover:
+                        if !node.is_hovered() {
+                            if hovered_node.is_some() {
+                                let node = nonnull_mut!(hovered_node);
+                                node.remove_status(Status::Hovered);
+                                if !node.is_selected() {
+                                    update = true;
+                                }
+                            }
 
-                    // Handle node hover:
-                    if !node.is_hovered() {
-                        if hovered_node.is_some() {
-                            let node = nonnull_mut!(hovered_node);
-                            node.remove_status(Status::Hovered);
+                            node.add_status(Status::Hovered);
+                            *hovered_node = NonNull::new(node);
                             if !node.is_selected() {
                                 update = true;
                             }
                         }
 
-                        node.add_status(Status::Hovered);
-                        *hovered_node = NonNull::new(node);
-                        if !node.is_selected() {
-                            update = true;
-                        }
-                    }
-
-                    let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
-                    // Handle node enter/leave:
-                    if entered_node.is_none() {
-                        *entered_node = NonNull::new(node);
-                        if let Some(ref on_node_enter) = self.on_node_enter {
-                            on_node_enter(node, mutex_mut, event)
-                        }
-                    } else {
-                        let previous_node = nonnull_mut!(entered_node);
-
-                        if previous_node.id() != node.id() {
+                        let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
+                        // Handle node enter/leave:
+                        if entered_node.is_none() {
                             *entered_node = NonNull::new(node);
-
-                            if let Some(ref on_node_leave) = self.on_node_leave {
-                                on_node_leave(previous_node, mutex_mut, event)
-                            }
                             if let Some(ref on_node_enter) = self.on_node_enter {
-                                on_node_enter(node, mutex_mut, event);
+                                on_node_enter(node, mutex_mut, event)
+                            }
+                        } else {
+                            let previous_node = nonnull_mut!(entered_node);
+
+                            if previous_node.id() != node.id() {
+                                *entered_node = NonNull::new(node);
+
+                                if let Some(ref on_node_leave) = self.on_node_leave {
+                                    on_node_leave(previous_node, mutex_mut, event)
+                                }
+                                if let Some(ref on_node_enter) = self.on_node_enter {
+                                    on_node_enter(node, mutex_mut, event);
+                                }
                             }
                         }
-                    }
 
-                    return update;
-                } else {
-                    let mut old_hover = hovered_node.take();
-                    if old_hover.is_some() {
-                        let node = nonnull_mut!(old_hover);
-                        if node.is_hovered() {
-                            node.remove_status(Status::Hovered);
-                            return true;
+                        return update;
+                    } else {
+                        let mut old_hover = hovered_node.take();
+                        if old_hover.is_some() {
+                            let node = nonnull_mut!(old_hover);
+                            if node.is_hovered() {
+                                node.remove_status(Status::Hovered);
+                                return true;
+                            }
                         }
                     }
-                }
 
-                false
-            },
-        );
+                    false
+                });
 
         if update {
             self.update();
@@ -353,61 +367,92 @@ impl ListViewImage {
     }
 
     fn handle_mouse_pressed(&mut self, event: &MouseEvent) {
-        let (_, y) = event.position();
-        let separator_height = self.store.separator_height() as f32;
-        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        let (x, y) = event.position();
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        let hit = self.store.hit_test(y - rect.y());
         let parent = ptr_mut!(self.get_raw_parent_mut().unwrap());
+        let drag_enabled = self.drag.enabled;
+        let multi_select = drag_enabled && event.modifier().ctrl();
+        let mut pressed_node = None;
+        let mut toggled = None;
+        let left_pressed = event.mouse_button() == MouseButton::LeftButton;
+        let (indent_length, line_height) = (self.indent_length, self.line_height);
+
+        let update = self
+            .store
+            .with_image_mut(|mut mutex, start, end, _, _, _, selected_node| {
+                let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
+                let item = index_item(&mut mutex.items, start, end, hit);
 
-        let update =
-            self.store
-                .with_image_mut(|mut mutex, start, end, y_offset, _, _, selected_node| {
-                    let mutex_ptr = &mut mutex as *mut ConcurrentStoreMutexGuard;
-                    let image = &mut mutex.items[start..end];
-                    let item = index_item(
-                        image,
-                        y_offset as f32,
-                        rect,
-                        separator_height,
-                        self.line_height,
-                        self.line_spacing,
-                        y as f32,
-                    );
-
-                    if let Some(item) = item {
-                        if item.item_type() == ItemType::Separator {
-                            return false;
-                        }
-                        let node = item.downcast_mut::<ListNode>().unwrap();
+                if let Some(item) = item {
+                    if item.item_type() == ItemType::Separator {
+                        return false;
+                    }
+                    let node = item.downcast_mut::<ListNode>().unwrap();
+                    let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
 
-                        if selected_node.is_some() {
-                            let node = nonnull_mut!(selected_node);
-                            node.remove_status(Status::Selected);
+                    if drag_enabled {
+                        // Keep the selected nodes when pressing on one of them,
+                        // so that all of them can be dragged together.
+                        if !multi_select && !node.is_selected() {
+                            mutex_mut.clear_selection();
                         }
+                    } else if selected_node.is_some() {
+                        let node = nonnull_mut!(selected_node);
+                        node.remove_status(Status::Selected);
+                    }
 
-                        node.add_status(Status::Selected);
-                        *selected_node = NonNull::new(node);
+                    node.add_status(Status::Selected);
+                    *selected_node = NonNull::new(node);
+                    pressed_node = Some(node.id());
+
+                    if left_pressed {
+                        let (_, node_y, _) = hit.unwrap();
+                        let geometry = FRect::new(
+                            (rect.x() + indent_length) as f32,
+                            (rect.y() + node_y) as f32,
+                            (rect.width() - indent_length) as f32,
+                            node.height().unwrap_or(line_height) as f32,
+                        );
+                        toggled = node
+                            .toggle_checkbox(geometry, FPoint::new(x as f32, y as f32))
+                            .map(|(cell, checked)| (node.id(), cell, checked));
+                    }
 
+                    if let Some(ref on_node_pressed) = self.on_node_pressed {
+                        on_node_pressed(node, mutex_mut, event);
+                    }
+                    true
+                } else {
+                    let mut update = false;
+                    let mut old_select = selected_node.take();
+                    if old_select.is_some() {
+                        let node = nonnull_mut!(old_select);
+                        node.remove_status(Status::Selected);
+                        update = true;
+                    }
+                    if drag_enabled {
                         let mutex_mut = unsafe { mutex_ptr.as_mut().unwrap() };
-                        if let Some(ref on_node_pressed) = self.on_node_pressed {
-                            on_node_pressed(node, mutex_mut, event);
-                        }
-                        true
-                    } else {
-                        let mut update = false;
-                        let mut old_select = selected_node.take();
-                        if old_select.is_some() {
-                            let node = nonnull_mut!(old_select);
-                            node.remove_status(Status::Selected);
-                            update = true;
-                        }
-
-                        if let Some(ref on_frea_area_pressed) = self.on_free_area_pressed {
-                            on_frea_area_pressed(parent, event);
-                        }
+                        mutex_mut.clear_selection();
+                    }
 
-                        update
+                    if let Some(ref on_frea_area_pressed) = self.on_free_area_pressed {
+                        on_frea_area_pressed(parent, event);
                     }
-                });
+
+                    update
+                }
+            });
+
+        if let Some(node) = pressed_node {
+            if drag_enabled && left_pressed && toggled.is_none() {
+                self.drag.press(Point::new(x, y), node);
+            }
+        }
+        if let Some((node, cell, checked)) = toggled {
+            let view = self.store.get_view().downcast_mut::<ListView>().unwrap();
+            emit!(view, checkbox_toggled(node, cell, checked));
+        }
 
         if update {
             self.update();
@@ -415,6 +460,28 @@ impl ListViewImage {
     }
 
     fn handle_mouse_released(&mut self, event: &MouseEvent) {
+        if self.drag.is_pressed() {
+            let dragging = self.drag.is_dragging();
+            let pressed_node = self.drag.pressed_node();
+            let node_drop = self.drag.finish();
+
+            if dragging {
+                self.set_cursor_shape(SystemCursorShape::ArrowCursor);
+                if let Some(node_drop) = node_drop {
+                    self.drop_nodes(node_drop);
+                }
+                self.update();
+                return;
+            }
+
+            if !event.modifier().ctrl() {
+                if let Some(mut mutex) = self.store.concurrent_store().try_lock() {
+                    mutex.retain_selection(pressed_node);
+                }
+                self.update();
+            }
+        }
+
         let parent = ptr_mut!(self.get_raw_parent_mut().unwrap());
         let mut selected_node = self.store.get_selected_node();
         if selected_node.is_none() {
@@ -433,41 +500,94 @@ impl ListViewImage {
     }
 }
 
-fn index_item(
-    image: &mut [Box<dyn ListItem>],
-    y_offset: f32,
-    rect: FRect,
-    separator_height: f32,
-    line_height: i32,
-    line_spacing: i32,
-    y: f32,
-) -> Option<&mut Box<dyn ListItem>> {
-    if image.is_empty() {
-        return None;
+impl ListViewImage {
+    fn start_drag(&mut self) {
+        let dragged = self.store.selected_ids();
+        self.drag.set_dragged(dragged);
+
+        self.set_cursor_shape(SystemCursorShape::DragMoveCursor);
     }
 
-    let mut height = if let Some(first) = image.first() {
-        match first.item_type() {
-            ItemType::Node => (line_height + line_spacing) as f32,
-            ItemType::Separator => separator_height,
-        }
-    } else {
-        0.
-    };
-    let mut offset = rect.y() - (y_offset / 10. * height);
-
-    for item in image {
-        height = match item.item_type() {
-            ItemType::Node => (line_height + line_spacing) as f32,
-            ItemType::Separator => separator_height,
+    fn handle_drag_move(&mut self, y: i32) {
+        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        let hit = self.store.hit_test(y - rect.y() as i32);
+        let dragged = self.drag.dragged().to_vec();
+        let mut target = None;
+
+        self.store
+            .with_image_mut(|mut mutex, start, end, _, _, _, _| {
+                if let Some((_, node_y, height)) = hit {
+                    let node_y = rect.y() + node_y as f32;
+                    let height = height as f32;
+                    let node = index_item(&mut mutex.items, start, end, hit)
+                        .and_then(|item| item.downcast_ref::<ListNode>());
+
+                    if let Some(node) = node {
+                        if dragged.contains(&node.id()) {
+                            return false;
+                        }
+
+                        let position = DropPosition::locate(node_y, height, y as f32, false);
+                        let vetoed = self
+                            .on_drop_veto
+                            .as_ref()
+                            .map_or(false, |veto| veto(&dragged, node, position));
+
+                        if !vetoed {
+                            let node_rect = FRect::new(rect.x(), node_y, rect.width(), height);
+                            target = Some((node.id(), position, position.indicator(node_rect)));
+                        }
+                    }
+                }
+                false
+            });
+
+        let update = match target {
+            Some((id, position, indicator)) => self
+                .drag
+                .update_target(Some((id, position)), Some(indicator)),
+            None => self.drag.update_target(None, None),
         };
 
-        if (offset..offset + height).contains(&y) {
-            return Some(item);
+        // Scroll the view when dragging near the top/bottom edge:
+        let scroll_bar = nonnull_mut!(self.scroll_bar);
+        let edge = self.line_height as f32;
+        if (y as f32) < rect.y() + edge {
+            scroll_bar.set_value(scroll_bar.value() - scroll_bar.single_step());
+        } else if (y as f32) > rect.y() + rect.height() - edge {
+            scroll_bar.set_value(scroll_bar.value() + scroll_bar.single_step());
         }
 
-        offset += height;
+        if update {
+            self.update();
+        }
     }
 
-    None
+    fn drop_nodes(&mut self, node_drop: NodeDrop) {
+        let NodeDrop {
+            nodes,
+            target,
+            position,
+        } = node_drop;
+
+        if self.store.move_nodes(&nodes, target, position) {
+            let view = self.store.get_view().downcast_mut::<ListView>().unwrap();
+            emit!(view, nodes_moved(nodes));
+        }
+    }
+}
+
+/// Get the visible item hit by the mouse, see [`ListStore::hit_test`].
+#[inline]
+fn index_item(
+    items: &mut [Box<dyn ListItem>],
+    start: usize,
+    end: usize,
+    hit: Option<(usize, i32, i32)>,
+) -> Option<&mut Box<dyn ListItem>> {
+    let (idx, _, _) = hit?;
+    if idx < start || idx >= end {
+        return None;
+    }
+    items.get_mut(idx)
 }
@@ -5,7 +5,7 @@ pub mod list_separator;
 pub mod list_store;
 pub mod list_view_image;
 pub mod list_view_object;
-use super::node::MouseEffect;
+use super::node::{node_drag::DropPosition, MouseEffect};
 use crate::{
     prelude::*,
     tlib::object::{ObjectImpl, ObjectSubclass},
@@ -19,7 +19,7 @@ use list_view_image::ListViewImage;
 use list_view_object::ListViewObject;
 use std::sync::Arc;
 use tipc::parking_lot::Mutex;
-use tlib::connect;
+use tlib::{connect, object::ObjectId, signals};
 
 /// UI component displays data in a list manner.
 ///
@@ -203,7 +203,7 @@ impl ListView {
 
     #[inline]
     pub fn set_line_spacing(&mut self, line_spacing: i32) {
-        self.get_image_mut().line_spacing = line_spacing;
+        self.get_image_mut().set_line_spacing(line_spacing)
     }
 
     #[inline]
@@ -211,10 +211,12 @@ impl ListView {
         self.get_image().line_height
     }
 
+    /// Set the default height of nodes, the node can specify it's own height through
+    /// [`NodeRenderBuilder::height()`](crate::views::node::node_render::NodeRenderBuilder::height)
+    /// or [`ListNode::set_height()`](list_node::ListNode::set_height).
     #[inline]
     pub fn set_line_height(&mut self, line_height: i32) {
-        self.get_image_mut().line_height = line_height;
-        self.get_image_mut().custom_line_height = true;
+        self.get_image_mut().set_line_height(line_height)
     }
 
     #[inline]
@@ -297,7 +299,71 @@ impl ListView {
     ) {
         self.get_image_mut().on_free_area_released = Some(Box::new(f));
     }
+
+    /// Enable/Disable dragging nodes to reorder them.
+    ///
+    /// When enabled, multiple nodes can be selected by pressing with `Ctrl`,
+    /// and dragged together. The signal [`nodes_moved()`](ListViewSignals::nodes_moved)
+    /// will be emitted after the nodes were dropped.
+    #[inline]
+    pub fn set_drag_enabled(&mut self, enabled: bool) {
+        self.get_image_mut().drag.enabled = enabled
+    }
+
+    #[inline]
+    pub fn is_drag_enabled(&self) -> bool {
+        self.get_image().drag.enabled
+    }
+
+    #[inline]
+    pub fn set_drop_indicator_color(&mut self, color: Color) {
+        self.get_image_mut().drag.indicator_color = color
+    }
+
+    /// Function closure will be executed when the dragging nodes hovered on the target node,
+    /// parameters are the ids of dragging nodes, the target node and the drop position.
+    ///
+    /// Return `true` to veto the drop, the drop indicator will not be shown.
+    #[inline]
+    pub fn register_drop_veto<F: 'static + Fn(&[ObjectId], &ListNode, DropPosition) -> bool>(
+        &mut self,
+        f: F,
+    ) {
+        self.get_image_mut().on_drop_veto = Some(Box::new(f));
+    }
+
+    /// Move the nodes with specified ids to the position relative to the target node.
+    ///
+    /// @return `false` if the target was not exist or was one of the moved nodes.
+    #[inline]
+    pub fn move_nodes(
+        &mut self,
+        ids: &[ObjectId],
+        target: ObjectId,
+        position: DropPosition,
+    ) -> bool {
+        self.get_store_mut().move_nodes(ids, target, position)
+    }
+}
+
+pub trait ListViewSignals: ActionExt {
+    signals!(
+        ListViewSignals:
+
+        /// Emitted when the nodes were moved by dragging.
+        ///
+        /// @param [`Vec<ObjectId>`]: the ids of moved nodes.
+        nodes_moved(Vec<ObjectId>);
+
+        /// Emitted when the checkbox cell of node was toggled by clicking.
+        ///
+        /// @param [`ObjectId`]: the id of node.
+        /// @param [`usize`]: the index of cell.
+        /// @param [`bool`]: the new value of cell.
+        checkbox_toggled(ObjectId, usize, bool);
+    );
 }
+impl ListViewSignals for ListView {}
 
 impl ListView {
     #[inline]
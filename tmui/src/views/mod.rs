@@ -1,4 +1,5 @@
 pub mod cell;
+pub mod grid_view;
 pub mod list_view;
 pub mod node;
 pub mod tree_view;
\ No newline at end of file
@@ -0,0 +1,122 @@
+/// Prefix-sum index of the row heights in `ListView`/`TreeView`,
+/// used for hit-testing and scrolling when the rows have different heights.
+///
+/// `prefix[i]` is the total height of the rows before the `i`th row,
+/// the height of row includes the line spacing.
+#[derive(Debug, Clone)]
+pub(crate) struct HeightIndex {
+    prefix: Vec<i32>,
+}
+
+impl Default for HeightIndex {
+    #[inline]
+    fn default() -> Self {
+        Self { prefix: vec![0] }
+    }
+}
+
+impl HeightIndex {
+    pub(crate) fn rebuild<I: IntoIterator<Item = i32>>(&mut self, heights: I) {
+        self.prefix.clear();
+        self.prefix.push(0);
+
+        let mut sum = 0;
+        for height in heights {
+            sum += height.max(0);
+            self.prefix.push(sum);
+        }
+    }
+
+    /// The number of rows.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.prefix.len() - 1
+    }
+
+    /// The total height of all the rows.
+    #[inline]
+    pub(crate) fn total(&self) -> i32 {
+        *self.prefix.last().unwrap()
+    }
+
+    /// The y offset of the row's top edge, relative to the first row.
+    ///
+    /// Return [`total()`](HeightIndex::total) if the `idx` was out of bounds.
+    #[inline]
+    pub(crate) fn offset_of(&self, idx: usize) -> i32 {
+        self.prefix[idx.min(self.len())]
+    }
+
+    #[inline]
+    pub(crate) fn height_of(&self, idx: usize) -> i32 {
+        if idx >= self.len() {
+            return 0;
+        }
+        self.prefix[idx + 1] - self.prefix[idx]
+    }
+
+    /// Get the index of row which contains the y offset relative to the first row.
+    ///
+    /// @return `None` if the `y` was out of the rows.
+    pub(crate) fn index_at(&self, y: i32) -> Option<usize> {
+        if y < 0 || y >= self.total() {
+            return None;
+        }
+        Some(self.prefix.partition_point(|&offset| offset <= y) - 1)
+    }
+
+    /// The maximum scroll offset that the rows can fill the view with the `view_height`.
+    #[inline]
+    pub(crate) fn max_scroll(&self, view_height: i32) -> i32 {
+        (self.total() - view_height).max(0)
+    }
+
+    /// Get the range of rows which are visible in the view.
+    ///
+    /// @return (start, end), `end` was exclusive.
+    pub(crate) fn visible_range(&self, scroll: i32, view_height: i32) -> (usize, usize) {
+        let len = self.len();
+        let start = self.index_at(scroll.max(0)).unwrap_or(len);
+        let bottom = scroll + view_height;
+        let end = self.prefix[..len].partition_point(|&offset| offset < bottom);
+
+        (start, end.max(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeightIndex;
+
+    #[test]
+    fn test_height_index() {
+        let mut index = HeightIndex::default();
+        assert_eq!(index.len(), 0);
+        assert_eq!(index.index_at(0), None);
+        assert_eq!(index.visible_range(0, 100), (0, 0));
+
+        index.rebuild([10, 30, 20, 40]);
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.total(), 100);
+        assert_eq!(index.offset_of(2), 40);
+        assert_eq!(index.offset_of(10), 100);
+        assert_eq!(index.height_of(1), 30);
+        assert_eq!(index.height_of(4), 0);
+
+        assert_eq!(index.index_at(-1), None);
+        assert_eq!(index.index_at(0), Some(0));
+        assert_eq!(index.index_at(9), Some(0));
+        assert_eq!(index.index_at(10), Some(1));
+        assert_eq!(index.index_at(59), Some(2));
+        assert_eq!(index.index_at(99), Some(3));
+        assert_eq!(index.index_at(100), None);
+
+        assert_eq!(index.max_scroll(50), 50);
+        assert_eq!(index.max_scroll(150), 0);
+
+        assert_eq!(index.visible_range(0, 40), (0, 2));
+        assert_eq!(index.visible_range(15, 30), (1, 3));
+        assert_eq!(index.visible_range(50, 50), (2, 4));
+        assert_eq!(index.visible_range(0, 1000), (0, 4));
+    }
+}
@@ -1,5 +1,7 @@
 use tlib::{bitflags::bitflags, figure::{Color, FRect}};
 
+pub(crate) mod height_index;
+pub mod node_drag;
 pub mod node_render;
 
 // #[repr(u8)]
@@ -0,0 +1,201 @@
+use crate::{event_hints::event_hints, graphics::painter::Painter};
+use derivative::Derivative;
+use tlib::{
+    figure::{Color, FRect, Point},
+    object::ObjectId,
+};
+
+const DEFAULT_INDICATOR_COLOR: Color = Color::rgb(51, 167, 255);
+const INDICATOR_WIDTH: f32 = 2.;
+
+/// The position where the dragged nodes will be dropped, relative to the target node.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DropPosition {
+    /// Insert the dragged nodes before the target node.
+    #[default]
+    Before,
+    /// Insert the dragged nodes after the target node.
+    After,
+    /// Make the dragged nodes to be the children of the target node,
+    /// only available on extensible node of `TreeView`.
+    Into,
+}
+
+impl DropPosition {
+    /// Determine the drop position by the y coordinate of mouse in the node area.
+    ///
+    /// @param `allow_into`: whether the target node can hold the dragged nodes as children.
+    pub(crate) fn locate(node_y: f32, node_height: f32, y: f32, allow_into: bool) -> Self {
+        let delta = y - node_y;
+
+        if allow_into {
+            if delta < node_height / 4. {
+                Self::Before
+            } else if delta > node_height * 3. / 4. {
+                Self::After
+            } else {
+                Self::Into
+            }
+        } else if delta < node_height / 2. {
+            Self::Before
+        } else {
+            Self::After
+        }
+    }
+
+    /// Get the drop indicator area based on the geometry of target node.
+    pub(crate) fn indicator(&self, node_geometry: FRect) -> FRect {
+        match self {
+            Self::Before => FRect::new(
+                node_geometry.x(),
+                node_geometry.y() - INDICATOR_WIDTH / 2.,
+                node_geometry.width(),
+                INDICATOR_WIDTH,
+            ),
+            Self::After => FRect::new(
+                node_geometry.x(),
+                node_geometry.y() + node_geometry.height() - INDICATOR_WIDTH / 2.,
+                node_geometry.width(),
+                INDICATOR_WIDTH,
+            ),
+            Self::Into => node_geometry,
+        }
+    }
+}
+
+/// The drop operation generated when the dragging nodes were released.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeDrop {
+    pub nodes: Vec<ObjectId>,
+    pub target: ObjectId,
+    pub position: DropPosition,
+}
+
+/// The dragging status of nodes in `ListView`/`TreeView`.
+#[derive(Derivative)]
+#[derivative(Default)]
+pub(crate) struct NodeDrag {
+    pub(crate) enabled: bool,
+    #[derivative(Default(value = "DEFAULT_INDICATOR_COLOR"))]
+    pub(crate) indicator_color: Color,
+
+    pressed_pos: Option<Point>,
+    pressed_node: Option<ObjectId>,
+    dragging: bool,
+    dragged: Vec<ObjectId>,
+    target: Option<(ObjectId, DropPosition)>,
+    indicator: Option<FRect>,
+}
+
+impl NodeDrag {
+    #[inline]
+    pub(crate) fn press(&mut self, pos: Point, node: ObjectId) {
+        self.pressed_pos = Some(pos);
+        self.pressed_node = Some(node);
+    }
+
+    #[inline]
+    pub(crate) fn pressed_node(&self) -> Option<ObjectId> {
+        self.pressed_node
+    }
+
+    #[inline]
+    pub(crate) fn is_pressed(&self) -> bool {
+        self.pressed_pos.is_some()
+    }
+
+    #[inline]
+    pub(crate) fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    #[inline]
+    pub(crate) fn dragged(&self) -> &[ObjectId] {
+        &self.dragged
+    }
+
+    /// Check whether the mouse has moved far enough to start the drag operation.
+    ///
+    /// @return `true` if the drag operation was started just now.
+    pub(crate) fn try_start(&mut self, pos: Point) -> bool {
+        if !self.enabled || self.dragging {
+            return false;
+        }
+        let pressed = match self.pressed_pos {
+            Some(pressed) => pressed,
+            None => return false,
+        };
+
+        let distance = (pos.x() - pressed.x()).abs() + (pos.y() - pressed.y()).abs();
+        if distance < event_hints().start_drag_distance() {
+            return false;
+        }
+
+        self.dragging = true;
+        true
+    }
+
+    #[inline]
+    pub(crate) fn set_dragged(&mut self, dragged: Vec<ObjectId>) {
+        self.dragged = dragged;
+    }
+
+    /// Update the drop target and the indicator area.
+    ///
+    /// @return `true` if the drop target has changed, should update the image.
+    pub(crate) fn update_target(
+        &mut self,
+        target: Option<(ObjectId, DropPosition)>,
+        indicator: Option<FRect>,
+    ) -> bool {
+        if self.target == target && self.indicator == indicator {
+            return false;
+        }
+        self.target = target;
+        self.indicator = indicator;
+        true
+    }
+
+    /// Finish the drag operation and reset the status.
+    ///
+    /// @return `Some` if there was a valid drop target.
+    pub(crate) fn finish(&mut self) -> Option<NodeDrop> {
+        let dragging = self.dragging;
+        let dragged = std::mem::take(&mut self.dragged);
+        let target = self.target.take();
+
+        self.pressed_pos = None;
+        self.pressed_node = None;
+        self.dragging = false;
+        self.indicator = None;
+
+        if !dragging || dragged.is_empty() {
+            return None;
+        }
+
+        target.map(|(target, position)| NodeDrop {
+            nodes: dragged,
+            target,
+            position,
+        })
+    }
+
+    pub(crate) fn render_indicator(&self, painter: &mut Painter) {
+        let indicator = match self.indicator {
+            Some(indicator) if self.dragging => indicator,
+            _ => return,
+        };
+
+        match self.target {
+            Some((_, DropPosition::Into)) => {
+                painter.save_pen();
+                painter.set_color(self.indicator_color);
+                painter.set_line_width(INDICATOR_WIDTH);
+                painter.draw_rect(indicator);
+                painter.restore_pen();
+            }
+            Some(_) => painter.fill_rect(indicator, self.indicator_color),
+            None => {}
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub struct NodeRender {
     #[derivative(Default(value = "DEFAULT_HOVER"))]
     hover_color: Color,
     pub(crate) border: Border,
+    height: Option<i32>,
 }
 
 impl NodeRender {
@@ -21,6 +22,12 @@ impl NodeRender {
     pub fn builder() -> NodeRenderBuilder {
         NodeRenderBuilder::default()
     }
+
+    /// The height of the node, `None` means using the line height of view.
+    #[inline]
+    pub fn height(&self) -> Option<i32> {
+        self.height
+    }
 }
 
 impl NodeRender {
@@ -97,6 +104,7 @@ pub struct NodeRenderBuilder {
     #[derivative(Default(value = "DEFAULT_HOVER"))]
     hover_color: Color,
     border: Border,
+    height: Option<i32>,
 }
 
 impl NodeRenderBuilder {
@@ -184,12 +192,20 @@ impl NodeRenderBuilder {
         self
     }
 
+    /// Specify the height of the node, instead of using the line height of view.
+    #[inline]
+    pub fn height(mut self, height: i32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
     #[inline]
     pub fn build(self) -> NodeRender {
         NodeRender {
             selection_color: self.selection_color,
             hover_color: self.hover_color,
             border: self.border,
+            height: self.height,
         }
     }
 }
@@ -3,15 +3,19 @@ pub mod tree_store;
 pub mod tree_view_image;
 pub mod tree_view_object;
 
-use self::{tree_node::TreeNode, tree_store::TreeStore, tree_view_image::TreeViewImage};
+use self::{
+    tree_node::TreeNode, tree_store::TreeStore, tree_view_image::TreeViewImage,
+    tree_view_object::TreeViewObject,
+};
 use crate::{
     prelude::*,
     tlib::object::{ObjectImpl, ObjectSubclass},
     widget::{InnerEventProcess, WidgetHndAsable, WidgetImpl},
 };
-use tlib::{compare::Compare, connect, signals};
+use std::future::Future;
+use tlib::{compare::Compare, connect, object::ObjectId, r#async::tokio_runtime, signals};
 
-use super::node::MouseEffect;
+use super::node::{node_drag::DropPosition, MouseEffect};
 
 /// Tree components display data in a hierarchical manner.
 ///
@@ -237,6 +241,79 @@ impl TreeView {
     ) {
         self.get_image_mut().register_free_area_released(f)
     }
+
+    /// Enable/Disable dragging nodes to reorder them or move them to another parent.
+    ///
+    /// When enabled, multiple nodes can be selected by pressing with `Ctrl`, and dragged together.
+    /// The expanding/collapsing of node will be triggered on mouse released instead of pressed.
+    /// The signal [`nodes_moved()`](TreeViewSignals::nodes_moved) will be emitted after the nodes were dropped.
+    #[inline]
+    pub fn set_drag_enabled(&mut self, enabled: bool) {
+        self.get_image_mut().drag_mut().enabled = enabled
+    }
+
+    #[inline]
+    pub fn is_drag_enabled(&self) -> bool {
+        self.get_image().drag().enabled
+    }
+
+    #[inline]
+    pub fn set_drop_indicator_color(&mut self, color: Color) {
+        self.get_image_mut().drag_mut().indicator_color = color
+    }
+
+    /// Function closure will be executed when the dragging nodes hovered on the target node,
+    /// parameters are the ids of dragging nodes, the target node and the drop position.
+    ///
+    /// Return `true` to veto the drop, the drop indicator will not be shown.
+    #[inline]
+    pub fn register_drop_veto<T: 'static + Fn(&[ObjectId], &TreeNode, DropPosition) -> bool>(
+        &mut self,
+        f: T,
+    ) {
+        self.get_image_mut().register_drop_veto(f)
+    }
+
+    /// Register the loader to load the children of lazy node,
+    /// see [`TreeViewObject::lazy()`].
+    ///
+    /// The loader will be called on the UI thread when the lazy node was expanded for the first time,
+    /// the returned future will be spawned on the tokio runtime, a loading spinner will be shown on the node
+    /// until it finished, and then the results will be added to the node as children on the UI thread.
+    ///
+    /// Example:
+    /// ```ignore
+    /// tree_view.register_lazy_loader(|node| {
+    ///     let path = node.get_value::<String>(0).unwrap();
+    ///     async move {
+    ///         read_remote_dir(path)
+    ///             .await
+    ///             .into_iter()
+    ///             .map(|entry| Box::new(entry) as Box<dyn TreeViewObject + Send>)
+    ///             .collect()
+    ///     }
+    /// });
+    /// ```
+    #[inline]
+    pub fn register_lazy_loader<F, Fut>(&mut self, f: F)
+    where
+        F: 'static + Fn(&TreeNode) -> Fut,
+        Fut: Future<Output = Vec<Box<dyn TreeViewObject + Send>>> + Send + 'static,
+    {
+        self.get_image_mut()
+            .register_lazy_loader(Box::new(move |node| tokio_runtime().spawn(f(node))))
+    }
+
+    /// See [`TreeStore::move_nodes`].
+    #[inline]
+    pub fn move_nodes(
+        &mut self,
+        ids: &[ObjectId],
+        target: ObjectId,
+        position: DropPosition,
+    ) -> bool {
+        self.get_store_mut().move_nodes(ids, target, position)
+    }
 }
 
 impl TreeView {
@@ -270,6 +347,18 @@ pub trait TreeViewSignals: ActionExt {
         selection_changed();
 
         row_activated();
+
+        /// Emitted when the nodes were moved by dragging.
+        ///
+        /// @param [`Vec<ObjectId>`]: the ids of moved nodes.
+        nodes_moved(Vec<ObjectId>);
+
+        /// Emitted when the checkbox cell of node was toggled by clicking.
+        ///
+        /// @param [`ObjectId`]: the id of node.
+        /// @param [`usize`]: the index of cell.
+        /// @param [`bool`]: the new value of cell.
+        checkbox_toggled(ObjectId, usize, bool);
     );
 }
 impl TreeViewSignals for TreeView {}
@@ -1,7 +1,7 @@
 use super::{tree_store::TreeStore, tree_view_object::TreeViewObject, TreeView};
 use crate::views::cell::cell_index::CellIndex;
 use crate::views::cell::cell_render::CellRender;
-use crate::views::cell::Cell;
+use crate::views::cell::{checkbox_at, layout_cells, Cell};
 use crate::views::node::node_render::NodeRender;
 use crate::views::node::{RenderCtx, Status};
 use crate::{application::is_ui_thread, prelude::*};
@@ -17,6 +17,21 @@ use tlib::{
     values::{FromValue, ToValue},
 };
 
+/// The loading state of children of the lazy [`TreeNode`],
+/// see [`TreeViewObject::lazy()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LazyState {
+    /// The node is not lazy, children were added directly.
+    #[default]
+    Eager,
+    /// The children have not been loaded yet.
+    Unloaded,
+    /// The loader is running, children will be added when it finished.
+    Loading,
+    /// The children have been loaded.
+    Loaded,
+}
+
 #[allow(clippy::vec_box)]
 pub struct TreeNode {
     pub(crate) store: ObjectId,
@@ -32,6 +47,8 @@ pub struct TreeNode {
     idx: usize,
     level: i32,
     status: Status,
+    lazy: LazyState,
+    height: Option<i32>,
 
     cells: Vec<Cell>,
     node_render: NodeRender,
@@ -80,6 +97,23 @@ impl TreeNode {
         self.add_node_directly_inner(node)
     }
 
+    /// Insert the node at the specified index of children, the node and it's children
+    /// will be placed as a whole, e.g. the node detached by [`take()`](TreeNode::take).
+    ///
+    /// The node will be appended to the end if the `index` was out of bounds.
+    pub fn insert_node_directly(
+        &mut self,
+        index: usize,
+        mut node: Box<TreeNode>,
+    ) -> Option<&mut TreeNode> {
+        if self.removed {
+            return None;
+        }
+        node.store = self.store;
+
+        self.insert_node_directly_inner(index, node)
+    }
+
     pub fn get_value<T: 'static + StaticType + FromValue>(&self, cell_idx: impl CellIndex) -> Option<T> {
         self.cells
             .get(cell_idx.index())
@@ -173,6 +207,54 @@ impl TreeNode {
         self.expanded
     }
 
+    /// The specified height of node, `None` means using the line height of view.
+    #[inline]
+    pub fn height(&self) -> Option<i32> {
+        self.height
+    }
+
+    /// Specify the height of node, `None` means using the line height of view.
+    pub fn set_height(&mut self, height: Option<i32>) {
+        if self.height == height {
+            return;
+        }
+        self.height = height;
+
+        if is_ui_thread() {
+            TreeStore::store_mut(self.store)
+                .unwrap()
+                .node_height_changed();
+        }
+    }
+
+    #[inline]
+    pub fn lazy_state(&self) -> LazyState {
+        self.lazy
+    }
+
+    /// Whether the children of lazy node were being loaded.
+    #[inline]
+    pub fn is_loading(&self) -> bool {
+        self.lazy == LazyState::Loading
+    }
+
+    /// Remove all the children of lazy node and reset it to be unloaded and collapsed,
+    /// so that the children will be reloaded when it was expanded next time.
+    ///
+    /// Do nothing if the node was not lazy or the children were being loaded.
+    pub fn reset_lazy(&mut self) {
+        if !matches!(self.lazy, LazyState::Loaded | LazyState::Unloaded) {
+            return;
+        }
+        if self.expanded {
+            self.shuffle_expand();
+        }
+        while let Some(child) = self.children.last_mut() {
+            child.remove();
+        }
+        self.lazy = LazyState::Unloaded;
+    }
+
     #[inline]
     pub fn is_hovered(&self) -> bool {
         self.status.contains(Status::Hovered)
@@ -186,19 +268,21 @@ impl TreeNode {
     #[inline]
     pub fn remove(&mut self) {
         self.removed = true;
-        let _hold = self.remove_node_inner();
+        let _hold = self.detach();
+    }
 
-        let child_id = self.id();
-        let child_expanded = self.is_expanded();
-        let mut parent = self.get_parent();
-        let mut deleted = vec![child_id];
-        deleted.extend_from_slice(self.get_children_ids());
-        TreeStore::store_mut(self.store).unwrap().node_deleted(
-            nonnull_mut!(parent),
-            child_id,
-            child_expanded,
-            deleted,
-        );
+    /// Detach the node from it's parent, different from [`remove()`](TreeNode::remove),
+    /// the node and it's children are still alive, and can be added to another parent
+    /// through [`insert_node_directly()`](TreeNode::insert_node_directly) or
+    /// [`add_node_directly()`](TreeNode::add_node_directly).
+    ///
+    /// @return `None` if the node was root.
+    #[inline]
+    pub fn take(&mut self) -> Option<Box<TreeNode>> {
+        let mut hold = self.detach()?;
+        hold.parent = None;
+
+        Some(hold)
     }
 
     #[inline]
@@ -248,17 +332,21 @@ impl TreeNode {
         self.notify_grand_child_expand(self.expanded, self.id, self.children_id_holder.clone());
 
         if is_ui_thread() {
-            TreeStore::store_mut(self.store)
-                .unwrap()
-                .node_expanded(self);
+            let store = TreeStore::store_mut(self.store).unwrap();
+            store.node_expanded(self);
+
+            if self.expanded && self.lazy == LazyState::Unloaded {
+                store.load_lazy(self);
+            }
         }
     }
 
     /// Get the area of TreeNode with the specific coordinate.
-    /// 
+    ///
     /// If the node is not in the screen image, return `None`
     pub fn rect(&self, coord: Coordinate) -> Option<Rect> {
-        let (image, y_offset) = TreeStore::store_ref(self.store).unwrap().get_image();
+        let store = TreeStore::store_ref(self.store).unwrap();
+        let (image, _) = store.get_image();
         let mut idx = 0;
         let mut find = false;
         for (i, node) in image.iter().enumerate() {
@@ -276,15 +364,14 @@ impl TreeNode {
                 .unwrap()
                 .get_image();
             let view_rect = view.contents_rect(Some(coord));
-            let line_height = view.line_height();
-            let line_spacing = view.line_spacing();
-
-            let y_start = view_rect.y()
-                - (y_offset as f32 / 10. * (line_height + line_spacing) as f32) as i32;
-            let height = line_height + line_spacing;
-            let y = y_start + idx as i32 * (height);
-
-            Some(Rect::new(view_rect.x(), y, view_rect.width(), height))
+            let (y, height) = store.image_node_geometry(idx);
+
+            Some(Rect::new(
+                view_rect.x(),
+                view_rect.y() + y,
+                view_rect.width(),
+                height,
+            ))
         } else {
             None
         }
@@ -307,6 +394,8 @@ impl TreeNode {
             idx: 0,
             level: -1,
             status: Status::empty(),
+            lazy: LazyState::Eager,
+            height: None,
             cells: vec![],
             node_render: NodeRender::default(),
         }
@@ -314,6 +403,9 @@ impl TreeNode {
 
     #[inline]
     pub(crate) fn create_from_obj(obj: &dyn TreeViewObject, store: ObjectId) -> Box<Self> {
+        let lazy = obj.extensible() && obj.lazy();
+        let node_render = obj.node_render();
+
         Self {
             id: TreeStore::store_ref(store)
                 .unwrap()
@@ -323,15 +415,21 @@ impl TreeNode {
             parent: None,
             is_root: false,
             extensible: obj.extensible(),
-            expanded: true,
+            expanded: !lazy,
             removed: false,
             children: vec![],
             children_id_holder: vec![],
             idx: 0,
             level: 0,
             status: Status::empty(),
+            lazy: if lazy {
+                LazyState::Unloaded
+            } else {
+                LazyState::Eager
+            },
+            height: node_render.height(),
             cells: obj.cells(),
-            node_render: obj.node_render(),
+            node_render,
         }
         .boxed()
     }
@@ -343,38 +441,41 @@ impl TreeNode {
         render_ctx: RenderCtx,
         ident_length: i32,
     ) {
-        let mut geometry: FRect = render_ctx.geometry;
+        let geometry = self.cells_geometry(render_ctx.geometry, ident_length);
 
         self.node_render
             .render(painter, render_ctx, self.status);
 
-        let tl = geometry.top_left();
-        geometry.set_x(tl.x() + (ident_length * self.level) as f32);
-        geometry.set_width(geometry.width() - (ident_length * self.level) as f32);
-
-        let gapping = geometry.width() / self.render_cell_size() as f32;
-        let mut offset = geometry.x();
         let status = self.status;
 
-        for cell in self.cells.iter() {
-            if let Some(cell_render) = cell.get_render() {
-                let mut cell_rect = geometry;
+        for (_, cell, cell_rect) in layout_cells(&self.cells, geometry) {
+            cell.render_cell(painter, cell_rect, status);
+        }
+    }
 
-                cell_rect.set_x(offset);
-                if let Some(width) = cell_render.width() {
-                    cell_rect.set_width(width as f32);
-                } else {
-                    cell_rect.set_width(gapping);
-                }
-                if let Some(height) = cell_render.height() {
-                    cell_rect.set_height(height as f32);
-                }
+    /// Toggle the checkbox cell at the `point`, `geometry` was the rect of node without indent.
+    ///
+    /// @return the index of toggled cell and its new value.
+    pub(crate) fn toggle_checkbox(
+        &mut self,
+        geometry: FRect,
+        ident_length: i32,
+        point: FPoint,
+    ) -> Option<(usize, bool)> {
+        let geometry = self.cells_geometry(geometry, ident_length);
 
-                offset += cell_rect.width();
+        let idx = checkbox_at(&self.cells, geometry, point)?;
+        let checked = self.cells[idx].toggle_checkbox()?;
+        Some((idx, checked))
+    }
 
-                cell.render_cell(painter, cell_rect, status);
-            }
-        }
+    /// The area where the cells were laid out, the rect of node excludes the indent.
+    #[inline]
+    fn cells_geometry(&self, mut geometry: FRect, ident_length: i32) -> FRect {
+        let indent = (ident_length * self.level) as f32;
+        geometry.set_x(geometry.x() + indent);
+        geometry.set_width(geometry.width() - indent);
+        geometry
     }
 
     #[inline]
@@ -382,20 +483,20 @@ impl TreeNode {
         self.parent
     }
 
+    /// Get the index of node in it's parent's children.
     #[inline]
-    pub(crate) fn get_children_ids(&self) -> &Vec<ObjectId> {
-        &self.children_id_holder
+    pub(crate) fn idx(&self) -> usize {
+        self.idx
     }
 
     #[inline]
-    pub(crate) fn render_cell_size(&self) -> usize {
-        let mut size = 0;
-        self.cells.iter().for_each(|cell| {
-            if cell.get_render().is_some() {
-                size += 1;
-            }
-        });
-        size
+    pub(crate) fn set_lazy_state(&mut self, lazy: LazyState) {
+        self.lazy = lazy
+    }
+
+    #[inline]
+    pub(crate) fn get_children_ids(&self) -> &Vec<ObjectId> {
+        &self.children_id_holder
     }
 
     pub(crate) fn add_node_inner(&mut self, mut node: Box<TreeNode>) -> Option<&mut TreeNode> {
@@ -446,12 +547,7 @@ impl TreeNode {
                 if let Some(id) = self.need_sort() {
                     idx = self.sort_inner(false, true, Some(id)).unwrap();
                 }
-                let image_len = store.image_len() as i32;
-                let buffer_len = store.buffer_len() as i32;
-                let scroll_to = (idx as i32 - image_len / 2)
-                    .max(0)
-                    .min((buffer_len - store.get_window_lines()).max(0));
-                store.scroll_to(scroll_to, true);
+                store.scroll_to_center(idx);
             }
         }
 
@@ -505,18 +601,95 @@ impl TreeNode {
                 if let Some(id) = self.need_sort() {
                     idx = self.sort_inner(false, true, Some(id)).unwrap();
                 }
-                let image_len = store.image_len() as i32;
-                let buffer_len = store.buffer_len() as i32;
-                let scroll_to = (idx as i32 - image_len / 2)
-                    .max(0)
-                    .min((buffer_len - store.get_window_lines()).max(0));
-                store.scroll_to(scroll_to, true);
+                store.scroll_to_center(idx);
+            }
+        }
+
+        store.get_node_mut(id)
+    }
+
+    pub(crate) fn insert_node_directly_inner(
+        &mut self,
+        index: usize,
+        mut node: Box<TreeNode>,
+    ) -> Option<&mut TreeNode> {
+        if !self.extensible {
+            return None;
+        }
+
+        debug_assert!(node.store != 0);
+
+        let index = index.min(self.children.len());
+        node.parent = NonNull::new(self);
+        node.removed = false;
+        node.set_level_recursive(self.level + 1);
+
+        let store = TreeStore::store_mut(self.store).unwrap();
+        node.add_cache_recursive(store);
+
+        let id = node.id;
+        let mut ids = vec![id];
+        if node.expanded {
+            ids.extend_from_slice(&node.children_id_holder);
+        }
+
+        // The id just before the inserted node in `children_id_holder`:
+        let anchor = if index == 0 {
+            self.id
+        } else {
+            let previous = &self.children[index - 1];
+            if previous.expanded {
+                *previous
+                    .children_id_holder
+                    .last()
+                    .unwrap_or(&previous.id)
+            } else {
+                previous.id
             }
+        };
+
+        self.children.insert(index, node);
+        for (i, c) in self.children.iter_mut().enumerate() {
+            c.idx = i;
+        }
+
+        if !self.is_expanded() && is_ui_thread() {
+            self.shuffle_expand()
+        }
+
+        let holder_idx = if anchor == self.id {
+            0
+        } else {
+            self.children_id_holder
+                .iter()
+                .position(|i| *i == anchor)
+                .map_or(self.children_id_holder.len(), |i| i + 1)
+        };
+        self.children_id_holder
+            .splice(holder_idx..holder_idx, ids.clone());
+        self.notify_grand_child_add(anchor, &ids);
+
+        if is_ui_thread() {
+            store.node_added(self, id, &ids);
         }
 
         store.get_node_mut(id)
     }
 
+    fn set_level_recursive(&mut self, level: i32) {
+        self.level = level;
+        for c in self.children.iter_mut() {
+            c.set_level_recursive(level + 1)
+        }
+    }
+
+    fn add_cache_recursive(&mut self, store: &mut TreeStore) {
+        store.add_node_cache(self);
+        for c in self.children.iter_mut() {
+            c.add_cache_recursive(store)
+        }
+    }
+
     pub(crate) fn notify_grand_child_add(&mut self, anchor: ObjectId, id: &Vec<ObjectId>) {
         if self.parent.is_some() {
             let parent = nonnull_mut!(self.parent);
@@ -547,13 +720,11 @@ impl TreeNode {
                 return;
             }
 
-            let mut idx = 0;
-            for (i, c) in parent.children_id_holder.iter().enumerate() {
-                if *c == ids[0] {
-                    idx = i;
-                    break;
-                }
-            }
+            // The ids were not held if the parent was collapsed, so as the ancestors.
+            let idx = match parent.children_id_holder.iter().position(|c| *c == ids[0]) {
+                Some(idx) => idx,
+                None => return,
+            };
 
             parent.children_id_holder.drain(idx..idx + ids.len());
 
@@ -610,6 +781,29 @@ impl TreeNode {
         parent.notify_grand_chlild_update(anchor, ids)
     }
 
+    /// Remove the node from it's parent and the nodes buffer of store.
+    fn detach(&mut self) -> Option<Box<TreeNode>> {
+        let mut parent = self.get_parent();
+        if parent.is_none() {
+            return None;
+        }
+
+        let child_id = self.id();
+        let child_expanded = self.is_expanded();
+        let mut deleted = vec![child_id];
+        deleted.extend_from_slice(self.get_children_ids());
+
+        let hold = self.remove_node_inner();
+        TreeStore::store_mut(self.store).unwrap().node_deleted(
+            nonnull_mut!(parent),
+            child_id,
+            child_expanded,
+            deleted,
+        );
+
+        hold
+    }
+
     pub(crate) fn remove_node_inner(&mut self) -> Option<Box<TreeNode>> {
         if self.parent.is_some() {
             let parent = nonnull_mut!(self.parent);
@@ -1,5 +1,11 @@
-use super::{tree_node::TreeNode, tree_view_object::TreeViewObject};
-use crate::{prelude::*, views::node::Status};
+use super::{
+    tree_node::{LazyState, TreeNode},
+    tree_view_object::TreeViewObject,
+};
+use crate::{
+    prelude::*,
+    views::node::{height_index::HeightIndex, node_drag::DropPosition, Status},
+};
 use log::warn;
 use nohash_hasher::IntMap;
 use once_cell::sync::Lazy;
@@ -12,9 +18,14 @@ use tlib::{
     namespace::MouseButton,
     nonnull_mut, nonnull_ref,
     object::{IdGenerator, ObjectId, ObjectOperation, ObjectSubclass},
+    r#async::tokio_runtime,
     signals,
+    tokio::task::JoinHandle,
 };
 
+pub(crate) type LazyChildren = Vec<Box<dyn TreeViewObject + Send>>;
+pub(crate) type FnLazyLoader = Box<dyn Fn(&TreeNode) -> JoinHandle<LazyChildren>>;
+
 #[extends(Object, ignore_default = true)]
 pub struct TreeStore {
     view: WidgetHnd,
@@ -24,14 +35,20 @@ pub struct TreeStore {
     nodes_buffer: Vec<Option<NonNull<TreeNode>>>,
     nodes_cache: IntMap<ObjectId, Option<NonNull<TreeNode>>>,
 
-    window_lines: i32,
-    current_line: i32,
-    y_offset: i32,
+    /// The heights of nodes in the nodes buffer.
+    heights: HeightIndex,
+    line_height: i32,
+    line_spacing: i32,
+    window_height: i32,
+    scroll_offset: i32,
 
     entered_node: Option<NonNull<TreeNode>>,
     hovered_node: Option<NonNull<TreeNode>>,
     selected_node: Option<NonNull<TreeNode>>,
 
+    lazy_loader: Option<FnLazyLoader>,
+    lazy_loads: Vec<(ObjectId, JoinHandle<LazyChildren>)>,
+
     pub(crate) id_increment: IdGenerator,
     pub(crate) sort_proxy: Option<Compare<TreeNode>>,
 }
@@ -156,12 +173,76 @@ impl TreeStore {
         self.sort_proxy = Some(compare);
     }
 
+    /// Move the nodes with specified `ids` to the position relative to the `target` node,
+    /// the relative order of moved nodes will be kept. If both the node and it's descendant
+    /// were specified, only the node will be moved as a whole.
+    ///
+    /// @return `false` if the move was invalid:
+    /// - the target was not exist, or was one of the moved nodes or their descendants.
+    /// - the position was [`DropPosition::Into`] but the target was not extensible.
+    /// - the position was not [`DropPosition::Into`] but the target was root.
+    pub fn move_nodes(
+        &mut self,
+        ids: &[ObjectId],
+        target: ObjectId,
+        position: DropPosition,
+    ) -> bool {
+        let target_node = match self.get_node(target) {
+            Some(node) => node,
+            None => return false,
+        };
+        match position {
+            DropPosition::Into if !target_node.is_extensible() => return false,
+            DropPosition::Before | DropPosition::After if target_node.is_root() => return false,
+            _ => {}
+        }
+        if ids.contains(&target) || self.has_ancestor_in(target, ids) {
+            return false;
+        }
+
+        let ids: Vec<ObjectId> = ids
+            .iter()
+            .filter(|id| !self.has_ancestor_in(**id, ids))
+            .copied()
+            .collect();
+
+        let mut taken = vec![];
+        for id in ids.iter() {
+            if let Some(node) = self.get_node_mut(*id) {
+                if let Some(node) = node.take() {
+                    taken.push(node);
+                }
+            }
+        }
+        if taken.is_empty() {
+            return false;
+        }
+
+        // Locate the target after the nodes were taken, the index of target may have changed.
+        let target_node = self.get_node_mut(target).unwrap();
+        let (mut parent, mut index) = match position {
+            DropPosition::Into => (NonNull::new(target_node as *mut TreeNode), usize::MAX),
+            DropPosition::Before => (target_node.get_parent(), target_node.idx()),
+            DropPosition::After => (target_node.get_parent(), target_node.idx() + 1),
+        };
+
+        let parent = nonnull_mut!(parent);
+        for node in taken {
+            parent.insert_node_directly_inner(index, node);
+            index = index.saturating_add(1);
+        }
+
+        emit!(self, notify_update());
+        true
+    }
+
     #[inline]
     pub fn clear(&mut self) {
         self.root_mut().clear_directly();
 
         self.nodes_buffer.clear();
         self.nodes_cache.clear();
+        self.rebuild_heights();
         self.entered_node = None;
         self.hovered_node = None;
         self.selected_node = None;
@@ -182,12 +263,16 @@ impl TreeStore {
             root,
             nodes_buffer: vec![],
             nodes_cache: nodes_map,
-            window_lines: 0,
-            current_line: 0,
-            y_offset: 0,
+            heights: HeightIndex::default(),
+            line_height: 0,
+            line_spacing: 0,
+            window_height: 0,
+            scroll_offset: 0,
             entered_node: None,
             hovered_node: None,
             selected_node: None,
+            lazy_loader: None,
+            lazy_loads: vec![],
             id_increment: IdGenerator::new(1),
             sort_proxy: None,
         };
@@ -239,32 +324,188 @@ impl TreeStore {
         }
     }
 
+    #[inline]
+    pub(crate) fn register_lazy_loader(&mut self, loader: FnLazyLoader) {
+        self.lazy_loader = Some(loader)
+    }
+
+    /// Start loading the children of lazy node by the registered loader.
+    ///
+    /// @return `false` if there was no loader registered.
+    pub(crate) fn load_lazy(&mut self, node: &mut TreeNode) -> bool {
+        let loader = match self.lazy_loader {
+            Some(ref loader) => loader,
+            None => return false,
+        };
+
+        self.lazy_loads.push((node.id(), loader(node)));
+        node.set_lazy_state(LazyState::Loading);
+
+        emit!(self, notify_update());
+        true
+    }
+
+    /// Whether there were children of lazy nodes being loaded.
+    #[inline]
+    pub(crate) fn is_lazy_loading(&self) -> bool {
+        !self.lazy_loads.is_empty()
+    }
+
+    /// Add the loaded children to their lazy nodes if the loading has finished,
+    /// the node will be reset to unloaded if the loader failed.
+    pub(crate) fn poll_lazy_loads(&mut self) {
+        let (finished, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.lazy_loads)
+            .into_iter()
+            .partition(|(_, handle)| handle.is_finished());
+        self.lazy_loads = pending;
+
+        for (id, handle) in finished {
+            let children = match tokio_runtime().block_on(handle) {
+                Ok(children) => Some(children),
+                Err(e) => {
+                    warn!(
+                        "Lazy loading children of tree node failed, id = {}: {:?}",
+                        id, e
+                    );
+                    None
+                }
+            };
+
+            // The node may have been removed during loading.
+            let node = match self.get_node_mut(id) {
+                Some(node) => node,
+                None => continue,
+            };
+
+            match children {
+                Some(children) => {
+                    node.set_lazy_state(LazyState::Loaded);
+
+                    let store = node.store;
+                    for obj in children.iter() {
+                        let child = TreeNode::create(store, 0, obj.as_ref());
+                        node.insert_node_directly(usize::MAX, child);
+                    }
+                    node.sort(false);
+                }
+                None => node.set_lazy_state(LazyState::Unloaded),
+            }
+        }
+    }
+
+    /// Whether one of the ancestors of node was in `ids`.
+    pub(crate) fn has_ancestor_in(&self, id: ObjectId, ids: &[ObjectId]) -> bool {
+        let mut parent = match self.get_node(id) {
+            Some(node) => node.get_parent(),
+            None => return false,
+        };
+
+        while parent.is_some() {
+            let node = nonnull_ref!(parent);
+            if ids.contains(&node.id()) {
+                return true;
+            }
+            parent = node.get_parent();
+        }
+        false
+    }
+
+    /// Get the ids of selected nodes in the order of nodes buffer,
+    /// the nodes whose ancestor was also selected were excluded.
+    pub(crate) fn selected_ids(&self) -> Vec<ObjectId> {
+        let selected: Vec<ObjectId> = self
+            .nodes_buffer
+            .iter()
+            .map(|node| nonnull_ref!(node))
+            .filter(|node| node.is_selected())
+            .map(|node| node.id())
+            .collect();
+
+        selected
+            .iter()
+            .filter(|id| !self.has_ancestor_in(**id, &selected))
+            .copied()
+            .collect()
+    }
+
+    /// Remove the selected status of all the nodes except the `retain` one.
+    pub(crate) fn retain_selection(&mut self, retain: Option<ObjectId>) {
+        for (id, node) in self.nodes_cache.iter_mut() {
+            if Some(*id) != retain {
+                nonnull_mut!(node).remove_status(Status::Selected);
+            }
+        }
+
+        self.selected_node = retain.and_then(|id| *self.nodes_cache.get(&id)?);
+        emit!(self, notify_update());
+    }
+
+    /// The y offset of the first visible node in pixels, the part was scrolled out of view.
     #[inline]
     pub(crate) fn y_offset(&self) -> i32 {
-        self.y_offset
+        let (start, _) = self.image_range();
+        self.scroll_offset - self.heights.offset_of(start)
     }
 
     #[inline]
     pub(crate) fn get_image(&self) -> (&[Option<NonNull<TreeNode>>], i32) {
-        let start = if self.y_offset == 0 {
-            self.current_line
-        } else {
-            (self.current_line - 1).max(0)
-        } as usize;
-
-        let end = (start + self.window_lines as usize).min(self.nodes_buffer.len());
+        let (start, end) = self.image_range();
 
-        (&self.nodes_buffer[start..end], self.y_offset)
+        (&self.nodes_buffer[start..end], self.y_offset())
     }
 
     #[inline]
     pub(crate) fn get_image_mut(&mut self) -> &mut [Option<NonNull<TreeNode>>] {
-        let start = self.current_line as usize;
-        let end = (start + self.window_lines as usize).min(self.nodes_buffer.len());
+        let (start, end) = self.image_range();
 
         &mut self.nodes_buffer[start..end]
     }
 
+    /// Get the index of node in image at the `y` coordinate relative to the top of view.
+    ///
+    /// @return `usize::MAX` if there was no node.
+    pub(crate) fn index_image(&self, y: i32) -> usize {
+        if y < 0 || y >= self.window_height {
+            return usize::MAX;
+        }
+        let (start, _) = self.image_range();
+
+        self.heights
+            .index_at(self.scroll_offset + y)
+            .map_or(usize::MAX, |idx| idx - start)
+    }
+
+    /// Get the geometry of node in image with the index.
+    ///
+    /// @return (y coordinate of node's top relative to the top of view, height of node)
+    pub(crate) fn image_node_geometry(&self, idx: usize) -> (i32, i32) {
+        let (start, _) = self.image_range();
+        let idx = start + idx;
+
+        (
+            self.heights.offset_of(idx) - self.scroll_offset,
+            self.heights.height_of(idx),
+        )
+    }
+
+    /// Get the geometry of node in nodes buffer with the index, see [`image_node_geometry()`](TreeStore::image_node_geometry).
+    #[inline]
+    pub(crate) fn buffer_node_geometry(&self, idx: usize) -> (i32, i32) {
+        (
+            self.heights.offset_of(idx) - self.scroll_offset,
+            self.heights.height_of(idx),
+        )
+    }
+
+    #[inline]
+    fn image_range(&self) -> (usize, usize) {
+        let len = self.nodes_buffer.len();
+        let (start, end) = self
+            .heights
+            .visible_range(self.scroll_offset, self.window_height);
+        (start.min(len), end.min(len))
+    }
+
     #[inline]
     pub(crate) fn get_image_node(&mut self, idx: usize) -> Option<&mut TreeNode> {
         let image = self.get_image_mut();
@@ -287,8 +528,7 @@ impl TreeStore {
 
     #[inline]
     pub(crate) fn image_len(&self) -> usize {
-        let start = self.current_line as usize;
-        let end = (start + self.window_lines as usize).min(self.nodes_buffer.len());
+        let (start, end) = self.image_range();
         end - start
     }
 
@@ -331,10 +571,13 @@ impl TreeStore {
         }
 
         let mut idx = 0;
-        for c in self.nodes_buffer.iter() {
-            idx += 1;
-            if nonnull_ref!(c).id() == anchor {
-                break;
+        // Root node was not in the nodes buffer.
+        if !(node.is_root() && anchor == node.id()) {
+            for c in self.nodes_buffer.iter() {
+                idx += 1;
+                if nonnull_ref!(c).id() == anchor {
+                    break;
+                }
             }
         }
 
@@ -344,6 +587,7 @@ impl TreeStore {
             .collect();
 
         self.nodes_buffer.splice(idx..idx, insert);
+        self.rebuild_heights();
 
         emit!(self, buffer_len_changed(self.nodes_buffer.len()));
         emit!(self, notify_update_rect(idx));
@@ -392,6 +636,7 @@ impl TreeStore {
         } else {
             self.nodes_buffer.drain(idx..idx + 1);
         }
+        self.rebuild_heights();
 
         emit!(self, buffer_len_changed(self.nodes_buffer.len()));
         emit!(self, notify_update_rect(idx));
@@ -439,6 +684,7 @@ impl TreeStore {
 
             self.nodes_buffer.drain(idx..idx + children.len());
         }
+        self.rebuild_heights();
 
         emit!(self, buffer_len_changed(self.nodes_buffer.len()));
         emit!(self, notify_update_rect(start_idx));
@@ -471,6 +717,7 @@ impl TreeStore {
 
         let update_len = update.len();
         self.nodes_buffer.splice(idx..idx + update_len, update);
+        self.rebuild_heights();
         emit!(self, notify_update_rect(idx));
 
         focus.map(|focus| {
@@ -484,13 +731,55 @@ impl TreeStore {
     }
 
     #[inline]
-    pub(crate) fn set_window_lines(&mut self, window_lines: i32) {
-        self.window_lines = window_lines;
+    pub(crate) fn set_window_height(&mut self, window_height: i32) {
+        self.window_height = window_height;
     }
 
+    /// Set the default height of nodes and the spacing between nodes,
+    /// the heights of nodes will be recalculated.
     #[inline]
-    pub(crate) fn get_window_lines(&self) -> i32 {
-        self.window_lines
+    pub(crate) fn set_line_metrics(&mut self, line_height: i32, line_spacing: i32) {
+        if self.line_height == line_height && self.line_spacing == line_spacing {
+            return;
+        }
+        self.line_height = line_height;
+        self.line_spacing = line_spacing;
+
+        self.node_height_changed();
+    }
+
+    /// The maximum scroll value in pixels.
+    #[inline]
+    pub(crate) fn max_scroll(&self) -> i32 {
+        self.heights.max_scroll(self.window_height)
+    }
+
+    /// Recalculate the heights of nodes after the height of node has changed.
+    pub(crate) fn node_height_changed(&mut self) {
+        self.rebuild_heights();
+
+        emit!(self, buffer_len_changed(self.nodes_buffer.len()));
+        emit!(self, notify_update());
+    }
+
+    /// Scroll the view to make the node with the index of nodes buffer located at the center.
+    pub(crate) fn scroll_to_center(&mut self, idx: usize) {
+        let center = self.heights.offset_of(idx) + self.heights.height_of(idx) / 2;
+        let scroll_to = (center - self.window_height / 2)
+            .max(0)
+            .min(self.max_scroll());
+
+        self.scroll_to(scroll_to, true);
+    }
+
+    fn rebuild_heights(&mut self) {
+        let (line_height, line_spacing) = (self.line_height, self.line_spacing);
+
+        self.heights.rebuild(
+            self.nodes_buffer
+                .iter()
+                .map(|node| nonnull_ref!(node).height().unwrap_or(line_height) + line_spacing),
+        );
     }
 
     #[inline]
@@ -525,6 +814,33 @@ impl TreeStore {
         emit!(self, notify_update());
     }
 
+    /// Select the node without expanding it, the selected nodes will be retained if
+    /// `multi` was `true`, or the pressed node was already selected.
+    ///
+    /// @return the id of the pressed node.
+    pub(crate) fn select_node(&mut self, idx: usize, multi: bool) -> Option<ObjectId> {
+        if idx >= self.image_len() {
+            if !multi {
+                self.retain_selection(None);
+            }
+            return None;
+        }
+
+        let mut node_ptr = self.get_image().0[idx];
+        let node = nonnull_mut!(node_ptr);
+        let id = node.id();
+
+        if !multi && !node.is_selected() {
+            self.retain_selection(None);
+        }
+
+        node.add_status(Status::Selected);
+        self.selected_node = node_ptr;
+
+        emit!(self, notify_update());
+        Some(id)
+    }
+
     pub(crate) fn click_node(&mut self, idx: usize, mouse_button: MouseButton) {
         if idx >= self.image_len() {
             let mut old_select = self.selected_node.take();
@@ -555,30 +871,22 @@ impl TreeStore {
         emit!(self, notify_update());
     }
 
+    /// @param `value`: the scroll value in pixels. <br>
     /// @param `internal`
     /// - true: The view scrolling triggered internally in TreeView
     ///   requires notifying the scroll bar to change the value.
     ///
     /// @return `true` if scroll value has updated, should update the image.
     #[inline]
-    pub(crate) fn scroll_to(&mut self, mut value: i32, internal: bool) -> bool {
-        if internal {
-            value *= 10;
-        }
-
-        let move_to = value / 10;
-        if move_to < 0 || move_to > self.nodes_buffer.len() as i32 {
+    pub(crate) fn scroll_to(&mut self, value: i32, internal: bool) -> bool {
+        if value < 0 || value > self.heights.total() {
             return false;
         }
-
-        self.y_offset = value % 10;
-        if self.current_line == move_to {
+        if self.scroll_offset == value {
             return true;
         }
 
-        // if value was 0, scroll to the begining, first node index was 0
-        // scroll to 1, first node index was 1
-        self.current_line = move_to;
+        self.scroll_offset = value;
 
         if internal {
             emit!(self, internal_scroll_value_changed(value));
@@ -607,3 +915,269 @@ impl ObjectSubclass for TreeStore {
     const NAME: &'static str = "TreeViewStore";
 }
 impl ObjectImpl for TreeStore {}
+
+#[cfg(test)]
+mod tests {
+    use super::TreeStore;
+    use crate::{
+        application::IS_UI_THREAD,
+        views::{
+            cell::Cell,
+            node::{node_drag::DropPosition, node_render::NodeRender},
+            tree_view::{tree_node::LazyState, tree_view_object::TreeViewObject},
+        },
+    };
+    use std::{
+        sync::{Mutex, MutexGuard},
+        thread,
+        time::Duration,
+    };
+    use tlib::{nonnull_ref, object::ObjectId, r#async::tokio_runtime};
+
+    struct TestObj(bool);
+    impl TreeViewObject for TestObj {
+        fn cells(&self) -> Vec<Cell> {
+            vec![]
+        }
+
+        fn extensible(&self) -> bool {
+            self.0
+        }
+
+        fn node_render(&self) -> NodeRender {
+            NodeRender::default()
+        }
+    }
+
+    struct LazyObj;
+    impl TreeViewObject for LazyObj {
+        fn cells(&self) -> Vec<Cell> {
+            vec![]
+        }
+
+        fn extensible(&self) -> bool {
+            true
+        }
+
+        fn node_render(&self) -> NodeRender {
+            NodeRender::default()
+        }
+
+        fn lazy(&self) -> bool {
+            true
+        }
+    }
+
+    /// The store map was not synchronized, run the tests one by one.
+    fn lock() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn add(store: &mut TreeStore, parent: ObjectId, extensible: bool) -> ObjectId {
+        store.add_node(parent, &TestObj(extensible)).unwrap().id()
+    }
+
+    fn children(store: &TreeStore, id: ObjectId) -> Vec<ObjectId> {
+        store
+            .get_node(id)
+            .unwrap()
+            .children()
+            .iter()
+            .map(|c| c.id())
+            .collect()
+    }
+
+    fn buffer(store: &TreeStore) -> Vec<ObjectId> {
+        store
+            .nodes_buffer
+            .iter()
+            .map(|node| nonnull_ref!(node).id())
+            .collect()
+    }
+
+    fn check_consistent(store: &TreeStore) {
+        let root = store.root();
+        assert_eq!(root.get_children_ids(), &buffer(store));
+        for (i, c) in root.children().iter().enumerate() {
+            assert_eq!(c.idx(), i);
+        }
+        for &id in root.get_children_ids() {
+            let node = store.get_node(id).unwrap();
+            for (i, c) in node.children().iter().enumerate() {
+                assert_eq!(c.idx(), i);
+                assert_eq!(c.level(), node.level() + 1);
+            }
+        }
+    }
+
+    fn new_store() -> Box<TreeStore> {
+        // The nodes buffer only follows the changes on the ui thread.
+        IS_UI_THREAD.with(|is_ui| *is_ui.borrow_mut() = true);
+
+        let mut store = Box::new(TreeStore::new());
+        store.prepare_store();
+        store
+    }
+
+    fn wait_lazy_loads(store: &mut TreeStore) {
+        while store.is_lazy_loading() {
+            thread::sleep(Duration::from_millis(1));
+            store.poll_lazy_loads();
+        }
+    }
+
+    #[test]
+    fn test_move_nodes() {
+        let _guard = lock();
+
+        // Index shifts when moving inside the same parent.
+        let mut store = new_store();
+        let a = add(&mut store, 0, true);
+        let b = add(&mut store, 0, false);
+        let c = add(&mut store, 0, false);
+        let d = add(&mut store, 0, false);
+
+        assert!(store.move_nodes(&[a], c, DropPosition::After));
+        assert_eq!(children(&store, 0), vec![b, c, a, d]);
+        check_consistent(&store);
+
+        assert!(store.move_nodes(&[d], b, DropPosition::Before));
+        assert_eq!(children(&store, 0), vec![d, b, c, a]);
+        check_consistent(&store);
+
+        assert!(store.move_nodes(&[d], a, DropPosition::After));
+        assert_eq!(children(&store, 0), vec![b, c, a, d]);
+        check_consistent(&store);
+
+        // Invalid targets.
+        assert!(!store.move_nodes(&[b], 0, DropPosition::Before));
+        assert!(!store.move_nodes(&[a], b, DropPosition::Into));
+        assert!(!store.move_nodes(&[a], 9999, DropPosition::Into));
+        assert!(!store.move_nodes(&[b, c], c, DropPosition::After));
+        assert_eq!(children(&store, 0), vec![b, c, a, d]);
+        check_consistent(&store);
+
+        // Reparenting into own descendant.
+        let mut store = new_store();
+        let a = add(&mut store, 0, true);
+        let a1 = add(&mut store, a, true);
+        let a11 = add(&mut store, a1, true);
+        let b = add(&mut store, 0, true);
+
+        assert!(!store.move_nodes(&[a], a11, DropPosition::Into));
+        assert!(!store.move_nodes(&[a], a1, DropPosition::Before));
+        assert!(!store.move_nodes(&[a, b], a, DropPosition::Into));
+        assert_eq!(buffer(&store), vec![a, a1, a11, b]);
+        check_consistent(&store);
+
+        // Moving the descendant out of it's ancestor is allowed.
+        assert!(store.move_nodes(&[a11], a, DropPosition::Before));
+        assert_eq!(children(&store, 0), vec![a11, a, b]);
+        assert_eq!(store.get_node(a11).unwrap().level(), 0);
+        assert!(children(&store, a1).is_empty());
+        assert_eq!(buffer(&store), vec![a11, a, a1, b]);
+        check_consistent(&store);
+
+        // Multi-select moves, the subtree was moved as a whole.
+        let mut store = new_store();
+        let a = add(&mut store, 0, true);
+        let a1 = add(&mut store, a, false);
+        let a2 = add(&mut store, a, false);
+        let b = add(&mut store, 0, true);
+        let c = add(&mut store, 0, false);
+
+        // `a1` was ignored since it's ancestor `a` was also moved.
+        assert!(store.move_nodes(&[c, a1, a], b, DropPosition::After));
+        assert_eq!(children(&store, 0), vec![b, c, a]);
+        assert_eq!(children(&store, a), vec![a1, a2]);
+        assert_eq!(buffer(&store), vec![b, c, a, a1, a2]);
+        check_consistent(&store);
+
+        assert!(store.move_nodes(&[a2, c], b, DropPosition::Into));
+        assert_eq!(children(&store, 0), vec![b, a]);
+        assert_eq!(children(&store, b), vec![a2, c]);
+        assert_eq!(children(&store, a), vec![a1]);
+        assert_eq!(store.get_node(c).unwrap().level(), 1);
+        assert_eq!(buffer(&store), vec![b, a2, c, a, a1]);
+        check_consistent(&store);
+
+        assert!(store.move_nodes(&[a, a2], c, DropPosition::Before));
+        assert_eq!(children(&store, 0), vec![b]);
+        assert_eq!(children(&store, b), vec![a, a2, c]);
+        assert_eq!(store.get_node(a1).unwrap().level(), 2);
+        assert_eq!(buffer(&store), vec![b, a, a1, a2, c]);
+        check_consistent(&store);
+    }
+
+    #[test]
+    fn test_lazy_load() {
+        let _guard = lock();
+
+        let mut store = new_store();
+        let lazy = store.add_node(0, &LazyObj).unwrap().id();
+        let node = store.get_node(lazy).unwrap();
+        assert_eq!(node.lazy_state(), LazyState::Unloaded);
+        assert!(!node.is_expanded());
+
+        // Nothing will be loaded without the loader.
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+        assert!(store.get_node(lazy).unwrap().is_expanded());
+        assert_eq!(
+            store.get_node(lazy).unwrap().lazy_state(),
+            LazyState::Unloaded
+        );
+        assert!(!store.is_lazy_loading());
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+
+        store.register_lazy_loader(Box::new(|_| {
+            tokio_runtime().spawn(async {
+                vec![
+                    Box::new(TestObj(false)) as Box<dyn TreeViewObject + Send>,
+                    Box::new(TestObj(false)),
+                ]
+            })
+        }));
+
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+        assert!(store.get_node(lazy).unwrap().is_loading());
+        assert!(store.is_lazy_loading());
+
+        // Reset was ignored during loading.
+        store.get_node_mut(lazy).unwrap().reset_lazy();
+        assert!(store.get_node(lazy).unwrap().is_loading());
+
+        wait_lazy_loads(&mut store);
+        let node = store.get_node(lazy).unwrap();
+        assert_eq!(node.lazy_state(), LazyState::Loaded);
+        assert_eq!(node.children().len(), 2);
+        assert!(node.children().iter().all(|c| c.level() == 1));
+        assert_eq!(buffer(&store).len(), 3);
+        check_consistent(&store);
+
+        // Collapsing the loaded node will not load it again.
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+        assert!(!store.is_lazy_loading());
+        assert_eq!(store.get_node(lazy).unwrap().children().len(), 2);
+
+        store.get_node_mut(lazy).unwrap().reset_lazy();
+        let node = store.get_node(lazy).unwrap();
+        assert_eq!(node.lazy_state(), LazyState::Unloaded);
+        assert!(!node.is_expanded());
+        assert!(node.children().is_empty());
+        assert!(node.get_children_ids().is_empty());
+        assert_eq!(buffer(&store), vec![lazy]);
+        check_consistent(&store);
+
+        // The node was removed during loading.
+        store.get_node_mut(lazy).unwrap().shuffle_expand();
+        assert!(store.get_node(lazy).unwrap().is_loading());
+        store.remove_node(lazy);
+
+        wait_lazy_loads(&mut store);
+        assert!(store.get_node(lazy).is_none());
+        assert!(buffer(&store).is_empty());
+        check_consistent(&store);
+    }
+}
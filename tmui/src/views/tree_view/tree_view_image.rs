@@ -1,17 +1,30 @@
-use super::{tree_node::TreeNode, tree_store::TreeStore};
+use super::{
+    tree_node::TreeNode,
+    tree_store::{FnLazyLoader, TreeStore},
+    TreeView, TreeViewSignals,
+};
 use crate::{
+    animation::frame_animator::FrameAnimator,
     font::FontCalculation,
+    loading::{LOADING_COLOR, LOADING_SIZE},
     prelude::*,
+    primitive::frame::Frame,
     scroll_bar::ScrollBar,
     tlib::object::{ObjectImpl, ObjectSubclass},
     views::{
-        node::{MouseEffect, RenderCtx},
+        node::{
+            node_drag::{DropPosition, NodeDrag, NodeDrop},
+            MouseEffect, RenderCtx,
+        },
         tree_view::tree_store::TreeStoreSignals,
     },
     widget::{RegionClear, WidgetImpl},
 };
 use std::ptr::NonNull;
-use tlib::{connect, disconnect, events::MouseEvent, nonnull_mut, nonnull_ref, run_after};
+use tlib::{
+    connect, disconnect, events::MouseEvent, frame_animator, namespace::MouseButton, nonnull_mut,
+    nonnull_ref, object::ObjectId, run_after, skia_safe::PaintStyle,
+};
 
 type FnNodePressed = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
 type FnNodeReleased = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
@@ -19,10 +32,12 @@ type FnNodeEnter = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
 type FnNodeLeave = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
 type FnFreeAreaPressed = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
 type FnFreeAreaReleased = Box<dyn Fn(&mut TreeNode, &MouseEvent)>;
+type FnDropVeto = Box<dyn Fn(&[ObjectId], &TreeNode, DropPosition) -> bool>;
 
 #[extends(Widget)]
 #[run_after]
 #[loadable]
+#[frame_animator]
 pub(crate) struct TreeViewImage {
     store: Box<TreeStore>,
     scroll_bar: Option<NonNull<ScrollBar>>,
@@ -34,6 +49,7 @@ pub(crate) struct TreeViewImage {
     line_spacing: i32,
     #[derivative(Default(value = "MouseEffect::all()"))]
     mouse_effect: MouseEffect,
+    drag: NodeDrag,
 
     on_node_pressed: Option<FnNodePressed>,
     on_node_released: Option<FnNodeReleased>,
@@ -41,6 +57,9 @@ pub(crate) struct TreeViewImage {
     on_node_leave: Option<FnNodeLeave>,
     on_free_area_pressed: Option<FnFreeAreaPressed>,
     on_free_area_released: Option<FnFreeAreaReleased>,
+    on_drop_veto: Option<FnDropVeto>,
+
+    lazy_spin: f32,
 }
 
 impl ObjectSubclass for TreeViewImage {
@@ -109,23 +128,32 @@ impl WidgetImpl for TreeViewImage {
         }
 
         let (image, y_offset) = self.store.get_image();
-        let mut offset = rect.y()
-            - (y_offset as f32 / 10. * (self.line_height + self.line_spacing) as f32) as i32;
+        let mut offset = rect.y() - y_offset;
 
         for node in image.iter() {
-            let geometry = Rect::new(rect.x(), offset, rect.width(), self.line_height);
-            offset += self.line_height + self.line_spacing;
+            let height = nonnull_ref!(node).height().unwrap_or(self.line_height);
+            let geometry = Rect::new(rect.x(), offset, rect.width(), height);
+            offset += height + self.line_spacing;
 
             let render_ctx =
                 RenderCtx::new(geometry.into(), self.opaque_background(), self.mouse_effect);
-            nonnull_ref!(node).render_node(painter, render_ctx, self.indent_length);
+            let node = nonnull_ref!(node);
+            node.render_node(painter, render_ctx, self.indent_length);
+
+            if node.is_loading() {
+                self.render_lazy_spinner(painter, geometry);
+            }
         }
+
+        self.drag.render_indicator(painter);
     }
 
     fn font_changed(&mut self) {
         let (_, h) = self.font().calc_font_dimension();
 
         self.line_height = h as i32;
+        self.store
+            .set_line_metrics(self.line_height, self.line_spacing);
     }
 
     fn on_mouse_move(&mut self, event: &MouseEvent) {
@@ -135,7 +163,17 @@ impl WidgetImpl for TreeViewImage {
             return;
         }
 
-        let (_, y) = event.position();
+        let (x, y) = event.position();
+        if self.drag.is_pressed() {
+            if self.drag.try_start(Point::new(x, y)) {
+                self.start_drag();
+            }
+            if self.drag.is_dragging() {
+                self.handle_drag_move(y);
+                return;
+            }
+        }
+
         let idx = self.index_node(y);
 
         self.store.hover_node(idx);
@@ -170,10 +208,23 @@ impl WidgetImpl for TreeViewImage {
     }
 
     fn on_mouse_pressed(&mut self, event: &MouseEvent) {
-        let (_, y) = event.position();
+        let (x, y) = event.position();
         let idx = self.index_node(y);
-
-        self.store.click_node(idx, event.mouse_button());
+        let toggled = if event.mouse_button() == MouseButton::LeftButton {
+            self.toggle_checkbox(idx, FPoint::new(x as f32, y as f32))
+        } else {
+            false
+        };
+
+        if self.drag.enabled && event.mouse_button() == MouseButton::LeftButton && !toggled {
+            // Expanding of the node was deferred to mouse released,
+            // so that pressing to drag the node would not expand/collapse it.
+            if let Some(id) = self.store.select_node(idx, event.modifier().ctrl()) {
+                self.drag.press(Point::new(x, y), id);
+            }
+        } else {
+            self.store.click_node(idx, event.mouse_button());
+        }
 
         if let Some(node) = self.store.get_image_node(idx) {
             if let Some(ref on_node_pressed) = self.on_node_pressed {
@@ -185,6 +236,28 @@ impl WidgetImpl for TreeViewImage {
     }
 
     fn on_mouse_released(&mut self, event: &MouseEvent) {
+        if self.drag.is_pressed() {
+            let dragging = self.drag.is_dragging();
+            let pressed_node = self.drag.pressed_node();
+            let node_drop = self.drag.finish();
+
+            if dragging {
+                self.set_cursor_shape(SystemCursorShape::ArrowCursor);
+                if let Some(node_drop) = node_drop {
+                    self.drop_nodes(node_drop);
+                }
+                self.update();
+                return;
+            }
+
+            if !event.modifier().ctrl() {
+                self.store.retain_selection(pressed_node);
+                if let Some(node) = pressed_node.and_then(|id| self.store.get_node_mut(id)) {
+                    node.shuffle_expand();
+                }
+            }
+        }
+
         let (_, y) = event.position();
         let idx = self.index_node(y);
 
@@ -202,6 +275,23 @@ impl WidgetImpl for TreeViewImage {
     }
 }
 
+impl FrameAnimator for TreeViewImage {
+    fn on_frame(&mut self, _: Frame) {
+        if !self.store.is_lazy_loading() {
+            return;
+        }
+
+        self.lazy_spin += 0.02;
+        if self.lazy_spin > 1. {
+            self.lazy_spin = 0.
+        }
+
+        self.store.poll_lazy_loads();
+
+        self.update();
+    }
+}
+
 impl TreeViewImage {
     #[inline]
     pub fn new(scroll_bar: &mut ScrollBar) -> Tr<Self> {
@@ -232,7 +322,9 @@ impl TreeViewImage {
 
     #[inline]
     pub fn set_line_spacing(&mut self, line_spacing: i32) {
-        self.line_spacing = line_spacing
+        self.line_spacing = line_spacing;
+        self.store
+            .set_line_metrics(self.line_height, self.line_spacing);
     }
 
     #[inline]
@@ -271,30 +363,28 @@ impl TreeViewImage {
 
     #[inline]
     pub(crate) fn calculate_window_lines(&mut self) {
-        let size = self.size();
-        let window_lines = size.height() / (self.line_height + self.line_spacing);
-        self.store.set_window_lines(window_lines);
+        let window_height = self.contents_rect(Some(Coordinate::Widget)).height();
+        self.store.set_window_height(window_height);
 
         let scroll_bar = nonnull_mut!(self.scroll_bar);
-        scroll_bar.set_single_step(4);
-        scroll_bar.set_page_step(window_lines * 10);
-        scroll_bar.set_visible_area(window_lines * 10);
+        scroll_bar.set_single_step(((self.line_height + self.line_spacing) * 2 / 5).max(1));
+        scroll_bar.set_page_step(window_height);
+        scroll_bar.set_visible_area(window_height);
     }
 
+    /// Get the index of node in image at the `y` coordinate of widget.
     #[inline]
     pub(crate) fn index_node(&self, y: i32) -> usize {
-        let y_offset = (self.store.y_offset() as f32 / 10.
-            * (self.line_height + self.line_spacing) as f32) as i32;
-        ((y + y_offset) / (self.line_height + self.line_spacing)) as usize
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        self.store.index_image(y - rect.y())
     }
 
     #[inline]
     pub(crate) fn notify_update_rect(&mut self, start_idx: usize) {
-        let y_offset = -(self.store.y_offset() as f32 / 10.
-            * (self.line_height + self.line_spacing) as f32) as i32;
         let size = self.size();
         let x = 0;
-        let y = start_idx as i32 * (self.line_height + self.line_spacing) + y_offset;
+        let y = self.contents_rect(Some(Coordinate::Widget)).y()
+            + self.store.buffer_node_geometry(start_idx).0;
 
         if y >= size.height() {
             return;
@@ -311,10 +401,10 @@ impl TreeViewImage {
     }
 
     #[inline]
-    pub(crate) fn when_nodes_buffer_changed(&mut self, buffer_len: usize) {
+    pub(crate) fn when_nodes_buffer_changed(&mut self, _: usize) {
         let scroll_bar = nonnull_mut!(self.scroll_bar);
 
-        scroll_bar.set_range(0, (buffer_len as i32 - self.store.get_window_lines()) * 10);
+        scroll_bar.set_range(0, self.store.max_scroll());
     }
 
     #[inline]
@@ -339,6 +429,150 @@ impl TreeViewImage {
         self.update();
     }
 
+    #[inline]
+    pub(crate) fn drag_mut(&mut self) -> &mut NodeDrag {
+        &mut self.drag
+    }
+
+    #[inline]
+    pub(crate) fn drag(&self) -> &NodeDrag {
+        &self.drag
+    }
+
+    #[inline]
+    pub(crate) fn register_drop_veto<
+        T: 'static + Fn(&[ObjectId], &TreeNode, DropPosition) -> bool,
+    >(
+        &mut self,
+        f: T,
+    ) {
+        self.on_drop_veto = Some(Box::new(f))
+    }
+
+    #[inline]
+    pub(crate) fn register_lazy_loader(&mut self, loader: FnLazyLoader) {
+        self.store.register_lazy_loader(loader)
+    }
+
+    /// Toggle the checkbox cell of the image node at the point.
+    ///
+    /// @return `true` if the checkbox was toggled.
+    fn toggle_checkbox(&mut self, idx: usize, point: FPoint) -> bool {
+        if self.store.get_image_node_ptr(idx).is_none() {
+            return false;
+        }
+        let rect = self.contents_rect(Some(Coordinate::Widget));
+        let (node_y, _) = self.store.image_node_geometry(idx);
+        let indent_length = self.indent_length;
+        let line_height = self.line_height;
+
+        let node = self.store.get_image_node(idx).unwrap();
+        let geometry = FRect::new(
+            rect.x() as f32,
+            (rect.y() + node_y) as f32,
+            rect.width() as f32,
+            node.height().unwrap_or(line_height) as f32,
+        );
+        let (cell, checked) = match node.toggle_checkbox(geometry, indent_length, point) {
+            Some(toggled) => toggled,
+            None => return false,
+        };
+        let id = node.id();
+        self.update();
+
+        let view = self.store.get_view().downcast_mut::<TreeView>().unwrap();
+        emit!(view, checkbox_toggled(id, cell, checked));
+        true
+    }
+
+    fn render_lazy_spinner(&self, painter: &mut Painter, geometry: Rect) {
+        let size = LOADING_SIZE.min(geometry.height() as f32 - 2.);
+        if size <= 0. {
+            return;
+        }
+        let x = (geometry.x() + geometry.width()) as f32 - size - 4.;
+        let y = geometry.y() as f32 + (geometry.height() as f32 - size) / 2.;
+
+        painter.save();
+        painter.set_line_width(2.);
+        painter.set_antialiasing(true);
+        painter.set_color(LOADING_COLOR);
+        painter.set_style(PaintStyle::Stroke);
+        painter.draw_arc_f(x, y, size, size, 360. * self.lazy_spin, 240., false);
+        painter.restore();
+    }
+
+    fn start_drag(&mut self) {
+        let dragged = self.store.selected_ids();
+        self.drag.set_dragged(dragged);
+
+        self.set_cursor_shape(SystemCursorShape::DragMoveCursor);
+    }
+
+    fn handle_drag_move(&mut self, y: i32) {
+        let rect = self.contents_rect_f(Some(Coordinate::Widget));
+        let line_height = (self.line_height + self.line_spacing) as f32;
+        let idx = self.index_node(y);
+
+        let mut target = None;
+        let node_ptr = self.store.get_image_node_ptr(idx);
+        if node_ptr.is_some() {
+            let node = nonnull_ref!(node_ptr);
+            let dragged = self.drag.dragged();
+
+            if !dragged.contains(&node.id()) && !self.store.has_ancestor_in(node.id(), dragged) {
+                let (node_y, height) = self.store.image_node_geometry(idx);
+                let (node_y, height) = (rect.y() + node_y as f32, height as f32);
+                let position = DropPosition::locate(node_y, height, y as f32, node.is_extensible());
+
+                let vetoed = self
+                    .on_drop_veto
+                    .as_ref()
+                    .map_or(false, |veto| veto(dragged, node, position));
+
+                if !vetoed {
+                    let level = node.level() as f32;
+                    let indent = self.indent_length as f32 * level;
+                    let node_rect =
+                        FRect::new(rect.x() + indent, node_y, rect.width() - indent, height);
+                    target = Some((node.id(), position, position.indicator(node_rect)));
+                }
+            }
+        }
+
+        let update = match target {
+            Some((id, position, indicator)) => self
+                .drag
+                .update_target(Some((id, position)), Some(indicator)),
+            None => self.drag.update_target(None, None),
+        };
+
+        // Scroll the view when dragging near the top/bottom edge:
+        let scroll_bar = nonnull_mut!(self.scroll_bar);
+        if (y as f32) < rect.y() + line_height {
+            scroll_bar.set_value(scroll_bar.value() - scroll_bar.single_step());
+        } else if (y as f32) > rect.y() + rect.height() - line_height {
+            scroll_bar.set_value(scroll_bar.value() + scroll_bar.single_step());
+        }
+
+        if update {
+            self.update();
+        }
+    }
+
+    fn drop_nodes(&mut self, node_drop: NodeDrop) {
+        let NodeDrop {
+            nodes,
+            target,
+            position,
+        } = node_drop;
+
+        if self.store.move_nodes(&nodes, target, position) {
+            let view = self.store.get_view().downcast_mut::<TreeView>().unwrap();
+            emit!(view, nodes_moved(nodes));
+        }
+    }
+
     #[inline]
     pub(crate) fn reset_effect_node(&mut self) {
         self.store.remove_effected_node_status();
@@ -10,4 +10,15 @@ pub trait TreeViewObject {
 
     /// Get the [`NodeRender`]
     fn node_render(&self) -> NodeRender;
+
+    /// Whether the children of [`TreeNode`](super::tree_node::TreeNode) should be loaded lazily.
+    ///
+    /// The lazy node was collapsed initially, it's children will be loaded by the loader registered through
+    /// [`register_lazy_loader()`](super::TreeView::register_lazy_loader) when it was expanded for the first time.
+    ///
+    /// Only available when [`extensible()`](TreeViewObject::extensible) returns `true`.
+    #[inline]
+    fn lazy(&self) -> bool {
+        false
+    }
 }
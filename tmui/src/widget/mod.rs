@@ -29,7 +29,7 @@ use std::{ptr::NonNull, slice::Iter};
 use tlib::{
     bitflags::bitflags,
     emit,
-    events::{InputMethodEvent, KeyEvent, MouseEvent, ReceiveCharacterEvent},
+    events::{FileEvent, InputMethodEvent, KeyEvent, MouseEvent, ReceiveCharacterEvent},
     figure::Color,
     namespace::{Align, BlendMode, Coordinate, Overflow},
     nonnull_mut, nonnull_ref,
@@ -312,6 +312,12 @@ impl Widget {
         ApplicationWindow::initialize_dynamic_component(child.as_dyn_mut(), self.is_in_tree());
     }
 
+    /// Take the child out, without removing it from the window.
+    #[inline]
+    pub(crate) fn _take_child_internal(&mut self) -> Option<DynTr> {
+        self.child.take()
+    }
+
     #[inline]
     pub fn _remove_child_internal(&mut self) {
         if let Some(child) = self.child.take() {
@@ -1444,6 +1450,13 @@ pub trait WidgetImpl:
     #[inline]
     fn on_window_restored(&mut self) {}
 
+    /// Invoke when the files were dropped or hovered on the window,
+    /// received by the widget under the cursor, or the focused widget.
+    ///
+    /// The files dropped on the `SharedWidget` were forwarded to the slave.
+    #[inline]
+    fn on_file_event(&mut self, event: &FileEvent) {}
+
     /// Invoke when widget's visibility changed.
     #[inline]
     fn on_visibility_changed(&mut self, visible: bool) {}
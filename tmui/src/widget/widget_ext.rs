@@ -1610,8 +1610,7 @@ impl<T: WidgetImpl> WidgetExt for T {
 
     #[inline]
     fn map_to_outer(&self, point: &Point) -> Point {
-        let pos = self.window().outer_position();
-        Point::new(point.x() + pos.x(), point.y() + pos.y())
+        self.window().map_to_outer(point)
     }
 
     #[inline]
@@ -1628,8 +1627,7 @@ impl<T: WidgetImpl> WidgetExt for T {
 
     #[inline]
     fn map_to_outer_f(&self, point: &FPoint) -> FPoint {
-        let pos = self.window().outer_position();
-        FPoint::new(point.x() + pos.x() as f32, point.y() + pos.y() as f32)
+        self.window().map_to_outer_f(point)
     }
 
     #[inline]
@@ -1,5 +1,6 @@
 pub mod win_builder;
 pub mod win_config;
+pub mod win_state;
 
 use self::win_config::WindowConfig;
 use crate::{application::FnActivate, prelude::RawWindowHandle6};
@@ -1,10 +1,10 @@
 use crate::{graphics::icon::Icon, prelude::RawWindowHandle6, primitive::Message};
 use derivative::Derivative;
 use tlib::{
-    figure::{Point, Size},
+    figure::{Point, Rect, Size},
     typedef::{WinitIcon, WinitPosition, WinitWindowBuilder},
     winit::{
-        dpi::{PhysicalPosition, PhysicalSize}, error::OsError, event_loop::EventLoopWindowTarget, window::{Window, WindowButtons, WindowLevel}
+        dpi::{LogicalSize, PhysicalPosition}, error::OsError, event_loop::EventLoopWindowTarget, window::{Window, WindowButtons, WindowLevel}
     },
 };
 #[cfg(windows_platform)]
@@ -22,9 +22,11 @@ pub(crate) fn build_window(
 #[derive(Debug, Clone)]
 pub struct WindowConfig {
     title: String,
-    /// The width of window.
+    /// The name of window, used as the key of window state persistence.
+    name: Option<String>,
+    /// The width of window in logical pixels.
     width: u32,
-    /// The height of window.
+    /// The height of window in logical pixels.
     height: u32,
     /// The maxmium size of window.
     max_size: Option<Size>,
@@ -65,6 +67,7 @@ impl WindowConfig {
     fn new() -> Self {
         Self {
             title: Default::default(),
+            name: Default::default(),
             width: Default::default(),
             height: Default::default(),
             max_size: Default::default(),
@@ -91,6 +94,11 @@ impl WindowConfig {
         WindowConfigBuilder::default()
     }
 
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     #[inline]
     pub fn size(&self) -> (u32, u32) {
         (self.width, self.height)
@@ -181,12 +189,25 @@ impl WindowConfig {
         self.parent_window = Some(rwh)
     }
 
+    /// Set the outer position(physical) and inner size(logical) of window.
+    #[inline]
+    pub(crate) fn set_geometry(&mut self, geometry: Rect) {
+        self.position = Some(geometry.top_left());
+        self.width = geometry.width() as u32;
+        self.height = geometry.height() as u32;
+    }
+
+    #[inline]
+    pub(crate) fn set_maximized(&mut self, maximized: bool) {
+        self.maximized = maximized
+    }
+
     pub(crate) fn create_window_builder(self) -> WinitWindowBuilder {
         let (width, height) = self.size();
 
         let mut window_bld = WinitWindowBuilder::new()
             .with_title(&self.title)
-            .with_inner_size(WinitSize::Physical(PhysicalSize::new(width, height)))
+            .with_inner_size(WinitSize::Logical(LogicalSize::new(width as f64, height as f64)))
             .with_decorations(self.decoration)
             .with_blur(self.blur)
             .with_visible(self.visible)
@@ -202,16 +223,16 @@ impl WindowConfig {
         }
 
         if let Some(max_size) = self.max_size {
-            window_bld = window_bld.with_max_inner_size(WinitSize::Physical(PhysicalSize::new(
-                max_size.width() as u32,
-                max_size.height() as u32,
+            window_bld = window_bld.with_max_inner_size(WinitSize::Logical(LogicalSize::new(
+                max_size.width() as f64,
+                max_size.height() as f64,
             )))
         }
 
         if let Some(min_size) = self.min_size {
-            window_bld = window_bld.with_min_inner_size(WinitSize::Physical(PhysicalSize::new(
-                min_size.width() as u32,
-                min_size.height() as u32,
+            window_bld = window_bld.with_min_inner_size(WinitSize::Logical(LogicalSize::new(
+                min_size.width() as f64,
+                min_size.height() as f64,
             )))
         }
 
@@ -239,6 +260,7 @@ impl WindowConfig {
 pub struct WindowConfigBuilder {
     #[derivative(Default(value = "\"Tmui Window\".to_string()"))]
     title: String,
+    name: Option<String>,
     width: Option<u32>,
     height: Option<u32>,
     max_size: Option<Size>,
@@ -278,14 +300,26 @@ impl WindowConfigBuilder {
         self
     }
 
-    /// Set the width of window.
+    /// Set the name of window, the geometry and state of window will be persisted
+    /// with the name as key, if [`WindowStateStore`](super::win_state::WindowStateStore)
+    /// was enabled. The recorded state will override the position, size and maximized
+    /// configurations when the window was created.
+    ///
+    /// The default value was [`None`].
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Set the width of window in logical pixels.
     #[inline]
     pub fn width(mut self, width: u32) -> Self {
         self.width = Some(width);
         self
     }
 
-    /// Set the height of window.
+    /// Set the height of window in logical pixels.
     #[inline]
     pub fn height(mut self, height: u32) -> Self {
         self.height = Some(height);
@@ -428,6 +462,7 @@ impl WindowConfigBuilder {
         let mut cfg = WindowConfig::new();
 
         cfg.title = self.title;
+        cfg.name = self.name;
         cfg.width = self.width.expect("`WindowConfig` must specify the width");
         cfg.height = self.height.expect("`WindowConfig` must specify the height");
         cfg.max_size = self.max_size;
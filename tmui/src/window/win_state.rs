@@ -0,0 +1,336 @@
+use super::win_config::WindowConfig;
+use crate::primitive::Message;
+use lazy_static::lazy_static;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tipc::parking_lot::Mutex;
+use tlib::{
+    figure::Rect,
+    typedef::WinitWindow,
+    winit::{event_loop::EventLoopWindowTarget, monitor::MonitorHandle, window::WindowId},
+};
+
+lazy_static! {
+    /// The store was shared by all the windows, it was only accessed in the main thread
+    /// which the windows were created and the window events were received.
+    static ref STORE: Mutex<Option<WindowStateStore>> = Mutex::new(None);
+    /// The names of living windows which enabled the state persistence.
+    static ref NAMES: Mutex<Vec<(WindowId, String)>> = Mutex::new(vec![]);
+}
+
+/// The persisted geometry and state of window.
+///
+/// The position was the outer position of window in `screen coordinate`, the size was
+/// the inner size of window in logical pixels. The geometry was the normal geometry before
+/// the window was maximized or minimized.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub minimized: bool,
+    /// The name of monitor which the window was on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+}
+
+impl WindowState {
+    #[inline]
+    pub fn geometry(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width as i32, self.height as i32)
+    }
+}
+
+/// The opt-in store to persist the window states, keyed by window name,
+/// see [`WindowConfigBuilder::name()`](super::win_config::WindowConfigBuilder::name).
+///
+/// The states were recorded when the windows were moved, resized, maximized or minimized,
+/// and saved to the json file when the window was closed. The states will be restored
+/// when the windows with the same name were created, clamped to the visible monitors.
+///
+/// ```ignore
+/// WindowStateStore::enable("config/window_state.json");
+///
+/// Application::builder()
+///     .width(1280)
+///     .height(800)
+///     .name("main")
+///     .build()
+///     .run();
+/// ```
+#[derive(Debug)]
+pub struct WindowStateStore {
+    path: PathBuf,
+    states: BTreeMap<String, WindowState>,
+}
+
+impl WindowStateStore {
+    /// Enable the window state persistence, load the states from the file if it exists.
+    ///
+    /// Should be called before the application was running.
+    pub fn enable(path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf();
+
+        let states = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Parse the window states `{:?}` failed, error = {}", path, e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        *STORE.lock() = Some(Self { path, states });
+    }
+
+    /// Disable the window state persistence, the saved file was not affected.
+    #[inline]
+    pub fn disable() {
+        *STORE.lock() = None;
+    }
+
+    #[inline]
+    pub fn is_enabled() -> bool {
+        STORE.lock().is_some()
+    }
+
+    /// Get the recorded state of window.
+    #[inline]
+    pub fn state(name: &str) -> Option<WindowState> {
+        STORE
+            .lock()
+            .as_ref()
+            .and_then(|store| store.states.get(name).cloned())
+    }
+
+    /// Remove the recorded state of window, the window will use its configuration
+    /// on the next creation.
+    #[inline]
+    pub fn remove(name: &str) {
+        if let Some(store) = STORE.lock().as_mut() {
+            store.states.remove(name);
+        }
+    }
+
+    /// Save the recorded states to the file.
+    pub fn save() {
+        let guard = STORE.lock();
+        let Some(store) = guard.as_ref() else {
+            return;
+        };
+
+        let result = serde_json::to_string_pretty(&store.states)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(dir) = store.path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&store.path, content).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            error!(
+                "Save the window states `{:?}` failed, error = {}",
+                store.path, e
+            );
+        }
+    }
+}
+
+/// Apply the recorded state to the window configuration before the window was built.
+///
+/// @return whether the window should be minimized after it was built.
+pub(crate) fn restore(
+    win_config: &mut WindowConfig,
+    target: &EventLoopWindowTarget<Message>,
+) -> bool {
+    let Some(state) = win_config.name().and_then(WindowStateStore::state) else {
+        return false;
+    };
+
+    let primary = target.primary_monitor();
+    let handles: Vec<MonitorHandle> = primary
+        .iter()
+        .cloned()
+        .chain(
+            target
+                .available_monitors()
+                .filter(|m| Some(m) != primary.as_ref()),
+        )
+        .collect();
+    let monitors: Vec<(Option<String>, Rect)> = handles
+        .iter()
+        .map(|m| (m.name(), monitor_rect(m)))
+        .collect();
+
+    // Fit the geometry in physical pixels, with the scale factor of monitor which it was on.
+    let scale_factor = state
+        .monitor
+        .as_ref()
+        .and_then(|name| handles.iter().find(|m| m.name().as_ref() == Some(name)))
+        .or(handles.first())
+        .map_or(1., |m| m.scale_factor());
+    let mut physical = state.clone();
+    physical.width = (state.width as f64 * scale_factor).round() as u32;
+    physical.height = (state.height as f64 * scale_factor).round() as u32;
+
+    let mut geometry = fit_monitors(&physical, &monitors);
+    geometry.set_width((geometry.width() as f64 / scale_factor).round() as i32);
+    geometry.set_height((geometry.height() as f64 / scale_factor).round() as i32);
+    win_config.set_geometry(geometry);
+    win_config.set_maximized(state.maximized);
+    state.minimized
+}
+
+/// Register the window which enabled the state persistence.
+pub(crate) fn register(window: &WinitWindow, name: Option<String>) {
+    if let Some(name) = name {
+        if WindowStateStore::is_enabled() {
+            NAMES.lock().push((window.id(), name));
+            record(window);
+        }
+    }
+}
+
+/// Record the current state of window.
+pub(crate) fn record(window: &WinitWindow) {
+    let names = NAMES.lock();
+    let Some((_, name)) = names.iter().find(|(id, _)| *id == window.id()) else {
+        return;
+    };
+    let mut guard = STORE.lock();
+    let Some(store) = guard.as_mut() else {
+        return;
+    };
+
+    let state = store.states.entry(name.clone()).or_default();
+    state.maximized = window.is_maximized();
+    state.minimized = window.is_minimized().unwrap_or_default();
+    state.monitor = window.current_monitor().and_then(|m| m.name());
+
+    // Keep the normal geometry, so the window can be restored from maximized/minimized.
+    if state.maximized || state.minimized {
+        return;
+    }
+    if let Ok(pos) = window.outer_position() {
+        state.x = pos.x;
+        state.y = pos.y;
+    }
+    let size = window.inner_size().to_logical::<u32>(window.scale_factor());
+    if size.width > 0 && size.height > 0 {
+        state.width = size.width;
+        state.height = size.height;
+    }
+}
+
+/// Record the state of closing window, and save the states to the file.
+pub(crate) fn record_closed(window: &WinitWindow) {
+    if !NAMES.lock().iter().any(|(id, _)| *id == window.id()) {
+        return;
+    }
+
+    record(window);
+    NAMES.lock().retain(|(id, _)| *id != window.id());
+    WindowStateStore::save();
+}
+
+#[inline]
+fn monitor_rect(monitor: &MonitorHandle) -> Rect {
+    let pos = monitor.position();
+    let size = monitor.size();
+    Rect::new(pos.x, pos.y, size.width as i32, size.height as i32)
+}
+
+/// Fit the geometry of state into the monitor which it was on, or the monitor which
+/// has the most overlapping with it, or the primary monitor(the first one).
+fn fit_monitors(state: &WindowState, monitors: &[(Option<String>, Rect)]) -> Rect {
+    let geometry = state.geometry();
+
+    let by_name = state
+        .monitor
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|(n, _)| n.as_ref() == Some(name)));
+    let by_overlap = || {
+        monitors
+            .iter()
+            .filter_map(|m| {
+                let intersect = m.1.intersects(&geometry)?;
+                Some((m, intersect.width() * intersect.height()))
+            })
+            .max_by_key(|(_, area)| *area)
+            .map(|(m, _)| m)
+    };
+    let Some((_, monitor)) = by_name.or_else(by_overlap).or(monitors.first()) else {
+        return geometry;
+    };
+
+    let width = geometry.width().min(monitor.width());
+    let height = geometry.height().min(monitor.height());
+    let x = geometry.x().clamp(monitor.x(), monitor.right() - width);
+    let y = geometry.y().clamp(monitor.y(), monitor.bottom() - height);
+
+    Rect::new(x, y, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_monitors() {
+        let monitors = vec![
+            (Some("primary".to_string()), Rect::new(0, 0, 1920, 1080)),
+            (Some("right".to_string()), Rect::new(1920, 0, 1280, 1024)),
+        ];
+        let state = |x, y, width, height, monitor: Option<&str>| WindowState {
+            x,
+            y,
+            width,
+            height,
+            monitor: monitor.map(|m| m.to_string()),
+            ..Default::default()
+        };
+
+        // Fully visible, unchanged.
+        assert_eq!(
+            fit_monitors(&state(100, 100, 800, 600, None), &monitors),
+            Rect::new(100, 100, 800, 600)
+        );
+        // Partially outside the monitor it was on.
+        assert_eq!(
+            fit_monitors(&state(2800, 700, 800, 600, Some("right")), &monitors),
+            Rect::new(2400, 424, 800, 600)
+        );
+        // The monitor was disconnected, fit into the monitor which overlaps the most.
+        assert_eq!(
+            fit_monitors(&state(1800, 100, 800, 600, Some("gone")), &monitors),
+            Rect::new(1920, 100, 800, 600)
+        );
+        // Nothing overlaps, moved to the primary monitor, and the size was clamped.
+        assert_eq!(
+            fit_monitors(&state(5000, 5000, 2560, 1440, None), &monitors),
+            Rect::new(0, 0, 1920, 1080)
+        );
+        // No monitor information.
+        assert_eq!(
+            fit_monitors(&state(5000, 5000, 800, 600, None), &[]),
+            Rect::new(5000, 5000, 800, 600)
+        );
+    }
+
+    #[test]
+    fn test_window_state_json() {
+        let state: WindowState =
+            serde_json::from_str(r#"{ "x": 10, "y": 20, "width": 800, "height": 600 }"#).unwrap();
+        assert!(!state.maximized && !state.minimized);
+        assert_eq!(state.monitor, None);
+        assert_eq!(state.geometry(), Rect::new(10, 20, 800, 600));
+    }
+}